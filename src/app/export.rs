@@ -0,0 +1,249 @@
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::history::Play;
+use super::stats::csv_field;
+use crate::library::track::Track;
+use crate::library::Library;
+
+/// What an export writes out
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dataset {
+    Library,
+    Queue,
+    History,
+}
+
+impl Dataset {
+    pub const ALL: [Dataset; 3] = [Dataset::Library, Dataset::Queue, Dataset::History];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Dataset::Library => "library",
+            Dataset::Queue => "queue",
+            Dataset::History => "history",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|d| d.name().eq_ignore_ascii_case(name))
+    }
+
+    pub fn next(self) -> Self {
+        let i = Self::ALL.iter().position(|&d| d == self).unwrap_or(0);
+        Self::ALL[(i + 1) % Self::ALL.len()]
+    }
+
+    /// `~/ommp-library.csv` and so on
+    pub fn default_path(self) -> String {
+        format!("~/ommp-{}.csv", self.name())
+    }
+}
+
+/// `~/...` from the home directory
+pub fn expand_home(path: &str) -> PathBuf {
+    match path.strip_prefix("~/") {
+        Some(rest) => {
+            let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
+            PathBuf::from(home).join(rest)
+        }
+        None => PathBuf::from(path),
+    }
+}
+
+/// Whether `path` would land inside `dir` once symlinks, `..` and relative parts are
+/// resolved; the file itself needn't exist yet, so the nearest existing ancestor is
+/// resolved and the rest appended
+pub fn is_inside(path: &Path, dir: &Path) -> bool {
+    let dir = dir.canonicalize().unwrap_or_else(|_| dir.to_path_buf());
+    let mut existing = path;
+    let mut rest = Vec::new();
+    let base = loop {
+        let lookup = if existing.as_os_str().is_empty() { Path::new(".") } else { existing };
+        if let Ok(resolved) = lookup.canonicalize() {
+            break resolved;
+        }
+        match (existing.parent(), existing.file_name()) {
+            (Some(parent), Some(name)) => {
+                rest.push(name);
+                existing = parent;
+            }
+            _ => return path.starts_with(&dir),
+        }
+    };
+    rest.iter().rev().fold(base, |p, name| p.join(name)).starts_with(&dir)
+}
+
+/// A track of the library or queue; tags are empty for queue entries whose file is
+/// no longer in the library
+#[derive(Serialize)]
+struct TrackRow<'a> {
+    /// 1-based queue position (queue exports only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    position: Option<usize>,
+    path: &'a Path,
+    title: Option<&'a str>,
+    artist: Option<&'a str>,
+    album: Option<&'a str>,
+    album_artist: Option<&'a str>,
+    genre: Option<&'a str>,
+    year: Option<u32>,
+    disc: Option<u32>,
+    track: Option<u32>,
+    duration_secs: Option<f64>,
+    format: String,
+    bitrate: Option<u32>,
+    sample_rate: Option<u32>,
+    bit_depth: Option<u8>,
+    bpm: Option<f64>,
+    replay_gain: Option<f64>,
+}
+
+impl<'a> TrackRow<'a> {
+    fn new(position: Option<usize>, path: &'a Path, track: Option<&'a Track>) -> Self {
+        let text = |s: &'a str| Some(s).filter(|s| !s.is_empty());
+        Self {
+            position,
+            path,
+            title: track.and_then(|t| text(&t.title)),
+            artist: track.and_then(|t| text(&t.artist)),
+            album: track.and_then(|t| text(&t.album)),
+            album_artist: track.and_then(|t| text(&t.album_artist)),
+            genre: track.and_then(|t| text(&t.genre)),
+            year: track.and_then(|t| t.year),
+            disc: track.and_then(|t| t.disc_number),
+            track: track.and_then(|t| t.track_number),
+            duration_secs: track.map(|t| t.duration.as_secs_f64()),
+            format: path.extension().map(|e| e.to_string_lossy().to_uppercase()).unwrap_or_default(),
+            bitrate: track.and_then(|t| t.bitrate),
+            sample_rate: track.and_then(|t| t.sample_rate),
+            bit_depth: track.and_then(|t| t.bit_depth),
+            bpm: track.and_then(|t| t.bpm),
+            replay_gain: track.and_then(|t| t.replay_gain),
+        }
+    }
+}
+
+/// CSV columns of a library track; queue exports put `position` first
+const TRACK_COLUMNS: [&str; 16] = [
+    "path", "title", "artist", "album", "album_artist", "genre", "year", "disc", "track",
+    "duration_secs", "format", "bitrate", "sample_rate", "bit_depth", "bpm", "replay_gain",
+];
+const HISTORY_COLUMNS: [&str; 8] =
+    ["started", "path", "title", "artist", "album", "format", "listened_secs", "duration_secs"];
+
+/// Values of a row for CSV output, in column order
+trait CsvRow {
+    fn fields(&self) -> Vec<String>;
+}
+
+fn opt<T: ToString>(value: Option<T>) -> String {
+    value.map(|v| v.to_string()).unwrap_or_default()
+}
+
+impl CsvRow for TrackRow<'_> {
+    fn fields(&self) -> Vec<String> {
+        let mut fields = if self.position.is_some() { vec![opt(self.position)] } else { Vec::new() };
+        fields.extend([
+            self.path.to_string_lossy().into_owned(),
+            opt(self.title),
+            opt(self.artist),
+            opt(self.album),
+            opt(self.album_artist),
+            opt(self.genre),
+            opt(self.year),
+            opt(self.disc),
+            opt(self.track),
+            opt(self.duration_secs.map(|d| format!("{:.3}", d))),
+            self.format.clone(),
+            opt(self.bitrate),
+            opt(self.sample_rate),
+            opt(self.bit_depth),
+            opt(self.bpm),
+            opt(self.replay_gain),
+        ]);
+        fields
+    }
+}
+
+impl CsvRow for &Play {
+    fn fields(&self) -> Vec<String> {
+        vec![
+            self.started.to_string(),
+            self.path.to_string_lossy().into_owned(),
+            self.title.clone(),
+            self.artist.clone(),
+            self.album.clone(),
+            self.format.clone(),
+            format!("{:.1}", self.listened),
+            format!("{:.3}", self.duration),
+        ]
+    }
+}
+
+/// Write a data set to `path`: CSV when it ends in `.csv`, JSON otherwise. Returns the
+/// number of rows written.
+pub fn write(dataset: Dataset, library: &Library, queue: &[PathBuf], history: &[Play], path: &Path) -> anyhow::Result<usize> {
+    let csv = path.extension().is_some_and(|e| e.eq_ignore_ascii_case("csv"));
+    match dataset {
+        Dataset::Library => {
+            let rows: Vec<TrackRow> = library.tracks.iter().map(|t| TrackRow::new(None, &t.path, Some(t))).collect();
+            save(&rows, &TRACK_COLUMNS, csv, path)
+        }
+        Dataset::Queue => {
+            let rows: Vec<TrackRow> = queue
+                .iter()
+                .enumerate()
+                .map(|(i, p)| TrackRow::new(Some(i + 1), p, library.track_by_path(p)))
+                .collect();
+            let header: Vec<&str> = std::iter::once("position").chain(TRACK_COLUMNS).collect();
+            save(&rows, &header, csv, path)
+        }
+        Dataset::History => {
+            let rows: Vec<&Play> = history.iter().collect();
+            save(&rows, &HISTORY_COLUMNS, csv, path)
+        }
+    }
+}
+
+fn save<R: Serialize + CsvRow>(rows: &[R], header: &[&str], csv: bool, path: &Path) -> anyhow::Result<usize> {
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)?;
+    }
+    let out = if csv {
+        let mut out = header.join(",");
+        out.push('\n');
+        for row in rows {
+            let fields: Vec<String> = row.fields().iter().map(|f| csv_field(f)).collect();
+            out.push_str(&fields.join(","));
+            out.push('\n');
+        }
+        out
+    } else {
+        serde_json::to_string_pretty(rows)?
+    };
+    fs::write(path, out)?;
+    Ok(rows.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn is_inside_resolves_dots_and_missing_files() {
+        let dir = std::env::temp_dir().join(format!("ommp-export-{}", std::process::id()));
+        let music = dir.join("Music");
+        fs::create_dir_all(music.join("Album")).unwrap();
+        assert!(is_inside(&music.join("Album/../out.csv"), &music));
+        assert!(is_inside(&music.join("new/dir/out.csv"), &music));
+        assert!(!is_inside(&music.join("../out.csv"), &music));
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(&music, dir.join("link")).unwrap();
+            assert!(is_inside(&dir.join("link/out.csv"), &music));
+        }
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,12 +1,23 @@
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use std::path::PathBuf;
 use std::time::{Duration, Instant};
 
-use crate::app::state::{FocusedPane, Tab};
-use crate::app::{App, AppAction};
+use crate::app::state::{FocusedPane, LayoutPreset, Tab};
+use crate::app::export;
+use crate::app::stats::{Period, Stats};
+use crate::library::organize;
+use crate::library::track::VARIOUS_ARTISTS;
+use crate::library::tag_edit::{self, TagField};
+use crate::app::{App, AppAction, READ_ONLY_MESSAGE};
 use crate::ui::layout::LayoutAreas;
 use crate::ui::pane::Pane;
-use crate::ui::widgets::{progress_bar, tab_bar};
+use crate::ui::scrollable_list::ScrollableList;
+use crate::ui::text_input::{Edit, TextInput};
+use crate::ui::widgets::{progress_bar, status_bar, tab_bar};
 use crate::ui::widgets::playlist_modal::PlaylistModalMode;
+use crate::ui::widgets::export_modal::ExportModal;
+use crate::ui::widgets::session_modal::SessionModal;
+use crate::ui::widgets::tag_modal::{TagModal, TagStep};
 use crate::ui::Ui;
 
 pub fn handle_key_event(key: KeyEvent, app: &App, ui: &mut Ui) -> Vec<AppAction> {
@@ -47,6 +58,170 @@ pub fn handle_key_event(key: KeyEvent, app: &App, ui: &mut Ui) -> Vec<AppAction>
         return actions;
     }
 
+    // Export modal: Tab picks the data set, the file name is edited in place
+    if let Some(modal) = ui.export_modal.as_mut() {
+        match key.code {
+            KeyCode::Esc => ui.export_modal = None,
+            KeyCode::Tab => modal.cycle(),
+            KeyCode::Enter => {
+                let path = modal.path.as_str().trim().to_string();
+                if !path.is_empty() {
+                    actions.push(AppAction::Export { dataset: modal.dataset, path: export::expand_home(&path) });
+                    ui.export_modal = None;
+                }
+            }
+            _ => {
+                modal.path.handle_key(&key);
+            }
+        }
+        return actions;
+    }
+
+    // Sessions modal: restore, save as / over, delete; a name prompt for "save as"
+    if let Some(modal) = ui.session_modal.as_mut() {
+        if let Some(input) = modal.naming.as_mut() {
+            match key.code {
+                KeyCode::Esc => modal.naming = None,
+                KeyCode::Enter => {
+                    let name = input.as_str().trim().to_string();
+                    if !name.is_empty() {
+                        actions.push(AppAction::SaveSession(name));
+                        ui.session_modal = None;
+                    }
+                }
+                _ => {
+                    input.handle_key(&key);
+                }
+            }
+            return actions;
+        }
+        let count = app.sessions.len();
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => ui.session_modal = None,
+            KeyCode::Char('j') | KeyCode::Down => modal.selected = (modal.selected + 1).min(count.saturating_sub(1)),
+            KeyCode::Char('k') | KeyCode::Up => modal.selected = modal.selected.saturating_sub(1),
+            KeyCode::Enter if modal.selected < count => {
+                actions.push(AppAction::RestoreSession(modal.selected));
+                ui.session_modal = None;
+            }
+            KeyCode::Char('a') => {
+                let mut input = TextInput::new();
+                input.set(app.active_session.clone().unwrap_or_default());
+                modal.naming = Some(input);
+            }
+            KeyCode::Char('s') if modal.selected < count => {
+                actions.push(AppAction::SaveSession(app.sessions[modal.selected].name.clone()));
+            }
+            KeyCode::Char('d') if modal.selected < count => {
+                actions.push(AppAction::DeleteSession(modal.selected));
+                if modal.selected > 0 && modal.selected + 1 >= count {
+                    modal.selected -= 1;
+                }
+            }
+            _ => {}
+        }
+        return actions;
+    }
+
+    // Batch tag modal: choose a field, type the value, review the diff, write
+    if let Some(modal) = ui.tag_modal.as_mut() {
+        let mut close = false;
+        match modal.step {
+            TagStep::Choose => match key.code {
+                KeyCode::Esc | KeyCode::Char('q') => close = true,
+                KeyCode::Char('a') | KeyCode::Char('g') => {
+                    let field = if key.code == KeyCode::Char('a') { TagField::AlbumArtist } else { TagField::Genre };
+                    // Start from the first track's value
+                    modal.input.set(modal.tracks.first()
+                        .and_then(|&i| app.library.tracks.get(i))
+                        .map(|t| if field == TagField::AlbumArtist { t.album_artist.clone() } else { t.genre.clone() })
+                        .unwrap_or_default());
+                    modal.step = TagStep::Input(field);
+                }
+                KeyCode::Char('n') | KeyCode::Char('t') => {
+                    let field = if key.code == KeyCode::Char('n') { TagField::TrackNumber } else { TagField::Title };
+                    modal.changes = tag_edit::preview(&app.library, &modal.tracks, field, "");
+                    modal.scroll = 0;
+                    modal.step = TagStep::Preview;
+                }
+                KeyCode::Char('o') => {
+                    modal.moves = organize::plan(&app.library, &app.music_dir, &modal.tracks);
+                    modal.scroll = 0;
+                    modal.step = TagStep::Organize;
+                }
+                _ => {}
+            },
+            TagStep::Input(field) => match key.code {
+                KeyCode::Esc => modal.step = TagStep::Choose,
+                KeyCode::Enter => {
+                    modal.changes = tag_edit::preview(&app.library, &modal.tracks, field, modal.input.as_str());
+                    modal.scroll = 0;
+                    modal.step = TagStep::Preview;
+                }
+                _ => {
+                    modal.input.handle_key(&key);
+                }
+            },
+            TagStep::Preview => match key.code {
+                KeyCode::Esc => modal.step = TagStep::Choose,
+                KeyCode::Char('j') | KeyCode::Down if modal.scroll + 1 < modal.changes.len() => {
+                    modal.scroll += 1;
+                }
+                KeyCode::Char('k') | KeyCode::Up => {
+                    modal.scroll = modal.scroll.saturating_sub(1);
+                }
+                KeyCode::Enter if !modal.changes.is_empty() => {
+                    actions.push(AppAction::WriteTags(std::mem::take(&mut modal.changes)));
+                    close = true;
+                }
+                _ => {}
+            },
+            TagStep::Organize => match key.code {
+                KeyCode::Esc => modal.step = TagStep::Choose,
+                KeyCode::Char('j') | KeyCode::Down if modal.scroll + 1 < modal.moves.len() => {
+                    modal.scroll += 1;
+                }
+                KeyCode::Char('k') | KeyCode::Up => {
+                    modal.scroll = modal.scroll.saturating_sub(1);
+                }
+                KeyCode::Enter if !modal.moves.is_empty() => {
+                    actions.push(AppAction::OrganizeFiles(std::mem::take(&mut modal.moves)));
+                    close = true;
+                }
+                _ => {}
+            },
+        }
+        if close {
+            ui.tag_modal = None;
+        }
+        return actions;
+    }
+
+    // Stats modal: h/l to change the period, e/v to export, Esc to close
+    if let Some((period, _)) = ui.stats_modal {
+        let new_period = match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => {
+                ui.stats_modal = None;
+                return actions;
+            }
+            KeyCode::Char('l') | KeyCode::Right | KeyCode::Tab => period.next(),
+            KeyCode::Char('h') | KeyCode::Left | KeyCode::BackTab => period.prev(),
+            KeyCode::Char('e') => {
+                actions.push(AppAction::ExportStats { period, csv: false });
+                period
+            }
+            KeyCode::Char('v') => {
+                actions.push(AppAction::ExportStats { period, csv: true });
+                period
+            }
+            _ => period,
+        };
+        if new_period != period {
+            ui.stats_modal = Some((new_period, Stats::compute(&app.history, new_period)));
+        }
+        return actions;
+    }
+
     // Playlist modal ("b" key) — list, create, rename modes
     if ui.show_playlist_modal {
         match ui.playlist_modal_mode {
@@ -57,7 +232,7 @@ pub fn handle_key_event(key: KeyEvent, app: &App, ui: &mut Ui) -> Vec<AppAction>
                         ui.playlist_modal_input.clear();
                     }
                     KeyCode::Enter => {
-                        let name = ui.playlist_modal_input.trim().to_string();
+                        let name = ui.playlist_modal_input.as_str().trim().to_string();
                         if !name.is_empty() {
                             if ui.playlist_modal_mode == PlaylistModalMode::Create {
                                 actions.push(AppAction::CreatePlaylist(name));
@@ -71,13 +246,9 @@ pub fn handle_key_event(key: KeyEvent, app: &App, ui: &mut Ui) -> Vec<AppAction>
                         ui.playlist_modal_mode = PlaylistModalMode::List;
                         ui.playlist_modal_input.clear();
                     }
-                    KeyCode::Backspace => {
-                        ui.playlist_modal_input.pop();
-                    }
-                    KeyCode::Char(c) => {
-                        ui.playlist_modal_input.push(c);
+                    _ => {
+                        ui.playlist_modal_input.handle_key(&key);
                     }
-                    _ => {}
                 }
             }
             PlaylistModalMode::List => {
@@ -85,60 +256,78 @@ pub fn handle_key_event(key: KeyEvent, app: &App, ui: &mut Ui) -> Vec<AppAction>
                     KeyCode::Esc => {
                         ui.show_playlist_modal = false;
                         ui.playlist_modal_selected = 0;
+                        ui.playlist_modal_target = None;
+                        ui.playlist_modal_track = None;
                     }
-                    KeyCode::Up | KeyCode::Char('k') => {
-                        if ui.playlist_modal_selected > 0 {
-                            ui.playlist_modal_selected -= 1;
-                        }
+                    KeyCode::Up | KeyCode::Char('k') if ui.playlist_modal_selected > 0 => {
+                        ui.playlist_modal_selected -= 1;
                     }
-                    KeyCode::Down | KeyCode::Char('j') => {
-                        if !app.playlists.is_empty()
-                            && ui.playlist_modal_selected < app.playlists.len() - 1
-                        {
-                            ui.playlist_modal_selected += 1;
+                    KeyCode::Down | KeyCode::Char('j')
+                        if !app.playlists.is_empty() && ui.playlist_modal_selected < app.playlists.len() - 1 =>
+                    {
+                        ui.playlist_modal_selected += 1;
+                    }
+                    KeyCode::Enter if ui.playlist_modal_target.is_some() => {
+                        // Add the whole album/artist/folder the modal was opened on
+                        if let Some((_, paths)) = &ui.playlist_modal_target {
+                            if ui.playlist_modal_selected < app.playlists.len() {
+                                actions.push(AppAction::AddTracksToPlaylist {
+                                    playlist_idx: ui.playlist_modal_selected,
+                                    paths: paths.clone(),
+                                });
+                            }
                         }
                     }
                     KeyCode::Enter => {
-                        // Toggle track in selected playlist
-                        if let Some(track_idx) = app.queue.current_index
-                            .and_then(|qi| app.queue.tracks.get(qi).copied())
-                        {
+                        // Toggle track in selected playlist (add another copy if duplicates are allowed)
+                        if let Some(path) = ui.playlist_modal_track.clone() {
                             let pl_idx = ui.playlist_modal_selected;
                             if pl_idx < app.playlists.len() {
-                                if app.playlists[pl_idx].tracks.contains(&track_idx) {
+                                let pl = &app.playlists[pl_idx];
+                                if pl.tracks.contains(&path) && !pl.allow_duplicates {
                                     actions.push(AppAction::RemoveFromPlaylist {
                                         playlist_idx: pl_idx,
-                                        track_idx,
+                                        path,
                                     });
                                 } else {
                                     actions.push(AppAction::AddToPlaylist {
                                         playlist_idx: pl_idx,
-                                        track_idx,
+                                        path,
                                     });
                                 }
                             }
                         }
                     }
+                    KeyCode::Char('x') if ui.playlist_modal_target.is_none() => {
+                        if let Some(path) = ui.playlist_modal_track.clone() {
+                            let pl_idx = ui.playlist_modal_selected;
+                            if app.playlists.get(pl_idx).is_some_and(|pl| pl.tracks.contains(&path)) {
+                                actions.push(AppAction::RemoveFromPlaylist {
+                                    playlist_idx: pl_idx,
+                                    path,
+                                });
+                            }
+                        }
+                    }
+                    KeyCode::Char('u') if ui.playlist_modal_selected < app.playlists.len() => {
+                        actions.push(AppAction::TogglePlaylistDuplicates(ui.playlist_modal_selected));
+                    }
                     KeyCode::Char('a') => {
                         ui.playlist_modal_mode = PlaylistModalMode::Create;
                         ui.playlist_modal_input.clear();
                     }
-                    KeyCode::Char('d') => {
-                        if !app.playlists.is_empty() {
-                            actions.push(AppAction::DeletePlaylist(ui.playlist_modal_selected));
-                            if ui.playlist_modal_selected > 0
-                                && ui.playlist_modal_selected >= app.playlists.len() - 1
-                            {
-                                ui.playlist_modal_selected -= 1;
-                            }
+                    KeyCode::Char('d') if !app.playlists.is_empty() => {
+                        actions.push(AppAction::DeletePlaylist(ui.playlist_modal_selected));
+                        if ui.playlist_modal_selected > 0
+                            && ui.playlist_modal_selected >= app.playlists.len() - 1
+                        {
+                            ui.playlist_modal_selected -= 1;
                         }
                     }
-                    KeyCode::Char('r') => {
-                        if !app.playlists.is_empty() {
-                            ui.playlist_modal_mode = PlaylistModalMode::Rename;
-                            ui.playlist_modal_input =
-                                app.playlists[ui.playlist_modal_selected].name.clone();
-                        }
+                    KeyCode::Char('r') if !app.playlists.is_empty() => {
+                        ui.playlist_modal_mode = PlaylistModalMode::Rename;
+                        ui.playlist_modal_input
+                            .set(app.playlists[ui.playlist_modal_selected].name.clone());
                     }
                     _ => {}
                 }
@@ -151,23 +340,24 @@ pub fn handle_key_event(key: KeyEvent, app: &App, ui: &mut Ui) -> Vec<AppAction>
     if ui.show_search_modal {
         match key.code {
             KeyCode::Esc => {
-                ui.show_search_modal = false;
-                ui.search_modal_input.clear();
-                ui.search_modal_results.clear();
-                ui.search_modal_selected = 0;
-                ui.search_modal_scroll = 0;
+                ui.close_search_modal();
             }
             KeyCode::Enter => {
                 if !ui.search_modal_results.is_empty() {
                     let track_idx = ui.search_modal_results[ui.search_modal_selected];
                     actions.push(AppAction::AddToQueue(vec![track_idx]));
-                    ui.show_search_modal = false;
-                    ui.search_modal_input.clear();
-                    ui.search_modal_results.clear();
-                    ui.search_modal_selected = 0;
-                    ui.search_modal_scroll = 0;
+                    ui.close_search_modal();
                 }
             }
+            // Up/Down on an empty input (and while cycling) walk the recent searches
+            KeyCode::Up if ui.search_modal_input.is_empty() || ui.search_history_pos.is_some() => {
+                ui.recall_search(true);
+            }
+            KeyCode::Down if ui.search_history_pos.is_some() => {
+                ui.recall_search(false);
+            }
+            // Tab / Shift+Tab complete an artist:, album: or genre: filter
+            KeyCode::Tab | KeyCode::BackTab if ui.complete_search(&app.library, key.code == KeyCode::Tab) => {}
             KeyCode::Up | KeyCode::BackTab => {
                 if ui.search_modal_selected > 0 {
                     ui.search_modal_selected -= 1;
@@ -187,19 +377,61 @@ pub fn handle_key_event(key: KeyEvent, app: &App, ui: &mut Ui) -> Vec<AppAction>
                     }
                 }
             }
-            KeyCode::Backspace => {
-                ui.search_modal_input.pop();
-                ui.search_modal_results = app.library.search(&ui.search_modal_input);
-                ui.search_modal_selected = 0;
-                ui.search_modal_scroll = 0;
+            // Secondary actions on the selected result
+            KeyCode::Char(c @ ('a' | 'n' | 'p' | 'b' | 'g' | 'd')) if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                let Some(track) = ui.search_modal_results.get(ui.search_modal_selected)
+                    .and_then(|&i| app.library.tracks.get(i))
+                else {
+                    return actions;
+                };
+                let path = track.path.clone();
+                match c {
+                    'a' => actions.push(AppAction::AppendToQueue(vec![path])),
+                    'n' => actions.push(AppAction::QueueNext(vec![path])),
+                    'p' => actions.push(AppAction::Preview(path)),
+                    'b' => {
+                        ui.playlist_modal_track = Some(path);
+                        ui.show_playlist_modal = true;
+                        ui.playlist_modal_selected = 0;
+                    }
+                    'g' => {
+                        let Some(pos) = app.library.album_position(track) else { return actions };
+                        ui.albums_pane.filter.clear();
+                        ui.albums_pane.list.selected = pos;
+                        actions.push(AppAction::SwitchTab(Tab::Albums));
+                        actions.push(AppAction::FocusPane(FocusedPane::Library));
+                    }
+                    _ => {
+                        ui.dir_browser_pane.reveal(&path, app);
+                        actions.push(AppAction::SwitchTab(Tab::Directories));
+                        actions.push(AppAction::FocusPane(FocusedPane::Library));
+                    }
+                }
+                // Queueing and previewing keep the modal open for more picks
+                if !matches!(c, 'a' | 'n' | 'p') {
+                    ui.close_search_modal();
+                }
+            }
+            KeyCode::Char('o') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                ui.cycle_search_order(&app.library);
             }
-            KeyCode::Char(c) => {
-                ui.search_modal_input.push(c);
-                ui.search_modal_results = app.library.search(&ui.search_modal_input);
-                ui.search_modal_selected = 0;
-                ui.search_modal_scroll = 0;
+            KeyCode::Char('t') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                // Batch-edit tags of every result
+                if app.config.read_only {
+                    actions.push(AppAction::Notify(READ_ONLY_MESSAGE.to_string()));
+                } else if !ui.search_modal_results.is_empty() {
+                    let name = format!("\"{}\"", ui.search_modal_input.as_str());
+                    ui.tag_modal = Some(TagModal::new(name, ui.search_modal_results.clone()));
+                    ui.close_search_modal();
+                }
+            }
+            _ => {
+                if ui.search_modal_input.handle_key(&key) == Edit::Changed {
+                    ui.search_history_pos = None;
+                    ui.search_completion = None;
+                    ui.search_input_changed();
+                }
             }
-            _ => {}
         }
         return actions;
     }
@@ -209,12 +441,58 @@ pub fn handle_key_event(key: KeyEvent, app: &App, ui: &mut Ui) -> Vec<AppAction>
         return actions;
     }
 
+    // Lyrics search: typing after `/`, and n/N/Esc while a search is active
+    if app.focus == FocusedPane::Lyrics && !ui.chord_pending && ui.lyrics_pane.captures_key(&key) {
+        actions.extend(ui.lyrics_pane.handle_key(key, app));
+        return actions;
+    }
+
+    // Artists / Albums / Genre filter: typing after `/`, and Esc to clear it
+    if app.focus == FocusedPane::Library && !ui.chord_pending {
+        let action = match app.tab {
+            Tab::Artists if ui.artists_pane.filter.captures_key(&key) => Some(ui.artists_pane.handle_key(key, app)),
+            Tab::Albums if ui.albums_pane.filter.captures_key(&key) => Some(ui.albums_pane.handle_key(key, app)),
+            Tab::Genre if ui.genre_pane.filter.captures_key(&key) => Some(ui.genre_pane.handle_key(key, app)),
+            _ => None,
+        };
+        if let Some(action) = action {
+            actions.extend(action);
+            return actions;
+        }
+    }
+
     // Chord: Ctrl+E pressed, waiting for next key
     if ui.chord_pending {
         ui.chord_pending = false;
         match key.code {
             KeyCode::Char('s') => {
                 ui.show_search_modal = true;
+                // Start from the selected artist/album/genre so refining is one keystroke
+                let filter = match (app.focus, app.tab) {
+                    (FocusedPane::Library, Tab::Artists) => app.library.get_artists()
+                        .get(ui.artists_pane.list.selected)
+                        .filter(|a| *a != VARIOUS_ARTISTS)
+                        .map(|a| format!("artist:\"{}\" ", a)),
+                    (FocusedPane::Library, Tab::Albums) => app.library.get_albums()
+                        .get(ui.albums_pane.list.selected)
+                        .map(|album| format!("album:\"{}\" ", album.name)),
+                    (FocusedPane::Library, Tab::Genre) => app.library.get_genres()
+                        .get(ui.genre_pane.list.selected)
+                        .map(|g| format!("genre:\"{}\" ", g)),
+                    _ => None,
+                };
+                if let Some(filter) = filter {
+                    ui.search_modal_input.set(filter);
+                    ui.search_input_changed();
+                }
+            }
+            KeyCode::Char('S') => {
+                // Repeat the last search
+                if let Some(last) = ui.search_history.last().cloned() {
+                    ui.show_search_modal = true;
+                    ui.search_modal_input.set(last);
+                    ui.search_input_changed();
+                }
             }
             KeyCode::Char('h') => {
                 ui.show_help_modal = true;
@@ -225,9 +503,42 @@ pub fn handle_key_event(key: KeyEvent, app: &App, ui: &mut Ui) -> Vec<AppAction>
             KeyCode::Char('i') => {
                 ui.show_about_modal = true;
             }
+            KeyCode::Char('t') => {
+                ui.stats_modal = Some((Period::Week, Stats::compute(&app.history, Period::Week)));
+            }
             KeyCode::Char('l') => {
                 actions.push(AppAction::LibrarySync);
             }
+            KeyCode::Char('n') => {
+                actions.push(AppAction::AnalyzeLoudness);
+            }
+            KeyCode::Char('m') => {
+                actions.push(AppAction::BuildMoodPlaylists);
+            }
+            KeyCode::Char('N') => {
+                actions.push(AppAction::ToggleNightMode);
+            }
+            KeyCode::Char('p') => {
+                // Turning it on previews the current selection right away
+                ui.preview_selected = None;
+                actions.push(AppAction::TogglePreviewMode);
+            }
+            KeyCode::Char('w') => {
+                ui.session_modal = Some(SessionModal::new(app));
+            }
+            KeyCode::Char('x') => {
+                ui.export_modal = Some(ExportModal::new());
+            }
+            KeyCode::Char('c') => {
+                let freed = ui.album_art_cache.bytes() + ui.backdrop_art.bytes();
+                ui.album_art_cache.clear();
+                ui.backdrop_art.clear();
+                ui.lyrics_pane.forget();
+                actions.push(AppAction::Notify(format!(
+                    "Cleared album art and lyrics caches ({:.1} MB)",
+                    freed as f64 / (1024.0 * 1024.0)
+                )));
+            }
             _ => {} // unknown chord, ignore
         }
         return actions;
@@ -284,6 +595,18 @@ pub fn handle_key_event(key: KeyEvent, app: &App, ui: &mut Ui) -> Vec<AppAction>
             actions.push(AppAction::PauseResume);
             return actions;
         }
+        (KeyModifiers::CONTROL, KeyCode::Char('n')) => {
+            actions.push(AppAction::NextAlbum);
+            return actions;
+        }
+        (KeyModifiers::CONTROL, KeyCode::Char('p')) => {
+            actions.push(AppAction::PrevAlbum);
+            return actions;
+        }
+        (_, KeyCode::Char('S')) => {
+            actions.push(AppAction::Stop);
+            return actions;
+        }
         (_, KeyCode::Char('n')) => {
             actions.push(AppAction::NextTrack);
             return actions;
@@ -300,12 +623,37 @@ pub fn handle_key_event(key: KeyEvent, app: &App, ui: &mut Ui) -> Vec<AppAction>
             actions.push(AppAction::VolumeDown);
             return actions;
         }
+        (KeyModifiers::SHIFT, KeyCode::Right) => {
+            actions.push(AppAction::SeekBy(app.config.long_seek_step));
+            return actions;
+        }
+        (KeyModifiers::SHIFT, KeyCode::Left) => {
+            actions.push(AppAction::SeekBy(-app.config.long_seek_step));
+            return actions;
+        }
         (_, KeyCode::Right) => {
-            actions.push(AppAction::SeekForward);
+            actions.push(AppAction::SeekBy(app.config.seek_step));
             return actions;
         }
         (_, KeyCode::Left) => {
-            actions.push(AppAction::SeekBackward);
+            actions.push(AppAction::SeekBy(-app.config.seek_step));
+            return actions;
+        }
+        // Chapter navigation for audiobooks, fixed jumps otherwise
+        (_, KeyCode::Char('>')) => {
+            actions.push(if app.playback.chapters.is_empty() {
+                AppAction::SeekBy(app.config.jump_step)
+            } else {
+                AppAction::NextChapter
+            });
+            return actions;
+        }
+        (_, KeyCode::Char('<')) => {
+            actions.push(if app.playback.chapters.is_empty() {
+                AppAction::SeekBy(-app.config.jump_step)
+            } else {
+                AppAction::PrevChapter
+            });
             return actions;
         }
         (_, KeyCode::Char('s')) => {
@@ -317,23 +665,92 @@ pub fn handle_key_event(key: KeyEvent, app: &App, ui: &mut Ui) -> Vec<AppAction>
             return actions;
         }
         (_, KeyCode::Char('b')) => {
-            // Only open if a track is playing
-            if app.queue.current_index.is_some() {
+            // Selected track, else the playing one
+            if let Some(path) = selected_track_path(app, ui) {
+                ui.playlist_modal_track = Some(path);
                 ui.show_playlist_modal = true;
                 ui.playlist_modal_selected = 0;
             }
             return actions;
         }
+        (_, KeyCode::Char('R')) => {
+            // Radio seeded from the selected track, else the playing one
+            if let Some(path) = selected_track_path(app, ui) {
+                actions.push(AppAction::StartRadio(path));
+            }
+            return actions;
+        }
+        (_, KeyCode::Char('B')) => {
+            // Selected artist/album/folder in the library pane
+            let selection = match (app.focus, app.tab) {
+                (FocusedPane::Library, Tab::Queue) => ui.library_pane.selection_tracks(app),
+                (FocusedPane::Library, Tab::Artists) => ui.artists_pane.selection_tracks(app),
+                (FocusedPane::Library, Tab::Albums) => ui.albums_pane.selection_tracks(app),
+                _ => None,
+            };
+            if let Some((name, indices)) = selection.filter(|(_, t)| !t.is_empty()) {
+                ui.playlist_modal_target = Some((name, app.library.paths_of(&indices)));
+                ui.show_playlist_modal = true;
+                ui.playlist_modal_selected = 0;
+            }
+            return actions;
+        }
+        (_, KeyCode::Char('T')) => {
+            // Batch-edit tags of the selected album/artist/genre/folder
+            if app.config.read_only {
+                actions.push(AppAction::Notify(READ_ONLY_MESSAGE.to_string()));
+                return actions;
+            }
+            let selection = match (app.focus, app.tab) {
+                (FocusedPane::Library, Tab::Queue) => ui.library_pane.selection_tracks(app),
+                (FocusedPane::Library, Tab::Directories) => ui.dir_browser_pane.selection_tracks(),
+                (FocusedPane::Library, Tab::Artists) => ui.artists_pane.selection_tracks(app),
+                (FocusedPane::Library, Tab::Albums) => ui.albums_pane.selection_tracks(app),
+                (FocusedPane::Library, Tab::Genre) => ui.genre_pane.selection_tracks(app),
+                _ => None,
+            };
+            if let Some((name, tracks)) = selection.filter(|(_, t)| !t.is_empty()) {
+                ui.tag_modal = Some(TagModal::new(name, tracks));
+            }
+            return actions;
+        }
+        (_, KeyCode::Char('y')) | (_, KeyCode::Char('O')) => {
+            let Some(path) = selected_track_path(app, ui) else { return actions };
+            if key.code == KeyCode::Char('y') {
+                actions.push(AppAction::CopyPath(path));
+            } else {
+                ui.dir_browser_pane.reveal(&path, app);
+                actions.push(AppAction::SwitchTab(Tab::Directories));
+                actions.push(AppAction::FocusPane(FocusedPane::Library));
+            }
+            return actions;
+        }
         (_, KeyCode::Char('p')) => {
             ui.info_view = ui.info_view.next();
             return actions;
         }
+        (_, KeyCode::Char('P')) => {
+            // Again, or with nothing selected, stops the preview
+            match preview_target(app, ui) {
+                Some(path) => actions.push(AppAction::Preview(path)),
+                None => actions.push(AppAction::StopPreview),
+            }
+            return actions;
+        }
         (_, KeyCode::Tab) => {
-            actions.push(AppAction::FocusNext);
+            actions.push(step_focus(app, ui, true));
             return actions;
         }
         (KeyModifiers::SHIFT, KeyCode::BackTab) => {
-            actions.push(AppAction::FocusPrev);
+            actions.push(step_focus(app, ui, false));
+            return actions;
+        }
+        (_, KeyCode::Char('L')) => {
+            // Cycle layout presets; focus moves off a pane that disappears
+            ui.layout_preset = ui.layout_preset.next();
+            if !ui.layout_preset.shows(app.focus) {
+                actions.push(AppAction::FocusPane(FocusedPane::Playlist));
+            }
             return actions;
         }
         // Tab switching with number keys
@@ -365,18 +782,33 @@ pub fn handle_key_event(key: KeyEvent, app: &App, ui: &mut Ui) -> Vec<AppAction>
             actions.push(AppAction::SwitchTab(Tab::Playlists));
             return actions;
         }
+        (_, KeyCode::Char('z')) => {
+            actions.push(AppAction::PlayRandomEntry);
+            return actions;
+        }
+        (_, KeyCode::Char('0')) => {
+            actions.push(AppAction::ReplayTrack);
+            return actions;
+        }
         // h/l for pane focus
         (_, KeyCode::Char('h')) => {
-            actions.push(AppAction::FocusPrev);
+            actions.push(step_focus(app, ui, false));
             return actions;
         }
         (_, KeyCode::Char('l')) => {
-            actions.push(AppAction::FocusNext);
+            actions.push(step_focus(app, ui, true));
             return actions;
         }
         _ => {}
     }
 
+    // 'a' in the library: insert the selection after the queue's selected row
+    // (marked in the queue) instead of replacing the queue
+    if app.focus == FocusedPane::Library && key.code == KeyCode::Char('a') {
+        actions.extend(insert_selection(app.tab, app, ui));
+        return actions;
+    }
+
     // Route to focused pane
     let action = match app.focus {
         FocusedPane::Library => match app.tab {
@@ -400,7 +832,7 @@ pub fn handle_key_event(key: KeyEvent, app: &App, ui: &mut Ui) -> Vec<AppAction>
 
     // Auto-focus to Queue pane when adding tracks from Library
     if let Some(ref a) = action {
-        if matches!(a, AppAction::AddToQueue(_)) && app.focus == FocusedPane::Library {
+        if matches!(a, AppAction::AddToQueue(_) | AppAction::QueuePlaylist(_)) && app.focus == FocusedPane::Library {
             actions.push(AppAction::FocusPane(FocusedPane::Playlist));
         }
     }
@@ -412,6 +844,123 @@ pub fn handle_key_event(key: KeyEvent, app: &App, ui: &mut Ui) -> Vec<AppAction>
     actions
 }
 
+/// Selected queue row or directory file, else the playing track
+fn selected_track_path(app: &App, ui: &Ui) -> Option<PathBuf> {
+    match (app.focus, app.tab) {
+        (FocusedPane::Playlist, _) => app.queue.tracks.get(app.queue.selected_index).cloned(),
+        (FocusedPane::Library, Tab::Directories) => ui.dir_browser_pane.selected_track()
+            .and_then(|i| app.library.tracks.get(i))
+            .map(|t| t.path.clone()),
+        (FocusedPane::Library, Tab::Queue) => ui.library_pane.selected_track(app)
+            .and_then(|i| app.library.tracks.get(i))
+            .map(|t| t.path.clone()),
+        _ => None,
+    }
+    .or_else(|| app.queue.current_index.and_then(|i| app.queue.tracks.get(i).cloned()))
+}
+
+/// Track a preview plays: the search result, queue row, Directories file or album
+/// track under the cursor
+pub fn preview_target(app: &App, ui: &Ui) -> Option<PathBuf> {
+    if ui.show_search_modal {
+        return ui
+            .search_modal_results
+            .get(ui.search_modal_selected)
+            .and_then(|&i| app.library.tracks.get(i))
+            .map(|t| t.path.clone());
+    }
+    match (app.focus, app.tab) {
+        (FocusedPane::Playlist, _) => app.queue.tracks.get(app.queue.selected_index).cloned(),
+        (FocusedPane::Library, Tab::Directories) => ui
+            .dir_browser_pane
+            .selected_track()
+            .and_then(|i| app.library.tracks.get(i))
+            .map(|t| t.path.clone()),
+        (FocusedPane::Library, Tab::Queue) => ui
+            .library_pane
+            .selected_track(app)
+            .and_then(|i| app.library.tracks.get(i))
+            .map(|t| t.path.clone()),
+        _ => None,
+    }
+}
+
+/// Focus the next/previous pane, skipping ones the layout preset hides
+fn step_focus(app: &App, ui: &Ui, forward: bool) -> AppAction {
+    let mut pane = app.focus;
+    loop {
+        pane = if forward { pane.next() } else { pane.prev() };
+        // The queue is always shown, so this ends
+        if ui.layout_preset.shows(pane) {
+            return AppAction::FocusPane(pane);
+        }
+    }
+}
+
+/// What `a` does for a library tab: its selection, to insert after the queue's selected row
+fn insert_selection(tab: Tab, app: &App, ui: &mut Ui) -> Option<AppAction> {
+    let enter_key = KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE);
+    let activate_action = match tab {
+        Tab::Queue => ui.library_pane.handle_key(enter_key, app),
+        // Enter on a folder would navigate into it
+        Tab::Directories => ui.dir_browser_pane.selected_track().map(|i| AppAction::AddToQueue(vec![i])),
+        Tab::Artists => ui.artists_pane.handle_key(enter_key, app),
+        Tab::Albums => ui.albums_pane.handle_key(enter_key, app),
+        Tab::Genre => ui.genre_pane.handle_key(enter_key, app),
+        Tab::Format => ui.format_pane.handle_key(enter_key, app),
+        Tab::Playlists => return Some(ui.playlists_pane.selection_paths(app))
+            .filter(|paths| !paths.is_empty())
+            .map(AppAction::InsertIntoQueue),
+    };
+    let paths = match activate_action {
+        Some(AppAction::AddToQueue(indices)) => app.library.paths_of(&indices),
+        Some(AppAction::QueuePlaylist(idx)) => app.playlists.get(idx).map(|pl| pl.tracks.clone()).unwrap_or_default(),
+        _ => Vec::new(),
+    };
+    (!paths.is_empty()).then_some(AppAction::InsertIntoQueue(paths))
+}
+
+/// Bracketed paste: into whichever text input is active, on one line
+pub fn handle_paste(text: &str, app: &App, ui: &mut Ui) {
+    let text: String = text.trim_end_matches(['\r', '\n'])
+        .chars()
+        .map(|c| if c == '\n' || c == '\r' || c == '\t' { ' ' } else { c })
+        .filter(|c| !c.is_control())
+        .collect();
+    if text.is_empty() {
+        return;
+    }
+    if let Some(modal) = ui.tag_modal.as_mut() {
+        if matches!(modal.step, TagStep::Input(_)) {
+            modal.input.insert_str(&text);
+        }
+    } else if let Some(modal) = ui.export_modal.as_mut() {
+        modal.path.insert_str(&text);
+    } else if let Some(modal) = ui.session_modal.as_mut() {
+        if let Some(input) = modal.naming.as_mut() {
+            input.insert_str(&text);
+        }
+    } else if ui.show_playlist_modal {
+        if ui.playlist_modal_mode != PlaylistModalMode::List {
+            ui.playlist_modal_input.insert_str(&text);
+        }
+    } else if ui.show_search_modal {
+        ui.search_modal_input.insert_str(&text);
+        ui.search_history_pos = None;
+        ui.search_completion = None;
+        ui.search_input_changed();
+    } else if app.focus == FocusedPane::Lyrics {
+        ui.lyrics_pane.paste(&text);
+    } else if app.focus == FocusedPane::Library {
+        match app.tab {
+            Tab::Artists => ui.artists_pane.filter.paste(&text),
+            Tab::Albums => ui.albums_pane.filter.paste(&text),
+            Tab::Genre => ui.genre_pane.filter.paste(&text),
+            _ => {}
+        }
+    }
+}
+
 pub fn handle_mouse_event(
     mouse: MouseEvent,
     app: &App,
@@ -419,7 +968,7 @@ pub fn handle_mouse_event(
     terminal_area: ratatui::layout::Rect,
 ) -> Vec<AppAction> {
     let mut actions = Vec::new();
-    let areas = LayoutAreas::compute(terminal_area, ui.pane_widths, ui.right_split);
+    let areas = LayoutAreas::compute(terminal_area, ui.pane_widths, ui.right_split, ui.layout_preset);
 
     let x = mouse.column;
     let y = mouse.row;
@@ -446,47 +995,37 @@ pub fn handle_mouse_event(
         }
 
         match mouse.kind {
-            MouseEventKind::Down(MouseButton::Left) => {
-                if in_results && !ui.search_modal_results.is_empty() {
-                    let clicked = ui.search_modal_scroll + (y - ra.y) as usize;
-                    if clicked < ui.search_modal_results.len() {
-                        // Double-click detection
-                        let is_double = if let Some((last_time, _lx, ly)) = ui.last_click {
-                            last_time.elapsed() < Duration::from_millis(400) && ly == y
-                        } else {
-                            false
-                        };
-                        ui.last_click = Some((Instant::now(), x, y));
-
-                        if is_double {
-                            // Double-click: select and confirm (add to queue)
-                            let track_idx = ui.search_modal_results[clicked];
-                            actions.push(AppAction::AddToQueue(vec![track_idx]));
-                            ui.show_search_modal = false;
-                            ui.search_modal_input.clear();
-                            ui.search_modal_results.clear();
-                            ui.search_modal_selected = 0;
-                            ui.search_modal_scroll = 0;
-                        } else {
-                            // Single click: select
-                            ui.search_modal_selected = clicked;
-                        }
+            MouseEventKind::Down(MouseButton::Left) if in_results && !ui.search_modal_results.is_empty() => {
+                let clicked = ui.search_modal_scroll + (y - ra.y) as usize;
+                if clicked < ui.search_modal_results.len() {
+                    // Double-click detection
+                    let is_double = if let Some((last_time, _lx, ly)) = ui.last_click {
+                        last_time.elapsed() < Duration::from_millis(400) && ly == y
+                    } else {
+                        false
+                    };
+                    ui.last_click = Some((Instant::now(), x, y));
+
+                    if is_double {
+                        // Double-click: select and confirm (add to queue)
+                        let track_idx = ui.search_modal_results[clicked];
+                        actions.push(AppAction::AddToQueue(vec![track_idx]));
+                        ui.close_search_modal();
+                    } else {
+                        // Single click: select
+                        ui.search_modal_selected = clicked;
                     }
                 }
             }
-            MouseEventKind::ScrollDown => {
-                if in_results && !ui.search_modal_results.is_empty() {
-                    let max_scroll = ui.search_modal_results.len()
-                        .saturating_sub(ui.search_modal_result_height);
-                    if ui.search_modal_scroll < max_scroll {
-                        ui.search_modal_scroll += 1;
-                    }
+            MouseEventKind::ScrollDown if in_results && !ui.search_modal_results.is_empty() => {
+                let max_scroll = ui.search_modal_results.len()
+                    .saturating_sub(ui.search_modal_result_height);
+                if ui.search_modal_scroll < max_scroll {
+                    ui.search_modal_scroll += 1;
                 }
             }
-            MouseEventKind::ScrollUp => {
-                if in_results && ui.search_modal_scroll > 0 {
-                    ui.search_modal_scroll -= 1;
-                }
+            MouseEventKind::ScrollUp if in_results && ui.search_modal_scroll > 0 => {
+                ui.search_modal_scroll -= 1;
             }
             _ => {}
         }
@@ -494,7 +1033,14 @@ pub fn handle_mouse_event(
     }
 
     // Block all mouse events when any other modal is open
-    if ui.show_about_modal || ui.show_help_modal || ui.show_playlist_modal {
+    if ui.show_about_modal
+        || ui.show_help_modal
+        || ui.show_playlist_modal
+        || ui.stats_modal.is_some()
+        || ui.tag_modal.is_some()
+        || ui.session_modal.is_some()
+        || ui.export_modal.is_some()
+    {
         return actions;
     }
 
@@ -519,18 +1065,20 @@ pub fn handle_mouse_event(
 
     // --- Tab hover highlight ---
     if y >= areas.tab_bar.y && y < areas.tab_bar.y + areas.tab_bar.height {
-        ui.hovered_tab = tab_bar::tab_hit_test(areas.tab_bar, x);
+        ui.hovered_tab = tab_bar::tab_hit_test(areas.tab_bar, app.tab, x);
     } else {
         ui.hovered_tab = None;
     }
 
-    // --- Focus switching on hover (any mouse event in a pane) ---
-    if in_library && app.focus != FocusedPane::Library {
-        actions.push(AppAction::FocusPane(FocusedPane::Library));
-    } else if in_playlist && app.focus != FocusedPane::Playlist {
-        actions.push(AppAction::FocusPane(FocusedPane::Playlist));
-    } else if in_lyrics && app.focus != FocusedPane::Lyrics {
-        actions.push(AppAction::FocusPane(FocusedPane::Lyrics));
+    // --- Focus switching on hover (any mouse event in a pane), or on click only ---
+    if app.config.focus_follows_mouse || matches!(mouse.kind, MouseEventKind::Down(_)) {
+        if in_library && app.focus != FocusedPane::Library {
+            actions.push(AppAction::FocusPane(FocusedPane::Library));
+        } else if in_playlist && app.focus != FocusedPane::Playlist {
+            actions.push(AppAction::FocusPane(FocusedPane::Playlist));
+        } else if in_lyrics && app.focus != FocusedPane::Lyrics {
+            actions.push(AppAction::FocusPane(FocusedPane::Lyrics));
+        }
     }
 
     // --- Border drag resize ---
@@ -593,9 +1141,31 @@ pub fn handle_mouse_event(
 
     // --- Handle specific event kinds ---
     match mouse.kind {
+        // Middle-click a tab: insert its selection after the queue's selected row, like `a`
+        MouseEventKind::Down(MouseButton::Middle) if y >= areas.tab_bar.y && y < areas.tab_bar.y + areas.tab_bar.height => {
+            if let Some(tab_idx) = tab_bar::tab_hit_test(areas.tab_bar, app.tab, x) {
+                actions.extend(insert_selection(Tab::from_index(tab_idx), app, ui));
+            }
+            return actions;
+        }
+        // Right-click in the library: back out a level, like a file manager
+        MouseEventKind::Down(MouseButton::Right) if in_library => {
+            let back = KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE);
+            let up = KeyEvent::new(KeyCode::Backspace, KeyModifiers::NONE);
+            let action = match app.tab {
+                Tab::Directories => ui.dir_browser_pane.handle_key(up, app),
+                Tab::Playlists => ui.playlists_pane.handle_key(back, app),
+                Tab::Artists => ui.artists_pane.handle_key(back, app),
+                Tab::Albums => ui.albums_pane.handle_key(back, app),
+                Tab::Genre => ui.genre_pane.handle_key(back, app),
+                Tab::Queue | Tab::Format => None,
+            };
+            actions.extend(action);
+            return actions;
+        }
         MouseEventKind::Down(MouseButton::Left) => {
-            // Border drag start detection
-            if in_dashboard_y {
+            // Border drag start detection (widths only apply to the full layout)
+            if in_dashboard_y && ui.layout_preset == LayoutPreset::All {
                 if x.abs_diff(border0_x) <= 1 {
                     ui.dragging_border = Some(0);
                     return actions;
@@ -625,14 +1195,52 @@ pub fn handle_mouse_event(
 
             // Tab bar click
             if y >= areas.tab_bar.y && y < areas.tab_bar.y + areas.tab_bar.height {
-                if let Some(tab_idx) = tab_bar::tab_hit_test(areas.tab_bar, x) {
+                if let Some(tab_idx) = tab_bar::tab_hit_test(areas.tab_bar, app.tab, x) {
                     actions.push(AppAction::SwitchTab(Tab::from_index(tab_idx)));
                 }
                 return actions;
             }
 
+            // Status bar: play state, bookmark star, shuffle and repeat icons
+            if y >= areas.status_bar.y && y < areas.status_bar.y + areas.status_bar.height {
+                match status_bar::status_hit_test(areas.status_bar, app, x, y) {
+                    Some(status_bar::StatusHit::PlayPause) => actions.push(AppAction::PauseResume),
+                    Some(status_bar::StatusHit::Shuffle) => actions.push(AppAction::ToggleShuffle),
+                    Some(status_bar::StatusHit::Repeat) => actions.push(AppAction::CycleRepeat),
+                    Some(status_bar::StatusHit::Bookmark) => {
+                        // Toggle the playing track in Bookmarks
+                        let current = app.queue.current_index.and_then(|qi| app.queue.tracks.get(qi));
+                        if let (Some(path), Some(bookmarks)) = (current, app.playlists.first()) {
+                            let path = path.clone();
+                            if bookmarks.tracks.contains(&path) {
+                                actions.push(AppAction::RemoveFromPlaylist { playlist_idx: 0, path });
+                            } else {
+                                actions.push(AppAction::AddToPlaylist { playlist_idx: 0, path });
+                            }
+                        }
+                    }
+                    None => {}
+                }
+                return actions;
+            }
+
             // Progress bar click
             if y >= areas.progress_bar.y && y < areas.progress_bar.y + areas.progress_bar.height {
+                match progress_bar::transport_hit_test(areas.progress_bar, x) {
+                    Some(progress_bar::TransportHit::Prev) => {
+                        actions.push(AppAction::PrevTrack);
+                        return actions;
+                    }
+                    Some(progress_bar::TransportHit::PlayPause) => {
+                        actions.push(AppAction::PauseResume);
+                        return actions;
+                    }
+                    Some(progress_bar::TransportHit::Next) => {
+                        actions.push(AppAction::NextTrack);
+                        return actions;
+                    }
+                    None => {}
+                }
                 let gauge_area = progress_bar::progress_gauge_area(areas.progress_bar);
                 if x >= gauge_area.x && x < gauge_area.x + gauge_area.width {
                     let ratio = (x - gauge_area.x) as f64 / gauge_area.width as f64;
@@ -648,7 +1256,7 @@ pub fn handle_mouse_event(
                     .borders(ratatui::widgets::Borders::ALL);
                 let inner = block.inner(areas.playlist);
                 if y >= inner.y && y < inner.y + inner.height {
-                    let clicked = ui.queue_pane.scroll_offset + (y - inner.y) as usize;
+                    let clicked = ui.queue_pane.list.scroll_offset + (y - inner.y) as usize;
                     if clicked < app.queue.tracks.len() {
                         actions.push(AppAction::PlayQueueIndex(clicked));
                         return actions;
@@ -656,7 +1264,8 @@ pub fn handle_mouse_event(
                 }
             }
 
-            // Single click in library → select + activate (Enter)
+            // Click in library → select; double-click (or single with
+            // `single_click_activates`) → activate like Enter
             if in_library {
                 // First, route mouse to pane for selection update
                 let _sel_action = match app.tab {
@@ -668,6 +1277,9 @@ pub fn handle_mouse_event(
                     Tab::Format => ui.format_pane.handle_mouse(mouse, areas.library, app),
                     Tab::Playlists => ui.playlists_pane.handle_mouse(mouse, areas.library, app),
                 };
+                if !is_double_click && !app.config.single_click_activates {
+                    return actions;
+                }
                 // Then, trigger Enter action to activate the clicked item
                 let enter_key = KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE);
                 let activate_action = match app.tab {
@@ -680,7 +1292,7 @@ pub fn handle_mouse_event(
                     Tab::Playlists => ui.playlists_pane.handle_key(enter_key, app),
                 };
                 if let Some(action) = activate_action {
-                    if matches!(action, AppAction::AddToQueue(_)) {
+                    if matches!(action, AppAction::AddToQueue(_) | AppAction::QueuePlaylist(_)) {
                         actions.push(AppAction::FocusPane(FocusedPane::Playlist));
                     }
                     actions.push(action);
@@ -694,7 +1306,7 @@ pub fn handle_mouse_event(
                     .borders(ratatui::widgets::Borders::ALL);
                 let inner = block.inner(areas.playlist);
                 if y >= inner.y && y < inner.y + inner.height {
-                    let clicked = ui.queue_pane.scroll_offset + (y - inner.y) as usize;
+                    let clicked = ui.queue_pane.list.scroll_offset + (y - inner.y) as usize;
                     if clicked < app.queue.tracks.len() {
                         actions.push(AppAction::SetQueueSelection(clicked));
                     }
@@ -739,14 +1351,14 @@ pub fn handle_mouse_event(
 
 /// Clear all hover_row state across all panes
 fn clear_all_hovers(ui: &mut Ui) {
-    ui.queue_pane.hover_row = None;
-    ui.library_pane.hover_row = None;
-    ui.dir_browser_pane.hover_row = None;
-    ui.artists_pane.hover_row = None;
-    ui.albums_pane.hover_row = None;
-    ui.genre_pane.hover_row = None;
-    ui.format_pane.hover_row = None;
-    ui.playlists_pane.hover_row = None;
+    ui.queue_pane.list.hover_row = None;
+    ui.library_pane.list.hover_row = None;
+    ui.dir_browser_pane.list.hover_row = None;
+    ui.artists_pane.list.hover_row = None;
+    ui.albums_pane.list.hover_row = None;
+    ui.genre_pane.list.hover_row = None;
+    ui.format_pane.list.hover_row = None;
+    ui.playlists_pane.list.hover_row = None;
 }
 
 /// Update hover_row state for panes based on mouse position
@@ -768,9 +1380,9 @@ fn update_hover(
         if x >= inner.x && x < inner.x + inner.width
             && y >= inner.y && y < inner.y + inner.height
         {
-            let row = ui.queue_pane.scroll_offset + (y - inner.y) as usize;
+            let row = ui.queue_pane.list.scroll_offset + (y - inner.y) as usize;
             if row < app.queue.tracks.len() {
-                ui.queue_pane.hover_row = Some(row);
+                ui.queue_pane.list.hover_row = Some(row);
             }
         }
     } else if in_library {
@@ -783,32 +1395,32 @@ fn update_hover(
             let visual_row = (y - inner.y) as usize;
             match app.tab {
                 Tab::Queue => {
-                    let row = ui.library_pane.scroll_offset + visual_row;
-                    ui.library_pane.hover_row = Some(row);
+                    let row = ui.library_pane.list.scroll_offset + visual_row;
+                    ui.library_pane.list.hover_row = Some(row);
                 }
                 Tab::Directories => {
-                    let row = ui.dir_browser_pane.scroll_offset + visual_row;
-                    ui.dir_browser_pane.hover_row = Some(row);
+                    let row = ui.dir_browser_pane.list.scroll_offset + visual_row;
+                    ui.dir_browser_pane.list.hover_row = Some(row);
                 }
                 Tab::Artists => {
-                    let row = ui.artists_pane.scroll_offset + visual_row;
-                    ui.artists_pane.hover_row = Some(row);
+                    let row = ui.artists_pane.list.scroll_offset + visual_row;
+                    ui.artists_pane.list.hover_row = Some(row);
                 }
                 Tab::Albums => {
-                    let row = ui.albums_pane.scroll_offset + visual_row;
-                    ui.albums_pane.hover_row = Some(row);
+                    let row = ui.albums_pane.list.scroll_offset + visual_row;
+                    ui.albums_pane.list.hover_row = Some(row);
                 }
                 Tab::Genre => {
-                    let row = ui.genre_pane.scroll_offset + visual_row;
-                    ui.genre_pane.hover_row = Some(row);
+                    let row = ui.genre_pane.list.scroll_offset + visual_row;
+                    ui.genre_pane.list.hover_row = Some(row);
                 }
                 Tab::Format => {
-                    let row = ui.format_pane.scroll_offset + visual_row;
-                    ui.format_pane.hover_row = Some(row);
+                    let row = ui.format_pane.list.scroll_offset + visual_row;
+                    ui.format_pane.list.hover_row = Some(row);
                 }
                 Tab::Playlists => {
-                    let row = ui.playlists_pane.scroll_offset + visual_row;
-                    ui.playlists_pane.hover_row = Some(row);
+                    let row = ui.playlists_pane.list.scroll_offset + visual_row;
+                    ui.playlists_pane.list.hover_row = Some(row);
                 }
             }
         }
@@ -821,11 +1433,19 @@ fn update_hover(
 pub fn refresh_hover(app: &App, ui: &mut Ui, terminal_area: ratatui::layout::Rect) -> Vec<AppAction> {
     let mut actions = Vec::new();
     // Skip hover updates when any modal is open
-    if ui.show_about_modal || ui.show_help_modal || ui.show_search_modal || ui.show_playlist_modal {
+    if ui.show_about_modal
+        || ui.show_help_modal
+        || ui.show_search_modal
+        || ui.show_playlist_modal
+        || ui.stats_modal.is_some()
+        || ui.tag_modal.is_some()
+        || ui.session_modal.is_some()
+        || ui.export_modal.is_some()
+    {
         return actions;
     }
     if let Some((x, y)) = ui.mouse_pos {
-        let areas = LayoutAreas::compute(terminal_area, ui.pane_widths, ui.right_split);
+        let areas = LayoutAreas::compute(terminal_area, ui.pane_widths, ui.right_split, ui.layout_preset);
         let in_library = x >= areas.library.x
             && x < areas.library.x + areas.library.width
             && y >= areas.library.y
@@ -842,18 +1462,20 @@ pub fn refresh_hover(app: &App, ui: &mut Ui, terminal_area: ratatui::layout::Rec
 
         // Tab hover highlight
         if y >= areas.tab_bar.y && y < areas.tab_bar.y + areas.tab_bar.height {
-            ui.hovered_tab = tab_bar::tab_hit_test(areas.tab_bar, x);
+            ui.hovered_tab = tab_bar::tab_hit_test(areas.tab_bar, app.tab, x);
         } else {
             ui.hovered_tab = None;
         }
 
         // Focus switching on hover
-        if in_library && app.focus != FocusedPane::Library {
-            actions.push(AppAction::FocusPane(FocusedPane::Library));
-        } else if in_playlist && app.focus != FocusedPane::Playlist {
-            actions.push(AppAction::FocusPane(FocusedPane::Playlist));
-        } else if in_lyrics && app.focus != FocusedPane::Lyrics {
-            actions.push(AppAction::FocusPane(FocusedPane::Lyrics));
+        if app.config.focus_follows_mouse {
+            if in_library && app.focus != FocusedPane::Library {
+                actions.push(AppAction::FocusPane(FocusedPane::Library));
+            } else if in_playlist && app.focus != FocusedPane::Playlist {
+                actions.push(AppAction::FocusPane(FocusedPane::Playlist));
+            } else if in_lyrics && app.focus != FocusedPane::Lyrics {
+                actions.push(AppAction::FocusPane(FocusedPane::Lyrics));
+            }
         }
     }
     actions
@@ -901,23 +1523,12 @@ fn resize_pane(ui: &mut Ui, focus: FocusedPane, delta: i16) {
 }
 
 /// Update queue selection based on keyboard in playlist focus
-pub fn update_queue_selection(app: &mut App, key: KeyEvent) {
+pub fn update_queue_selection(app: &mut App, list: &ScrollableList, key: KeyEvent) {
     let count = app.queue.tracks.len();
     if count == 0 {
         return;
     }
-
-    match key.code {
-        KeyCode::Char('j') | KeyCode::Down => {
-            if app.queue.selected_index < count - 1 {
-                app.queue.selected_index += 1;
-            }
-        }
-        KeyCode::Char('k') | KeyCode::Up => {
-            if app.queue.selected_index > 0 {
-                app.queue.selected_index -= 1;
-            }
-        }
-        _ => {}
+    if let Some(delta) = list.nav_delta(key.code) {
+        app.queue.selected_index = app.queue.selected_index.saturating_add_signed(delta).min(count - 1);
     }
 }
@@ -0,0 +1,100 @@
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::library::track::Track;
+
+/// A listen counts once this much of the track was heard (or half of a shorter track)
+const MIN_LISTEN_SECS: f64 = 30.0;
+
+/// One listen, as stored in `history.jsonl`. Tags are copied so stats survive
+/// files being renamed or removed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Play {
+    pub path: PathBuf,
+    pub title: String,
+    pub artist: String,
+    pub album: String,
+    /// Upper-case file extension ("FLAC")
+    pub format: String,
+    /// Unix time the track started
+    pub started: i64,
+    /// Seconds actually played (seeking and pauses don't count)
+    pub listened: f64,
+    pub duration: f64,
+}
+
+/// A listen in progress; `listened` grows with position updates while playing
+#[derive(Debug)]
+pub struct Listen {
+    pub play: Play,
+    last_pos: f64,
+}
+
+impl Listen {
+    pub fn start(track: &Track) -> Self {
+        let started = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or_default();
+        Self {
+            play: Play {
+                path: track.path.clone(),
+                title: track.title.clone(),
+                artist: track.display_artist().to_string(),
+                album: track.display_album().to_string(),
+                format: track.path.extension()
+                    .map(|e| e.to_string_lossy().to_uppercase())
+                    .unwrap_or_default(),
+                started,
+                listened: 0.0,
+                duration: track.duration.as_secs_f64(),
+            },
+            last_pos: 0.0,
+        }
+    }
+
+    /// Count playback progress; jumps (seeks) only move the reference point
+    pub fn advance(&mut self, position: f64) {
+        let delta = position - self.last_pos;
+        if delta > 0.0 && delta <= 2.0 {
+            self.play.listened += delta;
+        }
+        self.last_pos = position;
+    }
+
+    /// The finished play, if enough of it was heard to count
+    pub fn finish(self) -> Option<Play> {
+        let needed = MIN_LISTEN_SECS.min(self.play.duration / 2.0);
+        (self.play.listened >= needed && self.play.listened > 0.0).then_some(self.play)
+    }
+}
+
+fn history_path() -> PathBuf {
+    let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
+    PathBuf::from(home).join(".config/ommp/history.jsonl")
+}
+
+/// Append one play; the log is one JSON object per line so writes never rewrite old data
+pub fn append(play: &Play) -> anyhow::Result<()> {
+    let path = history_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", serde_json::to_string(play)?)?;
+    Ok(())
+}
+
+/// All recorded plays, oldest first; unreadable lines are skipped
+pub fn load() -> Vec<Play> {
+    fs::read_to_string(history_path())
+        .map(|data| {
+            data.lines()
+                .filter_map(|line| serde_json::from_str(line).ok())
+                .collect()
+        })
+        .unwrap_or_default()
+}
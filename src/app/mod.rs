@@ -1,49 +1,140 @@
+pub mod export;
 pub mod handler;
+pub mod history;
+pub mod now_playing;
 pub mod persist;
+pub mod shuffle;
 pub mod state;
+pub mod stats;
 
-use std::collections::HashMap;
-use std::path::PathBuf;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use crossbeam_channel::Sender;
 
 use crate::audio::{AudioEngine, PlayerCommand};
+use crate::config::Config;
 use crate::event::Event;
-use crate::library::Library;
+use crate::library::track::Track;
+use crate::library::{chapters, loudness, mood, organize, radio, tag_edit, Album, Library};
+use crate::localtime;
 use state::*;
 
+/// How long status bar feedback and the shuffle / repeat overlay stay visible
+const STATUS_MESSAGE_TIMEOUT: Duration = Duration::from_secs(3);
+const MODE_OVERLAY_TIMEOUT: Duration = Duration::from_secs(1);
+/// A long track's position is remembered once it's past this many seconds
+const RESUME_MIN_POSITION: f64 = 10.0;
+/// Measured loudness is written out every this many tracks while analysis runs
+const LOUDNESS_SAVE_EVERY: usize = 50;
+/// Shown when something would write to the music directory in read-only mode
+pub const READ_ONLY_MESSAGE: &str = "Read-only mode: the music directory isn't modified";
+/// Radio adds this many tracks at a time, once fewer than `RADIO_LOW` are left to play
+const RADIO_BATCH: usize = 15;
+const RADIO_LOW: usize = 3;
+/// Radio doesn't pick a track again while it's among the last this many queued
+const RADIO_MEMORY: usize = 100;
+/// Previews play at this fraction of the main volume
+const PREVIEW_VOLUME: f32 = 0.6;
+
 #[derive(Debug, Clone)]
 pub enum AppAction {
     Quit,
     PlayTrack(usize),
     PauseResume,
+    /// Stop playback and rewind, keeping the queue and its current entry
+    Stop,
     NextTrack,
     PrevTrack,
+    /// Play the current queue entry again from 0:00
+    ReplayTrack,
+    /// Play a random queue entry other than the current one, leaving shuffle alone
+    PlayRandomEntry,
+    /// Jump to the first queue entry of the next album in queue order
+    NextAlbum,
+    /// Jump to the first queue entry of the album before the playing one
+    PrevAlbum,
     SetVolume(f32),
     VolumeUp,
     VolumeDown,
     Seek(f64),
-    SeekForward,
-    SeekBackward,
+    /// Seek relative to the current position (negative = backward)
+    SeekBy(f64),
+    NextChapter,
+    /// Restart the current chapter, or go to the previous one near its start
+    PrevChapter,
     ToggleShuffle,
     CycleRepeat,
     SwitchTab(Tab),
-    FocusNext,
-    FocusPrev,
     FocusPane(FocusedPane),
     AddToQueue(Vec<usize>),
+    /// Insert tracks after the queue's selected row, keeping the rest of the queue
+    InsertIntoQueue(Vec<PathBuf>),
+    /// Insert tracks right after the playing one
+    QueueNext(Vec<PathBuf>),
+    /// Add tracks at the end of the queue
+    AppendToQueue(Vec<PathBuf>),
+    /// Replace the queue with a playlist's entries, unavailable ones included
+    QueuePlaylist(usize),
+    /// Queue a playlist and start it at entry `pos`
+    PlayPlaylistFrom { playlist_idx: usize, pos: usize },
     ClearQueue,
+    /// Drop repeated entries, keeping each track's first occurrence
+    DedupeQueue,
     RemoveFromQueue(usize),
     PlayQueueIndex(usize),
     UpdatePosition { position_secs: f64, duration_secs: f64 },
     TrackFinished,
     SetQueueSelection(usize),
-    AddToPlaylist { playlist_idx: usize, track_idx: usize },
-    RemoveFromPlaylist { playlist_idx: usize, track_idx: usize },
+    AddToPlaylist { playlist_idx: usize, path: PathBuf },
+    RemoveFromPlaylist { playlist_idx: usize, path: PathBuf },
+    /// Remove entries by position, from the playlist contents view
+    RemovePlaylistEntries { playlist_idx: usize, positions: Vec<usize> },
+    /// Move the `len` entries at `start` so the block begins at `to`
+    MovePlaylistEntries { playlist_idx: usize, start: usize, len: usize, to: usize },
+    /// Add a whole album/artist/folder; tracks already there are skipped unless duplicates are allowed
+    AddTracksToPlaylist { playlist_idx: usize, paths: Vec<PathBuf> },
     CreatePlaylist(String),
     DeletePlaylist(usize),
     RenamePlaylist { idx: usize, name: String },
     LibrarySync,
+    /// Toggle whether a playlist accepts the same track more than once
+    TogglePlaylistDuplicates(usize),
+    /// Run a library search on a worker thread; results come back as Event::SearchResults
+    Search { query: String, generation: u64 },
+    /// Write batch tag changes on a worker thread; Event::TagsWritten reports back
+    WriteTags(Vec<tag_edit::Change>),
+    /// Move files into the Artist/Album layout on a worker thread; Event::FilesMoved reports back
+    OrganizeFiles(Vec<organize::Move>),
+    /// Put a file's full path on the clipboard (OSC 52)
+    CopyPath(PathBuf),
+    /// Write the listening report for a period to a JSON or CSV file
+    ExportStats { period: stats::Period, csv: bool },
+    /// Write the library, queue or play history to a file (CSV by extension, else JSON)
+    Export { dataset: export::Dataset, path: PathBuf },
+    /// Show a message in the status bar (for feedback from UI-only actions)
+    Notify(String),
+    /// Start measuring the loudness of tracks without a ReplayGain tag or stored
+    /// value, or stop the analysis that's running
+    AnalyzeLoudness,
+    /// Fill the Chill / High energy / Dance playlists from loudness and BPM
+    BuildMoodPlaylists,
+    /// Replace the queue with the track and similar ones, topped up as it plays
+    StartRadio(PathBuf),
+    /// Save the queue, position and playback modes under a name (replacing one with that name)
+    SaveSession(String),
+    /// Load a saved session, first saving the current one back under its name
+    RestoreSession(usize),
+    DeleteSession(usize),
+    ToggleNightMode,
+    /// Play a short snippet of a track without touching the queue; again on the
+    /// same track stops it
+    Preview(PathBuf),
+    StopPreview,
+    /// Preview tracks as they're selected
+    TogglePreviewMode,
 }
 
 pub struct App {
@@ -52,36 +143,93 @@ pub struct App {
     pub focus: FocusedPane,
     pub playback: PlaybackState,
     pub queue: QueueState,
-    pub library: Library,
+    pub library: Arc<Library>,
     pub music_dir: PathBuf,
     pub search_query: String,
     pub search_mode: bool,
     pub search_results: Vec<usize>,
     pub playlists: Vec<state::Playlist>,
+    /// Bumped whenever `playlists` changes, so views can cache what they derive from it
+    pub playlists_rev: u64,
     pub track_just_changed: bool,
     pub sync_state: SyncState,
     pub initial_scan_complete: bool,
+    pub config: Config,
+    /// Short feedback shown in the status bar, with the time it was posted
+    pub status_message: Option<(String, Instant)>,
+    /// Centered "Shuffle ON" / "Repeat: One" box after a mode change
+    pub mode_overlay: Option<(String, Instant)>,
+    /// Playlists, volume or playback modes changed since the last save
+    pub state_changed: bool,
+    /// Recorded listens, oldest first, for the Stats view
+    pub history: Vec<history::Play>,
+    /// Where long tracks (`resume_min_minutes`) were left off, in seconds
+    pub resume_positions: HashMap<PathBuf, f64>,
+    /// Measured integrated loudness (LUFS) for `normalize`, by path
+    pub loudness: HashMap<PathBuf, f64>,
+    /// Loudness analysis in progress
+    pub loudness_job: Option<loudness::Job>,
+    /// Seed track while the queue is a radio of similar tracks
+    pub radio: Option<PathBuf>,
+    /// Whether the night mode hours were in effect at the last check; night mode
+    /// follows the schedule when it starts or ends and the key in between
+    night_scheduled: Option<bool>,
+    /// Track whose preview snippet is playing
+    pub preview: Option<PathBuf>,
+    /// Selecting a track previews it
+    pub preview_mode: bool,
+    /// Named queues saved from the sessions list
+    pub sessions: Vec<state::Session>,
+    /// Session the queue was last saved to or restored from
+    pub active_session: Option<String>,
+    /// Listen in progress, recorded when the track changes or playback ends
+    listen: Option<history::Listen>,
+    /// Play counts from `history`, for `weighted_shuffle`
+    play_counts: shuffle::PlayCounts,
+    /// Artists of the tracks started last, for `shuffle_artist_spacing`
+    recent_artists: shuffle::RecentArtists,
     audio_engine: Option<AudioEngine>,
     event_tx: Option<Sender<Event>>,
 }
 
 impl App {
-    pub fn new(music_dir: PathBuf) -> Self {
+    pub fn new(music_dir: PathBuf, config: Config) -> Self {
         Self {
             should_quit: false,
             tab: Tab::Queue,
             focus: FocusedPane::Library,
-            playback: PlaybackState::default(),
+            playback: PlaybackState {
+                volume: PlaybackState::default().volume.min(config.volume_cap()),
+                ..Default::default()
+            },
             queue: QueueState::default(),
-            library: Library::new(),
+            library: Arc::new(Library::new()),
             music_dir,
             search_query: String::new(),
             search_mode: false,
             search_results: Vec::new(),
             playlists: vec![state::Playlist::new("Bookmarks")],
+            playlists_rev: 0,
             track_just_changed: false,
             sync_state: SyncState::Idle,
             initial_scan_complete: false,
+            config,
+            status_message: None,
+            mode_overlay: None,
+            state_changed: false,
+            history: Vec::new(),
+            resume_positions: HashMap::new(),
+            loudness: HashMap::new(),
+            loudness_job: None,
+            radio: None,
+            night_scheduled: None,
+            preview: None,
+            preview_mode: false,
+            sessions: Vec::new(),
+            active_session: None,
+            listen: None,
+            play_counts: shuffle::PlayCounts::default(),
+            recent_artists: shuffle::RecentArtists::default(),
             audio_engine: None,
             event_tx: None,
         }
@@ -96,9 +244,39 @@ impl App {
     }
 
     pub fn handle_action(&mut self, action: AppAction) {
+        let playlists_edited = matches!(
+            action,
+            AppAction::AddToPlaylist { .. }
+                | AppAction::RemoveFromPlaylist { .. }
+                | AppAction::RemovePlaylistEntries { .. }
+                | AppAction::MovePlaylistEntries { .. }
+                | AppAction::AddTracksToPlaylist { .. }
+                | AppAction::CreatePlaylist(_)
+                | AppAction::DeletePlaylist(_)
+                | AppAction::RenamePlaylist { .. }
+                | AppAction::TogglePlaylistDuplicates(_)
+                | AppAction::BuildMoodPlaylists
+        );
+        if playlists_edited {
+            self.playlists_rev += 1;
+        }
+        if playlists_edited
+            || matches!(
+                action,
+                AppAction::SetVolume(_)
+                    | AppAction::ToggleShuffle
+                    | AppAction::CycleRepeat
+                    | AppAction::SaveSession(_)
+                    | AppAction::RestoreSession(_)
+                    | AppAction::DeleteSession(_)
+            )
+        {
+            self.state_changed = true;
+        }
         match action {
             AppAction::Quit => {
                 self.should_quit = true;
+                self.finish_listen();
                 if let Some(ref engine) = self.audio_engine {
                     engine.send(PlayerCommand::Stop);
                 }
@@ -107,13 +285,38 @@ impl App {
                 if track_idx < self.library.tracks.len() {
                     let path = self.library.tracks[track_idx].path.clone();
                     let dur = self.library.tracks[track_idx].duration.as_secs_f64();
+                    self.finish_listen();
+                    self.listen = Some(history::Listen::start(&self.library.tracks[track_idx]));
+                    self.recent_artists.push(self.library.tracks[track_idx].display_artist(), self.config.shuffle_artist_spacing);
+                    self.playback.gain_db = self.normalize_gain(track_idx);
+                    let gain = 10f32.powf(self.playback.gain_db.unwrap_or(0.0) as f32 / 20.0);
                     if let Some(ref engine) = self.audio_engine {
-                        engine.send(PlayerCommand::Play(path));
+                        engine.send(PlayerCommand::Play { path, gain });
                     }
                     self.playback.state = PlayState::Playing;
                     self.playback.position_secs = 0.0;
                     self.playback.duration_secs = dur;
+                    self.playback.repeats_left = self.config.repeat_count.saturating_sub(1);
+                    self.playback.stream_format = None;
+                    // Parsed on a worker so a slow disk can't stall the UI; Event::ChaptersRead reports back
+                    self.playback.chapters = Vec::new();
+                    if let Some(ref tx) = self.event_tx {
+                        let tx = tx.clone();
+                        let path = self.library.tracks[track_idx].path.clone();
+                        std::thread::spawn(move || {
+                            let chapters = chapters::read_chapters(&path);
+                            if !chapters.is_empty() {
+                                let _ = tx.send(Event::ChaptersRead { path, chapters });
+                            }
+                        });
+                    }
                     self.track_just_changed = true;
+                    if let Some(&pos) = self.resume_positions.get(&self.library.tracks[track_idx].path) {
+                        if self.is_resumable(dur) {
+                            self.handle_action(AppAction::Seek(pos));
+                            self.notify(format!("Resuming at {}:{:02}", pos as u64 / 60, pos as u64 % 60));
+                        }
+                    }
                 }
             }
             AppAction::PauseResume => match self.playback.state {
@@ -132,20 +335,70 @@ impl App {
                 PlayState::Stopped => {
                     // Try to play current queue item
                     if let Some(idx) = self.queue.current_index {
-                        if let Some(&track_idx) = self.queue.tracks.get(idx) {
-                            self.handle_action(AppAction::PlayTrack(track_idx));
-                        }
+                        self.play_queue_entry(idx);
                     }
                 }
             },
+            AppAction::Stop => {
+                if self.playback.state == PlayState::Stopped {
+                    return;
+                }
+                self.finish_listen();
+                if let Some(ref engine) = self.audio_engine {
+                    engine.send(PlayerCommand::Stop);
+                }
+                self.playback.state = PlayState::Stopped;
+                self.playback.position_secs = 0.0;
+            }
             AppAction::NextTrack => {
                 self.play_next();
             }
             AppAction::PrevTrack => {
                 self.play_prev();
             }
+            AppAction::ReplayTrack => {
+                let Some(idx) = self.queue.current_index else { return };
+                // From the very start, not where a long track was left off
+                if let Some(path) = self.queue.tracks.get(idx) {
+                    if self.resume_positions.remove(path).is_some() {
+                        self.state_changed = true;
+                    }
+                }
+                self.play_queue_entry(idx);
+            }
+            AppAction::PlayRandomEntry => {
+                use rand::seq::SliceRandom;
+                let available: Vec<usize> = (0..self.queue.tracks.len())
+                    .filter(|&i| Some(i) != self.queue.current_index && self.queue_entry_available(i))
+                    .collect();
+                match available.choose(&mut rand::thread_rng()) {
+                    Some(&qi) => self.play_queue_entry(qi),
+                    None => self.notify("Nothing else in the queue to jump to"),
+                }
+            }
+            AppAction::NextAlbum => {
+                self.skip_album(true);
+            }
+            AppAction::PrevAlbum => {
+                self.skip_album(false);
+            }
+            AppAction::SetVolume(_) if self.config.bit_perfect => {
+                // The player ignores volume so samples reach the device unscaled
+                self.playback.volume = 1.0;
+                self.notify("Volume is disabled in bit-perfect mode");
+            }
             AppAction::SetVolume(vol) => {
-                self.playback.volume = vol.clamp(0.0, 1.0);
+                let cap = self.config.volume_cap();
+                let previous = self.playback.volume;
+                self.playback.volume = vol.clamp(0.0, cap);
+                if vol > cap {
+                    self.notify(format!("Volume is capped at {}%", self.config.max_volume.min(100)));
+                } else if let Some(warn) = self.config.volume_warning {
+                    let threshold = warn as f32 / 100.0;
+                    if previous < threshold && self.playback.volume >= threshold {
+                        self.notify(format!("Volume above {}% \u{2014} mind your ears", warn));
+                    }
+                }
                 if let Some(ref engine) = self.audio_engine {
                     engine.send(PlayerCommand::SetVolume(self.playback.volume));
                 }
@@ -165,47 +418,193 @@ impl App {
                 }
                 self.playback.position_secs = clamped;
             }
-            AppAction::SeekForward => {
-                let pos = self.playback.position_secs + 5.0;
+            AppAction::SeekBy(secs) => {
+                let pos = self.playback.position_secs + secs;
                 self.handle_action(AppAction::Seek(pos));
             }
-            AppAction::SeekBackward => {
-                let pos = self.playback.position_secs - 5.0;
-                self.handle_action(AppAction::Seek(pos));
+            AppAction::NextChapter => {
+                let next = self.playback.current_chapter().map_or(0, |i| i + 1);
+                if let Some(chapter) = self.playback.chapters.get(next) {
+                    self.handle_action(AppAction::Seek(chapter.start));
+                }
+            }
+            AppAction::PrevChapter => {
+                if let Some(i) = self.playback.current_chapter() {
+                    let into = self.playback.position_secs - self.playback.chapters[i].start;
+                    let target = if into > 3.0 || i == 0 { i } else { i - 1 };
+                    self.handle_action(AppAction::Seek(self.playback.chapters[target].start));
+                }
             }
             AppAction::ToggleShuffle => {
                 self.playback.shuffle = !self.playback.shuffle;
+                let label = if self.playback.shuffle { "Shuffle ON" } else { "Shuffle OFF" };
+                self.mode_overlay = Some((label.to_string(), Instant::now()));
+            }
+            AppAction::ToggleNightMode if self.config.bit_perfect => {
+                self.notify("Night mode is disabled in bit-perfect mode");
+            }
+            AppAction::ToggleNightMode => {
+                self.set_night_mode(!self.playback.night_mode);
+                let label = if self.playback.night_mode { "Night mode ON" } else { "Night mode OFF" };
+                self.mode_overlay = Some((label.to_string(), Instant::now()));
+            }
+            AppAction::Preview(path) if self.preview.as_ref() == Some(&path) => {
+                self.handle_action(AppAction::StopPreview);
+            }
+            AppAction::Preview(path) => {
+                let title = self.library.track_by_path(&path).map(|t| t.title.clone());
+                if let Some(ref engine) = self.audio_engine {
+                    let volume = self.playback.volume * PREVIEW_VOLUME;
+                    engine.send(PlayerCommand::Preview { path: path.clone(), volume });
+                }
+                self.notify(format!("Preview: {}", title.unwrap_or_else(|| path.display().to_string())));
+                self.preview = Some(path);
+            }
+            AppAction::StopPreview => {
+                if self.preview.take().is_some() {
+                    if let Some(ref engine) = self.audio_engine {
+                        engine.send(PlayerCommand::StopPreview);
+                    }
+                }
+            }
+            AppAction::TogglePreviewMode => {
+                self.preview_mode = !self.preview_mode;
+                if !self.preview_mode {
+                    self.handle_action(AppAction::StopPreview);
+                }
+                let label = if self.preview_mode { "Preview mode ON" } else { "Preview mode OFF" };
+                self.mode_overlay = Some((label.to_string(), Instant::now()));
             }
             AppAction::CycleRepeat => {
                 self.playback.repeat = self.playback.repeat.next();
+                let label = if self.playback.repeat == RepeatMode::Count {
+                    // The play under way counts as the first
+                    self.playback.repeats_left = self.config.repeat_count.saturating_sub(1);
+                    format!("{} times", self.config.repeat_count)
+                } else {
+                    self.playback.repeat.as_str().to_string()
+                };
+                self.mode_overlay = Some((format!("Repeat: {}", label), Instant::now()));
             }
             AppAction::SwitchTab(tab) => {
                 self.tab = tab;
             }
-            AppAction::FocusNext => {
-                self.focus = self.focus.next();
-            }
-            AppAction::FocusPrev => {
-                self.focus = self.focus.prev();
-            }
             AppAction::FocusPane(pane) => {
                 self.focus = pane;
             }
             AppAction::AddToQueue(track_indices) => {
-                self.queue.tracks = track_indices;
+                self.queue.tracks = self.library.paths_of(&track_indices);
                 self.queue.current_index = if self.queue.tracks.is_empty() { None } else { Some(0) };
                 self.queue.selected_index = 0;
                 self.queue.scroll_offset = 0;
+                self.queue.playlist = None;
+                self.radio = None;
+                if !self.queue.tracks.is_empty() {
+                    self.notify(format!("Queued {}", self.tracks_label(&self.queue.tracks)));
+                }
+            }
+            AppAction::InsertIntoQueue(paths) => {
+                if paths.is_empty() {
+                    return;
+                }
+                let n = paths.len();
+                let label = self.tracks_label(&paths);
+                let at = if self.queue.tracks.is_empty() {
+                    0
+                } else {
+                    (self.queue.selected_index + 1).min(self.queue.tracks.len())
+                };
+                self.splice_into_queue(at, paths);
+                // Select the last inserted row so the next insert follows it
+                self.queue.selected_index = at + n - 1;
+                self.notify(format!("Inserted {} at #{}", label, at + 1));
+            }
+            AppAction::QueueNext(paths) => {
+                if paths.is_empty() {
+                    return;
+                }
+                let label = self.tracks_label(&paths);
+                let at = self.queue.current_index.map_or(self.queue.tracks.len(), |ci| ci + 1);
+                self.splice_into_queue(at, paths);
+                self.notify(format!("Playing {} next", label));
+            }
+            AppAction::AppendToQueue(paths) => {
+                if paths.is_empty() {
+                    return;
+                }
+                let label = self.tracks_label(&paths);
+                let at = self.queue.tracks.len();
+                self.splice_into_queue(at, paths);
+                self.notify(format!("Added {} at #{}", label, at + 1));
+            }
+            AppAction::QueuePlaylist(idx) => {
+                if let Some(pl) = self.playlists.get(idx) {
+                    self.queue.tracks = pl.tracks.clone();
+                    self.queue.current_index = if self.queue.tracks.is_empty() { None } else { Some(0) };
+                    self.queue.selected_index = 0;
+                    self.queue.scroll_offset = 0;
+                    self.queue.playlist = Some(idx);
+                    self.radio = None;
+                    let count = if pl.tracks.len() == 1 { "1 track".to_string() } else { format!("{} tracks", pl.tracks.len()) };
+                    let msg = format!("Queued {} from \u{201c}{}\u{201d}", count, pl.name);
+                    self.notify(msg);
+                }
+            }
+            AppAction::PlayPlaylistFrom { playlist_idx, pos } => {
+                let len = self.playlists.get(playlist_idx).map_or(0, |pl| pl.tracks.len());
+                if pos < len {
+                    self.handle_action(AppAction::QueuePlaylist(playlist_idx));
+                    self.queue.selected_index = pos;
+                    self.play_queue_entry(pos);
+                }
             }
             AppAction::ClearQueue => {
                 self.queue.tracks.clear();
                 self.queue.current_index = None;
                 self.queue.selected_index = 0;
                 self.queue.scroll_offset = 0;
+                self.queue.playlist = None;
+                self.radio = None;
+            }
+            AppAction::DedupeQueue => {
+                // The entry kept for each track: the playing one for its track, else the first
+                let mut kept: HashMap<&PathBuf, usize> = HashMap::new();
+                for (i, path) in self.queue.tracks.iter().enumerate() {
+                    kept.entry(path).or_insert(i);
+                }
+                if let Some(ci) = self.queue.current_index {
+                    if let Some(path) = self.queue.tracks.get(ci) {
+                        kept.insert(path, ci);
+                    }
+                }
+                let removed = self.queue.tracks.len() - kept.len();
+                if removed == 0 {
+                    self.notify("No duplicates in the queue");
+                    return;
+                }
+                let keep: Vec<bool> = self.queue.tracks.iter().enumerate().map(|(i, p)| kept[p] == i).collect();
+                // Where every entry ends up: its kept copy's new position
+                let mut shift = 0;
+                let mut kept_pos = vec![0; keep.len()];
+                for (i, &k) in keep.iter().enumerate() {
+                    kept_pos[i] = i - shift;
+                    shift += usize::from(!k);
+                }
+                let new_pos = |i: usize| kept_pos[kept[&self.queue.tracks[i]]];
+                self.queue.current_index = self.queue.current_index.filter(|&ci| ci < keep.len()).map(new_pos);
+                self.queue.selected_index = if self.queue.selected_index < keep.len() { new_pos(self.queue.selected_index) } else { 0 };
+                let mut i = 0;
+                self.queue.tracks.retain(|_| {
+                    i += 1;
+                    keep[i - 1]
+                });
+                self.queue.playlist = None;
+                self.notify(if removed == 1 { "Removed 1 duplicate".to_string() } else { format!("Removed {} duplicates", removed) });
             }
             AppAction::RemoveFromQueue(idx) => {
                 if idx < self.queue.tracks.len() {
                     self.queue.tracks.remove(idx);
+                    self.queue.playlist = None;
                     if self.queue.tracks.is_empty() {
                         self.queue.current_index = None;
                     } else if let Some(ref mut ci) = self.queue.current_index {
@@ -219,35 +618,130 @@ impl App {
             }
             AppAction::PlayQueueIndex(idx) => {
                 if idx < self.queue.tracks.len() {
-                    self.queue.current_index = Some(idx);
-                    let track_idx = self.queue.tracks[idx];
-                    self.handle_action(AppAction::PlayTrack(track_idx));
+                    self.play_queue_entry(idx);
                 }
             }
             AppAction::UpdatePosition { position_secs, duration_secs } => {
+                // A report sent just before a stop
+                if self.playback.state == PlayState::Stopped {
+                    return;
+                }
                 self.playback.position_secs = position_secs;
+                if self.playback.state == PlayState::Playing {
+                    if let Some(listen) = self.listen.as_mut() {
+                        listen.advance(position_secs);
+                    }
+                }
                 if duration_secs > 0.0 {
                     self.playback.duration_secs = duration_secs;
                 }
+                if position_secs >= RESUME_MIN_POSITION && self.is_resumable(self.playback.duration_secs) {
+                    if let Some(path) = self.current_track().map(|t| t.path.clone()) {
+                        // Saved every half minute of listening, not on every update
+                        let prev = self.resume_positions.insert(path, position_secs);
+                        if prev.is_none_or(|p| (p / 30.0).floor() != (position_secs / 30.0).floor()) {
+                            self.state_changed = true;
+                        }
+                    }
+                }
             }
             AppAction::TrackFinished => {
-                self.play_next();
+                // Listened to the end: next time it starts over
+                if let Some(path) = self.current_track().map(|t| t.path.clone()) {
+                    if self.resume_positions.remove(&path).is_some() {
+                        self.state_changed = true;
+                    }
+                }
+                match self.queue.current_index {
+                    Some(idx) if self.playback.repeat == RepeatMode::Count && self.playback.repeats_left > 0 => {
+                        let left = self.playback.repeats_left - 1;
+                        self.play_queue_entry(idx);
+                        self.playback.repeats_left = left;
+                    }
+                    _ => self.play_next(),
+                }
             }
             AppAction::SetQueueSelection(idx) => {
                 if idx < self.queue.tracks.len() {
                     self.queue.selected_index = idx;
                 }
             }
-            AppAction::AddToPlaylist { playlist_idx, track_idx } => {
+            AppAction::AddToPlaylist { playlist_idx, path } => {
                 if let Some(pl) = self.playlists.get_mut(playlist_idx) {
-                    if !pl.tracks.contains(&track_idx) {
-                        pl.tracks.push(track_idx);
+                    let msg = if !pl.tracks.contains(&path) {
+                        pl.tracks.push(path);
+                        format!("Added to {}", pl.name)
+                    } else if pl.allow_duplicates {
+                        pl.tracks.push(path);
+                        format!("Added to {} again", pl.name)
+                    } else {
+                        format!("Already in {}", pl.name)
+                    };
+                    self.notify(msg);
+                }
+            }
+            AppAction::RemoveFromPlaylist { playlist_idx, path } => {
+                if let Some(pl) = self.playlists.get_mut(playlist_idx) {
+                    pl.tracks.retain(|t| *t != path);
+                    let msg = format!("Removed from {}", pl.name);
+                    self.notify(msg);
+                }
+            }
+            AppAction::RemovePlaylistEntries { playlist_idx, positions } => {
+                if let Some(pl) = self.playlists.get_mut(playlist_idx) {
+                    let before = pl.tracks.len();
+                    let mut pos = 0;
+                    pl.tracks.retain(|_| {
+                        pos += 1;
+                        !positions.contains(&(pos - 1))
+                    });
+                    let removed = before - pl.tracks.len();
+                    let msg = if removed == 1 {
+                        format!("Removed from {}", pl.name)
+                    } else {
+                        format!("Removed {} tracks from {}", removed, pl.name)
+                    };
+                    self.notify(msg);
+                }
+            }
+            AppAction::MovePlaylistEntries { playlist_idx, start, len, to } => {
+                if let Some(pl) = self.playlists.get_mut(playlist_idx) {
+                    if start + len <= pl.tracks.len() && to + len <= pl.tracks.len() {
+                        let block: Vec<PathBuf> = pl.tracks.drain(start..start + len).collect();
+                        pl.tracks.splice(to..to, block);
                     }
                 }
             }
-            AppAction::RemoveFromPlaylist { playlist_idx, track_idx } => {
+            AppAction::AddTracksToPlaylist { playlist_idx, paths } => {
+                let label = self.tracks_label(&paths);
                 if let Some(pl) = self.playlists.get_mut(playlist_idx) {
-                    pl.tracks.retain(|&t| t != track_idx);
+                    let mut present: HashSet<PathBuf> = pl.tracks.iter().cloned().collect();
+                    let before = pl.tracks.len();
+                    for path in &paths {
+                        if pl.allow_duplicates || present.insert(path.clone()) {
+                            pl.tracks.push(path.clone());
+                        }
+                    }
+                    let added = pl.tracks.len() - before;
+                    let msg = if added == 0 {
+                        format!("Already in {}", pl.name)
+                    } else if added == paths.len() {
+                        format!("Added {} to {}", label, pl.name)
+                    } else {
+                        format!("Added {} of {} tracks to {}", added, paths.len(), pl.name)
+                    };
+                    self.notify(msg);
+                }
+            }
+            AppAction::TogglePlaylistDuplicates(idx) => {
+                if let Some(pl) = self.playlists.get_mut(idx) {
+                    pl.allow_duplicates = !pl.allow_duplicates;
+                    let msg = format!(
+                        "{}: duplicates {}",
+                        pl.name,
+                        if pl.allow_duplicates { "allowed" } else { "not allowed" }
+                    );
+                    self.notify(msg);
                 }
             }
             AppAction::CreatePlaylist(name) => {
@@ -256,11 +750,18 @@ impl App {
             AppAction::DeletePlaylist(idx) => {
                 if idx < self.playlists.len() {
                     self.playlists.remove(idx);
+                    self.queue.playlist = match self.queue.playlist {
+                        Some(p) if p == idx => None,
+                        Some(p) if p > idx => Some(p - 1),
+                        other => other,
+                    };
                 }
             }
             AppAction::RenamePlaylist { idx, name } => {
                 if let Some(pl) = self.playlists.get_mut(idx) {
                     pl.name = name;
+                    // A renamed mood playlist is the user's to keep
+                    pl.generated = false;
                 }
             }
             AppAction::LibrarySync => {
@@ -270,46 +771,178 @@ impl App {
                 self.sync_state = SyncState::Scanning;
                 if let Some(ref tx) = self.event_tx {
                     let dir = self.music_dir.clone();
+                    let config = self.config.clone();
+                    let tx = tx.clone();
+                    std::thread::spawn(move || {
+                        let lib = Library::scan(&dir, &config);
+                        let _ = tx.send(Event::LibraryReady(Box::new(lib)));
+                    });
+                }
+            }
+            AppAction::Search { query, generation } => {
+                if let Some(ref tx) = self.event_tx {
+                    let library = Arc::clone(&self.library);
+                    let tx = tx.clone();
+                    std::thread::spawn(move || {
+                        let results = library.search(&query);
+                        let _ = tx.send(Event::SearchResults { generation, results });
+                    });
+                }
+            }
+            AppAction::WriteTags(_) | AppAction::OrganizeFiles(_) if self.config.read_only => {
+                self.notify(READ_ONLY_MESSAGE.to_string());
+            }
+            AppAction::WriteTags(changes) => {
+                if let Some(ref tx) = self.event_tx {
+                    let tx = tx.clone();
+                    self.notify(format!("Writing tags to {} files\u{2026}", changes.len()));
+                    std::thread::spawn(move || {
+                        let (written, failed) = tag_edit::apply(&changes);
+                        let _ = tx.send(Event::TagsWritten { written, failed });
+                    });
+                }
+            }
+            AppAction::OrganizeFiles(moves) => {
+                if let Some(ref tx) = self.event_tx {
                     let tx = tx.clone();
+                    self.notify(format!("Moving {} files\u{2026}", moves.len()));
                     std::thread::spawn(move || {
-                        let lib = Library::scan(&dir);
-                        let _ = tx.send(Event::LibraryReady(lib));
+                        let (moved, failed) = organize::apply(&moves);
+                        let _ = tx.send(Event::FilesMoved { moved, failed });
                     });
                 }
             }
+            AppAction::CopyPath(path) => {
+                match crate::terminal::copy_to_clipboard(&path.to_string_lossy()) {
+                    Ok(()) => self.notify(format!("Copied {}", path.display())),
+                    Err(e) => self.notify(format!("Couldn't copy path: {}", e)),
+                }
+            }
+            AppAction::Export { path, .. } if self.config.read_only && export::is_inside(&path, &self.music_dir) => {
+                self.notify(READ_ONLY_MESSAGE.to_string());
+            }
+            AppAction::Export { dataset, path } => {
+                match export::write(dataset, &self.library, &self.queue.tracks, &self.history, &path) {
+                    Ok(rows) => self.notify(format!("Exported {} {} rows to {}", rows, dataset.name(), path.display())),
+                    Err(e) => self.notify(format!("Export failed: {}", e)),
+                }
+            }
+            AppAction::ExportStats { period, csv } => {
+                let report = stats::Stats::compute(&self.history, period);
+                let result = if csv { report.export_csv(period) } else { report.export_json(period) };
+                match result {
+                    Ok(path) => self.notify(format!("Exported {}", path.display())),
+                    Err(e) => self.notify(format!("Export failed: {}", e)),
+                }
+            }
+            AppAction::Notify(msg) => self.notify(msg),
+            AppAction::StartRadio(path) => {
+                let library = Arc::clone(&self.library);
+                let Some(seed) = library.track_by_path(&path) else { return };
+                let similar = radio::similar(&library, seed, &HashSet::new(), RADIO_BATCH);
+                if similar.is_empty() {
+                    self.notify(format!("Nothing similar to {} in the library", seed.title));
+                    return;
+                }
+                self.queue.tracks = std::iter::once(path.clone()).chain(similar).collect();
+                self.queue.selected_index = 0;
+                self.queue.scroll_offset = 0;
+                self.queue.playlist = None;
+                self.radio = Some(path);
+                self.play_queue_entry(0);
+                self.notify(format!("Radio from {}", seed.title));
+            }
+            AppAction::SaveSession(name) => {
+                let session = self.current_session(name.clone());
+                match self.sessions.iter_mut().find(|s| s.name == name) {
+                    Some(existing) => *existing = session,
+                    None => self.sessions.push(session),
+                }
+                self.notify(format!("Saved session \"{}\"", name));
+                self.active_session = Some(name);
+            }
+            AppAction::RestoreSession(idx) => {
+                let Some(session) = self.sessions.get(idx).cloned() else { return };
+                // Keep the session being left as it is now, so switching back resumes it
+                if let Some(active) = self.active_session.clone().filter(|a| *a != session.name) {
+                    if let Some(i) = self.sessions.iter().position(|s| s.name == active) {
+                        self.sessions[i] = self.current_session(active);
+                    }
+                }
+                self.queue.tracks = session.tracks;
+                self.queue.selected_index = session.current_index.unwrap_or(0);
+                self.queue.scroll_offset = 0;
+                self.queue.playlist = None;
+                self.radio = None;
+                self.playback.shuffle = session.shuffle;
+                self.playback.repeat = session.repeat;
+                self.queue.current_index = None;
+                if let Some(ci) = session.current_index.filter(|&ci| self.queue_entry_available(ci)) {
+                    self.play_queue_entry(ci);
+                    if session.position_secs > 0.0 {
+                        self.handle_action(AppAction::Seek(session.position_secs));
+                    }
+                } else if let Some(ref engine) = self.audio_engine {
+                    engine.send(PlayerCommand::Stop);
+                }
+                self.notify(format!("Restored session \"{}\"", session.name));
+                self.active_session = Some(session.name);
+            }
+            AppAction::DeleteSession(idx) => {
+                if idx < self.sessions.len() {
+                    let removed = self.sessions.remove(idx);
+                    if self.active_session.as_ref() == Some(&removed.name) {
+                        self.active_session = None;
+                    }
+                }
+            }
+            AppAction::BuildMoodPlaylists => {
+                let built = mood::build(&self.library.tracks, &self.loudness);
+                if built.iter().all(|(_, paths)| paths.is_empty()) {
+                    self.notify("No tracks with loudness or BPM yet (Ctrl+E, n measures loudness)");
+                    return;
+                }
+                let mut counts = Vec::new();
+                for (mood, paths) in built {
+                    counts.push(format!("{} {}", mood.name(), paths.len()));
+                    match self.playlists.iter_mut().find(|pl| pl.generated && pl.name == mood.name()) {
+                        Some(pl) => pl.tracks = paths,
+                        None if paths.is_empty() => {}
+                        None => {
+                            let mut pl = state::Playlist::new(mood.name());
+                            pl.generated = true;
+                            pl.tracks = paths;
+                            self.playlists.push(pl);
+                        }
+                    }
+                }
+                self.notify(format!("Mood playlists: {}", counts.join(", ")));
+            }
+            AppAction::AnalyzeLoudness => {
+                if let Some(job) = &self.loudness_job {
+                    job.cancel();
+                    self.notify("Stopping loudness analysis\u{2026}");
+                    return;
+                }
+                let paths: Vec<PathBuf> = self
+                    .library
+                    .tracks
+                    .iter()
+                    .filter(|t| t.replay_gain.is_none() && !self.loudness.contains_key(&t.path))
+                    .map(|t| t.path.clone())
+                    .collect();
+                if paths.is_empty() {
+                    self.notify("Every track already has a loudness value");
+                } else if let Some(ref tx) = self.event_tx {
+                    self.loudness_job = Some(loudness::spawn_analysis(paths, tx.clone()));
+                }
+            }
         }
     }
 
     pub fn replace_library(&mut self, new_lib: Library) {
-        // Build path→new_index map
-        let path_map: HashMap<PathBuf, usize> = new_lib.tracks.iter().enumerate()
-            .map(|(i, t)| (t.path.clone(), i))
-            .collect();
-
-        // Capture current playing track path
-        let playing_path = self.queue.current_index
-            .and_then(|qi| self.queue.tracks.get(qi))
-            .and_then(|&ti| self.library.tracks.get(ti))
-            .map(|t| t.path.clone());
-
-        // Remap queue tracks
-        let new_queue_tracks: Vec<usize> = self.queue.tracks.iter()
-            .filter_map(|&old_idx| {
-                self.library.tracks.get(old_idx)
-                    .and_then(|t| path_map.get(&t.path))
-                    .copied()
-            })
-            .collect();
-
-        // Remap current_index: find playing track in new queue
-        let new_current = playing_path.and_then(|pp| {
-            path_map.get(&pp).and_then(|&new_ti| {
-                new_queue_tracks.iter().position(|&idx| idx == new_ti)
-            })
-        });
-
-        self.queue.tracks = new_queue_tracks;
-        self.queue.current_index = new_current;
+        // Queue and playlists hold paths, so they carry over as-is; entries for files that
+        // are gone stay (shown as unavailable) and come back once the file does
         self.queue.selected_index = self.queue.selected_index.min(
             self.queue.tracks.len().saturating_sub(1)
         );
@@ -317,26 +950,210 @@ impl App {
             self.queue.tracks.len().saturating_sub(1)
         );
 
-        // Remap playlists
-        for pl in &mut self.playlists {
-            pl.tracks = pl.tracks.iter()
-                .filter_map(|&old_idx| {
-                    self.library.tracks.get(old_idx)
-                        .and_then(|t| path_map.get(&t.path))
-                        .copied()
-                })
-                .collect();
-        }
-
         // Remap search results
         if !self.search_query.is_empty() {
             self.search_results = new_lib.search(&self.search_query);
         }
 
-        self.library = new_lib;
+        self.library = Arc::new(new_lib);
         self.sync_state = SyncState::Idle;
     }
 
+    /// What a toast calls `paths`: the title of a single track, else the count and the
+    /// album or artist they all share (`12 tracks from "OK Computer"`)
+    fn tracks_label(&self, paths: &[PathBuf]) -> String {
+        let tracks: Vec<&Track> = paths.iter().filter_map(|p| self.library.track_by_path(p)).collect();
+        if let [track] = tracks[..] {
+            if paths.len() == 1 {
+                return format!("\u{201c}{}\u{201d}", track.title);
+            }
+        }
+        let count = if paths.len() == 1 { "1 track".to_string() } else { format!("{} tracks", paths.len()) };
+        let shared = |field: fn(&Track) -> &str| {
+            let first = field(tracks.first()?);
+            (!first.is_empty() && tracks.len() == paths.len() && tracks.iter().all(|t| field(t) == first)).then_some(first)
+        };
+        match shared(|t| &t.album).or_else(|| shared(|t| &t.artist)) {
+            Some(name) => format!("{} from \u{201c}{}\u{201d}", count, name),
+            None => count,
+        }
+    }
+
+    /// Insert `paths` at queue position `at`, keeping the playing entry current
+    fn splice_into_queue(&mut self, at: usize, paths: Vec<PathBuf>) {
+        let n = paths.len();
+        self.queue.tracks.splice(at..at, paths);
+        self.queue.current_index = match self.queue.current_index {
+            Some(ci) if ci >= at => Some(ci + n),
+            None => Some(0),
+            other => other,
+        };
+        self.queue.playlist = None;
+    }
+
+    /// Point queue and playlist entries at files' new locations after an organize,
+    /// all in one go so a save never sees half of them updated
+    pub fn remap_paths(&mut self, moved: &[organize::Move]) {
+        let map: std::collections::HashMap<&Path, &Path> =
+            moved.iter().map(|m| (m.from.as_path(), m.to.as_path())).collect();
+        let remap = |paths: &mut Vec<PathBuf>| {
+            for p in paths.iter_mut() {
+                if let Some(to) = map.get(p.as_path()) {
+                    *p = to.to_path_buf();
+                }
+            }
+        };
+        remap(&mut self.queue.tracks);
+        for pl in &mut self.playlists {
+            remap(&mut pl.tracks);
+        }
+        for session in &mut self.sessions {
+            remap(&mut session.tracks);
+        }
+        for m in moved {
+            if let Some(lufs) = self.loudness.remove(&m.from) {
+                self.loudness.insert(m.to.clone(), lufs);
+            }
+            if let Some(pos) = self.resume_positions.remove(&m.from) {
+                self.resume_positions.insert(m.to.clone(), pos);
+            }
+        }
+        self.playlists_rev += 1;
+        self.state_changed = true;
+    }
+
+    /// Normalization gain for a track in dB, when `normalize` is on and its loudness is known
+    fn normalize_gain(&self, track_idx: usize) -> Option<f64> {
+        if !self.config.normalize || self.config.bit_perfect {
+            return None;
+        }
+        let track = self.library.tracks.get(track_idx)?;
+        loudness::gain_db(track.replay_gain, self.loudness.get(&track.path).copied(), self.config.normalize_target)
+    }
+
+    fn set_night_mode(&mut self, on: bool) {
+        self.playback.night_mode = on;
+        if let Some(ref engine) = self.audio_engine {
+            engine.send(PlayerCommand::SetNightMode(on));
+        }
+    }
+
+    /// Switch night mode on or off as the configured hours begin or end; true when it
+    /// changed. Called every tick, so toggling by hand holds until the next boundary.
+    pub fn check_night_schedule(&mut self) -> bool {
+        let Some(start) = self.config.night_mode_start.filter(|_| !self.config.bit_perfect) else { return false };
+        let end = self.config.night_mode_end;
+        let hour = local_hour();
+        let scheduled = if start <= end {
+            (start..end).contains(&hour)
+        } else {
+            hour >= start || hour < end
+        };
+        if self.night_scheduled == Some(scheduled) {
+            return false;
+        }
+        self.night_scheduled = Some(scheduled);
+        if self.playback.night_mode == scheduled {
+            return false;
+        }
+        self.set_night_mode(scheduled);
+        true
+    }
+
+    /// Chapters of a track finished parsing; dropped if another track started meanwhile
+    pub fn chapters_read(&mut self, path: PathBuf, chapters: Vec<chapters::Chapter>) {
+        if self.current_track().is_some_and(|t| t.path == path) {
+            self.playback.chapters = chapters;
+        }
+    }
+
+    /// One track of the loudness analysis done (None: it couldn't be decoded)
+    pub fn loudness_measured(&mut self, path: PathBuf, lufs: Option<f64>) {
+        let Some(job) = self.loudness_job.as_mut() else { return };
+        job.done += 1;
+        let done = job.done;
+        if let Some(lufs) = lufs {
+            self.loudness.insert(path, lufs);
+        }
+        if done % LOUDNESS_SAVE_EVERY == 0 {
+            let _ = loudness::save(&self.loudness);
+        }
+    }
+
+    pub fn loudness_finished(&mut self) {
+        let Some(job) = self.loudness_job.take() else { return };
+        match loudness::save(&self.loudness) {
+            Ok(()) => self.notify(format!("Loudness analysis: {} of {} tracks measured", job.done, job.total)),
+            Err(e) => self.notify(format!("Couldn't save loudness values: {}", e)),
+        }
+    }
+
+    /// Record the current listen in the play history if enough of it was heard
+    fn finish_listen(&mut self) {
+        if let Some(play) = self.listen.take().and_then(history::Listen::finish) {
+            if let Err(e) = history::append(&play) {
+                self.notify(format!("Couldn't save play history: {}", e));
+            }
+            self.history.push(play);
+        }
+    }
+
+    /// Whether queue entry `qi` refers to a file currently in the library
+    pub fn queue_entry_available(&self, qi: usize) -> bool {
+        self.queue.tracks.get(qi).is_some_and(|p| self.library.path_to_index(p).is_some())
+    }
+
+    /// First available queue entry at or after `from`, wrapping around to the start if `wrap`
+    fn next_available(&self, from: usize, wrap: bool) -> Option<usize> {
+        let len = self.queue.tracks.len();
+        (from..len)
+            .chain(if wrap { 0..from.min(len) } else { 0..0 })
+            .find(|&i| self.queue_entry_available(i))
+    }
+
+    /// Make queue entry `qi` current and start it
+    fn play_queue_entry(&mut self, qi: usize) {
+        match self.queue.tracks.get(qi).and_then(|p| self.library.path_to_index(p)) {
+            Some(track_idx) => {
+                self.queue.current_index = Some(qi);
+                self.handle_action(AppAction::PlayTrack(track_idx));
+                self.top_up_radio();
+            }
+            None => {
+                if let Some(path) = self.queue.tracks.get(qi) {
+                    let name = path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+                    self.notify(format!("Unavailable: {}", name));
+                }
+            }
+        }
+    }
+
+    /// The queue, position and playback modes as a session named `name`
+    fn current_session(&self, name: String) -> state::Session {
+        state::Session {
+            name,
+            tracks: self.queue.tracks.clone(),
+            current_index: self.queue.current_index,
+            position_secs: if self.playback.state == PlayState::Stopped { 0.0 } else { self.playback.position_secs },
+            shuffle: self.playback.shuffle,
+            repeat: self.playback.repeat,
+        }
+    }
+
+    /// Keep a radio queue going: add similar tracks when it's nearly played through
+    fn top_up_radio(&mut self) {
+        let Some(seed_path) = &self.radio else { return };
+        let remaining = self.queue.current_index.map_or(0, |ci| self.queue.tracks.len().saturating_sub(ci + 1));
+        if remaining >= RADIO_LOW {
+            return;
+        }
+        let library = Arc::clone(&self.library);
+        let Some(seed) = library.track_by_path(seed_path) else { return };
+        let recent: HashSet<PathBuf> = self.queue.tracks.iter().rev().take(RADIO_MEMORY).cloned().collect();
+        let more = radio::similar(&library, seed, &recent, RADIO_BATCH);
+        self.queue.tracks.extend(more);
+    }
+
     fn play_next(&mut self) {
         if self.queue.tracks.is_empty() {
             return;
@@ -345,56 +1162,97 @@ impl App {
         match self.playback.repeat {
             RepeatMode::One => {
                 if let Some(idx) = self.queue.current_index {
-                    let track_idx = self.queue.tracks[idx];
-                    let path = self.library.tracks[track_idx].path.clone();
-                    let dur = self.library.tracks[track_idx].duration.as_secs_f64();
-                    if let Some(ref engine) = self.audio_engine {
-                        engine.send(PlayerCommand::Play(path));
-                    }
-                    self.playback.state = PlayState::Playing;
-                    self.playback.position_secs = 0.0;
-                    self.playback.duration_secs = dur;
-                    self.track_just_changed = true;
+                    self.play_queue_entry(idx);
                 }
             }
             _ => {
+                // Unavailable entries are skipped
                 let next = if self.playback.shuffle {
-                    use rand::Rng;
-                    let mut rng = rand::thread_rng();
-                    Some(rng.gen_range(0..self.queue.tracks.len()))
-                } else if let Some(idx) = self.queue.current_index {
-                    let next_idx = idx + 1;
-                    if next_idx < self.queue.tracks.len() {
-                        Some(next_idx)
-                    } else if self.playback.repeat == RepeatMode::All {
-                        Some(0)
-                    } else {
-                        None
-                    }
+                    self.shuffle_pick()
                 } else {
-                    Some(0)
+                    let from = self.queue.current_index.map_or(0, |idx| idx + 1);
+                    self.next_available(from, self.playback.repeat == RepeatMode::All)
                 };
 
                 if let Some(next_idx) = next {
-                    self.queue.current_index = Some(next_idx);
-                    let track_idx = self.queue.tracks[next_idx];
-                    let path = self.library.tracks[track_idx].path.clone();
-                    let dur = self.library.tracks[track_idx].duration.as_secs_f64();
-                    if let Some(ref engine) = self.audio_engine {
-                        engine.send(PlayerCommand::Play(path));
-                    }
-                    self.playback.state = PlayState::Playing;
-                    self.playback.position_secs = 0.0;
-                    self.playback.duration_secs = dur;
-                    self.track_just_changed = true;
+                    self.play_queue_entry(next_idx);
                 } else {
                     self.playback.state = PlayState::Stopped;
                     self.playback.position_secs = 0.0;
+                    self.finish_listen();
                 }
             }
         }
     }
 
+    /// Queue entries from `qi` back to the first of the run that shares its album
+    fn album_start(&self, qi: usize) -> usize {
+        let Some(album) = self.queue_album(qi) else { return qi };
+        let mut start = qi;
+        while start > 0 && self.queue_album(start - 1) == Some(album) {
+            start -= 1;
+        }
+        start
+    }
+
+    /// Album of queue entry `qi`, if its file is in the library
+    fn queue_album(&self, qi: usize) -> Option<&Album> {
+        let track = self.library.track_by_path(self.queue.tracks.get(qi)?)?;
+        self.library.album_position(track).map(|i| &self.library.get_albums()[i])
+    }
+
+    /// Play the first entry of the next (or previous) run of queue entries from one
+    /// album, in queue order whatever the shuffle setting
+    fn skip_album(&mut self, forward: bool) {
+        let Some(current) = self.queue.current_index else {
+            if let Some(first) = self.next_available(0, false) {
+                self.play_queue_entry(first);
+            }
+            return;
+        };
+        let target = if forward {
+            let album = self.queue_album(current);
+            (current + 1..self.queue.tracks.len())
+                .find(|&i| self.queue_entry_available(i) && (album.is_none() || self.queue_album(i) != album))
+        } else {
+            let start = self.album_start(current);
+            (0..start).rev().find(|&i| self.queue_entry_available(i)).map(|i| self.album_start(i))
+        };
+        match target {
+            Some(qi) => self.play_queue_entry(qi),
+            None if forward => self.notify("No next album in the queue"),
+            None => self.notify("No previous album in the queue"),
+        }
+    }
+
+    /// Random available queue entry, skipping artists heard in the last
+    /// `shuffle_artist_spacing` tracks unless that rules out everything; with
+    /// `weighted_shuffle`, tracks played less often and less recently come up more
+    fn shuffle_pick(&mut self) -> Option<usize> {
+        let available: Vec<usize> = (0..self.queue.tracks.len())
+            .filter(|&i| self.queue_entry_available(i))
+            .collect();
+        let fresh: Vec<usize> = available
+            .iter()
+            .copied()
+            .filter(|&i| {
+                self.library
+                    .track_by_path(&self.queue.tracks[i])
+                    .is_some_and(|t| !self.recent_artists.contains(t.display_artist()))
+            })
+            .collect();
+        let available = if fresh.is_empty() { available } else { fresh };
+        if !self.config.weighted_shuffle {
+            return shuffle::pick(&available, |_| 1.0);
+        }
+        self.play_counts.update(&self.history);
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or_default();
+        shuffle::pick(&available, |i| self.play_counts.weight(&self.queue.tracks[i], now))
+    }
+
     fn play_prev(&mut self) {
         if self.queue.tracks.is_empty() {
             return;
@@ -403,50 +1261,142 @@ impl App {
         // If more than 3 seconds in, restart current track
         if self.playback.position_secs > 3.0 {
             if let Some(idx) = self.queue.current_index {
-                let track_idx = self.queue.tracks[idx];
-                let path = self.library.tracks[track_idx].path.clone();
-                let dur = self.library.tracks[track_idx].duration.as_secs_f64();
-                if let Some(ref engine) = self.audio_engine {
-                    engine.send(PlayerCommand::Play(path));
-                }
-                self.playback.position_secs = 0.0;
-                self.playback.duration_secs = dur;
-                self.track_just_changed = true;
+                self.play_queue_entry(idx);
                 return;
             }
         }
 
-        let prev = if let Some(idx) = self.queue.current_index {
-            if idx > 0 {
-                Some(idx - 1)
-            } else if self.playback.repeat == RepeatMode::All {
-                Some(self.queue.tracks.len() - 1)
-            } else {
-                Some(0)
-            }
-        } else {
-            Some(0)
+        // Nearest available entry before the current one, skipping unavailable files
+        let prev = match self.queue.current_index {
+            Some(idx) => (0..idx).rev()
+                .find(|&i| self.queue_entry_available(i))
+                .or_else(|| {
+                    (self.playback.repeat == RepeatMode::All)
+                        .then(|| (idx + 1..self.queue.tracks.len()).rev().find(|&i| self.queue_entry_available(i)))
+                        .flatten()
+                })
+                .or(Some(idx)),
+            None => self.next_available(0, false),
         };
 
         if let Some(prev_idx) = prev {
-            self.queue.current_index = Some(prev_idx);
-            let track_idx = self.queue.tracks[prev_idx];
-            let path = self.library.tracks[track_idx].path.clone();
-            let dur = self.library.tracks[track_idx].duration.as_secs_f64();
-            if let Some(ref engine) = self.audio_engine {
-                engine.send(PlayerCommand::Play(path));
-            }
-            self.playback.state = PlayState::Playing;
-            self.playback.position_secs = 0.0;
-            self.playback.duration_secs = dur;
-            self.track_just_changed = true;
+            self.play_queue_entry(prev_idx);
         }
     }
 
+    /// Show a short message in the status bar
+    pub fn notify(&mut self, msg: impl Into<String>) {
+        self.status_message = Some((msg.into(), Instant::now()));
+    }
+
+    /// Clear the status message and mode overlay once shown long enough; true if either was cleared
+    pub fn expire_status_message(&mut self) -> bool {
+        let mut expired = false;
+        if self.status_message.as_ref().is_some_and(|(_, at)| at.elapsed() >= STATUS_MESSAGE_TIMEOUT) {
+            self.status_message = None;
+            expired = true;
+        }
+        if self.mode_overlay.as_ref().is_some_and(|(_, at)| at.elapsed() >= MODE_OVERLAY_TIMEOUT) {
+            self.mode_overlay = None;
+            expired = true;
+        }
+        expired
+    }
+
+    /// Whether a track this long remembers where it was left off
+    pub fn is_resumable(&self, duration_secs: f64) -> bool {
+        self.config.resume_min_minutes > 0.0 && duration_secs >= self.config.resume_min_minutes * 60.0
+    }
+
     pub fn current_track(&self) -> Option<&crate::library::track::Track> {
         self.queue
             .current_index
             .and_then(|qi| self.queue.tracks.get(qi))
-            .and_then(|&ti| self.library.tracks.get(ti))
+            .and_then(|p| self.library.track_by_path(p))
+    }
+}
+
+/// Hour of the day (0-23) in local time
+fn local_hour() -> u32 {
+    localtime::local_hms(localtime::now()).0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::library::track::test_track;
+
+    /// App whose library and queue hold one track per `(path, artist)`, in order
+    fn app_with(tracks: &[(&str, &str)], config: Config) -> App {
+        let mut app = App::new(PathBuf::from("/music"), config);
+        let tracks: Vec<Track> = tracks.iter()
+            .map(|&(path, artist)| Track { artist: artist.to_string(), ..test_track(path) })
+            .collect();
+        app.queue.tracks = tracks.iter().map(|t| t.path.clone()).collect();
+        app.library = Arc::new(Library::from_tracks(tracks));
+        app
+    }
+
+    fn queue_names(app: &App) -> Vec<&str> {
+        app.queue.tracks.iter().map(|p| p.to_str().unwrap()).collect()
+    }
+
+    #[test]
+    fn dedupe_keeps_the_playing_entry() {
+        let mut app = app_with(&[("/a", "A"), ("/b", "B"), ("/a", "A"), ("/c", "C"), ("/b", "B")], Config::default());
+        app.queue.current_index = Some(2);
+        app.queue.selected_index = 4;
+        app.handle_action(AppAction::DedupeQueue);
+        assert_eq!(queue_names(&app), ["/b", "/a", "/c"]);
+        assert_eq!(app.queue.current_index, Some(1));
+        assert_eq!(app.queue.selected_index, 0);
+    }
+
+    #[test]
+    fn dedupe_without_playback_keeps_first_copies() {
+        let mut app = app_with(&[("/a", "A"), ("/b", "B"), ("/a", "A")], Config::default());
+        app.queue.selected_index = 2;
+        app.handle_action(AppAction::DedupeQueue);
+        assert_eq!(queue_names(&app), ["/a", "/b"]);
+        assert_eq!(app.queue.current_index, None);
+        assert_eq!(app.queue.selected_index, 0);
+    }
+
+    #[test]
+    fn dedupe_leaves_a_unique_queue_alone() {
+        let mut app = app_with(&[("/a", "A"), ("/b", "B")], Config::default());
+        app.queue.current_index = Some(1);
+        app.handle_action(AppAction::DedupeQueue);
+        assert_eq!(queue_names(&app), ["/a", "/b"]);
+        assert_eq!(app.queue.current_index, Some(1));
+    }
+
+    #[test]
+    fn shuffle_skips_recent_artists() {
+        for weighted_shuffle in [false, true] {
+            let config = Config { shuffle_artist_spacing: 2, weighted_shuffle, ..Config::default() };
+            let mut app = app_with(&[("/a1", "A"), ("/b", "B"), ("/a2", "a"), ("/c", "C")], config);
+            app.recent_artists.push("A", 2);
+            app.recent_artists.push("C", 2);
+            for _ in 0..50 {
+                assert_eq!(app.shuffle_pick(), Some(1));
+            }
+        }
+    }
+
+    #[test]
+    fn shuffle_falls_back_when_every_artist_is_recent() {
+        let mut app = app_with(&[("/a1", "A"), ("/a2", "A")], Config::default());
+        app.recent_artists.push("A", 1);
+        assert!(matches!(app.shuffle_pick(), Some(0 | 1)));
+    }
+
+    #[test]
+    fn recent_artists_keeps_only_the_spacing() {
+        let mut app = app_with(&[("/a", "A"), ("/b", "B")], Config::default());
+        app.recent_artists.push("A", 1);
+        app.recent_artists.push("C", 1);
+        assert!(!app.recent_artists.contains("A"));
+        assert!(matches!(app.shuffle_pick(), Some(0 | 1)));
     }
 }
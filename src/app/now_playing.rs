@@ -0,0 +1,92 @@
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::state::PlayState;
+use super::App;
+
+/// Contents of `nowplaying.json`; track fields are null while nothing is loaded
+#[derive(Serialize, Default)]
+struct NowPlaying<'a> {
+    state: &'static str,
+    title: Option<&'a str>,
+    artist: Option<&'a str>,
+    album: Option<&'a str>,
+    album_artist: Option<&'a str>,
+    genre: Option<&'a str>,
+    path: Option<&'a Path>,
+    duration_secs: Option<f64>,
+    /// Position when the file was written; not updated while playing
+    position_secs: Option<f64>,
+    /// 1-based position in the queue
+    queue_position: Option<usize>,
+    queue_length: usize,
+}
+
+/// What the files show; they are only rewritten when this changes
+pub type Key = (Option<PathBuf>, PlayState);
+
+pub fn key(app: &App) -> Key {
+    (app.current_track().map(|t| t.path.clone()), app.playback.state)
+}
+
+fn cache_dir() -> PathBuf {
+    let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
+    PathBuf::from(home).join(".cache/ommp")
+}
+
+/// Write `~/.cache/ommp/nowplaying.json` and `nowplaying.txt` ("Artist - Title")
+/// for OBS text sources and status bar modules
+pub fn write(app: &App) -> anyhow::Result<()> {
+    let track = app.current_track().filter(|_| app.playback.state != PlayState::Stopped);
+    let state = match app.playback.state {
+        PlayState::Playing => "playing",
+        PlayState::Paused => "paused",
+        PlayState::Stopped => "stopped",
+    };
+    let info = match track {
+        Some(t) => NowPlaying {
+            state,
+            title: Some(&t.title),
+            artist: Some(t.display_artist()),
+            album: Some(t.display_album()),
+            album_artist: Some(t.album_owner()),
+            genre: Some(&t.genre).filter(|g| !g.is_empty()).map(|g| g.as_str()),
+            path: Some(&t.path),
+            duration_secs: Some(app.playback.duration_secs),
+            position_secs: Some(app.playback.position_secs),
+            queue_position: app.queue.current_index.map(|i| i + 1),
+            queue_length: app.queue.tracks.len(),
+        },
+        None => NowPlaying { state, queue_length: app.queue.tracks.len(), ..Default::default() },
+    };
+    let text = match track {
+        Some(t) if app.playback.state == PlayState::Paused => {
+            format!("{} - {} (paused)\n", t.display_artist(), t.title)
+        }
+        Some(t) => format!("{} - {}\n", t.display_artist(), t.title),
+        None => String::new(),
+    };
+    save(&info, &text)
+}
+
+/// Leave "stopped" behind on exit so overlays don't show a stale song
+pub fn clear() -> anyhow::Result<()> {
+    save(&NowPlaying { state: "stopped", ..Default::default() }, "")
+}
+
+fn save(info: &NowPlaying, text: &str) -> anyhow::Result<()> {
+    let dir = cache_dir();
+    fs::create_dir_all(&dir)?;
+    // Write-then-rename so readers polling the files never see a partial write
+    for (name, contents) in [
+        ("nowplaying.json", serde_json::to_string_pretty(info)?),
+        ("nowplaying.txt", text.to_string()),
+    ] {
+        let path = dir.join(name);
+        let tmp = dir.join(format!("{}.tmp", name));
+        fs::write(&tmp, contents)?;
+        fs::rename(&tmp, &path)?;
+    }
+    Ok(())
+}
@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
 
@@ -13,6 +14,23 @@ pub struct SavedState {
     pub info_view: String,
     #[serde(default = "default_right_split")]
     pub right_split: u16,
+    #[serde(default = "default_layout")]
+    pub layout: String,
+    #[serde(default)]
+    pub search_history: Vec<String>,
+    #[serde(default = "default_search_order")]
+    pub search_order: String,
+    /// Lyrics variant chosen per track, e.g. "Translated"
+    #[serde(default)]
+    pub lyrics_variants: HashMap<PathBuf, String>,
+    /// Seconds where long tracks were left off
+    #[serde(default)]
+    pub resume_positions: HashMap<PathBuf, f64>,
+    #[serde(default)]
+    pub sessions: Vec<SavedSession>,
+    /// Name of the session the queue was last saved to or restored from
+    #[serde(default)]
+    pub active_session: Option<String>,
 }
 
 fn default_info_view() -> String {
@@ -23,10 +41,35 @@ fn default_right_split() -> u16 {
     50
 }
 
+fn default_layout() -> String {
+    "All".to_string()
+}
+
+fn default_search_order() -> String {
+    "Relevance".to_string()
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct SavedPlaylist {
     pub name: String,
     pub tracks: Vec<PathBuf>,
+    #[serde(default)]
+    pub allow_duplicates: bool,
+    #[serde(default)]
+    pub generated: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SavedSession {
+    pub name: String,
+    pub tracks: Vec<PathBuf>,
+    pub current_index: Option<usize>,
+    #[serde(default)]
+    pub position_secs: f64,
+    #[serde(default)]
+    pub shuffle: bool,
+    #[serde(default)]
+    pub repeat: String,
 }
 
 fn state_path() -> PathBuf {
@@ -40,7 +83,10 @@ pub fn save(state: &SavedState) -> anyhow::Result<()> {
         fs::create_dir_all(parent)?;
     }
     let json = serde_json::to_string_pretty(state)?;
-    fs::write(&path, json)?;
+    // Write-then-rename so a crash mid-write never leaves a truncated state file
+    let tmp = path.with_extension("json.tmp");
+    fs::write(&tmp, json)?;
+    fs::rename(&tmp, &path)?;
     Ok(())
 }
 
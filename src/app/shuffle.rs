@@ -0,0 +1,120 @@
+use rand::distributions::{Distribution, WeightedIndex};
+use rand::seq::SliceRandom;
+use std::collections::{HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+
+use super::history::Play;
+
+/// A track played this many days ago or longer no longer counts as recent
+const RECENT_DAYS: f64 = 30.0;
+/// Weight left to a track played moments ago, relative to one never played
+const MIN_RECENCY: f64 = 0.05;
+
+/// Play count and last play time of every track in the history, for weighted shuffle
+#[derive(Debug, Default)]
+pub struct PlayCounts {
+    plays: HashMap<PathBuf, (u32, i64)>,
+    /// History entries counted so far; plays are only ever appended
+    seen: usize,
+}
+
+impl PlayCounts {
+    /// Count the plays added to `history` since the last update
+    pub fn update(&mut self, history: &[Play]) {
+        for play in history.iter().skip(self.seen) {
+            let entry = self.plays.entry(play.path.clone()).or_insert((0, play.started));
+            entry.0 += 1;
+            entry.1 = entry.1.max(play.started);
+        }
+        self.seen = history.len();
+    }
+
+    /// Chance of being picked relative to a track never played: lower the more often
+    /// and the more recently it was played
+    pub fn weight(&self, path: &Path, now: i64) -> f64 {
+        let Some(&(count, last)) = self.plays.get(path) else { return 1.0 };
+        let days = (now - last).max(0) as f64 / 86_400.0;
+        (days / RECENT_DAYS).clamp(MIN_RECENCY, 1.0) / (1.0 + count as f64)
+    }
+}
+
+/// Artists of the last few tracks started, oldest first, so shuffle can keep an
+/// artist from coming up again too soon
+#[derive(Debug, Default)]
+pub struct RecentArtists(VecDeque<String>);
+
+impl RecentArtists {
+    /// Remember a track's artist, keeping the last `keep`
+    pub fn push(&mut self, artist: &str, keep: usize) {
+        self.0.push_back(artist.to_lowercase());
+        while self.0.len() > keep {
+            self.0.pop_front();
+        }
+    }
+
+    pub fn contains(&self, artist: &str) -> bool {
+        let artist = artist.to_lowercase();
+        self.0.contains(&artist)
+    }
+}
+
+/// One of `candidates` at random, in proportion to `weight`; uniformly when the
+/// weights are unusable
+pub fn pick(candidates: &[usize], weight: impl Fn(usize) -> f64) -> Option<usize> {
+    let mut rng = rand::thread_rng();
+    match WeightedIndex::new(candidates.iter().map(|&c| weight(c))) {
+        Ok(dist) => Some(candidates[dist.sample(&mut rng)]),
+        Err(_) => candidates.choose(&mut rng).copied(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: i64 = 86_400;
+
+    fn play(path: &str, started: i64) -> Play {
+        Play {
+            path: PathBuf::from(path),
+            title: String::new(),
+            artist: String::new(),
+            album: String::new(),
+            format: String::new(),
+            started,
+            listened: 0.0,
+            duration: 0.0,
+        }
+    }
+
+    #[test]
+    fn weight_falls_with_plays_and_recency() {
+        let now = 100 * DAY;
+        let mut counts = PlayCounts::default();
+        counts.update(&[play("/a", now - 60 * DAY), play("/b", now - 60 * DAY), play("/b", now - 45 * DAY), play("/c", now)]);
+        assert_eq!(counts.weight(Path::new("/new"), now), 1.0);
+        assert_eq!(counts.weight(Path::new("/a"), now), 0.5);
+        assert!(counts.weight(Path::new("/b"), now) < counts.weight(Path::new("/a"), now));
+        assert_eq!(counts.weight(Path::new("/c"), now), MIN_RECENCY / 2.0);
+    }
+
+    #[test]
+    fn update_only_counts_new_plays() {
+        let mut history = vec![play("/a", 0)];
+        let mut counts = PlayCounts::default();
+        counts.update(&history);
+        history.push(play("/a", 0));
+        counts.update(&history);
+        counts.update(&history);
+        assert_eq!(counts.weight(Path::new("/a"), 100 * DAY), 1.0 / 3.0);
+    }
+
+    #[test]
+    fn pick_follows_weights() {
+        for _ in 0..50 {
+            assert_eq!(pick(&[3, 7], |i| if i == 7 { 1.0 } else { 0.0 }), Some(7));
+        }
+        assert!(matches!(pick(&[3, 7], |_| 0.0), Some(3 | 7)));
+        assert_eq!(pick(&[], |_| 1.0), None);
+    }
+}
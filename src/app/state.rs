@@ -1,3 +1,8 @@
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::library::chapters::Chapter;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SyncState {
     Idle,
@@ -16,6 +21,8 @@ pub enum RepeatMode {
     Off,
     All,
     One,
+    /// Play each track `repeat_count` times, then move on
+    Count,
 }
 
 impl RepeatMode {
@@ -23,7 +30,8 @@ impl RepeatMode {
         match self {
             RepeatMode::Off => RepeatMode::All,
             RepeatMode::All => RepeatMode::One,
-            RepeatMode::One => RepeatMode::Off,
+            RepeatMode::One => RepeatMode::Count,
+            RepeatMode::Count => RepeatMode::Off,
         }
     }
 
@@ -32,6 +40,7 @@ impl RepeatMode {
             RepeatMode::Off => "Off",
             RepeatMode::All => "All",
             RepeatMode::One => "One",
+            RepeatMode::Count => "Count",
         }
     }
 
@@ -39,15 +48,16 @@ impl RepeatMode {
         match s {
             "All" => RepeatMode::All,
             "One" => RepeatMode::One,
+            "Count" => RepeatMode::Count,
             _ => RepeatMode::Off,
         }
     }
 
     pub fn symbol(self) -> &'static str {
         match self {
-            RepeatMode::Off => "\u{F0456}",  // nf-md-repeat
+            RepeatMode::Off => "\u{F0457}",  // nf-md-repeat_off
             RepeatMode::All => "\u{F0456}",  // nf-md-repeat
-            RepeatMode::One => "\u{F0458}",  // nf-md-repeat_once
+            RepeatMode::One | RepeatMode::Count => "\u{F0458}",  // nf-md-repeat_once
         }
     }
 }
@@ -128,6 +138,18 @@ pub struct PlaybackState {
     pub volume: f32,
     pub shuffle: bool,
     pub repeat: RepeatMode,
+    /// Plays of the current track still to come in `RepeatMode::Count`
+    pub repeats_left: u32,
+    /// Device sample rate for the current track, and whether it matches the track's native format
+    pub output_format: Option<(u32, bool)>,
+    /// Decoded format of the current track as reported by the player
+    pub stream_format: Option<crate::event::StreamFormat>,
+    /// Chapters of the current track (M4B/M4A audiobooks)
+    pub chapters: Vec<Chapter>,
+    /// Loudness normalization applied to the current track, in dB
+    pub gain_db: Option<f64>,
+    /// Loud peaks compressed for quiet late-night listening
+    pub night_mode: bool,
 }
 
 impl Default for PlaybackState {
@@ -139,22 +161,54 @@ impl Default for PlaybackState {
             volume: 0.8,
             shuffle: false,
             repeat: RepeatMode::Off,
+            repeats_left: 0,
+            output_format: None,
+            stream_format: None,
+            chapters: Vec::new(),
+            gain_db: None,
+            night_mode: false,
         }
     }
 }
 
+impl PlaybackState {
+    /// Index of the chapter containing the current position
+    pub fn current_chapter(&self) -> Option<usize> {
+        self.chapters.iter().rposition(|c| c.start <= self.position_secs)
+    }
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct QueueState {
-    pub tracks: Vec<usize>,
+    /// Track file paths; resolve with `Library::path_to_index`
+    pub tracks: Vec<PathBuf>,
     pub current_index: Option<usize>,
     pub selected_index: usize,
     pub scroll_offset: usize,
+    /// Playlist the queue was loaded from, until the queue is edited
+    pub playlist: Option<usize>,
+}
+
+/// A saved queue with where it was playing and the playback modes, restored by name
+#[derive(Debug, Clone)]
+pub struct Session {
+    pub name: String,
+    pub tracks: Vec<PathBuf>,
+    pub current_index: Option<usize>,
+    pub position_secs: f64,
+    pub shuffle: bool,
+    pub repeat: RepeatMode,
 }
 
 #[derive(Debug, Clone)]
 pub struct Playlist {
     pub name: String,
-    pub tracks: Vec<usize>,
+    /// Track file paths; entries stay even while the file is missing from the library
+    pub tracks: Vec<PathBuf>,
+    /// Adding a track that's already in the playlist appends another copy
+    pub allow_duplicates: bool,
+    /// Mood playlist from `Ctrl+E, m`; its contents are replaced on the next rebuild
+    pub generated: bool,
 }
 
 impl Playlist {
@@ -162,10 +216,147 @@ impl Playlist {
         Self {
             name: name.into(),
             tracks: Vec::new(),
+            allow_duplicates: false,
+            generated: false,
+        }
+    }
+
+    /// Total length of the tracks that are in the library; missing files count as zero
+    pub fn total_duration(&self, library: &crate::library::Library) -> Duration {
+        self.tracks.iter()
+            .filter_map(|p| library.track_by_path(p))
+            .map(|t| t.duration)
+            .sum()
+    }
+
+    /// " (12 · 47:10)", or " (12 · 47:10, 2 unavailable)" when some files aren't in the library
+    pub fn count_label(&self, library: &crate::library::Library) -> String {
+        let missing = self.tracks.iter().filter(|p| library.path_to_index(p).is_none()).count();
+        let total = format_total_duration(self.total_duration(library));
+        if missing == 0 {
+            format!(" ({} · {})", self.tracks.len(), total)
+        } else {
+            format!(" ({} · {}, {} unavailable)", self.tracks.len(), total, missing)
         }
     }
 }
 
+/// "47:10", or "2:03:45" from an hour up
+pub fn format_total_duration(d: Duration) -> String {
+    let secs = d.as_secs();
+    if secs >= 3600 {
+        format!("{}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+    } else {
+        format!("{}:{:02}", secs / 60, secs % 60)
+    }
+}
+
+/// Which dashboard columns are shown
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LayoutPreset {
+    /// Browser, queue and the info/lyrics column
+    All,
+    BrowserQueue,
+    QueueLyrics,
+    FullQueue,
+}
+
+impl LayoutPreset {
+    pub fn next(self) -> Self {
+        match self {
+            LayoutPreset::All => LayoutPreset::BrowserQueue,
+            LayoutPreset::BrowserQueue => LayoutPreset::QueueLyrics,
+            LayoutPreset::QueueLyrics => LayoutPreset::FullQueue,
+            LayoutPreset::FullQueue => LayoutPreset::All,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LayoutPreset::All => "All",
+            LayoutPreset::BrowserQueue => "BrowserQueue",
+            LayoutPreset::QueueLyrics => "QueueLyrics",
+            LayoutPreset::FullQueue => "FullQueue",
+        }
+    }
+
+    pub fn from_label(s: &str) -> Self {
+        match s {
+            "BrowserQueue" => LayoutPreset::BrowserQueue,
+            "QueueLyrics" => LayoutPreset::QueueLyrics,
+            "FullQueue" => LayoutPreset::FullQueue,
+            _ => LayoutPreset::All,
+        }
+    }
+
+    pub fn shows_browser(self) -> bool {
+        matches!(self, LayoutPreset::All | LayoutPreset::BrowserQueue)
+    }
+
+    pub fn shows_right(self) -> bool {
+        matches!(self, LayoutPreset::All | LayoutPreset::QueueLyrics)
+    }
+
+    /// Whether `pane` is on screen in this preset
+    pub fn shows(self, pane: FocusedPane) -> bool {
+        match pane {
+            FocusedPane::Library => self.shows_browser(),
+            FocusedPane::Playlist => true,
+            FocusedPane::Lyrics => self.shows_right(),
+        }
+    }
+}
+
+/// Order of the search modal results; Artist and Album also group them
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchOrder {
+    Relevance,
+    Artist,
+    Album,
+    Title,
+    Duration,
+    Bpm,
+}
+
+impl SearchOrder {
+    pub fn next(self) -> Self {
+        match self {
+            SearchOrder::Relevance => SearchOrder::Artist,
+            SearchOrder::Artist => SearchOrder::Album,
+            SearchOrder::Album => SearchOrder::Title,
+            SearchOrder::Title => SearchOrder::Duration,
+            SearchOrder::Duration => SearchOrder::Bpm,
+            SearchOrder::Bpm => SearchOrder::Relevance,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SearchOrder::Relevance => "Relevance",
+            SearchOrder::Artist => "Artist",
+            SearchOrder::Album => "Album",
+            SearchOrder::Title => "Title",
+            SearchOrder::Duration => "Duration",
+            SearchOrder::Bpm => "BPM",
+        }
+    }
+
+    pub fn from_label(s: &str) -> Self {
+        match s {
+            "Artist" => SearchOrder::Artist,
+            "Album" => SearchOrder::Album,
+            "Title" => SearchOrder::Title,
+            "Duration" => SearchOrder::Duration,
+            "BPM" => SearchOrder::Bpm,
+            _ => SearchOrder::Relevance,
+        }
+    }
+
+    pub fn groups(self) -> bool {
+        matches!(self, SearchOrder::Artist | SearchOrder::Album)
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum InfoView {
     Clock,
@@ -0,0 +1,259 @@
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+use super::history::Play;
+use crate::localtime::{local, local_hms, now, LocalTime};
+
+const TOP_N: usize = 10;
+const DAY: i64 = 86_400;
+const MONTHS: [&str; 12] = ["Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"];
+const WEEKDAYS: [&str; 7] = ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"];
+
+/// Time range a stats report covers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Period {
+    Week,
+    Month,
+    Year,
+    All,
+}
+
+impl Period {
+    pub fn next(self) -> Self {
+        match self {
+            Self::Week => Self::Month,
+            Self::Month => Self::Year,
+            Self::Year => Self::All,
+            Self::All => Self::Week,
+        }
+    }
+
+    pub fn prev(self) -> Self {
+        match self {
+            Self::Week => Self::All,
+            Self::Month => Self::Week,
+            Self::Year => Self::Month,
+            Self::All => Self::Year,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Week => "Last 7 days",
+            Self::Month => "Last 30 days",
+            Self::Year => "Last 12 months",
+            Self::All => "All time",
+        }
+    }
+
+    fn slug(self) -> &'static str {
+        match self {
+            Self::Week => "week",
+            Self::Month => "month",
+            Self::Year => "year",
+            Self::All => "all",
+        }
+    }
+}
+
+/// Timeline bucket a play falls in, if it's inside the period
+type BucketFn<'a> = Box<dyn Fn(&Play) -> Option<usize> + 'a>;
+
+/// Plays and listening seconds for one name in a ranking
+#[derive(Debug, Clone, Serialize)]
+pub struct Entry {
+    pub name: String,
+    pub plays: usize,
+    pub secs: f64,
+}
+
+/// Listening report for a period: time per day/week/month/year, top lists and formats
+#[derive(Debug, Clone, Serialize)]
+pub struct Stats {
+    pub period: &'static str,
+    pub plays: usize,
+    pub secs: f64,
+    pub distinct_tracks: usize,
+    /// Days for the last week, weeks for the last month, months for the year, years for all time
+    pub timeline: Vec<Entry>,
+    pub top_artists: Vec<Entry>,
+    pub top_albums: Vec<Entry>,
+    pub top_tracks: Vec<Entry>,
+    pub formats: Vec<Entry>,
+}
+
+fn midnight(ts: i64) -> i64 {
+    let (h, m, s) = local_hms(ts);
+    ts - (h * 3600 + m * 60 + s) as i64
+}
+
+impl Stats {
+    pub fn compute(plays: &[Play], period: Period) -> Self {
+        let now = now();
+        let today = midnight(now);
+        let now_local = local(now);
+        let month_index = |t: &LocalTime| t.year * 12 + t.month as i32;
+        let days_ago = |ts: i64| ((today - midnight(ts)) as f64 / DAY as f64).round() as i64;
+
+        // Timeline buckets, newest last
+        let (labels, bucket_of): (Vec<String>, BucketFn) = match period {
+            Period::Week => (
+                (0..7).rev()
+                    .map(|d| {
+                        let t = local(today - d * DAY + DAY / 2);
+                        format!("{} {:02}", WEEKDAYS[t.weekday], t.day)
+                    })
+                    .collect(),
+                Box::new(move |p: &Play| {
+                    let d = days_ago(p.started);
+                    (0..7).contains(&d).then(|| 6 - d as usize)
+                }),
+            ),
+            Period::Month => (
+                (0..5).rev()
+                    .map(|w| {
+                        let t = local(today - (w * 7 + 6).min(29) * DAY + DAY / 2);
+                        format!("{} {:02}", MONTHS[t.month], t.day)
+                    })
+                    .collect(),
+                Box::new(move |p: &Play| {
+                    let d = days_ago(p.started);
+                    (0..30).contains(&d).then(|| 4 - d as usize / 7)
+                }),
+            ),
+            Period::Year => (
+                (0..12).rev()
+                    .map(|m| {
+                        let idx = month_index(&now_local) - m;
+                        format!("{} {}", MONTHS[idx.rem_euclid(12) as usize], idx.div_euclid(12))
+                    })
+                    .collect(),
+                Box::new(move |p: &Play| {
+                    let m = month_index(&now_local) - month_index(&local(p.started));
+                    (0..12).contains(&m).then(|| 11 - m as usize)
+                }),
+            ),
+            Period::All => {
+                let first = plays.iter().map(|p| local(p.started).year).min().unwrap_or(now_local.year);
+                let first = first.min(now_local.year);
+                (
+                    (first..=now_local.year).map(|y| y.to_string()).collect(),
+                    Box::new(move |p: &Play| Some((local(p.started).year - first).max(0) as usize)),
+                )
+            }
+        };
+
+        let mut timeline: Vec<Entry> = labels.into_iter()
+            .map(|name| Entry { name, plays: 0, secs: 0.0 })
+            .collect();
+        let mut artists: HashMap<&str, Entry> = HashMap::new();
+        let mut albums: HashMap<(&str, &str), Entry> = HashMap::new();
+        let mut tracks: HashMap<&PathBuf, Entry> = HashMap::new();
+        let mut formats: HashMap<&str, Entry> = HashMap::new();
+        let (mut total_plays, mut total_secs) = (0, 0.0);
+
+        let count = |entry: &mut Entry, secs: f64| {
+            entry.plays += 1;
+            entry.secs += secs;
+        };
+        let blank = |name: String| Entry { name, plays: 0, secs: 0.0 };
+
+        for p in plays {
+            let Some(bucket) = bucket_of(p) else { continue };
+            if let Some(entry) = timeline.get_mut(bucket) {
+                count(entry, p.listened);
+            }
+            total_plays += 1;
+            total_secs += p.listened;
+            count(artists.entry(&p.artist).or_insert_with(|| blank(p.artist.clone())), p.listened);
+            count(
+                albums.entry((&p.album, &p.artist))
+                    .or_insert_with(|| blank(format!("{} — {}", p.album, p.artist))),
+                p.listened,
+            );
+            count(
+                tracks.entry(&p.path).or_insert_with(|| blank(format!("{} — {}", p.title, p.artist))),
+                p.listened,
+            );
+            count(formats.entry(&p.format).or_insert_with(|| blank(p.format.clone())), p.listened);
+        }
+
+        let distinct_tracks = tracks.len();
+        Self {
+            period: period.label(),
+            plays: total_plays,
+            secs: total_secs,
+            distinct_tracks,
+            timeline,
+            top_artists: ranked(artists.into_values(), TOP_N),
+            top_albums: ranked(albums.into_values(), TOP_N),
+            top_tracks: ranked(tracks.into_values(), TOP_N),
+            formats: ranked(formats.into_values(), usize::MAX),
+        }
+    }
+
+    /// Write the report as `stats-<period>-<unix time>.json` next to the config; returns the path
+    pub fn export_json(&self, period: Period) -> anyhow::Result<PathBuf> {
+        let path = export_path(period, "json");
+        fs::write(&path, serde_json::to_string_pretty(self)?)?;
+        Ok(path)
+    }
+
+    /// Same report as CSV rows: `section,name,plays,seconds`
+    pub fn export_csv(&self, period: Period) -> anyhow::Result<PathBuf> {
+        let mut out = String::from("section,name,plays,seconds\n");
+        out.push_str(&format!("total,{},{},{:.0}\n", csv_field(self.period), self.plays, self.secs));
+        for (section, entries) in [
+            ("timeline", &self.timeline),
+            ("artist", &self.top_artists),
+            ("album", &self.top_albums),
+            ("track", &self.top_tracks),
+            ("format", &self.formats),
+        ] {
+            for e in entries {
+                out.push_str(&format!("{},{},{},{:.0}\n", section, csv_field(&e.name), e.plays, e.secs));
+            }
+        }
+        let path = export_path(period, "csv");
+        fs::write(&path, out)?;
+        Ok(path)
+    }
+}
+
+/// Most played first, listening time breaking ties
+fn ranked(entries: impl Iterator<Item = Entry>, n: usize) -> Vec<Entry> {
+    let mut v: Vec<Entry> = entries.collect();
+    v.sort_by(|a, b| b.plays.cmp(&a.plays).then(b.secs.total_cmp(&a.secs)).then(a.name.cmp(&b.name)));
+    v.truncate(n);
+    v
+}
+
+fn export_path(period: Period, ext: &str) -> PathBuf {
+    let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
+    let dir = PathBuf::from(home).join(".config/ommp");
+    let _ = fs::create_dir_all(&dir);
+    dir.join(format!("stats-{}-{}.{}", period.slug(), now(), ext))
+}
+
+/// Quote a CSV field when it contains a separator, quote or newline
+pub fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+/// "12h 05m", "45m", "30s"
+pub fn format_listening(secs: f64) -> String {
+    let secs = secs as u64;
+    if secs >= 3600 {
+        format!("{}h {:02}m", secs / 3600, secs / 60 % 60)
+    } else if secs >= 60 {
+        format!("{}m", secs / 60)
+    } else {
+        format!("{}s", secs)
+    }
+}
@@ -0,0 +1,189 @@
+use rodio::cpal::traits::HostTrait;
+use rodio::cpal::SampleFormat;
+use rodio::mixer::Mixer;
+use rodio::{DeviceTrait, OutputStream, OutputStreamBuilder};
+use std::sync::{Arc, Mutex};
+
+/// Which audio output the player renders into (`audio_backend` in the config file)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BackendKind {
+    /// rodio's default stream: the system default device, falling back to any working one
+    Rodio,
+    /// A specific cpal output device by name (`audio_device`), or the host default
+    Cpal(Option<String>),
+}
+
+impl BackendKind {
+    pub fn from_config(backend: &str, device: Option<&str>) -> Self {
+        match backend.to_ascii_lowercase().as_str() {
+            "cpal" => BackendKind::Cpal(device.map(|d| d.to_string())),
+            _ => BackendKind::Rodio,
+        }
+    }
+
+    /// The `audio_backend` value that selects this backend
+    pub fn name(&self) -> &'static str {
+        match self {
+            BackendKind::Rodio => "rodio",
+            BackendKind::Cpal(_) => "cpal",
+        }
+    }
+}
+
+/// Native format of a track, used to reopen the device in bit-perfect mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OutputFormat {
+    pub sample_rate: u32,
+    pub channels: u16,
+    pub bits: Option<u8>,
+}
+
+/// An opened audio output. The player connects one sink per track to its mixer;
+/// the output stays open for as long as the backend is alive.
+pub trait Backend {
+    fn mixer(&self) -> &Mixer;
+    /// Sample rate and channel count the device is currently running at
+    fn format(&self) -> (u32, u16);
+    /// Reopen the device at exactly `format` so nothing is resampled.
+    /// On error the current output is kept.
+    fn reopen(&mut self, format: OutputFormat) -> Result<(), String>;
+    /// Error the stream reported (device unplugged, sound server gone). The output
+    /// stays dead until `recover` succeeds.
+    fn error(&self) -> Option<String>;
+    /// Open the output again from scratch: the configured device if it's back,
+    /// else the system default
+    fn recover(&mut self) -> Result<(), String>;
+}
+
+/// Last error reported by the stream's callback, cleared when a new stream replaces it
+type StreamError = Arc<Mutex<Option<String>>>;
+
+/// Any backend that ends up as a cpal stream opened through rodio
+struct StreamBackend {
+    kind: BackendKind,
+    stream: OutputStream,
+    error: StreamError,
+}
+
+impl StreamBackend {
+    fn replace(&mut self, stream: OutputStream) {
+        // The old stream is dropped first, so a late error from it can't stick
+        self.stream = stream;
+        *self.error.lock().unwrap() = None;
+    }
+}
+
+impl Backend for StreamBackend {
+    fn mixer(&self) -> &Mixer {
+        self.stream.mixer()
+    }
+
+    fn format(&self) -> (u32, u16) {
+        let config = self.stream.config();
+        (config.sample_rate(), config.channel_count())
+    }
+
+    fn reopen(&mut self, format: OutputFormat) -> Result<(), String> {
+        // Integer formats matching the source depth first (what ALSA hw devices accept),
+        // then whatever sample format the device defaults to
+        let sample_formats: &[Option<SampleFormat>] = match format.bits {
+            Some(b) if b > 16 => &[Some(SampleFormat::I32), Some(SampleFormat::I24), None],
+            Some(_) => &[Some(SampleFormat::I16), None],
+            None => &[None],
+        };
+        let mut last_err = String::new();
+        for sample_format in sample_formats {
+            let mut builder = builder(&self.kind)?
+                .with_sample_rate(format.sample_rate)
+                .with_channels(format.channels);
+            if let Some(sf) = sample_format {
+                builder = builder.with_sample_format(*sf);
+            }
+            match watched(builder, &self.error) {
+                Ok(stream) => {
+                    self.replace(stream);
+                    return Ok(());
+                }
+                Err(e) => last_err = e,
+            }
+        }
+        Err(last_err)
+    }
+
+    fn error(&self) -> Option<String> {
+        self.error.lock().unwrap().clone()
+    }
+
+    fn recover(&mut self) -> Result<(), String> {
+        let stream = match self.kind {
+            BackendKind::Rodio => open_any(&self.error)?,
+            _ => builder(&self.kind)
+                .and_then(|b| watched(b, &self.error))
+                .or_else(|_| open_any(&self.error))?,
+        };
+        self.replace(stream);
+        Ok(())
+    }
+}
+
+/// Open the requested backend
+pub fn open(kind: &BackendKind) -> Result<Box<dyn Backend>, String> {
+    let error = StreamError::default();
+    let stream = match kind {
+        BackendKind::Rodio => open_any(&error)?,
+        _ => watched(builder(kind)?, &error)?,
+    };
+    Ok(Box::new(StreamBackend { kind: kind.clone(), stream, error }))
+}
+
+/// The default device, else the first output device that opens (like rodio's default stream)
+fn open_any(error: &StreamError) -> Result<OutputStream, String> {
+    builder(&BackendKind::Rodio)
+        .and_then(|b| watched(b, error))
+        .or_else(|first_err| {
+            rodio::cpal::default_host()
+                .output_devices()
+                .map_err(|e| e.to_string())?
+                .filter_map(|d| OutputStreamBuilder::from_device(d).ok())
+                .find_map(|b| watched(b, error).ok())
+                .ok_or(first_err)
+        })
+}
+
+/// Open a stream whose errors land in `error` instead of on stderr, over the TUI
+fn watched(builder: OutputStreamBuilder, error: &StreamError) -> Result<OutputStream, String> {
+    let error = Arc::clone(error);
+    let mut stream = builder
+        .with_error_callback(move |e| *error.lock().unwrap() = Some(e.to_string()))
+        .open_stream()
+        .map_err(|e| e.to_string())?;
+    // Reopening for bit-perfect playback drops streams; rodio would print over the TUI
+    stream.log_on_drop(false);
+    Ok(stream)
+}
+
+/// Stream builder for the backend's device with its default config
+fn builder(kind: &BackendKind) -> Result<OutputStreamBuilder, String> {
+    match kind {
+        BackendKind::Rodio | BackendKind::Cpal(None) => {
+            OutputStreamBuilder::from_default_device().map_err(|e| e.to_string())
+        }
+        BackendKind::Cpal(Some(device)) => named_builder(device),
+    }
+}
+
+/// Builder for the first output device whose name matches exactly, else the first containing `name`
+fn named_builder(name: &str) -> Result<OutputStreamBuilder, String> {
+    let devices: Vec<_> = rodio::cpal::default_host()
+        .output_devices()
+        .map_err(|e| e.to_string())?
+        .collect();
+    let names: Vec<String> = devices.iter().map(|d| d.name().unwrap_or_default()).collect();
+    let device = names
+        .iter()
+        .position(|n| n == name)
+        .or_else(|| names.iter().position(|n| n.contains(name)))
+        .and_then(|i| devices.into_iter().nth(i))
+        .ok_or_else(|| format!("audio device '{}' not found", name))?;
+    OutputStreamBuilder::from_device(device).map_err(|e| e.to_string())
+}
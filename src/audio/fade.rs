@@ -0,0 +1,72 @@
+use rodio::source::SeekError;
+use rodio::Source;
+use std::time::Duration;
+
+/// Gain ramp from silence over the first `fade_in_ms` of a track, and again after
+/// every seek, so playback never starts on a hard transient. A zero length passes
+/// samples through untouched.
+pub struct FadeIn<I> {
+    input: I,
+    /// Gain added per sample; 0 when fading is off
+    step: f32,
+    gain: f32,
+}
+
+impl<I: Source> FadeIn<I> {
+    pub fn new(input: I, length: Duration) -> Self {
+        let rate = input.sample_rate() as f32 * input.channels().max(1) as f32;
+        let samples = length.as_secs_f32() * rate;
+        let step = if samples >= 1.0 { 1.0 / samples } else { 0.0 };
+        Self {
+            input,
+            step,
+            gain: if step > 0.0 { 0.0 } else { 1.0 },
+        }
+    }
+}
+
+impl<I: Source> Iterator for FadeIn<I> {
+    type Item = f32;
+
+    #[inline]
+    fn next(&mut self) -> Option<f32> {
+        let x = self.input.next()?;
+        if self.gain >= 1.0 {
+            return Some(x);
+        }
+        let out = x * self.gain;
+        self.gain = (self.gain + self.step).min(1.0);
+        Some(out)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.input.size_hint()
+    }
+}
+
+impl<I: Source> Source for FadeIn<I> {
+    fn current_span_len(&self) -> Option<usize> {
+        self.input.current_span_len()
+    }
+
+    fn channels(&self) -> u16 {
+        self.input.channels()
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.input.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration()
+    }
+
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.input.try_seek(pos)?;
+        if self.step > 0.0 {
+            self.gain = 0.0;
+        }
+        Ok(())
+    }
+}
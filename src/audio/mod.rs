@@ -1,3 +1,7 @@
+pub mod backend;
+mod fade;
+mod night;
 pub mod player;
 
+pub use backend::BackendKind;
 pub use player::{AudioEngine, PlayerCommand};
@@ -0,0 +1,109 @@
+use rodio::source::SeekError;
+use rodio::Source;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Level above which peaks are squashed, in dBFS
+const THRESHOLD_DB: f32 = -30.0;
+/// Each dB over the threshold comes out as 1/RATIO dB
+const RATIO: f32 = 4.0;
+/// Lifts everything after compression so quiet passages stay audible
+const MAKEUP_DB: f32 = 9.0;
+/// Hard ceiling for whatever the envelope is too slow to catch
+const CEILING: f32 = 0.89; // -1 dBFS
+const ATTACK_SECS: f32 = 0.005;
+const RELEASE_SECS: f32 = 0.25;
+/// Switching on or off crossfades over this long instead of jumping
+const FADE_SECS: f32 = 0.05;
+
+/// Night mode: a compressor/limiter that narrows dynamics so loud peaks don't jump out
+/// at low listening levels. `enabled` is shared with the player so it can be toggled
+/// mid-track; while off (and faded out) samples pass through untouched.
+pub struct NightMode<I> {
+    input: I,
+    enabled: Arc<AtomicBool>,
+    attack: f32,
+    release: f32,
+    fade_step: f32,
+    /// Peak envelope, linear, linked across channels
+    envelope: f32,
+    /// 0 = dry, 1 = fully compressed
+    mix: f32,
+}
+
+impl<I: Source> NightMode<I> {
+    pub fn new(input: I, enabled: Arc<AtomicBool>) -> Self {
+        let rate = input.sample_rate() as f32 * input.channels().max(1) as f32;
+        let coef = |secs: f32| (-1.0 / (secs * rate)).exp();
+        let mix = if enabled.load(Ordering::Relaxed) { 1.0 } else { 0.0 };
+        Self {
+            attack: coef(ATTACK_SECS),
+            release: coef(RELEASE_SECS),
+            fade_step: 1.0 / (FADE_SECS * rate),
+            enabled,
+            input,
+            envelope: 0.0,
+            mix,
+        }
+    }
+
+    fn process(&mut self, x: f32) -> f32 {
+        let level = x.abs();
+        let coef = if level > self.envelope { self.attack } else { self.release };
+        self.envelope = coef * self.envelope + (1.0 - coef) * level;
+
+        let target = if self.enabled.load(Ordering::Relaxed) { 1.0 } else { 0.0 };
+        if self.mix < target {
+            self.mix = (self.mix + self.fade_step).min(1.0);
+        } else if self.mix > target {
+            self.mix = (self.mix - self.fade_step).max(0.0);
+        }
+        if self.mix == 0.0 {
+            return x;
+        }
+
+        let env_db = 20.0 * self.envelope.max(1e-6).log10();
+        let over = (env_db - THRESHOLD_DB).max(0.0);
+        let gain_db = MAKEUP_DB - over * (1.0 - 1.0 / RATIO);
+        let wet = (x * 10f32.powf(gain_db / 20.0)).clamp(-CEILING, CEILING);
+        x + (wet - x) * self.mix
+    }
+}
+
+impl<I: Source> Iterator for NightMode<I> {
+    type Item = f32;
+
+    #[inline]
+    fn next(&mut self) -> Option<f32> {
+        let x = self.input.next()?;
+        Some(self.process(x))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.input.size_hint()
+    }
+}
+
+impl<I: Source> Source for NightMode<I> {
+    fn current_span_len(&self) -> Option<usize> {
+        self.input.current_span_len()
+    }
+
+    fn channels(&self) -> u16 {
+        self.input.channels()
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.input.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration()
+    }
+
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.input.try_seek(pos)
+    }
+}
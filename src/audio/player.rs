@@ -1,11 +1,12 @@
 use anyhow::Result;
 use crossbeam_channel::{Receiver, Sender, select, tick};
 use rodio::buffer::SamplesBuffer;
-use rodio::mixer::Mixer;
-use rodio::{Decoder, OutputStreamBuilder, Sink, Source};
+use rodio::{Decoder, Sink, Source};
 use std::fs::File;
 use std::io::BufReader;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 use symphonia::core::audio::SampleBuffer;
 use symphonia::core::codecs::DecoderOptions;
@@ -14,16 +15,35 @@ use symphonia::core::io::MediaSourceStream;
 use symphonia::core::meta::MetadataOptions;
 use symphonia::core::probe::Hint;
 
+use super::backend::{self, Backend, BackendKind, OutputFormat};
+use super::fade::FadeIn;
+use super::night::NightMode;
 use crate::event::{AudioEvent, Event};
 
+/// How often a dead output is reopened
+const RECOVER_INTERVAL: Duration = Duration::from_secs(2);
+/// Length of a preview snippet
+const PREVIEW_LEN: Duration = Duration::from_secs(10);
+/// How far into the track a preview starts, past quiet intros
+const PREVIEW_START: f64 = 0.3;
+/// Fade at each end of a preview
+const PREVIEW_FADE: Duration = Duration::from_millis(400);
+
 #[derive(Debug, Clone)]
 pub enum PlayerCommand {
-    Play(PathBuf),
+    /// Start a track, scaled by `gain` (linear) for loudness normalization
+    Play { path: PathBuf, gain: f32 },
     Pause,
     Resume,
     Stop,
     SetVolume(f32),
     Seek(f64),
+    /// Compress loud peaks (takes effect mid-track)
+    SetNightMode(bool),
+    /// Play a short snippet of a track on a second sink, holding the main track
+    /// until it ends
+    Preview { path: PathBuf, volume: f32 },
+    StopPreview,
 }
 
 pub struct AudioEngine {
@@ -32,11 +52,12 @@ pub struct AudioEngine {
 }
 
 impl AudioEngine {
-    pub fn new(event_tx: Sender<Event>) -> Result<Self> {
+    /// `fade_in` ramps each track up from silence, and again after a seek (zero is off)
+    pub fn new(event_tx: Sender<Event>, backend: BackendKind, bit_perfect: bool, fade_in: Duration) -> Result<Self> {
         let (cmd_tx, cmd_rx) = crossbeam_channel::unbounded();
 
         let handle = std::thread::spawn(move || {
-            player_thread(cmd_rx, event_tx);
+            player_thread(cmd_rx, event_tx, backend, bit_perfect, fade_in);
         });
 
         Ok(Self {
@@ -50,29 +71,49 @@ impl AudioEngine {
     }
 }
 
-fn player_thread(cmd_rx: Receiver<PlayerCommand>, event_tx: Sender<Event>) {
-    let stream = match OutputStreamBuilder::open_default_stream() {
-        Ok(s) => s,
+fn player_thread(
+    cmd_rx: Receiver<PlayerCommand>,
+    event_tx: Sender<Event>,
+    kind: BackendKind,
+    bit_perfect: bool,
+    fade_in: Duration,
+) {
+    // Read by every track's `NightMode` source
+    let night = Arc::new(AtomicBool::new(false));
+    // A misconfigured backend falls back to rodio's default output rather than going silent
+    let output = match backend::open(&kind) {
+        Ok(b) => Ok(b),
+        Err(e) if kind != BackendKind::Rodio => {
+            let message = format!("{} ({}), using the default output", kind.name(), e);
+            let _ = event_tx.send(Event::Audio(AudioEvent::BackendFallback(message)));
+            backend::open(&BackendKind::Rodio)
+        }
+        Err(e) => Err(e),
+    };
+    let mut output = match output {
+        Ok(b) => b,
         Err(e) => {
-            eprintln!("Failed to open audio output: {}", e);
+            let _ = event_tx.send(Event::Audio(AudioEvent::NoOutput(e)));
             return;
         }
     };
 
-    let mixer = stream.mixer().clone();
     let position_ticker = tick(Duration::from_millis(250));
+    let mut last_recover = Instant::now();
+    let mut preview: Option<Sink> = None;
 
     loop {
         select! {
             recv(cmd_rx) -> msg => {
                 match msg {
-                    Ok(PlayerCommand::Play(path)) => {
-                        match open_and_play(&mixer, &path) {
+                    Ok(PlayerCommand::Play { path, gain }) => {
+                        end_preview(&mut preview, &event_tx);
+                        match open_and_play(&mut output, &path, gain, &night, bit_perfect, fade_in, &event_tx) {
                             Ok((sink, duration)) => {
                                 let _ = event_tx.send(Event::Audio(AudioEvent::Playing));
                                 run_playback_loop(
-                                    sink, &mixer, &cmd_rx, &event_tx,
-                                    &position_ticker, duration,
+                                    sink, &mut output, bit_perfect, fade_in, &night, &mut preview, &cmd_rx, &event_tx,
+                                    &position_ticker, path, gain, duration,
                                 );
                             }
                             Err(e) => {
@@ -81,18 +122,125 @@ fn player_thread(cmd_rx: Receiver<PlayerCommand>, event_tx: Sender<Event>) {
                         }
                     }
                     Ok(PlayerCommand::Stop) => {
+                        end_preview(&mut preview, &event_tx);
                         let _ = event_tx.send(Event::Audio(AudioEvent::Stopped));
                     }
+                    Ok(PlayerCommand::SetNightMode(on)) => night.store(on, Ordering::Relaxed),
+                    Ok(PlayerCommand::Preview { path, volume }) => {
+                        preview = start_preview(output.as_ref(), &path, volume, &event_tx);
+                    }
+                    Ok(PlayerCommand::StopPreview) => end_preview(&mut preview, &event_tx),
                     Ok(_) => {}
                     Err(_) => break,
                 }
             }
-            recv(position_ticker) -> _ => {}
+            recv(position_ticker) -> _ => {
+                if preview.as_ref().is_some_and(Sink::empty) {
+                    end_preview(&mut preview, &event_tx);
+                }
+                // Nothing is playing, so a lost device is just reopened quietly
+                if output.error().is_some() && last_recover.elapsed() >= RECOVER_INTERVAL {
+                    last_recover = Instant::now();
+                    let _ = output.recover();
+                }
+            }
         }
     }
 }
 
-fn open_and_play(mixer: &Mixer, path: &PathBuf) -> Result<(Sink, f64), String> {
+/// Start a preview snippet on a sink of its own, replacing any preview already
+/// playing. None (and `PreviewFinished`) if the track can't be opened.
+fn start_preview(output: &dyn Backend, path: &Path, volume: f32, event_tx: &Sender<Event>) -> Option<Sink> {
+    let (mut source, duration) = match open_source(path) {
+        Ok(opened) => opened,
+        Err(_) => {
+            let _ = event_tx.send(Event::Audio(AudioEvent::PreviewFinished));
+            return None;
+        }
+    };
+    // Into the track, but not so far that the snippet runs out early
+    let start = (duration * PREVIEW_START).min(duration - PREVIEW_LEN.as_secs_f64()).max(0.0);
+    let _ = source.try_seek(Duration::from_secs_f64(start));
+
+    let step = Duration::from_millis(20);
+    let fade = PREVIEW_FADE.as_secs_f32();
+    let len = PREVIEW_LEN.as_secs_f32();
+    let mut elapsed = Duration::ZERO;
+    let snippet = source.take_duration(PREVIEW_LEN).amplify(0.0).periodic_access(step, move |s| {
+        let t = elapsed.as_secs_f32();
+        s.set_factor((t / fade).min((len - t) / fade).clamp(0.0, 1.0));
+        elapsed += step;
+    });
+
+    let sink = Sink::connect_new(output.mixer());
+    sink.set_volume(volume);
+    sink.append(snippet);
+    Some(sink)
+}
+
+/// Stop the preview, if one is playing, and tell the app it's over
+fn end_preview(preview: &mut Option<Sink>, event_tx: &Sender<Event>) {
+    if preview.take().is_some() {
+        let _ = event_tx.send(Event::Audio(AudioEvent::PreviewFinished));
+    }
+}
+
+/// Start a track on the backend's mixer. In bit-perfect mode the device is first
+/// reopened at the track's native rate/channels/depth so rodio never resamples, and
+/// the decoded samples go to it untouched: no gain, night mode, fade or volume.
+fn open_and_play(
+    output: &mut Box<dyn Backend>,
+    path: &Path,
+    gain: f32,
+    night: &Arc<AtomicBool>,
+    bit_perfect: bool,
+    fade_in: Duration,
+    event_tx: &Sender<Event>,
+) -> Result<(Sink, f64), String> {
+    let (source, duration) = open_source(path)?;
+    if output.error().is_some() {
+        // Still dead: the playback loop keeps retrying
+        let _ = output.recover();
+    }
+
+    let native = OutputFormat {
+        sample_rate: source.sample_rate(),
+        channels: source.channels(),
+        bits: track_bit_depth(path),
+    };
+    if bit_perfect && output.format() != (native.sample_rate, native.channels) {
+        // Keep the current output (resampled) if the device rejects the native format
+        let _ = output.reopen(native);
+    }
+    let (sample_rate, channels) = output.format();
+    let _ = event_tx.send(Event::Audio(AudioEvent::OutputFormat {
+        stream: native,
+        sample_rate,
+        exact: (sample_rate, channels) == (native.sample_rate, native.channels),
+    }));
+
+    let sink = Sink::connect_new(output.mixer());
+    if bit_perfect {
+        sink.set_volume(1.0);
+        sink.append(source);
+        sink.play();
+        return Ok((sink, duration));
+    }
+    let source: Box<dyn Source + Send> = if gain == 1.0 { source } else { Box::new(source.amplify(gain)) };
+    // Samples pass through unchanged while night mode is off
+    sink.append(FadeIn::new(NightMode::new(source, Arc::clone(night)), fade_in));
+    sink.play();
+    Ok((sink, duration))
+}
+
+fn track_bit_depth(path: &Path) -> Option<u8> {
+    lofty::read_from_path(path)
+        .ok()
+        .and_then(|f| lofty::file::AudioFile::properties(&f).bit_depth())
+}
+
+/// Decode a track with rodio, falling back to symphonia, and return it with its duration
+fn open_source(path: &Path) -> Result<(Box<dyn Source + Send>, f64), String> {
     let ext = path
         .extension()
         .and_then(|e| e.to_str())
@@ -106,10 +254,7 @@ fn open_and_play(mixer: &Mixer, path: &PathBuf) -> Result<(Sink, f64), String> {
             let duration = Source::total_duration(&source)
                 .map(|d| d.as_secs_f64())
                 .unwrap_or(0.0);
-            let sink = Sink::connect_new(mixer);
-            sink.append(source);
-            sink.play();
-            return Ok((sink, duration));
+            return Ok((Box::new(source), duration));
         }
     }
 
@@ -127,19 +272,16 @@ fn open_and_play(mixer: &Mixer, path: &PathBuf) -> Result<(Sink, f64), String> {
             let duration = Source::total_duration(&source)
                 .map(|d| d.as_secs_f64())
                 .unwrap_or(0.0);
-            let sink = Sink::connect_new(mixer);
-            sink.append(source);
-            sink.play();
-            return Ok((sink, duration));
+            return Ok((Box::new(source), duration));
         }
     }
 
     // Fall back to symphonia direct decoding for m4a/mp4/etc
-    decode_with_symphonia(mixer, path)
+    decode_with_symphonia(path)
 }
 
-/// Decode using symphonia directly, buffer the entire track, and play via rodio Sink.
-fn decode_with_symphonia(mixer: &Mixer, path: &Path) -> Result<(Sink, f64), String> {
+/// Decode using symphonia directly and buffer the entire track for rodio.
+fn decode_with_symphonia(path: &Path) -> Result<(Box<dyn Source + Send>, f64), String> {
     let file = File::open(path).map_err(|e| format!("Open: {}", e))?;
     let mss = MediaSourceStream::new(Box::new(file), Default::default());
 
@@ -222,38 +364,59 @@ fn decode_with_symphonia(mixer: &Mixer, path: &Path) -> Result<(Sink, f64), Stri
         .map(|d| d.as_secs_f64())
         .unwrap_or(duration_secs);
 
-    let sink = Sink::connect_new(mixer);
-    sink.append(buffer);
-    sink.play();
-    Ok((sink, actual_duration))
+    Ok((Box::new(buffer), actual_duration))
 }
 
+#[allow(clippy::too_many_arguments)]
 fn run_playback_loop(
-    sink: Sink,
-    mixer: &Mixer,
+    mut sink: Sink,
+    output: &mut Box<dyn Backend>,
+    bit_perfect: bool,
+    fade_in: Duration,
+    night: &Arc<AtomicBool>,
+    preview: &mut Option<Sink>,
     cmd_rx: &Receiver<PlayerCommand>,
     event_tx: &Sender<Event>,
     position_ticker: &Receiver<Instant>,
+    path: PathBuf,
+    gain: f32,
     mut duration: f64,
 ) {
-    let mut play_start: Option<Instant> = Some(Instant::now());
-    let mut accumulated_secs: f64 = 0.0;
+    // Positions come from the sink (samples actually consumed), so a seek the decoder
+    // refuses or lands imprecisely shows up as-is instead of drifting from a wall clock
+    let report_position = |pos: Duration| {
+        let _ = event_tx.send(Event::Audio(AudioEvent::PositionUpdate {
+            position_secs: pos.as_secs_f64().min(duration),
+            duration_secs: duration,
+        }));
+    };
     let mut is_paused = false;
+    // The track is paused under a preview and picks up again when it ends
+    let mut held = false;
+    // Seek asked for while paused or held, made when playback picks up again. Until
+    // then it's the position reported, so the UI doesn't snap back.
+    let mut pending_seek: Option<Duration> = None;
+    // Set while the output is dead: where to resume, and when reopening was last tried
+    let mut lost: Option<(Duration, Instant)> = None;
 
     loop {
         select! {
             recv(cmd_rx) -> msg => {
                 match msg {
-                    Ok(PlayerCommand::Play(path)) => {
+                    Ok(PlayerCommand::Play { path, gain }) => {
+                        end_preview(preview, event_tx);
                         sink.stop();
-                        match open_and_play(mixer, &path) {
+                        let volume = sink.volume();
+                        match open_and_play(output, &path, gain, night, bit_perfect, fade_in, event_tx) {
                             Ok((new_sink, new_dur)) => {
-                                new_sink.set_volume(sink.volume());
+                                if !bit_perfect {
+                                    new_sink.set_volume(volume);
+                                }
                                 duration = new_dur;
                                 let _ = event_tx.send(Event::Audio(AudioEvent::Playing));
                                 run_playback_loop(
-                                    new_sink, mixer, cmd_rx, event_tx,
-                                    position_ticker, duration,
+                                    new_sink, output, bit_perfect, fade_in, night, preview, cmd_rx, event_tx,
+                                    position_ticker, path, gain, duration,
                                 );
                             }
                             Err(e) => {
@@ -263,61 +426,132 @@ fn run_playback_loop(
                         return;
                     }
                     Ok(PlayerCommand::Pause) => {
+                        end_preview(preview, event_tx);
+                        held = false;
                         if !is_paused {
                             sink.pause();
-                            if let Some(start) = play_start.take() {
-                                accumulated_secs += start.elapsed().as_secs_f64();
-                            }
                             is_paused = true;
                             let _ = event_tx.send(Event::Audio(AudioEvent::Paused));
                         }
                     }
                     Ok(PlayerCommand::Resume) => {
+                        end_preview(preview, event_tx);
+                        if std::mem::take(&mut held) {
+                            unpause(&sink, &mut pending_seek);
+                        }
                         if is_paused {
-                            sink.play();
-                            play_start = Some(Instant::now());
+                            unpause(&sink, &mut pending_seek);
                             is_paused = false;
                             let _ = event_tx.send(Event::Audio(AudioEvent::Playing));
                         }
                     }
                     Ok(PlayerCommand::Stop) => {
+                        end_preview(preview, event_tx);
                         sink.stop();
                         let _ = event_tx.send(Event::Audio(AudioEvent::Stopped));
                         return;
                     }
-                    Ok(PlayerCommand::SetVolume(vol)) => {
+                    // Bit-perfect output stays at unity
+                    Ok(PlayerCommand::SetVolume(vol)) if !bit_perfect => {
                         sink.set_volume(vol);
                     }
-                    Ok(PlayerCommand::Seek(secs)) => {
-                        if sink.try_seek(Duration::from_secs_f64(secs)).is_ok() {
-                            accumulated_secs = secs;
-                            if !is_paused {
-                                play_start = Some(Instant::now());
-                            }
+                    Ok(PlayerCommand::SetVolume(_)) => {}
+                    Ok(PlayerCommand::SetNightMode(on)) => night.store(on, Ordering::Relaxed),
+                    Ok(PlayerCommand::Preview { path, volume }) => {
+                        *preview = start_preview(output.as_ref(), &path, volume, event_tx);
+                        if preview.is_some() && !is_paused {
+                            sink.pause();
+                            held = true;
+                        } else if preview.is_none() && std::mem::take(&mut held) {
+                            unpause(&sink, &mut pending_seek);
+                        }
+                    }
+                    Ok(PlayerCommand::StopPreview) => {
+                        end_preview(preview, event_tx);
+                        if std::mem::take(&mut held) {
+                            unpause(&sink, &mut pending_seek);
                         }
                     }
+                    Ok(PlayerCommand::Seek(secs)) if lost.is_some() => {
+                        if let Some((resume_at, _)) = lost.as_mut() {
+                            *resume_at = Duration::from_secs_f64(secs);
+                        }
+                    }
+                    Ok(PlayerCommand::Seek(secs)) if is_paused || held => {
+                        pending_seek = Some(Duration::from_secs_f64(secs));
+                        report_position(Duration::from_secs_f64(secs));
+                    }
+                    Ok(PlayerCommand::Seek(secs)) => {
+                        // Report right away so the UI snaps to where playback really is,
+                        // including when the seek failed and position didn't move
+                        let _ = sink.try_seek(Duration::from_secs_f64(secs));
+                        report_position(sink.get_pos());
+                    }
                     Err(_) => return,
                 }
             }
             recv(position_ticker) -> _ => {
+                if preview.as_ref().is_some_and(Sink::empty) {
+                    end_preview(preview, event_tx);
+                    if std::mem::take(&mut held) {
+                        unpause(&sink, &mut pending_seek);
+                    }
+                }
+                if lost.is_none() {
+                    if let Some(err) = output.error() {
+                        let _ = event_tx.send(Event::Audio(AudioEvent::DeviceLost(err)));
+                        // Backdated so the first reopen is tried right away
+                        let tried = Instant::now().checked_sub(RECOVER_INTERVAL).unwrap_or_else(Instant::now);
+                        lost = Some((pending_seek.take().unwrap_or_else(|| sink.get_pos()), tried));
+                    }
+                }
+                if let Some((resume_at, tried)) = lost {
+                    if tried.elapsed() < RECOVER_INTERVAL {
+                        continue;
+                    }
+                    lost = Some((resume_at, Instant::now()));
+                    if output.recover().is_err() {
+                        continue;
+                    }
+                    // Same track on the new output, back where the old one stopped
+                    let volume = sink.volume();
+                    match open_and_play(output, &path, gain, night, bit_perfect, fade_in, event_tx) {
+                        Ok((new_sink, _)) => {
+                            if !bit_perfect {
+                                new_sink.set_volume(volume);
+                            }
+                            if is_paused || held {
+                                new_sink.pause();
+                                pending_seek = Some(resume_at);
+                            } else {
+                                let _ = new_sink.try_seek(resume_at);
+                            }
+                            sink = new_sink;
+                            lost = None;
+                            let _ = event_tx.send(Event::Audio(AudioEvent::DeviceRestored));
+                        }
+                        Err(e) => {
+                            let _ = event_tx.send(Event::Audio(AudioEvent::TrackError(e)));
+                            return;
+                        }
+                    }
+                }
+
                 if sink.empty() && !is_paused {
                     let _ = event_tx.send(Event::Audio(AudioEvent::TrackFinished));
                     return;
                 }
 
-                let pos = if is_paused {
-                    accumulated_secs
-                } else if let Some(start) = play_start {
-                    accumulated_secs + start.elapsed().as_secs_f64()
-                } else {
-                    accumulated_secs
-                };
-
-                let _ = event_tx.send(Event::Audio(AudioEvent::PositionUpdate {
-                    position_secs: pos.min(duration),
-                    duration_secs: duration,
-                }));
+                report_position(pending_seek.unwrap_or_else(|| sink.get_pos()));
             }
         }
     }
 }
+
+/// Play a paused sink again, first making the seek asked for while it was paused
+fn unpause(sink: &Sink, pending_seek: &mut Option<Duration>) {
+    if let Some(pos) = pending_seek.take() {
+        let _ = sink.try_seek(pos);
+    }
+    sink.play();
+}
@@ -0,0 +1,172 @@
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::audio::BackendKind;
+
+/// User configuration loaded from `~/.config/ommp/config.json`.
+/// Every field is optional in the file; missing fields use defaults.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// Glob patterns (relative to the music directory) the scanner and watcher skip
+    pub ignore: Vec<String>,
+    /// List "A feat. B" / multi-artist tags under each individual artist in the Artists tab
+    pub split_artists: bool,
+    /// Leading articles ignored when sorting artists/albums and ranking search ("The Beatles" under B)
+    pub sort_articles: Vec<String>,
+    /// Genre tag spellings mapped to one canonical name ("Alt Rock" → "Alternative Rock")
+    pub genre_aliases: HashMap<String, String>,
+    /// Show a "1-05" disc-track number column in the queue
+    pub queue_track_numbers: bool,
+    /// Show the clock time each upcoming queue entry should start at
+    pub queue_start_times: bool,
+    /// Audio output: "rodio" (default) or "cpal"
+    pub audio_backend: String,
+    /// Output device name for the "cpal" backend
+    pub audio_device: Option<String>,
+    /// Reopen the output at each track's native sample rate/channels/bit depth so nothing is
+    /// resampled, and skip volume, normalization, night mode and fade-in
+    pub bit_perfect: bool,
+    /// Milliseconds each track fades in over at its start and after a seek (0 disables)
+    pub fade_in_ms: u64,
+    /// Shuffle favors tracks with few plays in the history and none lately
+    pub weighted_shuffle: bool,
+    /// Shuffle avoids an artist heard in the last this many tracks, when the queue allows (0 disables)
+    pub shuffle_artist_spacing: usize,
+    /// Times `RepeatMode::Count` plays each track before moving on
+    pub repeat_count: u32,
+    /// Seconds moved by Left/Right
+    pub seek_step: f64,
+    /// Seconds moved by Shift+Left/Right
+    pub long_seek_step: f64,
+    /// Seconds moved by < / >
+    pub jump_step: f64,
+    /// React to keyboard media keys even when the terminal isn't focused (reads /dev/input)
+    pub global_hotkeys: bool,
+    /// Pause when the system suspends (logind PrepareForSleep, watched with `gdbus monitor`)
+    pub pause_on_suspend: bool,
+    /// Pause when the output device disappears, so playback doesn't carry on from another one
+    pub pause_on_disconnect: bool,
+    /// Tracks at least this many minutes long resume where they were left off (0 disables)
+    pub resume_min_minutes: f64,
+    /// Write the playing track to ~/.cache/ommp/nowplaying.{json,txt} for overlays and status bars
+    pub now_playing_file: bool,
+    /// Show the playing track in the OS media controls and take their buttons (macOS, Windows)
+    pub media_controls: bool,
+    /// Port for the HTTP remote-control API and web remote; None keeps it off
+    pub remote_port: Option<u16>,
+    /// Address the remote listens on; "0.0.0.0" to reach it from other devices
+    pub remote_bind: String,
+    /// Token every remote API call must carry when set; required to bind beyond loopback
+    pub remote_token: Option<String>,
+    /// Tokens that may only search the library and add to the queue; need `remote_token`
+    pub remote_guest_tokens: Vec<String>,
+    /// Hovering a pane focuses it; when off, focus changes on click (or keys) only
+    pub focus_follows_mouse: bool,
+    /// Single click in the library pane plays the item (old behavior) instead of only selecting it
+    pub single_click_activates: bool,
+    /// Lyrics text alignment: "left" or "center"
+    pub lyrics_align: String,
+    /// Blank columns kept on each side of the lyrics
+    pub lyrics_padding: u16,
+    /// Collapse runs of blank lyrics lines into one
+    pub lyrics_compress_blank: bool,
+    /// Play every track at the same loudness, from ReplayGain tags or measured values
+    pub normalize: bool,
+    /// Loudness `normalize` aims for, in LUFS
+    pub normalize_target: f64,
+    /// Hour (0-23) night mode switches on by itself; None leaves it to the key
+    pub night_mode_start: Option<u32>,
+    /// Hour night mode switches back off
+    pub night_mode_end: u32,
+    /// Startup splash shows the resumed track's album art instead of the logo
+    pub art_splash: bool,
+    /// Minutes without input before the screensaver comes up (0 disables)
+    pub screensaver_minutes: f64,
+    /// Megabytes of scaled album art kept in memory; least recently shown covers go first
+    pub art_cache_mb: f64,
+    /// Highest volume allowed, in percent; keys, mouse and restored state all stop here
+    pub max_volume: u32,
+    /// Warn in the status bar when the volume goes up past this percentage
+    pub volume_warning: Option<u32>,
+    /// Never write to the music directory (tags, moving files); also set by `--read-only`
+    pub read_only: bool,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            ignore: Vec::new(),
+            split_artists: true,
+            sort_articles: vec!["The".to_string()],
+            genre_aliases: HashMap::new(),
+            queue_track_numbers: false,
+            queue_start_times: false,
+            audio_backend: "rodio".to_string(),
+            audio_device: None,
+            bit_perfect: false,
+            fade_in_ms: 0,
+            weighted_shuffle: false,
+            shuffle_artist_spacing: 1,
+            repeat_count: 3,
+            seek_step: 5.0,
+            long_seek_step: 60.0,
+            jump_step: 600.0,
+            global_hotkeys: false,
+            pause_on_suspend: false,
+            pause_on_disconnect: false,
+            resume_min_minutes: 20.0,
+            now_playing_file: false,
+            media_controls: true,
+            remote_port: None,
+            remote_bind: "127.0.0.1".to_string(),
+            remote_token: None,
+            remote_guest_tokens: Vec::new(),
+            focus_follows_mouse: true,
+            single_click_activates: false,
+            lyrics_align: "left".to_string(),
+            lyrics_padding: 1,
+            lyrics_compress_blank: false,
+            normalize: false,
+            normalize_target: -18.0,
+            night_mode_start: None,
+            night_mode_end: 7,
+            art_splash: false,
+            screensaver_minutes: 0.0,
+            art_cache_mb: 64.0,
+            max_volume: 100,
+            volume_warning: None,
+            read_only: false,
+        }
+    }
+}
+
+fn config_path() -> PathBuf {
+    let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
+    PathBuf::from(home).join(".config/ommp/config.json")
+}
+
+impl Config {
+    pub fn backend_kind(&self) -> BackendKind {
+        BackendKind::from_config(&self.audio_backend, self.audio_device.as_deref())
+    }
+
+    /// `fade_in_ms`, capped at a second
+    pub fn fade_in(&self) -> std::time::Duration {
+        std::time::Duration::from_millis(self.fade_in_ms.min(1000))
+    }
+
+    /// `max_volume` as a 0.0–1.0 volume
+    pub fn volume_cap(&self) -> f32 {
+        self.max_volume.min(100) as f32 / 100.0
+    }
+
+    pub fn load() -> Self {
+        fs::read_to_string(config_path())
+            .ok()
+            .and_then(|data| serde_json::from_str(&data).ok())
+            .unwrap_or_default()
+    }
+}
@@ -0,0 +1,102 @@
+#[cfg(target_os = "linux")]
+pub use evdev::spawn_hotkey_listener;
+
+/// Global hotkeys read evdev devices, which only exist on Linux; elsewhere no
+/// device is ever watched
+#[cfg(not(target_os = "linux"))]
+pub fn spawn_hotkey_listener(_tx: crossbeam_channel::Sender<super::Event>) -> usize {
+    0
+}
+
+#[cfg(target_os = "linux")]
+mod evdev {
+    use crossbeam_channel::Sender;
+    use std::fs::File;
+    use std::io::Read;
+    use std::os::fd::AsRawFd;
+
+    use crate::event::{Event, MediaKey};
+
+    // linux/input-event-codes.h
+    const EV_KEY: u16 = 0x01;
+    const KEY_NEXTSONG: u16 = 163;
+    const KEY_PLAYPAUSE: u16 = 164;
+    const KEY_PREVIOUSSONG: u16 = 165;
+    const KEY_PLAYCD: u16 = 200;
+    const KEY_PAUSECD: u16 = 201;
+    const KEY_MAX: usize = 0x2ff;
+
+    fn media_key(code: u16) -> Option<MediaKey> {
+        match code {
+            KEY_PLAYPAUSE => Some(MediaKey::PlayPause),
+            KEY_PLAYCD => Some(MediaKey::Play),
+            KEY_PAUSECD => Some(MediaKey::Pause),
+            KEY_NEXTSONG => Some(MediaKey::Next),
+            KEY_PREVIOUSSONG => Some(MediaKey::Prev),
+            _ => None,
+        }
+    }
+
+    /// Listen for media keys on every evdev device that has them, without grabbing
+    /// the device, so they work while the terminal is unfocused. Needs read access
+    /// to `/dev/input/event*` (usually the `input` group). Returns how many devices
+    /// are being watched.
+    pub fn spawn_hotkey_listener(tx: Sender<Event>) -> usize {
+        let Ok(dir) = std::fs::read_dir("/dev/input") else {
+            return 0;
+        };
+        let mut watched = 0;
+        for entry in dir.flatten() {
+            let is_event_dev = entry.file_name().to_string_lossy().starts_with("event");
+            if !is_event_dev {
+                continue;
+            }
+            let Ok(file) = File::open(entry.path()) else {
+                continue;
+            };
+            if !has_media_keys(&file) {
+                continue;
+            }
+            let tx = tx.clone();
+            let spawned = std::thread::Builder::new()
+                .name("hotkeys".into())
+                .spawn(move || read_events(file, tx));
+            if spawned.is_ok() {
+                watched += 1;
+            }
+        }
+        watched
+    }
+
+    /// EVIOCGBIT(EV_KEY): does the device report play/pause? Filters out mice,
+    /// lid switches and the like.
+    fn has_media_keys(file: &File) -> bool {
+        let mut bits = [0u8; KEY_MAX / 8 + 1];
+        // _IOC(_IOC_READ, 'E', 0x20 + EV_KEY, len)
+        let request = (2 << 30) | ((bits.len() as u32) << 16) | ((b'E' as u32) << 8) | (0x20 + EV_KEY as u32);
+        let ret = unsafe { libc::ioctl(file.as_raw_fd(), request as _, bits.as_mut_ptr()) };
+        let bit = |code: u16| bits[code as usize / 8] & (1 << (code % 8)) != 0;
+        ret >= 0 && (bit(KEY_PLAYPAUSE) || bit(KEY_NEXTSONG))
+    }
+
+    fn read_events(mut file: File, tx: Sender<Event>) {
+        // struct input_event: timeval, then u16 type, u16 code, i32 value
+        let size = std::mem::size_of::<libc::input_event>();
+        let header = std::mem::size_of::<libc::timeval>();
+        let mut buf = vec![0u8; size];
+        while file.read_exact(&mut buf).is_ok() {
+            let kind = u16::from_ne_bytes([buf[header], buf[header + 1]]);
+            let code = u16::from_ne_bytes([buf[header + 2], buf[header + 3]]);
+            let value = i32::from_ne_bytes([buf[header + 4], buf[header + 5], buf[header + 6], buf[header + 7]]);
+            // value 1 = press; ignore releases (0) and autorepeat (2)
+            if kind != EV_KEY || value != 1 {
+                continue;
+            }
+            if let Some(key) = media_key(code) {
+                if tx.send(Event::MediaKey(key)).is_err() {
+                    break;
+                }
+            }
+        }
+    }
+}
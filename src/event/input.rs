@@ -8,25 +8,15 @@ pub fn spawn_input_thread(tx: Sender<Event>) -> std::thread::JoinHandle<()> {
     std::thread::spawn(move || {
         loop {
             if event::poll(Duration::from_millis(100)).unwrap_or(false) {
-                match event::read() {
-                    Ok(CtEvent::Key(key)) => {
-                        if key.kind == KeyEventKind::Press
-                            && tx.send(Event::Key(key)).is_err()
-                        {
-                            break;
-                        }
-                    }
-                    Ok(CtEvent::Mouse(mouse)) => {
-                        if tx.send(Event::Mouse(mouse)).is_err() {
-                            break;
-                        }
-                    }
-                    Ok(CtEvent::Resize(w, h)) => {
-                        if tx.send(Event::Resize(w, h)).is_err() {
-                            break;
-                        }
-                    }
-                    _ => {}
+                let event = match event::read() {
+                    Ok(CtEvent::Key(key)) if key.kind == KeyEventKind::Press => Event::Key(key),
+                    Ok(CtEvent::Mouse(mouse)) => Event::Mouse(mouse),
+                    Ok(CtEvent::Paste(text)) => Event::Paste(text),
+                    Ok(CtEvent::Resize(w, h)) => Event::Resize(w, h),
+                    _ => continue,
+                };
+                if tx.send(event).is_err() {
+                    break;
                 }
             }
         }
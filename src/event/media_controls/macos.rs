@@ -0,0 +1,205 @@
+//! macOS Now Playing: the track shows in Control Center and on the lock screen, and
+//! the keyboard's media keys, AirPods and Control Center's buttons drive ommp.
+
+use crossbeam_channel::Sender;
+use objc2::rc::{autoreleasepool, Retained};
+use objc2::runtime::{AnyObject, NSObject, Sel};
+use objc2::{class, define_class, msg_send, sel, AllocAnyThread, DefinedClass};
+use std::ffi::{c_void, CString};
+
+use crate::app::state::PlayState;
+use crate::app::App;
+use crate::event::{Event, MediaKey};
+
+// MPNowPlayingPlaybackState
+const PLAYBACK_PLAYING: usize = 1;
+const PLAYBACK_PAUSED: usize = 2;
+const PLAYBACK_STOPPED: usize = 3;
+// MPRemoteCommandHandlerStatus
+const COMMAND_SUCCESS: isize = 0;
+const COMMAND_FAILED: isize = 200;
+
+#[link(name = "MediaPlayer", kind = "framework")]
+extern "C" {
+    static MPMediaItemPropertyTitle: *const AnyObject;
+    static MPMediaItemPropertyArtist: *const AnyObject;
+    static MPMediaItemPropertyAlbumTitle: *const AnyObject;
+    static MPMediaItemPropertyPlaybackDuration: *const AnyObject;
+    static MPNowPlayingInfoPropertyElapsedPlaybackTime: *const AnyObject;
+    static MPNowPlayingInfoPropertyPlaybackRate: *const AnyObject;
+}
+
+#[link(name = "CoreFoundation", kind = "framework")]
+extern "C" {
+    static kCFRunLoopDefaultMode: *const c_void;
+    fn CFRunLoopRunInMode(mode: *const c_void, seconds: f64, return_after_source_handled: u8) -> i32;
+}
+
+#[derive(Debug)]
+struct Ivars {
+    tx: Sender<Event>,
+}
+
+define_class!(
+    // SAFETY: NSObject has no subclassing requirements and CommandTarget has no Drop
+    #[unsafe(super(NSObject))]
+    #[ivars = Ivars]
+    struct CommandTarget;
+
+    impl CommandTarget {
+        #[unsafe(method(togglePlayPause:))]
+        fn toggle_play_pause(&self, _event: *mut AnyObject) -> isize {
+            self.send(MediaKey::PlayPause)
+        }
+
+        #[unsafe(method(play:))]
+        fn play(&self, _event: *mut AnyObject) -> isize {
+            self.send(MediaKey::Play)
+        }
+
+        #[unsafe(method(pause:))]
+        fn pause(&self, _event: *mut AnyObject) -> isize {
+            self.send(MediaKey::Pause)
+        }
+
+        #[unsafe(method(nextTrack:))]
+        fn next_track(&self, _event: *mut AnyObject) -> isize {
+            self.send(MediaKey::Next)
+        }
+
+        #[unsafe(method(previousTrack:))]
+        fn previous_track(&self, _event: *mut AnyObject) -> isize {
+            self.send(MediaKey::Prev)
+        }
+    }
+);
+
+impl CommandTarget {
+    fn new(tx: Sender<Event>) -> Retained<Self> {
+        let this = Self::alloc().set_ivars(Ivars { tx });
+        unsafe { msg_send![super(this), init] }
+    }
+
+    fn send(&self, key: MediaKey) -> isize {
+        match self.ivars().tx.send(Event::MediaKey(key)) {
+            Ok(()) => COMMAND_SUCCESS,
+            Err(_) => COMMAND_FAILED,
+        }
+    }
+}
+
+/// Registered with the shared MPRemoteCommandCenter for as long as it lives. Must be
+/// created and used on the main thread, which also delivers the button presses in `poll`.
+pub struct MediaControls {
+    target: Retained<CommandTarget>,
+}
+
+impl MediaControls {
+    pub fn new(tx: Sender<Event>) -> Option<Self> {
+        let target = CommandTarget::new(tx);
+        autoreleasepool(|_| unsafe {
+            let center: *mut AnyObject = msg_send![class!(MPRemoteCommandCenter), sharedCommandCenter];
+            if center.is_null() {
+                return None;
+            }
+            for (getter, action) in commands() {
+                let command: *mut AnyObject = msg_send![center, performSelector: getter];
+                if !command.is_null() {
+                    let _: () = msg_send![command, setEnabled: true];
+                    // Returns an opaque handler token (id), unused: removeTarget: takes the target
+                    let _: *mut AnyObject = msg_send![command, addTarget: &*target, action: action];
+                }
+            }
+            Some(())
+        })?;
+        Some(Self { target })
+    }
+
+    /// Publish the current track and play state to the Now Playing widget
+    pub fn update(&mut self, app: &App) {
+        let track = app.current_track().filter(|_| app.playback.state != PlayState::Stopped);
+        autoreleasepool(|_| unsafe {
+            let center: *mut AnyObject = msg_send![class!(MPNowPlayingInfoCenter), defaultCenter];
+            if center.is_null() {
+                return;
+            }
+            let Some(track) = track else {
+                let _: () = msg_send![center, setNowPlayingInfo: std::ptr::null::<AnyObject>()];
+                let _: () = msg_send![center, setPlaybackState: PLAYBACK_STOPPED];
+                return;
+            };
+            let playing = app.playback.state == PlayState::Playing;
+            let info: *mut AnyObject = msg_send![class!(NSMutableDictionary), dictionary];
+            let entries = [
+                (MPMediaItemPropertyTitle, ns_string(&track.title)),
+                (MPMediaItemPropertyArtist, ns_string(track.display_artist())),
+                (MPMediaItemPropertyAlbumTitle, ns_string(track.display_album())),
+                (MPMediaItemPropertyPlaybackDuration, ns_number(app.playback.duration_secs)),
+                (MPNowPlayingInfoPropertyElapsedPlaybackTime, ns_number(app.playback.position_secs)),
+                // The widget advances the elapsed time itself at this rate
+                (MPNowPlayingInfoPropertyPlaybackRate, ns_number(if playing { 1.0 } else { 0.0 })),
+            ];
+            for (key, value) in entries {
+                if !value.is_null() {
+                    let _: () = msg_send![info, setObject: value, forKey: key];
+                }
+            }
+            let _: () = msg_send![center, setNowPlayingInfo: info];
+            let state = if playing { PLAYBACK_PLAYING } else { PLAYBACK_PAUSED };
+            let _: () = msg_send![center, setPlaybackState: state];
+        });
+    }
+
+    /// Run the main run loop briefly so queued button presses are handled; the
+    /// terminal event loop owns the main thread, so nothing else does
+    pub fn poll(&mut self) {
+        unsafe {
+            CFRunLoopRunInMode(kCFRunLoopDefaultMode, 0.0, 1);
+        }
+    }
+}
+
+impl Drop for MediaControls {
+    fn drop(&mut self) {
+        autoreleasepool(|_| unsafe {
+            let center: *mut AnyObject = msg_send![class!(MPRemoteCommandCenter), sharedCommandCenter];
+            if center.is_null() {
+                return;
+            }
+            for (getter, _) in commands() {
+                let command: *mut AnyObject = msg_send![center, performSelector: getter];
+                if !command.is_null() {
+                    let _: () = msg_send![command, removeTarget: &*self.target];
+                }
+            }
+            let info: *mut AnyObject = msg_send![class!(MPNowPlayingInfoCenter), defaultCenter];
+            if !info.is_null() {
+                let _: () = msg_send![info, setNowPlayingInfo: std::ptr::null::<AnyObject>()];
+            }
+        });
+    }
+}
+
+/// MPRemoteCommandCenter getter for each command handled, and the CommandTarget method for it
+fn commands() -> [(Sel, Sel); 5] {
+    [
+        (sel!(togglePlayPauseCommand), sel!(togglePlayPause:)),
+        (sel!(playCommand), sel!(play:)),
+        (sel!(pauseCommand), sel!(pause:)),
+        (sel!(nextTrackCommand), sel!(nextTrack:)),
+        (sel!(previousTrackCommand), sel!(previousTrack:)),
+    ]
+}
+
+/// Autoreleased NSString; null if the text has an interior NUL
+unsafe fn ns_string(s: &str) -> *mut AnyObject {
+    match CString::new(s) {
+        Ok(c) => msg_send![class!(NSString), stringWithUTF8String: c.as_ptr()],
+        Err(_) => std::ptr::null_mut(),
+    }
+}
+
+/// Autoreleased NSNumber
+unsafe fn ns_number(value: f64) -> *mut AnyObject {
+    msg_send![class!(NSNumber), numberWithDouble: value]
+}
@@ -0,0 +1,37 @@
+//! The operating system's own now-playing display and media buttons: macOS Now
+//! Playing and Windows SMTC, nothing elsewhere. Button presses come back as
+//! `Event::MediaKey`, the same as `global_hotkeys`.
+
+#[cfg(all(target_os = "macos", feature = "media-controls"))]
+mod macos;
+#[cfg(all(target_os = "macos", feature = "media-controls"))]
+pub use macos::MediaControls;
+
+#[cfg(all(windows, feature = "media-controls"))]
+mod smtc;
+#[cfg(all(windows, feature = "media-controls"))]
+pub use smtc::MediaControls;
+
+#[cfg(not(all(any(target_os = "macos", windows), feature = "media-controls")))]
+pub use unsupported::MediaControls;
+
+#[cfg(not(all(any(target_os = "macos", windows), feature = "media-controls")))]
+mod unsupported {
+    use crossbeam_channel::Sender;
+
+    use crate::app::App;
+    use crate::event::Event;
+
+    /// Nothing to integrate with on this platform
+    pub struct MediaControls;
+
+    impl MediaControls {
+        pub fn new(_tx: Sender<Event>) -> Option<Self> {
+            None
+        }
+
+        pub fn update(&mut self, _app: &App) {}
+
+        pub fn poll(&mut self) {}
+    }
+}
@@ -0,0 +1,112 @@
+//! Windows System Media Transport Controls: the track shows in the volume / media
+//! flyout and on the lock screen, and the media keys and the flyout's buttons drive ommp.
+
+use crossbeam_channel::Sender;
+use windows::core::{Result, HSTRING};
+use windows::Foundation::{EventRegistrationToken, TimeSpan, TypedEventHandler};
+use windows::Media::Playback::MediaPlayer;
+use windows::Media::{
+    MediaPlaybackStatus, MediaPlaybackType, SystemMediaTransportControls, SystemMediaTransportControlsButton,
+    SystemMediaTransportControlsButtonPressedEventArgs, SystemMediaTransportControlsTimelineProperties,
+};
+
+use crate::app::state::PlayState;
+use crate::app::App;
+use crate::event::{Event, MediaKey};
+
+/// A console app has no window to attach SMTC to, so a silent MediaPlayer lends its
+/// controls; its own handling of them is switched off. Button presses arrive on a
+/// WinRT worker thread.
+pub struct MediaControls {
+    _player: MediaPlayer,
+    controls: SystemMediaTransportControls,
+    token: EventRegistrationToken,
+}
+
+impl MediaControls {
+    pub fn new(tx: Sender<Event>) -> Option<Self> {
+        Self::open(tx).ok()
+    }
+
+    fn open(tx: Sender<Event>) -> Result<Self> {
+        let player = MediaPlayer::new()?;
+        player.CommandManager()?.SetIsEnabled(false)?;
+        let controls = player.SystemMediaTransportControls()?;
+        controls.SetIsEnabled(true)?;
+        controls.SetIsPlayEnabled(true)?;
+        controls.SetIsPauseEnabled(true)?;
+        controls.SetIsNextEnabled(true)?;
+        controls.SetIsPreviousEnabled(true)?;
+        let handler = TypedEventHandler::<SystemMediaTransportControls, SystemMediaTransportControlsButtonPressedEventArgs>::new(
+            move |_, args| {
+                let Some(args) = args else { return Ok(()) };
+                let key = match args.Button()? {
+                    SystemMediaTransportControlsButton::Play => MediaKey::Play,
+                    SystemMediaTransportControlsButton::Pause => MediaKey::Pause,
+                    SystemMediaTransportControlsButton::Next => MediaKey::Next,
+                    SystemMediaTransportControlsButton::Previous => MediaKey::Prev,
+                    _ => return Ok(()),
+                };
+                let _ = tx.send(Event::MediaKey(key));
+                Ok(())
+            },
+        );
+        let token = controls.ButtonPressed(&handler)?;
+        Ok(Self { _player: player, controls, token })
+    }
+
+    /// Publish the current track, play state and timeline to the flyout
+    pub fn update(&mut self, app: &App) {
+        let _ = self.publish(app);
+    }
+
+    fn publish(&self, app: &App) -> Result<()> {
+        let updater = self.controls.DisplayUpdater()?;
+        let track = app.current_track().filter(|_| app.playback.state != PlayState::Stopped);
+        let Some(track) = track else {
+            updater.ClearAll()?;
+            updater.Update()?;
+            return self.controls.SetPlaybackStatus(MediaPlaybackStatus::Stopped);
+        };
+        updater.SetType(MediaPlaybackType::Music)?;
+        let music = updater.MusicProperties()?;
+        music.SetTitle(&HSTRING::from(track.title.as_str()))?;
+        music.SetArtist(&HSTRING::from(track.display_artist()))?;
+        music.SetAlbumTitle(&HSTRING::from(track.display_album()))?;
+        music.SetAlbumArtist(&HSTRING::from(track.album_owner()))?;
+        updater.Update()?;
+
+        let timeline = SystemMediaTransportControlsTimelineProperties::new()?;
+        timeline.SetStartTime(time_span(0.0))?;
+        timeline.SetEndTime(time_span(app.playback.duration_secs))?;
+        timeline.SetMinSeekTime(time_span(0.0))?;
+        timeline.SetMaxSeekTime(time_span(app.playback.duration_secs))?;
+        timeline.SetPosition(time_span(app.playback.position_secs))?;
+        self.controls.UpdateTimelineProperties(&timeline)?;
+
+        let status = if app.playback.state == PlayState::Playing {
+            MediaPlaybackStatus::Playing
+        } else {
+            MediaPlaybackStatus::Paused
+        };
+        self.controls.SetPlaybackStatus(status)
+    }
+
+    /// Button presses don't need the main thread here
+    pub fn poll(&mut self) {}
+}
+
+impl Drop for MediaControls {
+    fn drop(&mut self) {
+        let _ = self.controls.RemoveButtonPressed(self.token);
+        if let Ok(updater) = self.controls.DisplayUpdater() {
+            let _ = updater.ClearAll();
+            let _ = updater.Update();
+        }
+    }
+}
+
+/// WinRT durations count 100 ns ticks
+fn time_span(secs: f64) -> TimeSpan {
+    TimeSpan { Duration: (secs * 10_000_000.0) as i64 }
+}
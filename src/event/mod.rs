@@ -1,4 +1,12 @@
+pub mod hotkeys;
 pub mod input;
+pub mod media_controls;
+pub mod remote;
+pub mod suspend;
+
+use crossterm::event::MouseEventKind;
+
+pub use crate::audio::backend::OutputFormat as StreamFormat;
 
 #[derive(Debug)]
 #[allow(dead_code)]
@@ -6,18 +14,97 @@ pub enum Event {
     Key(crossterm::event::KeyEvent),
     Mouse(crossterm::event::MouseEvent),
     Resize(u16, u16),
+    /// Text pasted into the terminal (bracketed paste)
+    Paste(String),
     Tick,
     Audio(AudioEvent),
-    LibraryReady(crate::library::Library),
+    LibraryReady(Box<crate::library::Library>),
+    /// Results from the background search worker, tagged with the request generation
+    SearchResults { generation: u64, results: Vec<usize> },
+    /// Batch tag write finished: files written and (path, error) for failures
+    TagsWritten { written: usize, failed: Vec<(std::path::PathBuf, String)> },
+    /// Organize finished: files moved and (path, error) for failures
+    FilesMoved { moved: Vec<crate::library::organize::Move>, failed: Vec<(std::path::PathBuf, String)> },
+    /// Media key pressed anywhere on the system (`global_hotkeys`)
+    MediaKey(MediaKey),
+    /// The system is about to suspend (`pause_on_suspend`)
+    Suspend,
+    /// A request to the HTTP remote (`remote_port`)
+    Remote(remote::Request),
+    /// Loudness analysis measured a track (None: couldn't decode it)
+    LoudnessMeasured { path: std::path::PathBuf, lufs: Option<f64> },
+    LoudnessFinished,
+    /// Chapter markers of a track that started playing
+    ChaptersRead { path: std::path::PathBuf, chapters: Vec<crate::library::chapters::Chapter> },
+}
+
+/// Media keys picked up outside the terminal, from evdev (`global_hotkeys`) or the
+/// OS media controls
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MediaKey {
+    PlayPause,
+    Play,
+    Pause,
+    Next,
+    Prev,
 }
 
 #[derive(Debug, Clone)]
 #[allow(dead_code)]
 pub enum AudioEvent {
     PositionUpdate { position_secs: f64, duration_secs: f64 },
+    /// Decoded stream format of the track and the device rate it plays at;
+    /// `exact` when the device matches the stream (no resampling)
+    OutputFormat { stream: StreamFormat, sample_rate: u32, exact: bool },
     TrackFinished,
     TrackError(String),
+    /// The output device failed (unplugged, sound server gone); the player keeps reopening it
+    DeviceLost(String),
+    /// Playback resumed on a reopened output
+    DeviceRestored,
+    /// The configured `audio_backend` couldn't be opened; playing through rodio's default instead
+    BackendFallback(String),
+    /// No audio output could be opened at all; the player thread has exited
+    NoOutput(String),
+    /// A preview snippet played out or was stopped
+    PreviewFinished,
     Playing,
     Paused,
     Stopped,
 }
+
+/// Upper bound on events drained per frame so a flood can't starve rendering
+pub const MAX_BATCH: usize = 256;
+
+/// Drop events superseded by the next one in the batch: consecutive mouse
+/// moves/drags, ticks, resizes and position updates only matter for their latest value.
+pub fn coalesce(events: Vec<Event>) -> Vec<Event> {
+    let mut out: Vec<Event> = Vec::with_capacity(events.len());
+    for event in events {
+        if out.last().is_some_and(|prev| superseded_by(prev, &event)) {
+            out.pop();
+        }
+        out.push(event);
+    }
+    out
+}
+
+fn superseded_by(prev: &Event, next: &Event) -> bool {
+    match (prev, next) {
+        (Event::Mouse(a), Event::Mouse(b)) => {
+            a.modifiers == b.modifiers
+                && match (a.kind, b.kind) {
+                    (MouseEventKind::Moved, MouseEventKind::Moved) => true,
+                    (MouseEventKind::Drag(x), MouseEventKind::Drag(y)) => x == y,
+                    _ => false,
+                }
+        }
+        (Event::Tick, Event::Tick) => true,
+        (Event::Resize(..), Event::Resize(..)) => true,
+        (
+            Event::Audio(AudioEvent::PositionUpdate { .. }),
+            Event::Audio(AudioEvent::PositionUpdate { .. }),
+        ) => true,
+        _ => false,
+    }
+}
@@ -0,0 +1,427 @@
+//! HTTP remote control (`remote_port`): a small JSON API for playback status and
+//! control, plus a one-page web remote at `/` for a phone on the couch.
+//!
+//! Requests are parsed on the server thread and handed to the main loop as
+//! `Event::Remote`; the main loop runs them like key presses and answers with the
+//! resulting status, so the API never touches `App` from another thread.
+//!
+//! Guest tokens (`remote_guest_tokens`) can only look: status, the upcoming queue
+//! and library search, plus appending tracks to the queue. Skipping, seeking,
+//! volume and the rest stay with the owner's `remote_token`.
+
+use crossbeam_channel::{bounded, Sender};
+use serde_json::{json, Value};
+use std::io::Cursor;
+use std::net::{IpAddr, SocketAddr};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tiny_http::{Header, Method, Response, Server};
+
+use super::Event;
+use crate::app::state::PlayState;
+use crate::app::{App, AppAction};
+use crate::library::track::Track;
+use crate::library::Library;
+
+const PAGE: &str = include_str!("remote.html");
+/// How long a request waits for the main loop before giving up
+const REPLY_TIMEOUT: Duration = Duration::from_secs(2);
+/// Most search results and upcoming queue entries returned
+const MAX_ENTRIES: usize = 50;
+
+/// What a request's token lets it do
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Access {
+    Owner,
+    /// Search and add to the queue only
+    Guest,
+}
+
+/// Tokens the server accepts. Without an owner token the API is open to anyone who
+/// can reach it, so the server won't start with guest tokens alone.
+pub struct Tokens {
+    pub owner: Option<String>,
+    pub guests: Vec<String>,
+}
+
+impl Tokens {
+    fn access(&self, given: Option<&str>) -> Option<Access> {
+        match (&self.owner, given) {
+            (None, _) => Some(Access::Owner),
+            (Some(owner), Some(given)) if same_token(owner, given) => Some(Access::Owner),
+            // Every guest token is compared, so the timing doesn't tell which one was close
+            (Some(_), Some(given)) if self.guests.iter().fold(false, |found, g| found | same_token(g, given)) => {
+                Some(Access::Guest)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Token comparison that takes as long for a near miss as for a wrong first byte
+fn same_token(expected: &str, given: &str) -> bool {
+    let (a, b) = (expected.as_bytes(), given.as_bytes());
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+/// What a remote request asks of the player
+#[derive(Debug)]
+pub enum Command {
+    Status,
+    PlayPause,
+    Next,
+    Prev,
+    /// Percent
+    Volume(u32),
+    /// Seconds into the current track
+    Seek(f64),
+    Shuffle,
+    Repeat,
+    /// Entries after the playing one
+    Queue,
+    Search(String),
+    /// Append a library track to the queue
+    Enqueue(PathBuf),
+}
+
+impl Command {
+    /// Whether a guest token may send this
+    fn guest_allowed(&self) -> bool {
+        matches!(self, Command::Status | Command::Queue | Command::Search(_) | Command::Enqueue(_))
+    }
+
+    fn actions(&self) -> Vec<AppAction> {
+        match *self {
+            Command::Status | Command::Queue | Command::Search(_) | Command::Enqueue(_) => vec![],
+            Command::PlayPause => vec![AppAction::PauseResume],
+            Command::Next => vec![AppAction::NextTrack],
+            Command::Prev => vec![AppAction::PrevTrack],
+            Command::Volume(pct) => vec![AppAction::SetVolume(pct.min(100) as f32 / 100.0)],
+            Command::Seek(secs) => vec![AppAction::Seek(secs.max(0.0))],
+            Command::Shuffle => vec![AppAction::ToggleShuffle],
+            Command::Repeat => vec![AppAction::CycleRepeat],
+        }
+    }
+}
+
+/// A command for the main loop, which answers on `reply` once it has run
+#[derive(Debug)]
+pub struct Request {
+    pub command: Command,
+    pub access: Access,
+    pub reply: Sender<Reply>,
+}
+
+#[derive(Debug)]
+pub enum Reply {
+    Json(Value),
+    /// The library, for a search to run on the server thread instead of the main loop
+    Library(Arc<Library>),
+    Error(u16, String),
+}
+
+/// Carry out a request on the main loop and answer it. Returns whether anything
+/// on screen may have changed.
+pub fn answer(request: Request, app: &mut App) -> bool {
+    let (reply, changed) = match request.command {
+        Command::Status => (Reply::Json(status(app)), false),
+        Command::Queue => (Reply::Json(upcoming(app)), false),
+        Command::Search(_) => (Reply::Library(Arc::clone(&app.library)), false),
+        Command::Enqueue(path) => match app.library.track_by_path(&path).map(|t| t.title.clone()) {
+            None => (Reply::Error(404, "not in the library".to_string()), false),
+            Some(title) => {
+                app.handle_action(AppAction::AppendToQueue(vec![path]));
+                if request.access == Access::Guest {
+                    app.notify(format!("A guest added \u{201c}{}\u{201d} at #{}", title, app.queue.tracks.len()));
+                }
+                (Reply::Json(status(app)), true)
+            }
+        },
+        command => {
+            for action in command.actions() {
+                app.handle_action(action);
+            }
+            (Reply::Json(status(app)), true)
+        }
+    };
+    let _ = request.reply.send(reply);
+    changed
+}
+
+/// Playback status as `GET /api/status` reports it
+pub fn status(app: &App) -> Value {
+    let track = app.current_track().filter(|_| app.playback.state != PlayState::Stopped);
+    json!({
+        "state": match app.playback.state {
+            PlayState::Playing => "playing",
+            PlayState::Paused => "paused",
+            PlayState::Stopped => "stopped",
+        },
+        "title": track.map(|t| t.title.as_str()),
+        "artist": track.map(|t| t.display_artist()),
+        "album": track.map(|t| t.display_album()),
+        "position": app.playback.position_secs,
+        "duration": app.playback.duration_secs,
+        "volume": (app.playback.volume * 100.0).round() as u32,
+        "shuffle": app.playback.shuffle,
+        "repeat": app.playback.repeat.as_str().to_lowercase(),
+        "queue_index": app.queue.current_index,
+        "queue_length": app.queue.tracks.len(),
+    })
+}
+
+fn track_json(track: &Track) -> Value {
+    json!({
+        "path": track.path,
+        "title": track.title,
+        "artist": track.display_artist(),
+        "album": track.display_album(),
+        "duration": track.duration.as_secs_f64(),
+    })
+}
+
+/// Up to `MAX_ENTRIES` queue entries after the playing one, for `GET /api/queue`
+fn upcoming(app: &App) -> Value {
+    let start = app.queue.current_index.map_or(0, |i| i + 1);
+    let entries: Vec<Value> = app
+        .queue
+        .tracks
+        .iter()
+        .skip(start)
+        .take(MAX_ENTRIES)
+        .filter_map(|path| app.library.track_by_path(path))
+        .map(track_json)
+        .collect();
+    json!({ "upcoming": entries })
+}
+
+/// Listen on `bind:port` and serve requests from a background thread. With an owner
+/// token, every API call must carry a token as `?token=` or an `Authorization: Bearer`
+/// header; the page itself is served to anyone and holds nothing. Without one the
+/// server only listens on loopback, and only takes API calls addressed to loopback.
+pub fn spawn_server(bind: &str, port: u16, tokens: Tokens, tx: Sender<Event>) -> anyhow::Result<()> {
+    let open = tokens.owner.is_none();
+    // Guests would silently get full control
+    if open && !tokens.guests.is_empty() {
+        anyhow::bail!("remote_guest_tokens need a remote_token");
+    }
+    if open && !is_loopback(bind) {
+        anyhow::bail!("listening on {} needs a remote_token", bind);
+    }
+    let server = Server::http((bind, port)).map_err(|e| anyhow::anyhow!(e))?;
+    std::thread::spawn(move || {
+        for request in server.incoming_requests() {
+            let response = if foreign_origin(&request, open) {
+                error(403, "cross-origin requests aren't accepted")
+            } else {
+                handle(&request, &tokens, &tx)
+            };
+            let _ = request.respond(response);
+        }
+    });
+    Ok(())
+}
+
+fn handle(request: &tiny_http::Request, tokens: &Tokens, tx: &Sender<Event>) -> Response<Cursor<Vec<u8>>> {
+    let (path, query) = request.url().split_once('?').unwrap_or((request.url(), ""));
+    if path == "/" && *request.method() == Method::Get {
+        return Response::from_string(PAGE).with_header(content_type("text/html; charset=utf-8"));
+    }
+    let Some(endpoint) = path.strip_prefix("/api/") else {
+        return error(404, "not found");
+    };
+    let bearer = request
+        .headers()
+        .iter()
+        .find(|h| h.field.equiv("Authorization"))
+        .and_then(|h| h.value.as_str().strip_prefix("Bearer "))
+        .map(str::to_string);
+    let Some(access) = tokens.access(query_param(query, "token").or(bearer).as_deref()) else {
+        return error(401, "missing or wrong token");
+    };
+
+    let command = match (request.method(), endpoint) {
+        (Method::Get, "status") => Command::Status,
+        (Method::Post, "play-pause") => Command::PlayPause,
+        (Method::Post, "next") => Command::Next,
+        (Method::Post, "prev") => Command::Prev,
+        (Method::Post, "shuffle") => Command::Shuffle,
+        (Method::Post, "repeat") => Command::Repeat,
+        (Method::Post, "volume") => match query_param(query, "value").and_then(|v| v.parse().ok()) {
+            Some(pct) => Command::Volume(pct),
+            None => return error(400, "volume needs ?value=0-100"),
+        },
+        (Method::Post, "seek") => match query_param(query, "position").and_then(|v| v.parse().ok()) {
+            Some(secs) => Command::Seek(secs),
+            None => return error(400, "seek needs ?position=<seconds>"),
+        },
+        (Method::Get, "queue") => Command::Queue,
+        (Method::Get, "search") => match query_param(query, "q").filter(|q| !q.trim().is_empty()) {
+            Some(q) => Command::Search(q),
+            None => return error(400, "search needs ?q=<query>"),
+        },
+        (Method::Post, "queue") => match query_param(query, "path") {
+            Some(path) => Command::Enqueue(PathBuf::from(path)),
+            None => return error(400, "adding to the queue needs ?path=<track path>"),
+        },
+        _ => return error(404, "unknown endpoint"),
+    };
+    if access == Access::Guest && !command.guest_allowed() {
+        return error(403, "guests can only search and add to the queue");
+    }
+    let search = match &command {
+        Command::Search(q) => Some(q.clone()),
+        _ => None,
+    };
+
+    let (reply, answer) = bounded(1);
+    if tx.send(Event::Remote(Request { command, access, reply })).is_err() {
+        return error(503, "player is shutting down");
+    }
+    match answer.recv_timeout(REPLY_TIMEOUT) {
+        Ok(Reply::Json(mut body)) => {
+            body["access"] = json!(if access == Access::Guest { "guest" } else { "owner" });
+            json_response(200, &body)
+        }
+        Ok(Reply::Library(library)) => {
+            let query = search.unwrap_or_default();
+            let results: Vec<Value> = library
+                .search(&query)
+                .into_iter()
+                .take(MAX_ENTRIES)
+                .map(|i| track_json(&library.tracks[i]))
+                .collect();
+            json_response(200, &json!({ "results": results }))
+        }
+        Ok(Reply::Error(code, message)) => error(code, &message),
+        Err(_) => error(503, "player didn't answer"),
+    }
+}
+
+/// `127.0.0.1`, `::1`, `localhost` and the like, with or without a port
+fn is_loopback(host: &str) -> bool {
+    if let Ok(addr) = host.parse::<SocketAddr>() {
+        return addr.ip().is_loopback();
+    }
+    if let Ok(ip) = host.trim_start_matches('[').trim_end_matches(']').parse::<IpAddr>() {
+        return ip.is_loopback();
+    }
+    let name = host.split(':').next().unwrap_or_default();
+    name.eq_ignore_ascii_case("localhost")
+}
+
+/// Whether an API call comes from a page the remote didn't serve. A browser lets
+/// any site send simple cross-origin POSTs, but it names that site in `Origin`;
+/// DNS rebinding gets past that by pointing the attacker's own name at us, so
+/// without a token to stop it the `Host` must be loopback too.
+fn foreign_origin(request: &tiny_http::Request, open: bool) -> bool {
+    if !request.url().starts_with("/api/") {
+        return false;
+    }
+    let header = |name: &'static str| {
+        request
+            .headers()
+            .iter()
+            .find(|h| h.field.equiv(name))
+            .map(|h| h.value.as_str())
+    };
+    let host = header("Host").unwrap_or_default();
+    if open && !is_loopback(host) {
+        return true;
+    }
+    header("Origin").is_some_and(|origin| origin.strip_prefix("http://") != Some(host))
+}
+
+fn content_type(value: &str) -> Header {
+    Header::from_bytes(&b"Content-Type"[..], value.as_bytes()).expect("valid header")
+}
+
+fn json_response(code: u16, body: &Value) -> Response<Cursor<Vec<u8>>> {
+    Response::from_string(body.to_string())
+        .with_status_code(code)
+        .with_header(content_type("application/json"))
+}
+
+fn error(code: u16, message: &str) -> Response<Cursor<Vec<u8>>> {
+    json_response(code, &json!({ "error": message }))
+}
+
+/// Percent-decoded value of `name` in a query string
+fn query_param(query: &str, name: &str) -> Option<String> {
+    query
+        .split('&')
+        .map(|pair| pair.split_once('=').unwrap_or((pair, "")))
+        .find(|(key, _)| *key == name)
+        .map(|(_, value)| percent_decode(value))
+}
+
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => out.push(b' '),
+            b'%' => {
+                let hex = bytes.get(i + 1..i + 3).and_then(|h| std::str::from_utf8(h).ok());
+                match hex.and_then(|h| u8::from_str_radix(h, 16).ok()) {
+                    Some(b) => {
+                        out.push(b);
+                        i += 2;
+                    }
+                    None => out.push(b'%'),
+                }
+            }
+            b => out.push(b),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn percent_decode_handles_escapes_and_plus() {
+        assert_eq!(percent_decode("a%20b+c"), "a b c");
+        assert_eq!(percent_decode("%2Fmusic%2fx.flac"), "/music/x.flac");
+        assert_eq!(percent_decode("Bj%C3%B6rk"), "Björk");
+    }
+
+    #[test]
+    fn percent_decode_keeps_malformed_escapes() {
+        assert_eq!(percent_decode("100%"), "100%");
+        assert_eq!(percent_decode("%zz"), "%zz");
+        assert_eq!(percent_decode("%4"), "%4");
+        // A lone byte that isn't UTF-8 comes out replaced, not as a panic
+        assert_eq!(percent_decode("%FF"), "\u{FFFD}");
+    }
+
+    #[test]
+    fn query_param_finds_and_decodes() {
+        assert_eq!(query_param("q=daft+punk&token=x", "q").as_deref(), Some("daft punk"));
+        assert_eq!(query_param("flag&q=1", "flag").as_deref(), Some(""));
+        assert_eq!(query_param("q=1", "token"), None);
+    }
+
+    #[test]
+    fn loopback_hosts() {
+        for host in ["127.0.0.1", "127.0.0.1:8787", "::1", "[::1]:8787", "localhost", "LOCALHOST:8787"] {
+            assert!(is_loopback(host), "{}", host);
+        }
+        for host in ["0.0.0.0", "192.168.1.5:8787", "evil.example:8787", "localhost.evil.example", ""] {
+            assert!(!is_loopback(host), "{}", host);
+        }
+    }
+
+    #[test]
+    fn tokens_compare_exactly() {
+        assert!(same_token("secret", "secret"));
+        assert!(!same_token("secret", "secreT"));
+        assert!(!same_token("secret", "secret2"));
+        assert!(!same_token("secret", ""));
+    }
+}
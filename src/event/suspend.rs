@@ -0,0 +1,101 @@
+use crossbeam_channel::Sender;
+use std::io::{BufRead, BufReader};
+use std::process::{Child, Command, Stdio};
+use std::sync::{Arc, Mutex};
+
+use super::Event;
+
+/// Watches for suspend while alive (`pause_on_suspend`); dropping it stops the
+/// `gdbus` monitor and lets go of the sleep inhibitor
+pub struct SuspendListener {
+    monitor: Child,
+    inhibitor: Arc<Mutex<Option<Child>>>,
+}
+
+impl SuspendListener {
+    /// Playback is paused: let the pending sleep go ahead. The inhibitor is taken
+    /// again on wake-up.
+    pub fn release(&self) {
+        release(&self.inhibitor);
+    }
+}
+
+impl Drop for SuspendListener {
+    fn drop(&mut self) {
+        let _ = self.monitor.kill();
+        let _ = self.monitor.wait();
+        release(&self.inhibitor);
+    }
+}
+
+/// Watch logind's `PrepareForSleep` signal on the system bus through `gdbus monitor`
+/// and send `Event::Suspend` when the machine is about to sleep. A logind delay
+/// inhibitor holds the sleep back until `release`, so the pause lands before the
+/// machine goes down. None if `gdbus` (from GLib) couldn't be started.
+pub fn spawn_suspend_listener(tx: Sender<Event>) -> Option<SuspendListener> {
+    let mut monitor = Command::new("gdbus")
+        .args([
+            "monitor",
+            "--system",
+            "--dest",
+            "org.freedesktop.login1",
+            "--object-path",
+            "/org/freedesktop/login1",
+        ])
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .ok()?;
+    let Some(stdout) = monitor.stdout.take() else {
+        let _ = monitor.kill();
+        let _ = monitor.wait();
+        return None;
+    };
+    let inhibitor = Arc::new(Mutex::new(inhibit()));
+    let held = Arc::clone(&inhibitor);
+    std::thread::spawn(move || {
+        // "/org/freedesktop/login1: org.freedesktop.login1.Manager.PrepareForSleep (true,)"
+        // before sleeping, and "(false,)" on wake-up
+        for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+            if line.contains(".PrepareForSleep (true") {
+                if tx.send(Event::Suspend).is_err() {
+                    break;
+                }
+            } else if line.contains(".PrepareForSleep (false") {
+                let mut held = held.lock().unwrap();
+                if held.is_none() {
+                    *held = inhibit();
+                }
+            }
+        }
+    });
+    Some(SuspendListener { monitor, inhibitor })
+}
+
+/// Take a logind "delay" lock on sleep. `systemd-inhibit` holds it for as long as
+/// `cat` runs, and `cat` runs until its stdin (ours) closes, so the lock also goes
+/// away if ommp dies. None without systemd-inhibit: suspend then just isn't delayed.
+fn inhibit() -> Option<Child> {
+    Command::new("systemd-inhibit")
+        .args([
+            "--what=sleep",
+            "--mode=delay",
+            "--who=ommp",
+            "--why=Pause playback before sleep",
+            "cat",
+        ])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .ok()
+}
+
+fn release(inhibitor: &Mutex<Option<Child>>) {
+    if let Some(mut child) = inhibitor.lock().unwrap().take() {
+        // Closing cat's stdin ends it, and systemd-inhibit with it
+        drop(child.stdin.take());
+        let _ = child.wait();
+    }
+}
@@ -0,0 +1,447 @@
+use std::fs::File;
+use std::io::{self, BufReader, Read, Seek, SeekFrom};
+use std::path::Path;
+
+/// Most samples read from a chapter track whose samples share one fixed size,
+/// where no table bounds the count
+const MAX_FIXED_SAMPLES: u32 = 10_000;
+
+/// A chapter marker in an M4B/M4A audiobook
+#[derive(Debug, Clone)]
+pub struct Chapter {
+    pub title: String,
+    /// Start offset in seconds
+    pub start: f64,
+}
+
+/// Chapters of an MP4-family file, from the Nero `chpl` atom or a QuickTime
+/// chapter text track. Empty for other formats or files without chapters.
+pub fn read_chapters(path: &Path) -> Vec<Chapter> {
+    let is_mp4 = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| matches!(e.to_ascii_lowercase().as_str(), "m4a" | "m4b" | "mp4"));
+    if !is_mp4 {
+        return Vec::new();
+    }
+    File::open(path)
+        .and_then(|f| parse(BufReader::new(f)))
+        .unwrap_or_default()
+}
+
+/// Box payload range in the file
+struct Atom {
+    kind: [u8; 4],
+    start: u64,
+    end: u64,
+}
+
+/// Per-track data needed to find and read a chapter track
+#[derive(Default)]
+struct Trak {
+    id: u32,
+    chapter_refs: Vec<u32>,
+    is_text: bool,
+    timescale: u32,
+    /// stts: (sample count, sample delta)
+    durations: Vec<(u32, u32)>,
+    /// stsz: per-sample sizes (empty when `sample_size` is fixed)
+    sizes: Vec<u32>,
+    sample_size: u32,
+    sample_count: u32,
+    /// stsc: (first chunk, samples per chunk), chunks numbered from 1
+    chunk_runs: Vec<(u32, u32)>,
+    /// stco/co64
+    chunk_offsets: Vec<u64>,
+}
+
+fn parse<R: Read + Seek>(mut r: R) -> io::Result<Vec<Chapter>> {
+    let len = r.seek(SeekFrom::End(0))?;
+    let file = Atom { kind: *b"file", start: 0, end: len };
+    let top = children(&mut r, &file)?;
+    let Some(moov) = find(&top, b"moov") else {
+        return Ok(Vec::new());
+    };
+
+    let mut traks = Vec::new();
+    for atom in children(&mut r, moov)? {
+        match &atom.kind {
+            b"udta" => {
+                if let Some(chpl) = find(&children(&mut r, &atom)?, b"chpl") {
+                    let chapters = read_chpl(&mut r, chpl)?;
+                    if !chapters.is_empty() {
+                        return Ok(chapters);
+                    }
+                }
+            }
+            b"trak" => traks.push(read_trak(&mut r, &atom)?),
+            _ => {}
+        }
+    }
+
+    // QuickTime chapters: a text track referenced from another track's tref/chap
+    let referenced = |id: u32| traks.iter().any(|t| t.chapter_refs.contains(&id));
+    match traks.iter().find(|t| t.is_text && referenced(t.id)) {
+        Some(trak) => read_text_track(&mut r, trak),
+        None => Ok(Vec::new()),
+    }
+}
+
+/// Child atoms of `parent`, reading only their headers
+fn children<R: Read + Seek>(r: &mut R, parent: &Atom) -> io::Result<Vec<Atom>> {
+    let mut atoms = Vec::new();
+    let mut pos = parent.start;
+    while pos + 8 <= parent.end {
+        r.seek(SeekFrom::Start(pos))?;
+        let size = read_u32(r)? as u64;
+        let mut kind = [0u8; 4];
+        r.read_exact(&mut kind)?;
+        let (start, size) = match size {
+            0 => (pos + 8, parent.end - pos),
+            1 => (pos + 16, read_u64(r)?),
+            n => (pos + 8, n),
+        };
+        if size < start - pos {
+            break;
+        }
+        // A size past the end of the file (or of u64) is clamped to the parent;
+        // stop at anything that wouldn't move the walk forward
+        let end = pos.checked_add(size).map_or(parent.end, |end| end.min(parent.end));
+        if end <= pos || end < start {
+            break;
+        }
+        atoms.push(Atom { kind, start, end });
+        pos = end;
+    }
+    Ok(atoms)
+}
+
+fn find<'a>(atoms: &'a [Atom], kind: &[u8; 4]) -> Option<&'a Atom> {
+    atoms.iter().find(|a| &a.kind == kind)
+}
+
+/// Nero chapter list: start times in 100 ns units with length-prefixed titles
+fn read_chpl<R: Read + Seek>(r: &mut R, atom: &Atom) -> io::Result<Vec<Chapter>> {
+    r.seek(SeekFrom::Start(atom.start))?;
+    let version = read_u8(r)?;
+    r.seek(SeekFrom::Current(3))?;
+    if version > 0 {
+        r.seek(SeekFrom::Current(4))?;
+    }
+    let count = read_u8(r)?;
+    let mut chapters = Vec::with_capacity(count as usize);
+    for _ in 0..count {
+        let start = read_u64(r)?;
+        let len = read_u8(r)? as usize;
+        let mut title = vec![0u8; len];
+        r.read_exact(&mut title)?;
+        chapters.push(Chapter {
+            title: String::from_utf8_lossy(&title).trim().to_string(),
+            start: start as f64 / 10_000_000.0,
+        });
+    }
+    Ok(chapters)
+}
+
+fn read_trak<R: Read + Seek>(r: &mut R, trak: &Atom) -> io::Result<Trak> {
+    let mut t = Trak::default();
+    for atom in children(r, trak)? {
+        match &atom.kind {
+            b"tkhd" => {
+                r.seek(SeekFrom::Start(atom.start))?;
+                let version = read_u8(r)?;
+                // flags, then creation/modification times (32- or 64-bit)
+                r.seek(SeekFrom::Current(if version == 1 { 3 + 16 } else { 3 + 8 }))?;
+                t.id = read_u32(r)?;
+            }
+            b"tref" => {
+                for chap in children(r, &atom)?.iter().filter(|a| &a.kind == b"chap") {
+                    r.seek(SeekFrom::Start(chap.start))?;
+                    for _ in 0..chap.end.saturating_sub(chap.start) / 4 {
+                        t.chapter_refs.push(read_u32(r)?);
+                    }
+                }
+            }
+            b"mdia" => {
+                let mdia = children(r, &atom)?;
+                if let Some(mdhd) = find(&mdia, b"mdhd") {
+                    r.seek(SeekFrom::Start(mdhd.start))?;
+                    let version = read_u8(r)?;
+                    r.seek(SeekFrom::Current(if version == 1 { 3 + 16 } else { 3 + 8 }))?;
+                    t.timescale = read_u32(r)?;
+                }
+                if let Some(hdlr) = find(&mdia, b"hdlr") {
+                    // version/flags, pre-defined, then the handler type
+                    r.seek(SeekFrom::Start(hdlr.start + 8))?;
+                    let mut handler = [0u8; 4];
+                    r.read_exact(&mut handler)?;
+                    t.is_text = &handler == b"text" || &handler == b"sbtl";
+                }
+                // Sample tables are only needed for the (small) text tracks
+                if t.is_text {
+                    let stbl = match find(&mdia, b"minf") {
+                        Some(minf) => children(r, minf)?.into_iter().find(|a| &a.kind == b"stbl"),
+                        None => None,
+                    };
+                    if let Some(stbl) = stbl {
+                        read_sample_tables(r, &stbl, &mut t)?;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(t)
+}
+
+fn read_sample_tables<R: Read + Seek>(r: &mut R, stbl: &Atom, t: &mut Trak) -> io::Result<()> {
+    for atom in children(r, stbl)? {
+        // Skip version/flags; entry counts are capped by the atom size
+        r.seek(SeekFrom::Start(atom.start + 4))?;
+        let room = |entry: u64| (atom.end.saturating_sub(atom.start + 8) / entry) as u32;
+        match &atom.kind {
+            b"stts" => {
+                let count = read_u32(r)?.min(room(8));
+                for _ in 0..count {
+                    t.durations.push((read_u32(r)?, read_u32(r)?));
+                }
+            }
+            b"stsz" => {
+                t.sample_size = read_u32(r)?;
+                // A count beyond the entries the atom has room for is a lie
+                t.sample_count = match t.sample_size {
+                    0 => read_u32(r)?.min(room(4).saturating_sub(1)),
+                    _ => read_u32(r)?.min(MAX_FIXED_SAMPLES),
+                };
+                if t.sample_size == 0 {
+                    for _ in 0..t.sample_count {
+                        t.sizes.push(read_u32(r)?);
+                    }
+                }
+            }
+            b"stsc" => {
+                let count = read_u32(r)?.min(room(12));
+                for _ in 0..count {
+                    let first = read_u32(r)?;
+                    let per_chunk = read_u32(r)?;
+                    read_u32(r)?; // sample description index
+                    t.chunk_runs.push((first, per_chunk));
+                }
+            }
+            b"stco" => {
+                let count = read_u32(r)?.min(room(4));
+                for _ in 0..count {
+                    t.chunk_offsets.push(read_u32(r)? as u64);
+                }
+            }
+            b"co64" => {
+                let count = read_u32(r)?.min(room(8));
+                for _ in 0..count {
+                    t.chunk_offsets.push(read_u64(r)?);
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Each sample of a chapter track is a 16-bit length followed by the title text
+fn read_text_track<R: Read + Seek>(r: &mut R, t: &Trak) -> io::Result<Vec<Chapter>> {
+    if t.timescale == 0 {
+        return Ok(Vec::new());
+    }
+
+    // File offset of every sample, walking chunks and their sample counts
+    let sample_count = if t.sizes.is_empty() { t.sample_count as usize } else { t.sizes.len() };
+    let size_of = |i: usize| if t.sizes.is_empty() { t.sample_size } else { t.sizes[i] };
+    let mut offsets = Vec::new();
+    for (chunk, &chunk_offset) in t.chunk_offsets.iter().enumerate() {
+        let per_chunk = t.chunk_runs.iter()
+            .take_while(|&&(first, _)| first as usize <= chunk + 1)
+            .last()
+            .map_or(1, |&(_, n)| n);
+        let mut offset = chunk_offset;
+        for _ in 0..per_chunk {
+            if offsets.len() == sample_count {
+                break;
+            }
+            offsets.push(offset);
+            offset = offset.saturating_add(size_of(offsets.len() - 1) as u64);
+        }
+    }
+
+    let starts = t.durations.iter()
+        .flat_map(|&(count, delta)| std::iter::repeat_n(delta, count as usize))
+        .scan(0u64, |time, delta| {
+            let start = *time;
+            *time += delta as u64;
+            Some(start)
+        });
+
+    let mut chapters = Vec::with_capacity(offsets.len());
+    for (offset, start) in offsets.into_iter().zip(starts) {
+        r.seek(SeekFrom::Start(offset))?;
+        let len = read_u16(r)? as usize;
+        let mut text = vec![0u8; len];
+        r.read_exact(&mut text)?;
+        chapters.push(Chapter {
+            title: decode_text(&text),
+            start: start as f64 / t.timescale as f64,
+        });
+    }
+    Ok(chapters)
+}
+
+/// Chapter text is UTF-8, or UTF-16 when it starts with a byte order mark
+fn decode_text(bytes: &[u8]) -> String {
+    match bytes {
+        [0xFE, 0xFF, rest @ ..] => {
+            let units: Vec<u16> = rest.chunks_exact(2).map(|c| u16::from_be_bytes([c[0], c[1]])).collect();
+            String::from_utf16_lossy(&units)
+        }
+        [0xFF, 0xFE, rest @ ..] => {
+            let units: Vec<u16> = rest.chunks_exact(2).map(|c| u16::from_le_bytes([c[0], c[1]])).collect();
+            String::from_utf16_lossy(&units)
+        }
+        _ => String::from_utf8_lossy(bytes).into_owned(),
+    }
+    .trim()
+    .to_string()
+}
+
+fn read_u8<R: Read>(r: &mut R) -> io::Result<u8> {
+    let mut b = [0u8; 1];
+    r.read_exact(&mut b)?;
+    Ok(b[0])
+}
+
+fn read_u16<R: Read>(r: &mut R) -> io::Result<u16> {
+    let mut b = [0u8; 2];
+    r.read_exact(&mut b)?;
+    Ok(u16::from_be_bytes(b))
+}
+
+fn read_u32<R: Read>(r: &mut R) -> io::Result<u32> {
+    let mut b = [0u8; 4];
+    r.read_exact(&mut b)?;
+    Ok(u32::from_be_bytes(b))
+}
+
+fn read_u64<R: Read>(r: &mut R) -> io::Result<u64> {
+    let mut b = [0u8; 8];
+    r.read_exact(&mut b)?;
+    Ok(u64::from_be_bytes(b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn atom(kind: &[u8; 4], payload: &[u8]) -> Vec<u8> {
+        let mut out = ((payload.len() + 8) as u32).to_be_bytes().to_vec();
+        out.extend_from_slice(kind);
+        out.extend_from_slice(payload);
+        out
+    }
+
+    /// version 0 chpl with the given (start in 100 ns units, title) entries
+    fn chpl(entries: &[(u64, &str)]) -> Vec<u8> {
+        let mut payload = vec![0, 0, 0, 0, entries.len() as u8];
+        for (start, title) in entries {
+            payload.extend_from_slice(&start.to_be_bytes());
+            payload.push(title.len() as u8);
+            payload.extend_from_slice(title.as_bytes());
+        }
+        atom(b"chpl", &payload)
+    }
+
+    fn nero_file() -> Vec<u8> {
+        let chpl = chpl(&[(0, "Intro"), (600_000_000, "Part One")]);
+        let mut file = atom(b"ftyp", b"M4B ");
+        file.extend(atom(b"moov", &atom(b"udta", &chpl)));
+        file
+    }
+
+    fn parse_bytes(bytes: Vec<u8>) -> io::Result<Vec<Chapter>> {
+        parse(Cursor::new(bytes))
+    }
+
+    #[test]
+    fn reads_nero_chapters() {
+        let chapters = parse_bytes(nero_file()).unwrap();
+        assert_eq!(chapters.len(), 2);
+        assert_eq!(chapters[0].title, "Intro");
+        assert_eq!(chapters[1].title, "Part One");
+        assert_eq!(chapters[1].start, 60.0);
+    }
+
+    #[test]
+    fn truncated_files_do_not_panic() {
+        let file = nero_file();
+        for len in 0..file.len() {
+            let _ = parse_bytes(file[..len].to_vec());
+        }
+    }
+
+    #[test]
+    fn oversized_atoms_are_clamped_to_the_file() {
+        let mut file = nero_file();
+        // moov claims 4 GiB
+        let moov = file.windows(4).position(|w| w == b"moov").unwrap() - 4;
+        file[moov..moov + 4].copy_from_slice(&u32::MAX.to_be_bytes());
+        assert_eq!(parse_bytes(file).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn largest_64_bit_size_does_not_overflow() {
+        let mut file = 1u32.to_be_bytes().to_vec();
+        file.extend_from_slice(b"moov");
+        file.extend_from_slice(&u64::MAX.to_be_bytes());
+        file.extend(atom(b"udta", &chpl(&[(0, "Only")])));
+        assert_eq!(parse_bytes(file).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn undersized_atoms_end_the_walk() {
+        // A size smaller than the header would otherwise loop in place
+        let mut moov = 4u32.to_be_bytes().to_vec();
+        moov.extend_from_slice(b"udta");
+        let file = atom(b"moov", &moov);
+        assert!(parse_bytes(file).unwrap().is_empty());
+    }
+
+    #[test]
+    fn huge_sample_counts_are_capped() {
+        let mut stts = vec![0, 0, 0, 0, 0, 0, 0, 1];
+        stts.extend_from_slice(&u32::MAX.to_be_bytes());
+        stts.extend_from_slice(&1u32.to_be_bytes());
+        // Fixed 4-byte samples, claimed u32::MAX of them
+        let mut stsz = vec![0, 0, 0, 0, 0, 0, 0, 4];
+        stsz.extend_from_slice(&u32::MAX.to_be_bytes());
+        // One run putting u32::MAX samples in every chunk
+        let mut stsc = vec![0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 1];
+        stsc.extend_from_slice(&u32::MAX.to_be_bytes());
+        stsc.extend_from_slice(&1u32.to_be_bytes());
+        let stco = [0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0];
+        let stbl = [atom(b"stts", &stts), atom(b"stsz", &stsz), atom(b"stsc", &stsc), atom(b"stco", &stco)].concat();
+
+        let tkhd = |id: u32| {
+            let mut payload = vec![0u8; 12];
+            payload.extend_from_slice(&id.to_be_bytes());
+            atom(b"tkhd", &payload)
+        };
+        let mut mdhd = vec![0u8; 12];
+        mdhd.extend_from_slice(&1000u32.to_be_bytes());
+        let mut hdlr = vec![0u8; 8];
+        hdlr.extend_from_slice(b"text");
+        let mdia = [atom(b"mdhd", &mdhd), atom(b"hdlr", &hdlr), atom(b"minf", &atom(b"stbl", &stbl))].concat();
+        let text_trak = atom(b"trak", &[tkhd(2), atom(b"mdia", &mdia)].concat());
+        let audio_trak = atom(b"trak", &[tkhd(1), atom(b"tref", &atom(b"chap", &2u32.to_be_bytes()))].concat());
+
+        let file = atom(b"moov", &[audio_trak, text_trak].concat());
+        // Samples run off the end of the file: an error, but a prompt one
+        assert!(parse_bytes(file).map_or(true, |c| c.len() <= MAX_FIXED_SAMPLES as usize));
+    }
+}
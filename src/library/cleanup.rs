@@ -0,0 +1,54 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use super::cover::find_cover_image;
+use super::track::Track;
+
+/// Metadata problem a track can be listed under in the Cleanup section
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Issue {
+    NoTitle,
+    NoArt,
+    ZeroDuration,
+    NoGenre,
+}
+
+impl Issue {
+    pub const ALL: [Issue; 4] = [Issue::NoTitle, Issue::NoArt, Issue::ZeroDuration, Issue::NoGenre];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Issue::NoTitle => "Missing title",
+            Issue::NoArt => "Missing album art",
+            Issue::ZeroDuration => "Zero duration",
+            Issue::NoGenre => "Empty genre",
+        }
+    }
+
+    fn affects(self, track: &Track, has_art: bool) -> bool {
+        match self {
+            Issue::NoTitle => track.untitled || track.title.trim().is_empty(),
+            Issue::NoArt => !has_art,
+            Issue::ZeroDuration => track.duration.is_zero(),
+            Issue::NoGenre => track.genre.trim().is_empty(),
+        }
+    }
+}
+
+/// Track indices affected by each issue, in `Issue::ALL` order. Cover lookups hit
+/// the disk, so each directory is only checked once.
+pub fn find(tracks: &[Track]) -> Vec<Vec<usize>> {
+    let mut covers: HashMap<&Path, bool> = HashMap::new();
+    let mut found = vec![Vec::new(); Issue::ALL.len()];
+    for (i, track) in tracks.iter().enumerate() {
+        let has_art = track.path.parent().is_some_and(|dir| {
+            *covers.entry(dir).or_insert_with(|| find_cover_image(dir).is_some())
+        });
+        for (issue, list) in Issue::ALL.iter().zip(found.iter_mut()) {
+            if issue.affects(track, has_art) {
+                list.push(i);
+            }
+        }
+    }
+    found
+}
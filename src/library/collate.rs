@@ -0,0 +1,41 @@
+use unicode_normalization::char::is_combining_mark;
+use unicode_normalization::UnicodeNormalization;
+
+/// Key used to merge names that differ only by case or Unicode composition
+/// ("Radiohead" / "RADIOHEAD", precomposed "é" / "e" + combining accent).
+pub fn dedupe_key(s: &str) -> String {
+    s.nfc().flat_map(char::to_lowercase).collect()
+}
+
+/// Drop a leading article ("The Beatles" → "Beatles") for sorting.
+/// Articles match case-insensitively and only when followed by a space and more text.
+pub fn strip_article<'a>(s: &'a str, articles: &[String]) -> &'a str {
+    for article in articles {
+        let n = article.len();
+        if s.len() > n + 1
+            && s.is_char_boundary(n)
+            && s[..n].eq_ignore_ascii_case(article)
+            && s.as_bytes()[n] == b' '
+        {
+            return s[n + 1..].trim_start();
+        }
+    }
+    s
+}
+
+/// Primary sort key: leading article removed, compatibility-decomposed,
+/// accents stripped, lowercased, so "Édith Piaf" sorts next to "Edith"
+/// and "The Beatles" under B.
+pub fn sort_key(s: &str, articles: &[String]) -> String {
+    strip_article(s, articles)
+        .nfkd()
+        .filter(|c| !is_combining_mark(*c))
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Sort names in place by collation order (accent/case-insensitive first,
+/// then the raw string so the order stays deterministic).
+pub fn sort_names(names: &mut [String], articles: &[String]) {
+    names.sort_by_cached_key(|n| (sort_key(n, articles), n.clone()));
+}
@@ -0,0 +1,47 @@
+use std::path::{Path, PathBuf};
+
+/// First JPEG or PNG in `dir`, which is where album art comes from
+pub fn find_cover_image(dir: &Path) -> Option<PathBuf> {
+    let entries = std::fs::read_dir(dir).ok()?;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if !path.is_file() {
+            continue;
+        }
+        // Check extension first (fast path)
+        if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
+            let l = ext.to_ascii_lowercase();
+            if l == "jpg" || l == "jpeg" || l == "png" {
+                return Some(path);
+            }
+        }
+        // No extension or unknown ext — check magic bytes
+        if is_image_by_magic(&path) {
+            return Some(path);
+        }
+    }
+    None
+}
+
+/// Check file header bytes to detect JPEG/PNG regardless of extension.
+fn is_image_by_magic(path: &Path) -> bool {
+    use std::fs::File;
+    use std::io::Read;
+    let mut f = match File::open(path) {
+        Ok(f) => f,
+        Err(_) => return false,
+    };
+    let mut buf = [0u8; 8];
+    if f.read_exact(&mut buf).is_err() {
+        return false;
+    }
+    // JPEG: FF D8 FF
+    if buf[0] == 0xFF && buf[1] == 0xD8 && buf[2] == 0xFF {
+        return true;
+    }
+    // PNG: 89 50 4E 47 0D 0A 1A 0A
+    if buf == [0x89, 0x50, 0x4E, 0x47, 0x0D, 0x0A, 0x1A, 0x0A] {
+        return true;
+    }
+    false
+}
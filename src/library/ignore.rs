@@ -0,0 +1,132 @@
+use std::path::{Path, PathBuf};
+
+const IGNORE_FILE: &str = ".ommpignore";
+
+/// Glob patterns the scanner and watcher skip.
+///
+/// Patterns come from `<music_dir>/.ommpignore` (one per line, `#` comments)
+/// and the `ignore` list in the config file. A pattern without `/` is matched
+/// against each path component (`*demo*`); a pattern with `/` is matched against
+/// the path relative to the music directory (`*/Audiobooks/*`).
+#[derive(Debug, Clone, Default)]
+pub struct IgnoreRules {
+    root: PathBuf,
+    patterns: Vec<String>,
+}
+
+impl IgnoreRules {
+    pub fn load(root: &Path, extra: &[String]) -> Self {
+        let mut patterns: Vec<String> = extra.to_vec();
+        if let Ok(data) = std::fs::read_to_string(root.join(IGNORE_FILE)) {
+            for line in data.lines() {
+                let line = line.trim();
+                if !line.is_empty() && !line.starts_with('#') {
+                    patterns.push(line.to_string());
+                }
+            }
+        }
+        Self {
+            root: root.to_path_buf(),
+            patterns,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.patterns.is_empty()
+    }
+
+    /// Check whether a path under the music directory should be skipped.
+    /// Directories are also tested with a trailing `/` so `*/Audiobooks/*` prunes the whole folder.
+    pub fn is_ignored(&self, path: &Path, is_dir: bool) -> bool {
+        if self.patterns.is_empty() {
+            return false;
+        }
+        let rel = match path.strip_prefix(&self.root) {
+            Ok(r) => r,
+            Err(_) => return false,
+        };
+        let mut rel_str = rel.to_string_lossy().replace('\\', "/");
+        if rel_str.is_empty() {
+            return false;
+        }
+        if is_dir {
+            rel_str.push('/');
+        }
+        let rooted = format!("/{}", rel_str);
+
+        self.patterns.iter().any(|pat| {
+            if pat.contains('/') {
+                glob_match(pat, &rel_str) || glob_match(pat, &rooted)
+            } else {
+                rel.components()
+                    .any(|c| glob_match(pat, &c.as_os_str().to_string_lossy()))
+            }
+        })
+    }
+}
+
+/// Minimal glob matcher: `*` matches any run of characters (including `/`),
+/// `?` matches exactly one character. Case-sensitive.
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let p: Vec<char> = pattern.chars().collect();
+    let t: Vec<char> = text.chars().collect();
+    let (mut pi, mut ti) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+
+    while ti < t.len() {
+        if pi < p.len() && (p[pi] == '?' || p[pi] == t[ti]) {
+            pi += 1;
+            ti += 1;
+        } else if pi < p.len() && p[pi] == '*' {
+            star = Some((pi, ti));
+            pi += 1;
+        } else if let Some((sp, st)) = star {
+            // Backtrack: let the last '*' swallow one more character
+            pi = sp + 1;
+            ti = st + 1;
+            star = Some((sp, st + 1));
+        } else {
+            return false;
+        }
+    }
+    while pi < p.len() && p[pi] == '*' {
+        pi += 1;
+    }
+    pi == p.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::glob_match;
+
+    #[test]
+    fn literal_patterns_match_whole_text() {
+        assert!(glob_match("Podcasts", "Podcasts"));
+        assert!(!glob_match("Podcasts", "Podcasts2"));
+        assert!(!glob_match("podcasts", "Podcasts"));
+    }
+
+    #[test]
+    fn question_mark_matches_one_character() {
+        assert!(glob_match("CD?", "CD1"));
+        assert!(!glob_match("CD?", "CD"));
+        assert!(!glob_match("CD?", "CD10"));
+    }
+
+    #[test]
+    fn star_matches_any_run_including_slashes() {
+        assert!(glob_match("*.cue", "album.cue"));
+        assert!(glob_match("*.cue", ".cue"));
+        assert!(glob_match("/Audiobooks/*", "/Audiobooks/Author/Book"));
+        assert!(glob_match("*demo*", "the demo tapes"));
+        assert!(!glob_match("*.cue", "album.cue.bak"));
+    }
+
+    #[test]
+    fn star_backtracks() {
+        assert!(glob_match("*ab", "aab"));
+        assert!(glob_match("a*b*c", "axbxxbc"));
+        assert!(!glob_match("a*b*c", "axbxx"));
+        assert!(glob_match("**", ""));
+    }
+}
@@ -0,0 +1,296 @@
+use std::collections::HashMap;
+use std::f64::consts::PI;
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use crossbeam_channel::Sender;
+use symphonia::core::audio::SampleBuffer;
+use symphonia::core::codecs::{DecoderOptions, CODEC_TYPE_NULL};
+use symphonia::core::formats::FormatOptions;
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::MetadataOptions;
+use symphonia::core::probe::Hint;
+
+use crate::event::Event;
+
+/// Loudness (LUFS) that ReplayGain 2.0 gains are relative to
+pub const REPLAYGAIN_REFERENCE: f64 = -18.0;
+
+/// Most a quiet track is boosted, so near-silent files don't come out as noise
+const MAX_BOOST_DB: f64 = 12.0;
+
+fn loudness_path() -> PathBuf {
+    let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
+    PathBuf::from(home).join(".config/ommp/loudness.json")
+}
+
+/// Measured integrated loudness (LUFS) by file path
+pub fn load() -> HashMap<PathBuf, f64> {
+    fs::read_to_string(loudness_path())
+        .ok()
+        .and_then(|data| serde_json::from_str(&data).ok())
+        .unwrap_or_default()
+}
+
+pub fn save(values: &HashMap<PathBuf, f64>) -> anyhow::Result<()> {
+    let path = loudness_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    // Write-then-rename so a crash mid-write never loses every measurement
+    let tmp = path.with_extension("json.tmp");
+    fs::write(&tmp, serde_json::to_string(values)?)?;
+    fs::rename(&tmp, &path)?;
+    Ok(())
+}
+
+/// Gain in dB that brings a track to `target` LUFS: from its ReplayGain tag when it
+/// has one, else from a measured loudness. None when neither is known.
+pub fn gain_db(replay_gain: Option<f64>, measured: Option<f64>, target: f64) -> Option<f64> {
+    let gain = replay_gain
+        .map(|rg| rg + (target - REPLAYGAIN_REFERENCE))
+        .or_else(|| measured.map(|lufs| target - lufs))?;
+    Some(gain.min(MAX_BOOST_DB))
+}
+
+/// Background loudness analysis; dropping the handle doesn't stop it, `cancel` does
+pub struct Job {
+    cancel: Arc<AtomicBool>,
+    pub done: usize,
+    pub total: usize,
+}
+
+impl Job {
+    pub fn cancel(&self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Measure `paths` one after another on a worker thread, sending
+/// `Event::LoudnessMeasured` for each (None when it couldn't be decoded) and
+/// `Event::LoudnessFinished` at the end or when cancelled
+pub fn spawn_analysis(paths: Vec<PathBuf>, tx: Sender<Event>) -> Job {
+    let cancel = Arc::new(AtomicBool::new(false));
+    let job = Job {
+        cancel: Arc::clone(&cancel),
+        done: 0,
+        total: paths.len(),
+    };
+    std::thread::spawn(move || {
+        for path in paths {
+            if cancel.load(Ordering::Relaxed) {
+                break;
+            }
+            let lufs = measure(&path);
+            if tx.send(Event::LoudnessMeasured { path, lufs }).is_err() {
+                return;
+            }
+        }
+        let _ = tx.send(Event::LoudnessFinished);
+    });
+    job
+}
+
+/// Integrated loudness of a file per EBU R128 / ITU-R BS.1770: K-weighted mean
+/// square over 400 ms blocks (75% overlap), gated at -70 LUFS and then 10 LU below
+/// the ungated level
+pub fn measure(path: &Path) -> Option<f64> {
+    let file = File::open(path).ok()?;
+    let mss = MediaSourceStream::new(Box::new(file), Default::default());
+    let mut hint = Hint::new();
+    if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
+        hint.with_extension(ext);
+    }
+    let probed = symphonia::default::get_probe()
+        .format(&hint, mss, &FormatOptions::default(), &MetadataOptions::default())
+        .ok()?;
+    let mut format = probed.format;
+    let track = format.tracks().iter().find(|t| t.codec_params.codec != CODEC_TYPE_NULL)?;
+    let track_id = track.id;
+    let mut decoder = symphonia::default::get_codecs()
+        .make(&track.codec_params, &DecoderOptions::default())
+        .ok()?;
+
+    // Set up from the first decoded packet: some containers don't declare the layout
+    let mut meter: Option<Meter> = None;
+    let mut buf: Option<SampleBuffer<f32>> = None;
+    while let Ok(packet) = format.next_packet() {
+        if packet.track_id() != track_id {
+            continue;
+        }
+        let Ok(decoded) = decoder.decode(&packet) else { continue };
+        let spec = *decoded.spec();
+        if buf.as_ref().is_none_or(|b| b.capacity() < decoded.capacity() * spec.channels.count()) {
+            buf = Some(SampleBuffer::new(decoded.capacity() as u64, spec));
+        }
+        let meter = meter.get_or_insert_with(|| Meter::new(spec.rate as f64, spec.channels.count()));
+        if let Some(buf) = buf.as_mut() {
+            buf.copy_interleaved_ref(decoded);
+            meter.push(buf.samples(), spec.channels.count());
+        }
+    }
+    meter?.integrated()
+}
+
+/// Second-order IIR section (direct form I)
+#[derive(Clone, Copy)]
+struct Biquad {
+    b: [f64; 3],
+    a: [f64; 2],
+    x: [f64; 2],
+    y: [f64; 2],
+}
+
+impl Biquad {
+    fn new(b: [f64; 3], a: [f64; 2]) -> Self {
+        Self { b, a, x: [0.0; 2], y: [0.0; 2] }
+    }
+
+    fn process(&mut self, x: f64) -> f64 {
+        let y = self.b[0] * x + self.b[1] * self.x[0] + self.b[2] * self.x[1]
+            - self.a[0] * self.y[0]
+            - self.a[1] * self.y[1];
+        self.x = [x, self.x[0]];
+        self.y = [y, self.y[0]];
+        y
+    }
+}
+
+/// K-weighting for any sample rate: a high shelf (head effect) then a high pass,
+/// with the BS.1770 filter parameters
+fn k_weighting(rate: f64) -> [Biquad; 2] {
+    let (f0, gain, q) = (1681.974450955533, 3.999843853973347, 0.7071752369554196);
+    let k = (PI * f0 / rate).tan();
+    let vh = 10f64.powf(gain / 20.0);
+    let vb = vh.powf(0.4996667741545416);
+    let a0 = 1.0 + k / q + k * k;
+    let shelf = Biquad::new(
+        [(vh + vb * k / q + k * k) / a0, 2.0 * (k * k - vh) / a0, (vh - vb * k / q + k * k) / a0],
+        [2.0 * (k * k - 1.0) / a0, (1.0 - k / q + k * k) / a0],
+    );
+
+    let (f0, q) = (38.13547087602444, 0.5003270373238773);
+    let k = (PI * f0 / rate).tan();
+    let a0 = 1.0 + k / q + k * k;
+    let high_pass = Biquad::new([1.0, -2.0, 1.0], [2.0 * (k * k - 1.0) / a0, (1.0 - k / q + k * k) / a0]);
+    [shelf, high_pass]
+}
+
+/// Running K-weighted energy in 100 ms steps
+struct Meter {
+    filters: Vec<[Biquad; 2]>,
+    weights: Vec<f64>,
+    step_len: usize,
+    step_frames: usize,
+    step_sum: f64,
+    /// Mean square of each finished 100 ms step
+    steps: Vec<f64>,
+}
+
+impl Meter {
+    fn new(rate: f64, channels: usize) -> Self {
+        // 5.1: no LFE, surrounds weighted +1.5 dB
+        let weights = (0..channels)
+            .map(|ch| match (channels, ch) {
+                (6, 3) => 0.0,
+                (6, 4 | 5) => 1.41,
+                _ => 1.0,
+            })
+            .collect();
+        Self {
+            filters: vec![k_weighting(rate); channels],
+            weights,
+            step_len: (rate / 10.0).round().max(1.0) as usize,
+            step_frames: 0,
+            step_sum: 0.0,
+            steps: Vec::new(),
+        }
+    }
+
+    fn push(&mut self, samples: &[f32], channels: usize) {
+        if channels != self.filters.len() || channels == 0 {
+            return;
+        }
+        for frame in samples.chunks_exact(channels) {
+            for (ch, &s) in frame.iter().enumerate() {
+                let [shelf, high_pass] = &mut self.filters[ch];
+                let y = high_pass.process(shelf.process(s as f64));
+                self.step_sum += self.weights[ch] * y * y;
+            }
+            self.step_frames += 1;
+            if self.step_frames == self.step_len {
+                self.steps.push(self.step_sum / self.step_len as f64);
+                self.step_frames = 0;
+                self.step_sum = 0.0;
+            }
+        }
+    }
+
+    fn integrated(&self) -> Option<f64> {
+        let loudness = |energy: f64| -0.691 + 10.0 * energy.log10();
+        let blocks: Vec<f64> = self
+            .steps
+            .windows(4)
+            .map(|w| w.iter().sum::<f64>() / 4.0)
+            .filter(|&e| e > 0.0 && loudness(e) > -70.0)
+            .collect();
+        if blocks.is_empty() {
+            return None;
+        }
+        let relative_gate = loudness(blocks.iter().sum::<f64>() / blocks.len() as f64) - 10.0;
+        let gated: Vec<f64> = blocks.into_iter().filter(|&e| loudness(e) > relative_gate).collect();
+        (!gated.is_empty()).then(|| loudness(gated.iter().sum::<f64>() / gated.len() as f64))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Integrated loudness of `secs` of a 1 kHz sine at `dbfs` peak on every channel
+    fn sine_loudness(rate: f64, channels: usize, dbfs: f64, secs: f64) -> Option<f64> {
+        let amplitude = 10f64.powf(dbfs / 20.0);
+        let frames = (rate * secs) as usize;
+        let samples: Vec<f32> = (0..frames)
+            .flat_map(|i| {
+                let s = (amplitude * (2.0 * PI * 1000.0 * i as f64 / rate).sin()) as f32;
+                std::iter::repeat_n(s, channels)
+            })
+            .collect();
+        let mut meter = Meter::new(rate, channels);
+        // Fed in decoder-sized pieces, as the analysis does
+        for chunk in samples.chunks(4096 * channels) {
+            meter.push(chunk, channels);
+        }
+        meter.integrated()
+    }
+
+    #[test]
+    fn stereo_sine_at_minus_23_dbfs_reads_minus_23_lufs() {
+        // EBU Tech 3341, test case 1
+        let lufs = sine_loudness(48_000.0, 2, -23.0, 20.0).unwrap();
+        assert!((lufs + 23.0).abs() < 0.1, "{}", lufs);
+    }
+
+    #[test]
+    fn k_weighting_holds_at_other_sample_rates() {
+        let lufs = sine_loudness(44_100.0, 2, -23.0, 20.0).unwrap();
+        assert!((lufs + 23.0).abs() < 0.1, "{}", lufs);
+    }
+
+    #[test]
+    fn full_scale_mono_sine_reads_minus_3_lufs() {
+        // BS.1770: a 0 dBFS 1 kHz sine in one channel is -3.01 LKFS
+        let lufs = sine_loudness(48_000.0, 1, 0.0, 5.0).unwrap();
+        assert!((lufs + 3.01).abs() < 0.1, "{}", lufs);
+    }
+
+    #[test]
+    fn silence_has_no_loudness() {
+        let mut meter = Meter::new(48_000.0, 2);
+        meter.push(&vec![0.0; 48_000 * 2 * 5], 2);
+        assert_eq!(meter.integrated(), None);
+    }
+}
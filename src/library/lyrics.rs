@@ -0,0 +1,151 @@
+use std::fs;
+use std::path::Path;
+
+use super::track::Track;
+
+/// One lyrics line; `time` is set for synced (LRC) lyrics
+#[derive(Debug, Clone)]
+pub struct LyricLine {
+    pub time: Option<f64>,
+    pub text: String,
+}
+
+/// Embedded lyrics, else a `.lrc` / `.txt` file next to the track
+pub fn load(track: &Track) -> Option<Vec<LyricLine>> {
+    let text = track.lyrics.clone()
+        .filter(|l| !l.trim().is_empty())
+        .or_else(|| sidecar(&track.path))?;
+    let lines = parse(&text);
+    (!lines.is_empty()).then_some(lines)
+}
+
+fn sidecar(path: &Path) -> Option<String> {
+    ["lrc", "txt"].iter()
+        .filter_map(|ext| fs::read_to_string(path.with_extension(ext)).ok())
+        .find(|s| !s.trim().is_empty())
+}
+
+/// Plain or LRC text to lines. `[mm:ss.xx]` stamps are taken off (a line with several
+/// is repeated at each time), `[ar:...]`-style header tags are dropped, and synced
+/// lyrics come back in time order.
+pub fn parse(text: &str) -> Vec<LyricLine> {
+    let mut lines = Vec::new();
+    for raw in text.lines() {
+        let mut rest = raw.trim_end_matches('\r');
+        let mut times = Vec::new();
+        let mut header = false;
+        while let Some(tag) = rest.strip_prefix('[').and_then(|r| r.split_once(']')) {
+            match parse_time(tag.0) {
+                Some(t) => times.push(t),
+                None if tag.0.contains(':') => header = true,
+                None => break,
+            }
+            rest = tag.1;
+        }
+        if header && times.is_empty() {
+            continue;
+        }
+        let text = rest.trim().to_string();
+        if times.is_empty() {
+            lines.push(LyricLine { time: None, text });
+        } else {
+            lines.extend(times.into_iter().map(|t| LyricLine { time: Some(t), text: text.clone() }));
+        }
+    }
+    if lines.iter().any(|l| l.time.is_some()) {
+        lines.retain(|l| l.time.is_some());
+        lines.sort_by(|a, b| a.time.unwrap_or_default().total_cmp(&b.time.unwrap_or_default()));
+    }
+    // Trim blank lines at the ends
+    while lines.last().is_some_and(|l| l.text.is_empty()) {
+        lines.pop();
+    }
+    let lead = lines.iter().take_while(|l| l.text.is_empty()).count();
+    lines.drain(..lead);
+    lines
+}
+
+/// Which lines of dual-language synced lyrics to show. Lines sharing a timestamp
+/// are read as original + translation, or original + romanization + translation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Variant {
+    All,
+    Original,
+    Romanized,
+    Translated,
+}
+
+impl Variant {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Variant::All => "All",
+            Variant::Original => "Original",
+            Variant::Romanized => "Romanized",
+            Variant::Translated => "Translated",
+        }
+    }
+
+    pub fn from_label(s: &str) -> Self {
+        match s {
+            "Original" => Variant::Original,
+            "Romanized" => Variant::Romanized,
+            "Translated" => Variant::Translated,
+            _ => Variant::All,
+        }
+    }
+}
+
+/// Runs of lines with the same timestamp
+fn groups(lines: &[LyricLine]) -> impl Iterator<Item = &[LyricLine]> {
+    lines.chunk_by(|a, b| a.time.is_some() && a.time == b.time)
+}
+
+/// Variants these lyrics have, `All` first; just `All` for single-language lyrics
+pub fn variants(lines: &[LyricLine]) -> Vec<Variant> {
+    let widest = groups(lines).map(<[LyricLine]>::len).max().unwrap_or(0);
+    let mut out = vec![Variant::All];
+    if widest >= 2 {
+        out.push(Variant::Original);
+        if widest >= 3 {
+            out.push(Variant::Romanized);
+        }
+        out.push(Variant::Translated);
+    }
+    out
+}
+
+/// The lines to show for `variant`; a line with no such version shows the original
+pub fn select(lines: &[LyricLine], variant: Variant) -> Vec<LyricLine> {
+    if variant == Variant::All {
+        return lines.to_vec();
+    }
+    groups(lines)
+        .map(|g| {
+            let pick = match variant {
+                Variant::Romanized if g.len() >= 3 => &g[1],
+                Variant::Translated if g.len() >= 2 => &g[g.len() - 1],
+                _ => &g[0],
+            };
+            pick.clone()
+        })
+        .collect()
+}
+
+/// Keep only the first of consecutive blank lines
+pub fn compress_blank(lines: &mut Vec<LyricLine>) {
+    let mut prev_blank = false;
+    lines.retain(|l| {
+        let blank = l.text.is_empty();
+        let keep = !(blank && prev_blank);
+        prev_blank = blank;
+        keep
+    });
+}
+
+/// "01:23.45" / "1:23" → seconds
+fn parse_time(s: &str) -> Option<f64> {
+    let (m, sec) = s.split_once(':')?;
+    let m: u32 = m.trim().parse().ok()?;
+    let sec: f64 = sec.trim().parse().ok()?;
+    Some(m as f64 * 60.0 + sec)
+}
@@ -1,92 +1,468 @@
+pub mod chapters;
+pub mod collate;
+pub mod cleanup;
+pub mod cover;
+pub mod ignore;
 pub mod scanner;
+pub mod search_index;
+pub mod loudness;
+pub mod lyrics;
+pub mod mood;
+pub mod radio;
+pub mod organize;
+pub mod tag_edit;
 pub mod track;
 pub mod watcher;
 
-use std::collections::BTreeSet;
-use std::path::Path;
-use track::Track;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use track::{Track, VARIOUS_ARTISTS};
+
+use crate::config::Config;
+use ignore::IgnoreRules;
+use search_index::{Field, SearchIndex};
 
 #[derive(Debug)]
 pub struct Library {
     pub tracks: Vec<Track>,
+    pub scan_stats: scanner::ScanStats,
+    /// Browse artists by individual names instead of the combined tag string
+    pub split_artists: bool,
+    /// Leading articles skipped when sorting artist/album names
+    pub sort_articles: Vec<String>,
+    /// Canonical genre by `genre_key` of each configured alias (and of the canonical name itself)
+    genre_aliases: HashMap<String, String>,
+    aggregates: Aggregates,
+    index: SearchIndex,
+    /// Track index by file path; the queue and playlists store paths and resolve through this
+    by_path: HashMap<PathBuf, usize>,
+    /// Position in `get_albums` of each track's album, by track index
+    album_of: Vec<Option<usize>>,
+}
+
+/// Browse lists derived from `tracks`, built once per scan so panes
+/// don't walk the whole library on every render or key press
+#[derive(Debug, Default)]
+struct Aggregates {
+    artists: Vec<String>,
+    album_artists: Vec<String>,
+    genres: Vec<String>,
+    albums: Vec<Album>,
+    formats: Vec<String>,
+    folders: Vec<Folder>,
+    /// Tracks with each `cleanup::Issue`, in `Issue::ALL` order
+    issues: Vec<Vec<usize>>,
+}
+
+/// A directory in the library's folder tree, listed in display (pre-)order
+#[derive(Debug, Clone)]
+pub struct Folder {
+    pub path: PathBuf,
+    /// Name relative to the parent row; chains of single subfolders are merged ("Artist/Album")
+    pub label: String,
+    pub depth: usize,
+    pub has_children: bool,
+    /// Tracks in this folder and everything below it
+    pub track_count: usize,
+}
+
+/// An album as browsed: its name and owner (see `Track::album_owner`), split further
+/// when different releases share both, e.g. two "Greatest Hits" compilations
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Album {
+    pub name: String,
+    pub artist: String,
+    /// Shown after the name when other albums share name and artist: the release
+    /// year, or the folder name when years don't tell them apart
+    pub edition: Option<String>,
+    split: Split,
+}
+
+/// What a track must share to belong to one of several same-named albums
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Split {
+    None,
+    Folder(PathBuf),
+    Year(Option<u32>),
+}
+
+impl Album {
+    pub fn contains(&self, t: &Track) -> bool {
+        same_name(&t.album, &self.name)
+            && same_name(t.album_owner(), &self.artist)
+            && match &self.split {
+                Split::None => true,
+                Split::Folder(dir) => album_dir(t) == Some(dir.as_path()),
+                Split::Year(year) => t.year == *year,
+            }
+    }
+
+    /// "Greatest Hits (1981)"
+    pub fn title(&self) -> String {
+        match &self.edition {
+            Some(edition) => format!("{} ({})", self.name, edition),
+            None => self.name.clone(),
+        }
+    }
+}
+
+/// Folder an album lives in; disc subfolders ("CD1", "Disc 2") count as their parent
+fn album_dir(t: &Track) -> Option<&Path> {
+    let dir = t.path.parent()?;
+    let name = dir.file_name()?.to_string_lossy().to_lowercase();
+    let rest = ["disc", "disk", "cd"].iter().find_map(|p| name.strip_prefix(p));
+    match rest.map(str::trim_start) {
+        Some(n) if !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()) => dir.parent().or(Some(dir)),
+        _ => Some(dir),
+    }
+}
+
+/// Directory tree built from track paths
+#[derive(Default)]
+struct DirTree {
+    children: HashMap<String, DirTree>,
+    tracks: usize,
+}
+
+impl DirTree {
+    fn total(&self) -> usize {
+        self.tracks + self.children.values().map(DirTree::total).sum::<usize>()
+    }
+
+    fn flatten(&self, path: &Path, depth: usize, out: &mut Vec<Folder>) {
+        let mut names: Vec<String> = self.children.keys().cloned().collect();
+        collate::sort_names(&mut names, &[]);
+        for name in names {
+            let mut node = &self.children[&name];
+            let mut path = path.join(&name);
+            let mut label = name;
+            while node.tracks == 0 && node.children.len() == 1 {
+                let (child_name, child) = node.children.iter().next().unwrap();
+                path.push(child_name);
+                label = format!("{}/{}", label, child_name);
+                node = child;
+            }
+            out.push(Folder {
+                path: path.clone(),
+                label,
+                depth,
+                has_children: !node.children.is_empty(),
+                track_count: node.total(),
+            });
+            node.flatten(&path, depth + 1, out);
+        }
+    }
+}
+
+/// Collect names, merging case/composition variants (first spelling wins),
+/// and return them in collation order.
+fn unique_sorted<'a>(names: impl Iterator<Item = &'a str>, articles: &[String]) -> Vec<String> {
+    let mut seen = HashSet::new();
+    let mut result: Vec<String> = Vec::new();
+    for name in names {
+        if !name.is_empty() && seen.insert(collate::dedupe_key(name)) {
+            result.push(name.to_string());
+        }
+    }
+    collate::sort_names(&mut result, articles);
+    result
+}
+
+/// Album artist tag, with compilations normalised to "Various Artists"; empty if untagged
+fn tagged_album_artist(t: &Track) -> &str {
+    if t.compilation {
+        t.album_owner()
+    } else {
+        &t.album_artist
+    }
+}
+
+/// Loose genre spelling key: lowercase letters and digits only, so "Alt-Rock",
+/// "alt rock" and "AltRock" all look up the same alias
+fn genre_key(genre: &str) -> String {
+    genre.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Case- and composition-insensitive name equality
+fn same_name(a: &str, b: &str) -> bool {
+    a == b || collate::dedupe_key(a) == collate::dedupe_key(b)
 }
 
 impl Library {
     pub fn new() -> Self {
         Self {
             tracks: Vec::new(),
+            scan_stats: scanner::ScanStats::default(),
+            split_artists: true,
+            sort_articles: vec!["The".to_string()],
+            genre_aliases: HashMap::new(),
+            aggregates: Aggregates::default(),
+            index: SearchIndex::default(),
+            by_path: HashMap::new(),
+            album_of: Vec::new(),
         }
     }
 
-    pub fn scan(path: &Path) -> Self {
-        let tracks = scanner::scan_directory(path);
-        Self { tracks }
+    /// Library of just `tracks`, indexed by path but with no aggregates or search
+    #[cfg(test)]
+    pub fn from_tracks(tracks: Vec<Track>) -> Self {
+        let by_path = tracks.iter().enumerate().map(|(i, t)| (t.path.clone(), i)).collect();
+        Self { tracks, by_path, ..Self::new() }
     }
 
-    pub fn get_artists(&self) -> Vec<String> {
-        let mut set = BTreeSet::new();
+    pub fn scan(path: &Path, config: &Config) -> Self {
+        let ignore = IgnoreRules::load(path, &config.ignore);
+        let (tracks, scan_stats) = scanner::scan_directory(path, &ignore);
+        let mut lib = Self {
+            tracks,
+            scan_stats,
+            split_artists: config.split_artists,
+            sort_articles: config.sort_articles.clone(),
+            genre_aliases: config.genre_aliases.iter()
+                .flat_map(|(alias, canonical)| [
+                    (genre_key(alias), canonical.clone()),
+                    (genre_key(canonical), canonical.clone()),
+                ])
+                .collect(),
+            aggregates: Aggregates::default(),
+            index: SearchIndex::default(),
+            by_path: HashMap::new(),
+            album_of: Vec::new(),
+        };
+        lib.aggregates = Aggregates {
+            artists: lib.collect_artists(),
+            album_artists: lib.collect_album_artists(),
+            genres: lib.collect_genres(),
+            albums: lib.collect_albums(),
+            formats: lib.collect_formats(),
+            folders: lib.collect_folders(path),
+            issues: cleanup::find(&lib.tracks),
+        };
+        lib.index = SearchIndex::build(&lib.tracks, |t| lib.genre_of(t));
+        lib.by_path = lib.tracks.iter().enumerate()
+            .map(|(i, t)| (t.path.clone(), i))
+            .collect();
+        lib.album_of = lib.collect_album_of();
+        lib
+    }
+
+    pub fn get_artists(&self) -> &[String] {
+        &self.aggregates.artists
+    }
+
+    #[allow(dead_code)]
+    pub fn get_album_artists(&self) -> &[String] {
+        &self.aggregates.album_artists
+    }
+
+    pub fn get_genres(&self) -> &[String] {
+        &self.aggregates.genres
+    }
+
+    /// Tracks listed under a Cleanup view
+    pub fn tracks_with(&self, issue: cleanup::Issue) -> &[usize] {
+        cleanup::Issue::ALL.iter()
+            .position(|&i| i == issue)
+            .and_then(|i| self.aggregates.issues.get(i))
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// Albums in collation order
+    pub fn get_albums(&self) -> &[Album] {
+        &self.aggregates.albums
+    }
+
+    pub fn get_formats(&self) -> &[String] {
+        &self.aggregates.formats
+    }
+
+    /// Folder tree under the music directory, in display order
+    pub fn get_folders(&self) -> &[Folder] {
+        &self.aggregates.folders
+    }
+
+    /// Artist names of a track as browsed (split or combined)
+    fn browse_artists<'a>(&self, t: &'a Track) -> Vec<&'a str> {
+        if self.split_artists && !t.artists.is_empty() {
+            t.artists.iter().map(|a| a.as_str()).collect()
+        } else {
+            vec![t.artist.as_str()]
+        }
+    }
+
+    /// Artists who appear only on compilations are folded into a single
+    /// "Various Artists" entry instead of one entry each
+    fn collect_artists(&self) -> Vec<String> {
         let mut has_unknown = false;
-        for t in &self.tracks {
-            if t.artist.is_empty() {
+        let mut has_various = false;
+        let regular: HashSet<String> = self.tracks.iter()
+            .filter(|t| !t.compilation)
+            .flat_map(|t| self.browse_artists(t))
+            .map(collate::dedupe_key)
+            .collect();
+        let names = self.tracks.iter().flat_map(|t| {
+            if t.compilation {
+                has_various = true;
+                self.browse_artists(t)
+                    .into_iter()
+                    .filter(|a| regular.contains(&collate::dedupe_key(a)))
+                    .collect()
+            } else if t.artist.is_empty() {
                 has_unknown = true;
+                Vec::new()
             } else {
-                set.insert(t.artist.clone());
+                self.browse_artists(t)
             }
+        });
+        let mut result = unique_sorted(names, &self.sort_articles);
+        if has_various && !result.iter().any(|a| same_name(a, VARIOUS_ARTISTS)) {
+            result.push(VARIOUS_ARTISTS.to_string());
+            collate::sort_names(&mut result, &self.sort_articles);
         }
-        let mut result: Vec<String> = set.into_iter().collect();
         if has_unknown {
             result.push("Unknown Artist".to_string());
         }
         result
     }
 
-    #[allow(dead_code)]
-    pub fn get_album_artists(&self) -> Vec<String> {
-        let mut set = BTreeSet::new();
-        for t in &self.tracks {
-            if !t.album_artist.is_empty() {
-                set.insert(t.album_artist.clone());
-            }
-        }
-        set.into_iter().collect()
+    fn collect_album_artists(&self) -> Vec<String> {
+        unique_sorted(self.tracks.iter().map(tagged_album_artist), &self.sort_articles)
     }
 
-    pub fn get_genres(&self) -> Vec<String> {
-        let mut set = BTreeSet::new();
-        for t in &self.tracks {
-            if !t.genre.is_empty() {
-                set.insert(t.genre.clone());
-            }
+    /// Genre a track is browsed and searched under, after the configured alias map
+    pub fn genre_of<'a>(&'a self, t: &'a Track) -> &'a str {
+        if self.genre_aliases.is_empty() {
+            return &t.genre;
         }
-        set.into_iter().collect()
+        self.genre_aliases.get(&genre_key(&t.genre)).map_or(&t.genre, |g| g.as_str())
     }
 
-    pub fn get_albums(&self) -> Vec<(String, String)> {
-        let mut set = BTreeSet::new();
+    fn collect_genres(&self) -> Vec<String> {
+        unique_sorted(self.tracks.iter().map(|t| self.genre_of(t)), &[])
+    }
+
+    /// One album per name and owner, unless its tracks sit in several album folders
+    /// (one album per folder) or, all in one folder, carry several years (one per year)
+    fn collect_albums(&self) -> Vec<Album> {
+        let mut groups: Vec<Vec<&Track>> = Vec::new();
+        let mut group_of: HashMap<(String, String), usize> = HashMap::new();
         for t in &self.tracks {
             if !t.album.is_empty() {
-                let artist = if t.album_artist.is_empty() {
-                    t.artist.clone()
-                } else {
-                    t.album_artist.clone()
-                };
-                set.insert((t.album.clone(), artist));
+                let key = (collate::dedupe_key(&t.album), collate::dedupe_key(t.album_owner()));
+                let g = *group_of.entry(key).or_insert_with(|| {
+                    groups.push(Vec::new());
+                    groups.len() - 1
+                });
+                groups[g].push(t);
             }
         }
-        set.into_iter().collect()
+
+        let mut result: Vec<Album> = Vec::new();
+        for group in groups {
+            let album = |edition: Option<String>, split: Split| Album {
+                name: group[0].album.clone(),
+                artist: group[0].album_owner().to_string(),
+                edition,
+                split,
+            };
+            let mut folders: Vec<&Path> = Vec::new();
+            for dir in group.iter().filter_map(|t| album_dir(t)) {
+                if !folders.contains(&dir) {
+                    folders.push(dir);
+                }
+            }
+            let mut years: Vec<Option<u32>> = Vec::new();
+            for t in &group {
+                if !years.contains(&t.year) {
+                    years.push(t.year);
+                }
+            }
+
+            if folders.len() > 1 {
+                // Name each folder's album by its year when that alone tells it apart
+                let folder_years: Vec<Option<u32>> = folders.iter()
+                    .map(|&dir| {
+                        let mut in_dir = group.iter().filter(|t| album_dir(t) == Some(dir)).map(|t| t.year);
+                        let first = in_dir.next().flatten();
+                        in_dir.all(|y| y == first).then_some(first).flatten()
+                    })
+                    .collect();
+                for (dir, year) in folders.iter().zip(&folder_years) {
+                    let edition = match year {
+                        Some(y) if folder_years.iter().filter(|&other| other == year).count() == 1 => y.to_string(),
+                        _ => dir.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default(),
+                    };
+                    result.push(album(Some(edition), Split::Folder(dir.to_path_buf())));
+                }
+            } else if years.len() > 1 {
+                years.sort();
+                for year in years {
+                    let edition = year.map_or_else(|| "no year".to_string(), |y| y.to_string());
+                    result.push(album(Some(edition), Split::Year(year)));
+                }
+            } else {
+                result.push(album(None, Split::None));
+            }
+        }
+        result.sort_by_cached_key(|a| {
+            (
+                collate::sort_key(&a.name, &self.sort_articles),
+                collate::sort_key(&a.artist, &self.sort_articles),
+                a.edition.clone(),
+                a.name.clone(),
+            )
+        });
+        result
+    }
+
+    /// Position of the track's album in `get_albums`
+    pub fn album_position(&self, t: &Track) -> Option<usize> {
+        self.path_to_index(&t.path).and_then(|i| self.album_of[i])
+    }
+
+    /// Only albums sharing a track's name and owner can hold it, so each track is
+    /// checked against those few rather than every album
+    fn collect_album_of(&self) -> Vec<Option<usize>> {
+        let mut by_name: HashMap<(String, String), Vec<usize>> = HashMap::new();
+        for (i, a) in self.aggregates.albums.iter().enumerate() {
+            by_name.entry((collate::dedupe_key(&a.name), collate::dedupe_key(&a.artist))).or_default().push(i);
+        }
+        self.tracks
+            .iter()
+            .map(|t| {
+                let key = (collate::dedupe_key(&t.album), collate::dedupe_key(t.album_owner()));
+                by_name.get(&key)?.iter().copied().find(|&i| self.aggregates.albums[i].contains(t))
+            })
+            .collect()
+    }
+
+    /// Position of the track's genre in `get_genres`
+    pub fn genre_position(&self, t: &Track) -> Option<usize> {
+        let genre = self.genre_of(t);
+        self.get_genres().iter().position(|g| same_name(g, genre))
+    }
+
+    /// Whether the track is listed under `artist` in the Artists tab
+    pub fn is_by_artist(&self, t: &Track, artist: &str) -> bool {
+        if artist == "Unknown Artist" {
+            t.artist.is_empty() && !t.compilation
+        } else if same_name(artist, VARIOUS_ARTISTS) && t.compilation {
+            true
+        } else if self.split_artists && !t.artists.is_empty() {
+            t.artists.iter().any(|a| same_name(a, artist))
+        } else {
+            same_name(&t.artist, artist)
+        }
     }
 
     pub fn get_tracks_by_artist(&self, artist: &str) -> Vec<usize> {
         self.tracks
             .iter()
             .enumerate()
-            .filter(|(_, t)| {
-                if artist == "Unknown Artist" {
-                    t.artist.is_empty()
-                } else {
-                    t.artist == artist
-                }
-            })
+            .filter(|(_, t)| self.is_by_artist(t, artist))
             .map(|(i, _)| i)
             .collect()
     }
@@ -96,41 +472,46 @@ impl Library {
         self.tracks
             .iter()
             .enumerate()
-            .filter(|(_, t)| t.album_artist == album_artist)
+            .filter(|(_, t)| same_name(tagged_album_artist(t), album_artist))
             .map(|(i, _)| i)
             .collect()
     }
 
-    pub fn get_tracks_by_album(&self, album: &str) -> Vec<usize> {
-        self.tracks
+    /// Tracks of an album in disc+track order, so multi-disc albums play in sequence.
+    /// Albums are keyed by name, owner and edition, so "Greatest Hits" by two artists,
+    /// or two "Greatest Hits" compilations in different folders, stay apart.
+    pub fn get_tracks_by_album(&self, album: &Album) -> Vec<usize> {
+        let mut result: Vec<usize> = self.tracks
             .iter()
             .enumerate()
-            .filter(|(_, t)| t.album == album)
+            .filter(|(_, t)| album.contains(t))
             .map(|(i, _)| i)
-            .collect()
+            .collect();
+        result.sort_by_key(|&i| self.tracks[i].disc_track());
+        result
     }
 
     pub fn get_tracks_by_genre(&self, genre: &str) -> Vec<usize> {
         self.tracks
             .iter()
             .enumerate()
-            .filter(|(_, t)| t.genre == genre)
+            .filter(|(_, t)| same_name(self.genre_of(t), genre))
             .map(|(i, _)| i)
             .collect()
     }
 
     #[allow(dead_code)]
     pub fn get_albums_by_album_artist(&self, album_artist: &str) -> Vec<String> {
-        let mut set = BTreeSet::new();
-        for t in &self.tracks {
-            if t.album_artist == album_artist && !t.album.is_empty() {
-                set.insert(t.album.clone());
-            }
-        }
-        set.into_iter().collect()
+        unique_sorted(
+            self.tracks
+                .iter()
+                .filter(|t| same_name(tagged_album_artist(t), album_artist))
+                .map(|t| t.album.as_str()),
+            &self.sort_articles,
+        )
     }
 
-    pub fn get_formats(&self) -> Vec<String> {
+    fn collect_formats(&self) -> Vec<String> {
         let mut set = BTreeSet::new();
         for t in &self.tracks {
             let ext = t.path.extension()
@@ -143,6 +524,31 @@ impl Library {
         set.into_iter().collect()
     }
 
+    fn collect_folders(&self, root: &Path) -> Vec<Folder> {
+        let mut tree = DirTree::default();
+        for t in &self.tracks {
+            let Some(rel) = t.path.parent().and_then(|p| p.strip_prefix(root).ok()) else {
+                continue;
+            };
+            let mut node = &mut tree;
+            for c in rel.components() {
+                node = node.children.entry(c.as_os_str().to_string_lossy().into_owned()).or_default();
+            }
+            node.tracks += 1;
+        }
+        let mut folders = Vec::new();
+        tree.flatten(root, 0, &mut folders);
+        folders
+    }
+
+    /// Tracks in `dir` and all its subdirectories, in library order
+    pub fn get_tracks_under(&self, dir: &Path) -> Vec<usize> {
+        self.tracks.iter().enumerate()
+            .filter(|(_, t)| t.path.starts_with(dir))
+            .map(|(i, _)| i)
+            .collect()
+    }
+
     pub fn get_tracks_by_format(&self, format: &str) -> Vec<usize> {
         self.tracks
             .iter()
@@ -172,11 +578,58 @@ impl Library {
             }
         }
 
-        (subdirs.into_iter().collect(), tracks)
+        let mut subdirs: Vec<String> = subdirs.into_iter().collect();
+        collate::sort_names(&mut subdirs, &[]);
+        (subdirs, tracks)
+    }
+
+    /// Whether any track lives in `dir` or below it
+    pub fn has_directory(&self, dir: &Path) -> bool {
+        self.tracks.iter().any(|t| t.path.starts_with(dir))
     }
 
     pub fn path_to_index(&self, path: &Path) -> Option<usize> {
-        self.tracks.iter().position(|t| t.path == path)
+        self.by_path.get(path).copied()
+    }
+
+    pub fn track_by_path(&self, path: &Path) -> Option<&Track> {
+        self.path_to_index(path).map(|i| &self.tracks[i])
+    }
+
+    /// Paths of the given track indices
+    pub fn paths_of(&self, indices: &[usize]) -> Vec<PathBuf> {
+        indices.iter()
+            .filter_map(|&i| self.tracks.get(i).map(|t| t.path.clone()))
+            .collect()
+    }
+
+    /// Artist / album / genre names for completing a `field:` search filter: names
+    /// starting with `typed` first, then ones containing it
+    pub fn field_completions(&self, field: &str, typed: &str) -> Vec<String> {
+        let names: Vec<&str> = match field {
+            "artist" => self.get_artists().iter().map(String::as_str).collect(),
+            "album" => {
+                let mut seen = HashSet::new();
+                self.get_albums().iter()
+                    .map(|album| album.name.as_str())
+                    .filter(|a| seen.insert(collate::dedupe_key(a)))
+                    .collect()
+            }
+            "genre" => self.get_genres().iter().map(String::as_str).collect(),
+            _ => return Vec::new(),
+        };
+        let typed = typed.to_lowercase();
+        let (mut starts, mut contains) = (Vec::new(), Vec::new());
+        for name in names {
+            let lower = name.to_lowercase();
+            if lower.starts_with(&typed) {
+                starts.push(name.to_string());
+            } else if lower.contains(&typed) {
+                contains.push(name.to_string());
+            }
+        }
+        starts.extend(contains);
+        starts
     }
 
     pub fn search(&self, query: &str) -> Vec<usize> {
@@ -197,52 +650,116 @@ impl Library {
                 .collect();
         }
 
-        // Field-specific filter: artist:, album:, genre:, title:
+        // Field-specific filter: artist:, album:, genre:, title:. A quoted value
+        // (`artist:"the beatles" help`) leaves the rest of the query to narrow it down.
         if let Some((prefix, value)) = query.split_once(':') {
             let field = prefix.trim().to_lowercase();
+            let (value, rest) = match value.trim_start().strip_prefix('"') {
+                Some(quoted) => quoted.split_once('"').unwrap_or((quoted, "")),
+                None => (value, ""),
+            };
+            // bpm:120..128, bpm:..100, bpm:170.. or bpm:128, then words to narrow it down
+            if field == "bpm" {
+                let (range, rest) = match rest {
+                    "" => value.trim().split_once(' ').unwrap_or((value.trim(), "")),
+                    _ => (value.trim(), rest),
+                };
+                if let Some((lo, hi)) = parse_bpm_range(range) {
+                    let mut results: Vec<usize> = self.tracks.iter().enumerate()
+                        .filter(|(_, t)| t.bpm.is_some_and(|b| b >= lo && b <= hi))
+                        .map(|(i, _)| i)
+                        .collect();
+                    if !rest.trim().is_empty() {
+                        let narrowed: HashSet<usize> = self.search(rest.trim()).into_iter().collect();
+                        results.retain(|i| narrowed.contains(i));
+                    }
+                    return results;
+                }
+            }
             let v = value.trim().to_lowercase();
             if !v.is_empty() {
-                match field.as_str() {
-                    "artist" => {
-                        return self.tracks.iter().enumerate()
-                            .filter(|(_, t)| t.artist.to_lowercase().contains(&v))
-                            .map(|(i, _)| i).collect();
-                    }
-                    "album" => {
-                        return self.tracks.iter().enumerate()
-                            .filter(|(_, t)| t.album.to_lowercase().contains(&v))
-                            .map(|(i, _)| i).collect();
-                    }
-                    "genre" => {
-                        return self.tracks.iter().enumerate()
-                            .filter(|(_, t)| t.genre.to_lowercase().contains(&v))
-                            .map(|(i, _)| i).collect();
-                    }
-                    "title" => {
-                        return self.tracks.iter().enumerate()
-                            .filter(|(_, t)| t.title.to_lowercase().contains(&v))
-                            .map(|(i, _)| i).collect();
+                let field = match field.as_str() {
+                    "artist" => Some(Field::Artist),
+                    "album" => Some(Field::Album),
+                    "genre" => Some(Field::Genre),
+                    "title" => Some(Field::Title),
+                    _ => None, // unknown prefix, fall through to general search
+                };
+                if let Some(field) = field {
+                    let mut results = self.index.find(&v, &[field]);
+                    if !rest.trim().is_empty() {
+                        let narrowed: HashSet<usize> = self.search(rest.trim()).into_iter().collect();
+                        results.retain(|i| narrowed.contains(i));
                     }
-                    _ => {} // unknown prefix, fall through to general search
+                    results.sort_by_cached_key(|&i| self.prefix_rank(i, field, &v));
+                    return results;
                 }
             }
         }
 
         // General search: title, artist, album, genre, filename
         let q = query.to_lowercase();
-        self.tracks
-            .iter()
-            .enumerate()
-            .filter(|(_, t)| {
-                t.title.to_lowercase().contains(&q)
-                    || t.artist.to_lowercase().contains(&q)
-                    || t.album.to_lowercase().contains(&q)
-                    || t.genre.to_lowercase().contains(&q)
-                    || t.path.file_name()
-                        .map(|f| f.to_string_lossy().to_lowercase().contains(&q))
-                        .unwrap_or(false)
-            })
-            .map(|(i, _)| i)
-            .collect()
+        let mut results = self.index.find(
+            &q,
+            &[Field::Title, Field::Artist, Field::Album, Field::Genre, Field::FileName],
+        );
+
+        // Rank names that start with the query first (stable, so library order is kept within a rank)
+        results.sort_by_cached_key(|&i| {
+            self.prefix_rank(i, Field::Title, &q)
+                .min(self.prefix_rank(i, Field::Artist, &q))
+                .min(self.prefix_rank(i, Field::Album, &q))
+        });
+        results
+    }
+
+    /// 0 if the track's `field` starts with the (lowercased) query, ignoring a leading
+    /// article and accents, so "beat" ranks "The Beatles" as a prefix hit; 1 otherwise.
+    fn prefix_rank(&self, track_idx: usize, field: Field, query: &str) -> u8 {
+        if self.index.field(track_idx, field).starts_with(query)
+            || collate::sort_key(self.index.field(track_idx, field), &self.sort_articles)
+                .starts_with(&collate::sort_key(query, &[]))
+        {
+            0
+        } else {
+            1
+        }
+    }
+}
+
+/// Inclusive tempo bounds from "120..128", "..100", "170.." or "128" (which allows
+/// half a beat either way, for tags rounded differently)
+fn parse_bpm_range(s: &str) -> Option<(f64, f64)> {
+    let bound = |b: &str, open: f64| if b.is_empty() { Some(open) } else { b.trim().parse::<f64>().ok() };
+    match s.split_once("..") {
+        Some((lo, hi)) if !(lo.is_empty() && hi.is_empty()) => Some((bound(lo, 0.0)?, bound(hi, f64::MAX)?)),
+        Some(_) => None,
+        None => s.parse::<f64>().ok().map(|b| (b - 0.5, b + 0.5)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_bpm_range;
+
+    #[test]
+    fn closed_and_open_ranges() {
+        assert_eq!(parse_bpm_range("120..128"), Some((120.0, 128.0)));
+        assert_eq!(parse_bpm_range("..100"), Some((0.0, 100.0)));
+        assert_eq!(parse_bpm_range("170.."), Some((170.0, f64::MAX)));
+        assert_eq!(parse_bpm_range("92.5..95"), Some((92.5, 95.0)));
+    }
+
+    #[test]
+    fn single_tempo_allows_half_a_beat() {
+        assert_eq!(parse_bpm_range("128"), Some((127.5, 128.5)));
+    }
+
+    #[test]
+    fn rejects_empty_and_garbage() {
+        assert_eq!(parse_bpm_range(".."), None);
+        assert_eq!(parse_bpm_range(""), None);
+        assert_eq!(parse_bpm_range("fast"), None);
+        assert_eq!(parse_bpm_range("120..fast"), None);
     }
 }
@@ -0,0 +1,87 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use super::loudness::REPLAYGAIN_REFERENCE;
+use super::track::Track;
+
+/// Mood playlists built by `Ctrl+E, m`, in the order they're listed
+pub const MOODS: [Mood; 3] = [Mood::Chill, Mood::HighEnergy, Mood::Dance];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mood {
+    Chill,
+    HighEnergy,
+    Dance,
+}
+
+impl Mood {
+    pub fn name(self) -> &'static str {
+        match self {
+            Mood::Chill => "Chill",
+            Mood::HighEnergy => "High energy",
+            Mood::Dance => "Dance",
+        }
+    }
+
+    fn fits(self, f: &Features) -> bool {
+        match self {
+            Mood::Chill => f.energy < 0.35,
+            Mood::HighEnergy => f.energy > 0.7,
+            Mood::Dance => f.danceability.is_some_and(|d| d > 0.7),
+        }
+    }
+}
+
+/// Rough 0–1 scores derived from a track's loudness and tempo
+#[derive(Debug, Clone, Copy)]
+pub struct Features {
+    /// Loud and fast is high; from whichever of loudness and BPM are known
+    pub energy: f64,
+    /// Tempo near 120 BPM with a solid level; needs a BPM
+    pub danceability: Option<f64>,
+}
+
+/// Integrated loudness of a track: from its ReplayGain tag, else the measured value
+pub fn track_loudness(track: &Track, measured: &HashMap<PathBuf, f64>) -> Option<f64> {
+    track
+        .replay_gain
+        .map(|rg| REPLAYGAIN_REFERENCE - rg)
+        .or_else(|| measured.get(&track.path).copied())
+}
+
+/// None when neither loudness nor tempo is known
+pub fn features(track: &Track, measured: &HashMap<PathBuf, f64>) -> Option<Features> {
+    // -20 LUFS (quiet acoustic) .. -6 LUFS (brickwalled)
+    let loud = track_loudness(track, measured).map(|lufs| ((lufs + 20.0) / 14.0).clamp(0.0, 1.0));
+    // 70 .. 160 BPM
+    let tempo = track.bpm.map(|bpm| ((bpm - 70.0) / 90.0).clamp(0.0, 1.0));
+    let energy = match (loud, tempo) {
+        (Some(l), Some(t)) => (l + t) / 2.0,
+        (Some(x), None) | (None, Some(x)) => x,
+        (None, None) => return None,
+    };
+    let danceability = track.bpm.map(|bpm| {
+        let groove = (1.0 - (bpm - 120.0).abs() / 40.0).clamp(0.0, 1.0);
+        groove * (0.6 + 0.4 * loud.unwrap_or(0.5))
+    });
+    Some(Features { energy, danceability })
+}
+
+/// Tracks for each of `MOODS`, in library order
+pub fn build(tracks: &[Track], measured: &HashMap<PathBuf, f64>) -> Vec<(Mood, Vec<PathBuf>)> {
+    let scored: Vec<(&Track, Features)> = tracks
+        .iter()
+        .filter_map(|t| features(t, measured).map(|f| (t, f)))
+        .collect();
+    MOODS
+        .iter()
+        .map(|&mood| {
+            let paths = scored
+                .iter()
+                .filter(|(_, f)| mood.fits(f))
+                .map(|(t, _)| t.path.clone())
+                .collect();
+            (mood, paths)
+        })
+        .collect()
+}
@@ -0,0 +1,211 @@
+use std::collections::HashSet;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use super::Library;
+
+/// Files sharing a track's name that belong with it: lyrics (see `lyrics::load`)
+const SIDECARS: [&str; 2] = ["lrc", "txt"];
+
+/// One file's pending move, shown in the dry-run preview before anything is touched
+#[derive(Debug, Clone)]
+pub struct Move {
+    pub from: PathBuf,
+    pub to: PathBuf,
+}
+
+/// Where `Artist/Album/NN - Title.ext` puts each track under `root`. Files already in
+/// place are left out, as are ones whose target exists or is claimed by another track.
+pub fn plan(library: &Library, root: &Path, tracks: &[usize]) -> Vec<Move> {
+    let mut claimed: HashSet<PathBuf> = HashSet::new();
+    tracks.iter()
+        .filter_map(|&idx| {
+            let t = library.tracks.get(idx)?;
+            let ext = t.path.extension().map(|e| e.to_string_lossy().to_lowercase()).unwrap_or_default();
+            let title = if t.title.is_empty() {
+                t.path.file_stem().map(|s| s.to_string_lossy().into_owned()).unwrap_or_default()
+            } else {
+                t.title.clone()
+            };
+            let number = match (t.disc_number, t.track_number) {
+                (Some(d), Some(n)) if d > 1 => format!("{}-{:02} - ", d, n),
+                (_, Some(n)) => format!("{:02} - ", n),
+                _ => String::new(),
+            };
+            let owner = if t.album_owner().is_empty() { "Unknown Artist" } else { t.album_owner() };
+            let to = root
+                .join(component(owner))
+                .join(component(t.display_album()))
+                .join(format!("{}{}.{}", number, component(&title), ext));
+            if to == t.path || to.exists() || !claimed.insert(to.clone()) {
+                return None;
+            }
+            Some(Move { from: t.path.clone(), to })
+        })
+        .collect()
+}
+
+/// Move the files, creating folders as needed and removing folders the moves emptied.
+/// Returns the moves that happened and (path, error) for the ones that didn't.
+pub fn apply(moves: &[Move]) -> (Vec<Move>, Vec<(PathBuf, String)>) {
+    let mut done = Vec::new();
+    let mut failed = Vec::new();
+    for m in moves {
+        match move_file(&m.from, &m.to) {
+            Ok(()) => done.push(m.clone()),
+            Err(e) => {
+                failed.push((m.from.clone(), e.to_string()));
+                continue;
+            }
+        }
+        for ext in SIDECARS {
+            let from = m.from.with_extension(ext);
+            if from.is_file() {
+                if let Err(e) = move_file(&from, &m.to.with_extension(ext)) {
+                    failed.push((from, e.to_string()));
+                }
+            }
+        }
+    }
+    for m in &done {
+        // Only succeeds on empty folders, so siblings that stayed keep their folder
+        let mut dir = m.from.parent();
+        while let Some(d) = dir {
+            if fs::remove_dir(d).is_err() {
+                break;
+            }
+            dir = d.parent();
+        }
+    }
+    (done, failed)
+}
+
+fn move_file(from: &Path, to: &Path) -> anyhow::Result<()> {
+    if to.exists() {
+        anyhow::bail!("{} already exists", to.display());
+    }
+    if let Some(parent) = to.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    match fs::rename(from, to) {
+        // Rename can't cross filesystems: copy, then remove the original, keeping
+        // exactly one copy whatever fails
+        Err(e) if e.kind() == io::ErrorKind::CrossesDevices => {
+            if let Err(e) = fs::copy(from, to).and_then(|_| fs::remove_file(from)) {
+                let _ = fs::remove_file(to);
+                return Err(e.into());
+            }
+            Ok(())
+        }
+        result => Ok(result?),
+    }
+}
+
+/// Tag text made safe for a single path component
+fn component(s: &str) -> String {
+    let cleaned: String = s.chars()
+        .map(|c| if matches!(c, '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|') || c.is_control() { '_' } else { c })
+        .collect();
+    let cleaned = cleaned.trim().trim_start_matches('.').trim_end_matches('.').trim();
+    if cleaned.is_empty() { "_".to_string() } else { cleaned.to_string() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::library::track::{test_track, Track};
+
+    fn track(path: &str, artist: &str, album: &str, title: &str, number: Option<u32>) -> Track {
+        Track {
+            artist: artist.to_string(),
+            album: album.to_string(),
+            title: title.to_string(),
+            track_number: number,
+            ..test_track(path)
+        }
+    }
+
+    fn library(tracks: Vec<Track>) -> Library {
+        let mut lib = Library::new();
+        lib.tracks = tracks;
+        lib
+    }
+
+    /// Fresh directory for one test, removed first in case an earlier run left it
+    fn scratch(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("ommp-organize-{}-{}", std::process::id(), name));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn plans_artist_album_number_title() {
+        let mut disc_two = track("/in/b.mp3", "Queen", "Live", "Mustapha", Some(3));
+        disc_two.disc_number = Some(2);
+        let lib = library(vec![track("/in/a.FLAC", "AC/DC", "Back in Black", "Hells Bells", Some(1)), disc_two]);
+        let moves = plan(&lib, Path::new("/music"), &[0, 1]);
+        assert_eq!(moves[0].to, Path::new("/music/AC_DC/Back in Black/01 - Hells Bells.flac"));
+        assert_eq!(moves[1].to, Path::new("/music/Queen/Live/2-03 - Mustapha.mp3"));
+    }
+
+    #[test]
+    fn untagged_tracks_get_placeholders() {
+        let lib = library(vec![test_track("/in/demo.ogg")]);
+        let moves = plan(&lib, Path::new("/music"), &[0]);
+        assert_eq!(moves[0].to, Path::new("/music/Unknown Artist/Unknown Album/demo.ogg"));
+    }
+
+    #[test]
+    fn skips_tracks_in_place_and_second_claims() {
+        let lib = library(vec![
+            track("/music/A/B/01 - C.mp3", "A", "B", "C", Some(1)),
+            track("/in/x.mp3", "A", "B", "D", Some(2)),
+            track("/in/y.mp3", "A", "B", "D", Some(2)),
+        ]);
+        let moves = plan(&lib, Path::new("/music"), &[0, 1, 2]);
+        assert_eq!(moves.len(), 1);
+        assert_eq!(moves[0].from, Path::new("/in/x.mp3"));
+    }
+
+    #[test]
+    fn component_strips_separators_and_dots() {
+        assert_eq!(component("a/b\\c:d"), "a_b_c_d");
+        assert_eq!(component("..hidden."), "hidden");
+        assert_eq!(component("  "), "_");
+    }
+
+    #[test]
+    fn apply_moves_sidecars_and_prunes_empty_folders() {
+        let dir = scratch("apply");
+        let from = dir.join("old/sub/song.flac");
+        fs::create_dir_all(from.parent().unwrap()).unwrap();
+        fs::write(&from, b"audio").unwrap();
+        fs::write(from.with_extension("lrc"), b"[00:01.00]hi").unwrap();
+        let to = dir.join("new/Artist/Album/01 - Song.flac");
+
+        let (done, failed) = apply(&[Move { from: from.clone(), to: to.clone() }]);
+        assert_eq!(done.len(), 1);
+        assert!(failed.is_empty());
+        assert_eq!(fs::read(&to).unwrap(), b"audio");
+        assert!(to.with_extension("lrc").is_file());
+        assert!(!dir.join("old").exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn apply_never_overwrites() {
+        let dir = scratch("overwrite");
+        let (from, to) = (dir.join("a.mp3"), dir.join("b.mp3"));
+        fs::write(&from, b"new").unwrap();
+        fs::write(&to, b"old").unwrap();
+
+        let (done, failed) = apply(&[Move { from: from.clone(), to: to.clone() }]);
+        assert!(done.is_empty());
+        assert_eq!(failed.len(), 1);
+        assert_eq!(fs::read(&from).unwrap(), b"new");
+        assert_eq!(fs::read(&to).unwrap(), b"old");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,55 @@
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+use rand::distributions::{Distribution, WeightedIndex};
+
+use super::track::Track;
+use super::Library;
+
+/// Tracks like `seed`, picked at random with the closest ones favored: the same
+/// artist, genre, decade and a similar tempo each count. Tracks in `exclude` and
+/// ones sharing nothing with the seed are left out; fewer than `count` come back
+/// when the library runs short.
+pub fn similar(library: &Library, seed: &Track, exclude: &HashSet<PathBuf>, count: usize) -> Vec<PathBuf> {
+    let seed_genre = library.genre_of(seed).to_lowercase();
+    let mut candidates: Vec<(&Track, u32)> = library
+        .tracks
+        .iter()
+        .filter(|t| t.path != seed.path && !exclude.contains(&t.path))
+        .map(|t| (t, score(library, seed, &seed_genre, t)))
+        .filter(|&(_, s)| s > 0)
+        .collect();
+
+    let mut rng = rand::thread_rng();
+    let mut picked = Vec::new();
+    while picked.len() < count && !candidates.is_empty() {
+        // Squared so a close match is much likelier than a one-point one
+        let Ok(dist) = WeightedIndex::new(candidates.iter().map(|(_, s)| s * s)) else { break };
+        let (track, _) = candidates.swap_remove(dist.sample(&mut rng));
+        picked.push(track.path.clone());
+    }
+    picked
+}
+
+fn score(library: &Library, seed: &Track, seed_genre: &str, t: &Track) -> u32 {
+    let mut score = 0;
+    if !seed.artist.is_empty() && t.artist.eq_ignore_ascii_case(&seed.artist) {
+        score += 3;
+    } else if t.artists.iter().any(|a| seed.artists.contains(a)) {
+        score += 2;
+    }
+    if !seed_genre.is_empty() && library.genre_of(t).to_lowercase() == seed_genre {
+        score += 2;
+    }
+    if let (Some(a), Some(b)) = (seed.year, t.year) {
+        if a / 10 == b / 10 {
+            score += 1;
+        }
+    }
+    if let (Some(a), Some(b)) = (seed.bpm, t.bpm) {
+        if (a - b).abs() <= a * 0.08 {
+            score += 1;
+        }
+    }
+    score
+}
@@ -1,14 +1,21 @@
+use crossbeam_channel::{Receiver, Sender};
 use rodio::Decoder;
+use std::collections::{HashMap, HashSet};
 use std::fs::File;
 use std::io::BufReader;
+#[cfg(unix)]
+use std::os::unix::fs::MetadataExt;
 use std::panic;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 use symphonia::core::formats::FormatOptions;
 use symphonia::core::io::MediaSourceStream;
 use symphonia::core::meta::MetadataOptions;
 use symphonia::core::probe::Hint;
 use walkdir::WalkDir;
 
+use super::collate;
+use super::ignore::IgnoreRules;
 use super::track::Track;
 
 const AUDIO_EXTENSIONS: &[&str] = &["flac", "mp3", "m4a", "ogg", "wav", "opus", "aac", "wma"];
@@ -44,50 +51,298 @@ fn is_decodable(path: &Path) -> bool {
     result.unwrap_or(false)
 }
 
-pub fn scan_directory(path: &Path) -> Vec<Track> {
+/// Paths skipped while walking the music directory
+#[derive(Debug, Clone, Default)]
+pub struct ScanStats {
+    /// Files or directories reachable through more than one path (symlinks, hard links)
+    pub duplicates: usize,
+    /// Symlinks pointing back at one of their own ancestors
+    pub loops: usize,
+    /// Directories that couldn't be read and files that timed out (e.g. a hung network share)
+    pub unreachable: Vec<PathBuf>,
+    /// The music directory itself couldn't be read: unmounted drive or share down
+    pub root_offline: bool,
+}
+
+/// How long reading one file's format and tags may take before it is skipped
+const FILE_TIMEOUT: Duration = Duration::from_secs(10);
+/// How long listing one directory may take before it and everything under it
+/// are skipped
+const WALK_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// (device, inode)
+#[cfg(unix)]
+type FileId = (u64, u64);
+/// Canonical path: no inode to go by, but symlinks still resolve to one entry
+#[cfg(not(unix))]
+type FileId = PathBuf;
+
+/// Identify a file by (device, inode) so every link to it is indexed once
+#[cfg(unix)]
+fn file_id(path: &Path) -> Option<FileId> {
+    std::fs::metadata(path).ok().map(|m| (m.dev(), m.ino()))
+}
+
+#[cfg(not(unix))]
+fn file_id(path: &Path) -> Option<FileId> {
+    std::fs::canonicalize(path).ok()
+}
+
+/// What listing a directory turned up
+enum WalkItem {
+    Dir(PathBuf),
+    File(PathBuf),
+    Unreadable(PathBuf),
+}
+
+/// A directory's identity and entries
+struct Listing {
+    id: Option<FileId>,
+    items: Vec<WalkItem>,
+}
+
+/// Lists directories on a helper thread, so a directory read that never returns
+/// (a hung NFS/SMB mount) can be timed out. As with `FileWorker`, a directory that
+/// overruns `WALK_TIMEOUT` is skipped and its thread abandoned, with a fresh one
+/// taking over the rest of the walk.
+struct DirWorker {
+    jobs: Sender<PathBuf>,
+    results: Receiver<Listing>,
+}
+
+impl DirWorker {
+    fn new() -> Self {
+        let (jobs, job_rx) = crossbeam_channel::unbounded::<PathBuf>();
+        let (result_tx, results) = crossbeam_channel::unbounded();
+        std::thread::spawn(move || {
+            for dir in job_rx {
+                let items = WalkDir::new(&dir)
+                    .follow_links(true)
+                    .min_depth(1)
+                    .max_depth(1)
+                    .into_iter()
+                    .filter_map(|entry| match entry {
+                        Ok(e) if e.file_type().is_dir() => Some(WalkItem::Dir(e.into_path())),
+                        Ok(e) if e.file_type().is_file() => Some(WalkItem::File(e.into_path())),
+                        Ok(_) => None,
+                        Err(e) => e.path().map(|p| WalkItem::Unreadable(p.to_path_buf())),
+                    })
+                    .collect();
+                if result_tx.send(Listing { id: file_id(&dir), items }).is_err() {
+                    break;
+                }
+            }
+        });
+        Self { jobs, results }
+    }
+
+    /// The directory's listing, or None if reading it timed out
+    fn list(&mut self, dir: PathBuf) -> Option<Listing> {
+        let result = match self.jobs.send(dir) {
+            Ok(()) => self.results.recv_timeout(WALK_TIMEOUT).ok(),
+            Err(_) => None,
+        };
+        if result.is_none() {
+            *self = Self::new();
+        }
+        result
+    }
+}
+
+/// Probes files on a helper thread. A file that overruns `FILE_TIMEOUT` is skipped
+/// and its thread abandoned (it stays blocked in the kernel), with a fresh one
+/// taking over.
+struct FileWorker {
+    jobs: Sender<PathBuf>,
+    results: Receiver<(Option<FileId>, Option<Track>)>,
+}
+
+impl FileWorker {
+    fn new() -> Self {
+        let (jobs, job_rx) = crossbeam_channel::unbounded::<PathBuf>();
+        let (result_tx, results) = crossbeam_channel::unbounded();
+        std::thread::spawn(move || {
+            for path in job_rx {
+                let id = file_id(&path);
+                let track = if is_decodable(&path) { Track::from_path(&path) } else { None };
+                if result_tx.send((id, track)).is_err() {
+                    break;
+                }
+            }
+        });
+        Self { jobs, results }
+    }
+
+    /// File identity and track, or None if the file timed out
+    fn probe(&mut self, path: PathBuf) -> Option<(Option<FileId>, Option<Track>)> {
+        let result = match self.jobs.send(path) {
+            Ok(()) => self.results.recv_timeout(FILE_TIMEOUT).ok(),
+            Err(_) => None,
+        };
+        if result.is_none() {
+            *self = Self::new();
+        }
+        result
+    }
+}
+
+pub fn scan_directory(path: &Path, ignore: &IgnoreRules) -> (Vec<Track>, ScanStats) {
     let mut tracks = Vec::new();
+    let mut stats = ScanStats::default();
+    let mut seen_files = HashSet::new();
+    let mut worker = FileWorker::new();
+    let mut dirs = DirWorker::new();
+    let mut seen_dirs = HashSet::new();
+    // Directories still to read, depth first, each with its ancestors' ids to spot
+    // symlinks pointing back up the tree
+    let mut pending: Vec<(PathBuf, Vec<FileId>)> = vec![(path.to_path_buf(), Vec::new())];
 
-    for entry in WalkDir::new(path)
-        .follow_links(true)
-        .into_iter()
-        .filter_map(|e| e.ok())
-    {
-        let path = entry.path();
-        if !path.is_file() {
+    while let Some((dir, mut ancestors)) = pending.pop() {
+        let Some(listing) = dirs.list(dir.clone()) else {
+            // Skip just this subtree and carry on with its siblings; nothing at all
+            // from the root means it's offline
+            if dir == path {
+                stats.root_offline = true;
+            }
+            stats.unreachable.push(dir);
             continue;
+        };
+        if let Some(id) = listing.id {
+            if ancestors.contains(&id) {
+                stats.loops += 1;
+                continue;
+            }
+            // Already walked through another symlink
+            #[allow(clippy::clone_on_copy)] // FileId is only Copy on unix
+            let first_visit = seen_dirs.insert(id.clone());
+            if !first_visit {
+                stats.duplicates += 1;
+                continue;
+            }
+            ancestors.push(id);
         }
 
-        // Skip macOS resource fork files
-        if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
-            if name.starts_with("._") {
-                continue;
+        let mut subdirs = Vec::new();
+        let mut files = Vec::new();
+        for item in listing.items {
+            match item {
+                WalkItem::Dir(d) if !ignore.is_ignored(&d, true) => subdirs.push(d),
+                WalkItem::File(f) if !ignore.is_ignored(&f, false) => files.push(f),
+                WalkItem::Unreadable(p) => {
+                    if p == path {
+                        stats.root_offline = true;
+                    }
+                    stats.unreachable.push(p);
+                }
+                _ => {}
             }
         }
+        pending.extend(subdirs.into_iter().rev().map(|d| (d, ancestors.clone())));
 
-        let ext = path
-            .extension()
-            .and_then(|e| e.to_str())
-            .map(|e| e.to_lowercase());
+        for path_buf in files {
+            let file = path_buf.as_path();
 
-        if let Some(ext) = ext {
-            if AUDIO_EXTENSIONS.contains(&ext.as_str()) {
-                if !is_decodable(path) {
+            // Skip macOS resource fork files
+            if let Some(name) = file.file_name().and_then(|n| n.to_str()) {
+                if name.starts_with("._") {
                     continue;
                 }
-                if let Some(track) = Track::from_path(path) {
-                    tracks.push(track);
+            }
+
+            let ext = file
+                .extension()
+                .and_then(|e| e.to_str())
+                .map(|e| e.to_lowercase());
+
+            if let Some(ext) = ext {
+                if AUDIO_EXTENSIONS.contains(&ext.as_str()) {
+                    let Some((id, track)) = worker.probe(path_buf.clone()) else {
+                        stats.unreachable.push(path_buf);
+                        continue;
+                    };
+                    if let Some(id) = id {
+                        if !seen_files.insert(id) {
+                            stats.duplicates += 1;
+                            continue;
+                        }
+                    }
+                    if let Some(track) = track {
+                        tracks.push(track);
+                    }
                 }
             }
         }
     }
 
+    mark_folder_compilations(&mut tracks);
+
     tracks.sort_by(|a, b| {
-        a.album_artist
-            .cmp(&b.album_artist)
+        a.album_owner()
+            .cmp(b.album_owner())
             .then(a.album.cmp(&b.album))
-            .then(a.track_number.cmp(&b.track_number))
+            // Same-named albums in different folders stay apart
+            .then(super::album_dir(a).cmp(&super::album_dir(b)))
+            .then(a.disc_track().cmp(&b.disc_track()))
             .then(a.title.cmp(&b.title))
     });
 
-    tracks
+    (tracks, stats)
+}
+
+/// Flag untagged compilations: an album with no album artist whose tracks in one
+/// folder come from at least three artists, none of them on half the tracks
+fn mark_folder_compilations(tracks: &mut [Track]) {
+    let mut groups: HashMap<(PathBuf, String), Vec<usize>> = HashMap::new();
+    for (i, t) in tracks.iter().enumerate() {
+        if t.compilation || t.album.is_empty() || !t.album_artist.is_empty() {
+            continue;
+        }
+        let dir = t.path.parent().map(Path::to_path_buf).unwrap_or_default();
+        groups.entry((dir, collate::dedupe_key(&t.album))).or_default().push(i);
+    }
+
+    for indices in groups.into_values() {
+        let mut per_artist: HashMap<String, usize> = HashMap::new();
+        for &i in &indices {
+            *per_artist.entry(collate::dedupe_key(&tracks[i].artist)).or_default() += 1;
+        }
+        let top = per_artist.values().copied().max().unwrap_or(0);
+        if per_artist.len() >= 3 && top * 2 < indices.len() {
+            for i in indices {
+                tracks[i].compilation = true;
+            }
+        }
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::{symlink, PermissionsExt};
+
+    #[test]
+    fn walk_counts_loops_and_duplicates_and_skips_unreadable_subtrees() {
+        let root = std::env::temp_dir().join(format!("ommp-scan-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(root.join("a/deep")).unwrap();
+        std::fs::create_dir_all(root.join("locked")).unwrap();
+        std::fs::write(root.join("a/deep/notes.txt"), b"").unwrap();
+        symlink(&root, root.join("a/up")).unwrap();
+        symlink(root.join("a"), root.join("again")).unwrap();
+        std::fs::set_permissions(root.join("locked"), std::fs::Permissions::from_mode(0o000)).unwrap();
+        // The superuser can read it anyway, so only check it when the lock holds
+        let locked = std::fs::read_dir(root.join("locked")).is_err();
+
+        let (tracks, stats) = scan_directory(&root, &IgnoreRules::default());
+        std::fs::set_permissions(root.join("locked"), std::fs::Permissions::from_mode(0o755)).unwrap();
+        std::fs::remove_dir_all(&root).unwrap();
+
+        assert!(tracks.is_empty());
+        assert_eq!(stats.loops, 1);
+        assert_eq!(stats.duplicates, 1);
+        assert!(!stats.root_offline);
+        if locked {
+            assert_eq!(stats.unreachable, [root.join("locked")]);
+        }
+    }
 }
@@ -0,0 +1,117 @@
+use std::collections::HashMap;
+
+use super::track::Track;
+
+/// Searchable fields, in the order they are stored per track
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Field {
+    Title = 0,
+    Artist = 1,
+    Album = 2,
+    Genre = 3,
+    FileName = 4,
+}
+
+const FIELD_COUNT: usize = 5;
+
+type Trigram = [char; 3];
+
+/// Trigram index over lowercased title/artist/album/genre/filename, built at scan time.
+///
+/// Queries of three or more characters intersect the posting lists of their
+/// trigrams to get a small candidate set, then confirm each candidate with a
+/// substring check (trigrams alone can match out of order). Shorter queries
+/// fall back to a scan over the pre-lowercased fields.
+#[derive(Debug, Default)]
+pub struct SearchIndex {
+    /// Lowercased field text per track, indexed by `Field`
+    fields: Vec<[String; FIELD_COUNT]>,
+    /// Sorted track indices containing each trigram, with the fields it occurs in as a bitmask
+    postings: HashMap<Trigram, Vec<(u32, u8)>>,
+}
+
+impl SearchIndex {
+    /// `genre` maps a track to the genre name it is browsed under
+    pub fn build<'a>(tracks: &'a [Track], genre: impl Fn(&'a Track) -> &'a str) -> Self {
+        let mut fields = Vec::with_capacity(tracks.len());
+        let mut postings: HashMap<Trigram, Vec<(u32, u8)>> = HashMap::new();
+
+        for (i, t) in tracks.iter().enumerate() {
+            let file_name = t.path.file_name()
+                .map(|f| f.to_string_lossy().to_lowercase())
+                .unwrap_or_default();
+            let lower = [
+                t.title.to_lowercase(),
+                t.artist.to_lowercase(),
+                t.album.to_lowercase(),
+                genre(t).to_lowercase(),
+                file_name,
+            ];
+            for (f, text) in lower.iter().enumerate() {
+                let bit = 1u8 << f;
+                for tri in trigrams(text) {
+                    let list = postings.entry(tri).or_default();
+                    match list.last_mut() {
+                        Some((id, mask)) if *id == i as u32 => *mask |= bit,
+                        _ => list.push((i as u32, bit)),
+                    }
+                }
+            }
+            fields.push(lower);
+        }
+
+        Self { fields, postings }
+    }
+
+    pub fn field(&self, track_idx: usize, field: Field) -> &str {
+        &self.fields[track_idx][field as usize]
+    }
+
+    /// Track indices (ascending) where any of `fields` contains the lowercased query
+    pub fn find(&self, query: &str, fields: &[Field]) -> Vec<usize> {
+        let mask = fields.iter().fold(0u8, |m, &f| m | 1 << f as usize);
+        let matches = |i: usize| fields.iter().any(|&f| self.field(i, f).contains(query));
+
+        let grams = trigrams(query);
+        if grams.is_empty() {
+            return (0..self.fields.len()).filter(|&i| matches(i)).collect();
+        }
+
+        // Intersect starting from the rarest trigram to keep the candidate set small
+        let mut lists: Vec<&Vec<(u32, u8)>> = Vec::with_capacity(grams.len());
+        for tri in &grams {
+            match self.postings.get(tri) {
+                Some(list) => lists.push(list),
+                None => return Vec::new(),
+            }
+        }
+        lists.sort_by_key(|l| l.len());
+
+        let mut candidates: Vec<u32> = lists[0].iter()
+            .filter(|(_, m)| m & mask != 0)
+            .map(|&(id, _)| id)
+            .collect();
+        for list in &lists[1..] {
+            candidates.retain(|id| {
+                list.binary_search_by_key(id, |&(i, _)| i)
+                    .is_ok_and(|pos| list[pos].1 & mask != 0)
+            });
+            if candidates.is_empty() {
+                break;
+            }
+        }
+
+        candidates.into_iter()
+            .map(|id| id as usize)
+            .filter(|&i| matches(i))
+            .collect()
+    }
+}
+
+fn trigrams(text: &str) -> Vec<Trigram> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut grams: Vec<Trigram> = chars.windows(3).map(|w| [w[0], w[1], w[2]]).collect();
+    grams.sort_unstable();
+    grams.dedup();
+    grams
+}
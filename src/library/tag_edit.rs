@@ -0,0 +1,140 @@
+use lofty::config::WriteOptions;
+use lofty::file::AudioFile;
+use lofty::prelude::*;
+use lofty::tag::Tag;
+use std::path::{Path, PathBuf};
+
+use super::Library;
+
+/// Tag a batch edit changes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TagField {
+    AlbumArtist,
+    Genre,
+    TrackNumber,
+    Title,
+}
+
+impl TagField {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::AlbumArtist => "Album artist",
+            Self::Genre => "Genre",
+            Self::TrackNumber => "Track number",
+            Self::Title => "Title",
+        }
+    }
+}
+
+/// One file's pending tag change, shown in the preview before anything is written
+#[derive(Debug, Clone)]
+pub struct Change {
+    pub path: PathBuf,
+    pub field: TagField,
+    pub old: String,
+    pub new: String,
+}
+
+/// Changes that setting `field` to `value` on `tracks` would make; files that
+/// already have the value are left out. `TrackNumber` ignores `value` and numbers
+/// the tracks 1..n in the given order; `Title` ignores it too and gives untitled
+/// tracks their file name, leaving tagged titles alone.
+pub fn preview(library: &Library, tracks: &[usize], field: TagField, value: &str) -> Vec<Change> {
+    tracks.iter()
+        .enumerate()
+        .filter_map(|(i, &idx)| {
+            let t = library.tracks.get(idx)?;
+            let (old, new) = match field {
+                TagField::AlbumArtist => (t.album_artist.clone(), value.trim().to_string()),
+                TagField::Genre => (t.genre.clone(), value.trim().to_string()),
+                TagField::TrackNumber => (
+                    t.track_number.map(|n| n.to_string()).unwrap_or_default(),
+                    (i + 1).to_string(),
+                ),
+                TagField::Title if !t.untitled => return None,
+                TagField::Title => (String::new(), t.title.clone()),
+            };
+            (old != new).then(|| Change { path: t.path.clone(), field, old, new })
+        })
+        .collect()
+}
+
+/// Write the changes; returns how many files were written and the ones that failed
+pub fn apply(changes: &[Change]) -> (usize, Vec<(PathBuf, String)>) {
+    let mut written = 0;
+    let mut failed = Vec::new();
+    for change in changes {
+        match write_change(&change.path, change) {
+            Ok(()) => written += 1,
+            Err(e) => failed.push((change.path.clone(), e.to_string())),
+        }
+    }
+    (written, failed)
+}
+
+fn write_change(path: &Path, change: &Change) -> anyhow::Result<()> {
+    let mut file = lofty::read_from_path(path)?;
+    if file.primary_tag().is_none() {
+        let tag_type = file.primary_tag_type();
+        file.insert_tag(Tag::new(tag_type));
+    }
+    let tag = file.primary_tag_mut().ok_or_else(|| anyhow::anyhow!("no writable tag"))?;
+    match change.field {
+        TagField::AlbumArtist if change.new.is_empty() => tag.remove_key(&ItemKey::AlbumArtist),
+        TagField::AlbumArtist => {
+            tag.insert_text(ItemKey::AlbumArtist, change.new.clone());
+        }
+        TagField::Genre if change.new.is_empty() => tag.remove_genre(),
+        TagField::Genre => tag.set_genre(change.new.clone()),
+        TagField::TrackNumber => tag.set_track(change.new.parse()?),
+        TagField::Title => tag.set_title(change.new.clone()),
+    }
+    file.save_to_path(path, WriteOptions::default())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::library::track::test_track;
+
+    fn library() -> Library {
+        let mut lib = Library::new();
+        let mut tagged = test_track("/m/b.mp3");
+        tagged.title = "Tagged".to_string();
+        tagged.untitled = false;
+        tagged.genre = "Jazz".to_string();
+        tagged.track_number = Some(1);
+        let mut untitled = test_track("/m/a.mp3");
+        untitled.title = "a".to_string();
+        lib.tracks = vec![tagged, untitled];
+        lib
+    }
+
+    #[test]
+    fn leaves_out_files_that_already_match() {
+        let changes = preview(&library(), &[0, 1], TagField::Genre, " Jazz ");
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].path, Path::new("/m/a.mp3"));
+        assert_eq!((changes[0].old.as_str(), changes[0].new.as_str()), ("", "Jazz"));
+    }
+
+    #[test]
+    fn numbers_tracks_in_the_given_order() {
+        let changes = preview(&library(), &[1, 0], TagField::TrackNumber, "");
+        let numbers: Vec<_> = changes.iter().map(|c| (c.path.to_str().unwrap(), c.new.as_str())).collect();
+        assert_eq!(numbers, [("/m/a.mp3", "1"), ("/m/b.mp3", "2")]);
+    }
+
+    #[test]
+    fn titles_only_untitled_tracks() {
+        let changes = preview(&library(), &[0, 1], TagField::Title, "ignored");
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].new, "a");
+    }
+
+    #[test]
+    fn skips_stale_indices() {
+        assert!(preview(&library(), &[7], TagField::Genre, "Rock").is_empty());
+    }
+}
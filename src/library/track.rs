@@ -3,19 +3,37 @@ use lofty::prelude::*;
 use std::path::{Path, PathBuf};
 use std::time::Duration;
 
+/// Album artist that compilations are grouped under
+pub const VARIOUS_ARTISTS: &str = "Various Artists";
+
 #[derive(Debug, Clone)]
 pub struct Track {
     pub path: PathBuf,
     pub title: String,
+    /// No title tag; `title` is the file name
+    pub untitled: bool,
     pub artist: String,
+    /// Individual artist names parsed from all artist frames ("A feat. B" → [A, B])
+    pub artists: Vec<String>,
     pub album: String,
     pub album_artist: String,
+    /// Part of a various-artists compilation, from the compilation flag (TCMP/cpil),
+    /// a "Various Artists" album artist, or the per-folder heuristic in the scanner
+    pub compilation: bool,
     pub genre: String,
     pub track_number: Option<u32>,
+    pub disc_number: Option<u32>,
+    pub year: Option<u32>,
     pub duration: Duration,
     pub bitrate: Option<u32>,
-    #[allow(dead_code)]
+    pub sample_rate: Option<u32>,
+    pub bit_depth: Option<u8>,
+    pub channels: Option<u8>,
     pub lyrics: Option<String>,
+    /// ReplayGain track gain in dB (relative to -18 LUFS), when tagged
+    pub replay_gain: Option<f64>,
+    /// Tempo from the BPM tag (TBPM, BPM, tmpo)
+    pub bpm: Option<f64>,
 }
 
 impl Track {
@@ -29,8 +47,11 @@ impl Track {
         let properties = tagged_file.properties();
         let duration = properties.duration();
         let bitrate = properties.audio_bitrate();
+        let sample_rate = properties.sample_rate();
+        let bit_depth = properties.bit_depth();
+        let channels = properties.channels();
 
-        let (title, artist, album, album_artist, genre, track_number, lyrics) =
+        let (title, artist, album, album_artist, genre, track_number, disc_number, lyrics) =
             if let Some(tag) = tag {
                 let title_str: String = tag.title().map(|s| s.to_string()).unwrap_or_default();
                 let artist_str: String = tag.artist().map(|s| s.to_string()).unwrap_or_default();
@@ -41,15 +62,47 @@ impl Track {
                     .unwrap_or_default();
                 let genre_str: String = tag.genre().map(|s| s.to_string()).unwrap_or_default();
                 let track_num = tag.track();
+                let disc_num = tag.disk();
                 let lyrics_str: Option<String> = tag
                     .get_string(&ItemKey::Lyrics)
                     .map(|s| s.to_string());
-                (title_str, artist_str, album_str, aa_str, genre_str, track_num, lyrics_str)
+                (title_str, artist_str, album_str, aa_str, genre_str, track_num, disc_num, lyrics_str)
             } else {
-                (String::new(), String::new(), String::new(), String::new(), String::new(), None, None)
+                (String::new(), String::new(), String::new(), String::new(), String::new(), None, None, None)
             };
 
-        let title = if title.is_empty() {
+        let compilation = is_various_artists(&album_artist)
+            || tag
+                .and_then(|tag| tag.get_string(&ItemKey::FlagCompilation))
+                .is_some_and(|v| v.trim() == "1" || v.trim().eq_ignore_ascii_case("true"));
+
+        let replay_gain = tag
+            .and_then(|tag| tag.get_string(&ItemKey::ReplayGainTrackGain))
+            .and_then(parse_gain);
+
+        let year = tag.and_then(|tag| tag.year()).filter(|&y| y > 0);
+
+        let bpm = tag
+            .and_then(|tag| tag.get_string(&ItemKey::Bpm).or_else(|| tag.get_string(&ItemKey::IntegerBpm)))
+            .and_then(|v| v.trim().parse::<f64>().ok())
+            .filter(|b| b.is_finite() && *b > 0.0);
+
+        let artists = tag
+            .map(|tag| {
+                let mut names: Vec<String> = Vec::new();
+                for value in tag.get_strings(&ItemKey::TrackArtist) {
+                    for name in split_artist_names(value) {
+                        if !names.contains(&name) {
+                            names.push(name);
+                        }
+                    }
+                }
+                names
+            })
+            .unwrap_or_default();
+
+        let untitled = title.is_empty();
+        let title = if untitled {
             path.file_stem()
                 .map(|s| s.to_string_lossy().to_string())
                 .unwrap_or_else(|| "Unknown".to_string())
@@ -60,14 +113,24 @@ impl Track {
         Some(Self {
             path: path.to_path_buf(),
             title,
+            untitled,
             artist,
+            artists,
             album,
             album_artist,
+            compilation,
             genre,
             track_number,
+            disc_number,
+            year,
             duration,
             bitrate,
+            sample_rate,
+            bit_depth,
+            channels,
             lyrics,
+            replay_gain,
+            bpm,
         })
     }
 
@@ -87,6 +150,57 @@ impl Track {
         }
     }
 
+    /// Artist the track's album is filed under: the album artist tag, then
+    /// "Various Artists" for compilations, then the track artist
+    pub fn album_owner(&self) -> &str {
+        if self.compilation && (self.album_artist.is_empty() || is_various_artists(&self.album_artist)) {
+            VARIOUS_ARTISTS
+        } else if !self.album_artist.is_empty() {
+            &self.album_artist
+        } else {
+            &self.artist
+        }
+    }
+
+    /// Disc-aware ordering key within an album; untagged discs count as disc 1
+    pub fn disc_track(&self) -> (u32, Option<u32>) {
+        let (disc, track) = self.numbers();
+        (disc.unwrap_or(1), track)
+    }
+
+    /// Disc and track number from the tags, or for files without a track number,
+    /// from the file name ("03 - Song", "1-03 Song")
+    pub fn numbers(&self) -> (Option<u32>, Option<u32>) {
+        if self.track_number.is_some() {
+            return (self.disc_number, self.track_number);
+        }
+        let stem = self.path.file_stem().map(|s| s.to_string_lossy()).unwrap_or_default();
+        match leading_number(&stem) {
+            Some((first, rest)) => match rest.strip_prefix('-').and_then(leading_number) {
+                Some((track, _)) => (self.disc_number.or(Some(first)), Some(track)),
+                None => (self.disc_number, Some(first)),
+            },
+            None => (self.disc_number, None),
+        }
+    }
+
+    /// "1-05" style number for multi-disc albums, "05" otherwise, empty if unnumbered
+    pub fn format_number(&self) -> String {
+        match self.numbers() {
+            (Some(d), Some(n)) => format!("{}-{:02}", d, n),
+            (None, Some(n)) => format!("{:02}", n),
+            _ => String::new(),
+        }
+    }
+
+    /// Codec and stream format, e.g. "FLAC 24/96 stereo" or "MP3 44.1kHz stereo"
+    pub fn format_quality(&self) -> String {
+        let ext = self.path.extension()
+            .map(|e| e.to_string_lossy().to_uppercase())
+            .unwrap_or_default();
+        format_stream(&ext, self.sample_rate, self.bit_depth, self.channels.map(u16::from))
+    }
+
     pub fn format_duration(&self) -> String {
         let secs = self.duration.as_secs();
         let mins = secs / 60;
@@ -94,3 +208,160 @@ impl Track {
         format!("{}:{:02}", mins, secs)
     }
 }
+
+/// "FLAC 24/96 stereo" style description; lossy formats without a bit depth get "44.1kHz"
+pub fn format_stream(codec: &str, sample_rate: Option<u32>, bit_depth: Option<u8>, channels: Option<u16>) -> String {
+    let khz = |hz: u32| {
+        if hz.is_multiple_of(1000) {
+            (hz / 1000).to_string()
+        } else {
+            format!("{:.1}", hz as f64 / 1000.0)
+        }
+    };
+    let mut parts: Vec<String> = Vec::new();
+    if !codec.is_empty() {
+        parts.push(codec.to_string());
+    }
+    match (bit_depth, sample_rate) {
+        (Some(bits), Some(hz)) => parts.push(format!("{}/{}", bits, khz(hz))),
+        (None, Some(hz)) => parts.push(format!("{}kHz", khz(hz))),
+        _ => {}
+    }
+    match channels {
+        Some(1) => parts.push("mono".to_string()),
+        Some(2) => parts.push("stereo".to_string()),
+        Some(n) => parts.push(format!("{}ch", n)),
+        None => {}
+    }
+    parts.join(" ")
+}
+
+/// A 1-3 digit number at the start of a file name and what follows it, which must
+/// be a separator so "1999 Remaster" or "3am" aren't read as track numbers
+fn leading_number(s: &str) -> Option<(u32, &str)> {
+    let digits = s.len() - s.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    let (number, rest) = s.split_at(digits);
+    let separated = rest.is_empty() || rest.starts_with([' ', '.', '-', '_', ')']);
+    if !(1..=3).contains(&digits) || !separated {
+        return None;
+    }
+    Some((number.parse().ok()?, rest))
+}
+
+/// "Various Artists", "Various", "VA" and similar album artist placeholders
+pub fn is_various_artists(name: &str) -> bool {
+    matches!(
+        name.trim().to_lowercase().as_str(),
+        "various artists" | "various" | "va" | "v.a." | "v/a"
+    )
+}
+
+/// Split a combined artist string into individual names.
+/// Separators: `;`, NUL (ID3v2.4 multi-value), spaced ` / `, and featuring markers
+/// (`feat.`, `ft.`, `featuring`). A bare `/` is kept so names like "AC/DC" stay intact.
+pub fn split_artist_names(raw: &str) -> Vec<String> {
+    let mut names = Vec::new();
+    for chunk in raw.split(['\0', ';']) {
+        for piece in chunk.split(" / ") {
+            split_featuring(piece, &mut names);
+        }
+    }
+    names
+}
+
+fn split_featuring(s: &str, out: &mut Vec<String>) {
+    const MARKERS: [&str; 8] = [
+        " feat. ", " feat ", " ft. ", " ft ", " featuring ", "(feat. ", "(ft. ", "[feat. ",
+    ];
+    // ASCII lowercase keeps byte offsets aligned with the original string
+    let lower = s.to_ascii_lowercase();
+    let hit = MARKERS
+        .iter()
+        .filter_map(|m| lower.find(m).map(|pos| (pos, m.len())))
+        .min_by_key(|&(pos, _)| pos);
+
+    match hit {
+        Some((pos, len)) => {
+            push_name(&s[..pos], out);
+            split_featuring(&s[pos + len..], out);
+        }
+        None => push_name(s, out),
+    }
+}
+
+fn push_name(s: &str, out: &mut Vec<String>) {
+    let name = s
+        .trim()
+        .trim_start_matches(['(', '['])
+        .trim_end_matches([')', ']'])
+        .trim();
+    if !name.is_empty() && !out.iter().any(|n| n == name) {
+        out.push(name.to_string());
+    }
+}
+
+/// "-6.54 dB" (or a bare "-6.54") as a ReplayGain value
+fn parse_gain(raw: &str) -> Option<f64> {
+    let raw = raw.trim();
+    let number = raw.strip_suffix("dB").or_else(|| raw.strip_suffix("db")).unwrap_or(raw);
+    number.trim().parse().ok().filter(|g: &f64| g.is_finite())
+}
+
+/// A track at `path` with no tags, for tests to fill in
+#[cfg(test)]
+pub fn test_track(path: &str) -> Track {
+    Track {
+        path: PathBuf::from(path),
+        title: String::new(),
+        untitled: true,
+        artist: String::new(),
+        artists: Vec::new(),
+        album: String::new(),
+        album_artist: String::new(),
+        compilation: false,
+        genre: String::new(),
+        track_number: None,
+        disc_number: None,
+        year: None,
+        duration: Duration::from_secs(180),
+        bitrate: None,
+        sample_rate: None,
+        bit_depth: None,
+        channels: None,
+        lyrics: None,
+        replay_gain: None,
+        bpm: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::split_artist_names;
+
+    #[test]
+    fn splits_on_semicolons_nul_and_spaced_slash() {
+        assert_eq!(split_artist_names("Daft Punk; Pharrell Williams"), ["Daft Punk", "Pharrell Williams"]);
+        assert_eq!(split_artist_names("Simon\0Garfunkel"), ["Simon", "Garfunkel"]);
+        assert_eq!(split_artist_names("Bob Marley / The Wailers"), ["Bob Marley", "The Wailers"]);
+    }
+
+    #[test]
+    fn keeps_bare_slashes_in_names() {
+        assert_eq!(split_artist_names("AC/DC"), ["AC/DC"]);
+    }
+
+    #[test]
+    fn splits_featuring_markers_in_any_case() {
+        assert_eq!(split_artist_names("Calvin Harris feat. Rihanna"), ["Calvin Harris", "Rihanna"]);
+        assert_eq!(split_artist_names("Eminem FT. Dido"), ["Eminem", "Dido"]);
+        assert_eq!(split_artist_names("Santana featuring Rob Thomas"), ["Santana", "Rob Thomas"]);
+        assert_eq!(split_artist_names("Gorillaz (feat. De La Soul)"), ["Gorillaz", "De La Soul"]);
+        assert_eq!(split_artist_names("A feat. B; C ft. D"), ["A", "B", "C", "D"]);
+    }
+
+    #[test]
+    fn drops_blanks_and_duplicates() {
+        assert_eq!(split_artist_names("Björk; ; Björk"), ["Björk"]);
+        assert!(split_artist_names("").is_empty());
+    }
+}
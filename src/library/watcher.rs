@@ -1,17 +1,24 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 
 use crossbeam_channel::Sender;
 use notify::{RecommendedWatcher, RecursiveMode, Watcher, EventKind};
 
+use crate::config::Config;
 use crate::event::Event;
+use crate::library::ignore::IgnoreRules;
 use crate::library::Library;
 
-pub fn spawn_watcher(music_dir: &Path, event_tx: Sender<Event>) -> Option<RecommendedWatcher> {
+/// How often the mount table is checked for the music directory's drive coming or going
+const MOUNT_POLL: Duration = Duration::from_secs(3);
+
+pub fn spawn_watcher(music_dir: &Path, event_tx: Sender<Event>, config: Config) -> Option<Arc<Mutex<RecommendedWatcher>>> {
     let (notify_tx, notify_rx) = crossbeam_channel::unbounded();
+    let mount_tx = notify_tx.clone();
     let dir = music_dir.to_path_buf();
+    let ignore = IgnoreRules::load(music_dir, &config.ignore);
 
     let mut watcher = RecommendedWatcher::new(
         move |res: Result<notify::Event, notify::Error>| {
@@ -20,7 +27,10 @@ pub fn spawn_watcher(music_dir: &Path, event_tx: Sender<Event>) -> Option<Recomm
                     ev.kind,
                     EventKind::Create(_) | EventKind::Remove(_) | EventKind::Modify(_)
                 );
-                if dominated {
+                // Changes confined to ignored paths never trigger a rescan
+                let relevant = ignore.is_empty()
+                    || ev.paths.iter().any(|p| !ignore.is_ignored(p, p.is_dir()));
+                if dominated && relevant {
                     let _ = notify_tx.send(());
                 }
             }
@@ -28,7 +38,11 @@ pub fn spawn_watcher(music_dir: &Path, event_tx: Sender<Event>) -> Option<Recomm
         notify::Config::default(),
     ).ok()?;
 
-    watcher.watch(music_dir, RecursiveMode::Recursive).ok()?;
+    // May fail while the drive holding the music directory isn't mounted;
+    // the mount monitor below starts watching once it appears
+    let _ = watcher.watch(music_dir, RecursiveMode::Recursive);
+    let watcher = Arc::new(Mutex::new(watcher));
+    spawn_mount_monitor(music_dir.to_path_buf(), Arc::clone(&watcher), mount_tx);
 
     let scanning = Arc::new(AtomicBool::new(false));
 
@@ -61,8 +75,8 @@ pub fn spawn_watcher(music_dir: &Path, event_tx: Sender<Event>) -> Option<Recomm
                         // Only rescan if we actually saw events since last scan
                         if last_event.elapsed() < debounce + Duration::from_millis(600) {
                             scanning_clone.store(true, Ordering::Relaxed);
-                            let lib = Library::scan(&dir);
-                            let _ = event_tx.send(Event::LibraryReady(lib));
+                            let lib = Library::scan(&dir, &config);
+                            let _ = event_tx.send(Event::LibraryReady(Box::new(lib)));
                             scanning_clone.store(false, Ordering::Relaxed);
                         }
                     }
@@ -74,3 +88,88 @@ pub fn spawn_watcher(music_dir: &Path, event_tx: Sender<Event>) -> Option<Recomm
 
     Some(watcher)
 }
+
+/// Rescan (through the debounce thread) whenever a mount at, above or below the
+/// music directory changes, e.g. a USB drive being plugged in or removed. Entries
+/// for tracks on a removed drive stay in the queue and playlists as unavailable
+/// and come back after the rescan that follows remounting.
+fn spawn_mount_monitor(dir: PathBuf, watcher: Arc<Mutex<RecommendedWatcher>>, rescan_tx: Sender<()>) {
+    std::thread::spawn(move || {
+        let mut mounts = mounts_affecting(&dir);
+        loop {
+            std::thread::sleep(MOUNT_POLL);
+            let current = mounts_affecting(&dir);
+            if current == mounts {
+                continue;
+            }
+            mounts = current;
+            // inotify watches die with the old mount; watch the new tree instead
+            if let Ok(mut w) = watcher.lock() {
+                let _ = w.unwatch(&dir);
+                let _ = w.watch(&dir, RecursiveMode::Recursive);
+            }
+            if rescan_tx.send(()).is_err() {
+                break;
+            }
+        }
+    });
+}
+
+/// Mount points that contain `dir` or lie inside it, from /proc/self/mountinfo.
+/// Empty where that isn't available, which disables mount detection.
+fn mounts_affecting(dir: &Path) -> Vec<PathBuf> {
+    let Ok(info) = std::fs::read_to_string("/proc/self/mountinfo") else {
+        return Vec::new();
+    };
+    info.lines()
+        .filter_map(|line| line.split(' ').nth(4))
+        .map(|mount_point| PathBuf::from(unescape_mount_path(mount_point)))
+        .filter(|mp| dir.starts_with(mp) || mp.starts_with(dir))
+        .collect()
+}
+
+/// mountinfo escapes space, tab, newline and backslash as octal (`\040`)
+fn unescape_mount_path(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(pos) = rest.find('\\') {
+        out.push_str(&rest[..pos]);
+        let code = rest.get(pos + 1..pos + 4).and_then(|o| u8::from_str_radix(o, 8).ok());
+        match code {
+            Some(c) => {
+                out.push(c as char);
+                rest = &rest[pos + 4..];
+            }
+            None => {
+                out.push('\\');
+                rest = &rest[pos + 1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::unescape_mount_path;
+
+    #[test]
+    fn octal_escapes_are_decoded() {
+        assert_eq!(unescape_mount_path("/media/My\\040Music"), "/media/My Music");
+        assert_eq!(unescape_mount_path("/mnt/a\\011b\\012c\\134d"), "/mnt/a\tb\nc\\d");
+    }
+
+    #[test]
+    fn plain_paths_pass_through() {
+        assert_eq!(unescape_mount_path("/home/me/Music"), "/home/me/Music");
+        assert_eq!(unescape_mount_path("/mnt/Müsik"), "/mnt/Müsik");
+    }
+
+    #[test]
+    fn malformed_escapes_keep_the_backslash() {
+        assert_eq!(unescape_mount_path("/mnt/x\\9"), "/mnt/x\\9");
+        assert_eq!(unescape_mount_path("/mnt/trailing\\"), "/mnt/trailing\\");
+        assert_eq!(unescape_mount_path("\\04"), "\\04");
+    }
+}
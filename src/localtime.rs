@@ -0,0 +1,52 @@
+//! Unix time to local wall-clock fields, through the C library so the system
+//! time zone applies
+
+/// Calendar fields of a Unix time in the local time zone
+pub struct LocalTime {
+    pub year: i32,
+    /// 0-11
+    pub month: usize,
+    /// 1-31
+    pub day: i32,
+    /// 0-6, Sunday first
+    pub weekday: usize,
+    pub hour: u32,
+    pub minute: u32,
+    pub second: u32,
+}
+
+pub fn local(ts: i64) -> LocalTime {
+    let t = ts as libc::time_t;
+    let mut tm: libc::tm = unsafe { std::mem::zeroed() };
+    #[cfg(unix)]
+    unsafe {
+        libc::localtime_r(&t, &mut tm)
+    };
+    #[cfg(windows)]
+    unsafe {
+        libc::localtime_s(&mut tm, &t)
+    };
+    LocalTime {
+        year: tm.tm_year + 1900,
+        month: tm.tm_mon.clamp(0, 11) as usize,
+        day: tm.tm_mday,
+        weekday: tm.tm_wday.clamp(0, 6) as usize,
+        hour: tm.tm_hour.clamp(0, 23) as u32,
+        minute: tm.tm_min.clamp(0, 59) as u32,
+        // 60 on a leap second
+        second: tm.tm_sec.clamp(0, 60) as u32,
+    }
+}
+
+/// Local (hour, minute, second) of a Unix time
+pub fn local_hms(ts: i64) -> (u32, u32, u32) {
+    let t = local(ts);
+    (t.hour, t.minute, t.second)
+}
+
+/// The current Unix time in seconds
+pub fn now() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs() as i64)
+}
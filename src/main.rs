@@ -1,54 +1,58 @@
 mod app;
 mod audio;
+mod config;
 mod event;
 mod library;
+mod localtime;
+mod terminal;
 mod ui;
 
-use std::io::{self, Write};
+use std::io;
 use std::path::PathBuf;
 use std::time::Duration;
 
 use anyhow::Result;
-use crossterm::{
-    event::{DisableMouseCapture, EnableMouseCapture},
-    execute,
-    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
-};
 use ratatui::backend::CrosstermBackend;
 use ratatui::Terminal;
 
 use app::handler;
+use app::now_playing;
 use app::persist;
-use app::state::{FocusedPane, InfoView, RepeatMode};
+use app::state::{FocusedPane, InfoView, LayoutPreset, PlayState, RepeatMode, SearchOrder};
 use app::App;
 use audio::AudioEngine;
+use config::Config;
+use event::MediaKey;
 use event::input;
+use event::media_controls::MediaControls;
 use event::{AudioEvent, Event};
+use library::lyrics;
+
+/// Save state at least this often while running
+const AUTOSAVE_INTERVAL: Duration = Duration::from_secs(180);
+/// Delay after a playlist/volume change before saving, so bursts save once
+const AUTOSAVE_DEBOUNCE: Duration = Duration::from_secs(2);
 
 fn main() -> Result<()> {
-    // Setup terminal
-    enable_raw_mode()?;
-    let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
-    // Explicitly enable mouse motion tracking (SGR any-event mode)
-    // Some terminals need this even after EnableMouseCapture
-    stdout.write_all(b"\x1b[?1003h")?;
-    stdout.flush()?;
-    let backend = CrosstermBackend::new(stdout);
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().is_some_and(|a| a == "export") {
+        if let Err(e) = export_command(&args[1..]) {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    // Setup terminal; the guard and panic hook put it back however we exit
+    terminal::install_panic_hook();
+    let guard = terminal::TerminalGuard::enter()?;
+    let backend = CrosstermBackend::new(io::stdout());
     let mut terminal = Terminal::new(backend)?;
 
     let result = run_app(&mut terminal);
 
     // Restore terminal
-    disable_raw_mode()?;
-    // Disable mouse motion tracking
-    execute!(
-        terminal.backend_mut(),
-        crossterm::style::Print("\x1b[?1003l"),
-        LeaveAlternateScreen,
-        DisableMouseCapture
-    )?;
-    terminal.show_cursor()?;
+    drop(guard);
 
     if let Err(e) = result {
         eprintln!("Error: {}", e);
@@ -59,6 +63,10 @@ fn main() -> Result<()> {
 
 fn run_app(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> Result<()> {
     let music_dir = dirs_music_path();
+    let mut config = Config::load();
+    if std::env::args().skip(1).any(|arg| arg == "--read-only") {
+        config.read_only = true;
+    }
 
     // Detect terminal image protocol BEFORE input thread steals stdin
     let picker = ratatui_image::picker::Picker::from_query_stdio()
@@ -70,23 +78,59 @@ fn run_app(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> Result<()>
     // Spawn input thread
     let _input_handle = input::spawn_input_thread(event_tx.clone());
     let _tick_handle = input::spawn_tick_thread(event_tx.clone(), Duration::from_millis(200));
+    let hotkey_devices = if config.global_hotkeys {
+        Some(event::hotkeys::spawn_hotkey_listener(event_tx.clone()))
+    } else {
+        None
+    };
+
+    let mut media_controls = if config.media_controls { MediaControls::new(event_tx.clone()) } else { None };
+
+    let remote = config.remote_port.map(|port| {
+        let tokens = event::remote::Tokens {
+            owner: config.remote_token.clone(),
+            guests: config.remote_guest_tokens.clone(),
+        };
+        (port, event::remote::spawn_server(&config.remote_bind, port, tokens, event_tx.clone()))
+    });
+
+    // Held until run_app returns; dropping it stops the listener
+    let suspend_listener = if config.pause_on_suspend {
+        event::suspend::spawn_suspend_listener(event_tx.clone())
+    } else {
+        None
+    };
 
     // Audio engine
-    let audio_engine = AudioEngine::new(event_tx.clone())?;
+    let audio_engine = AudioEngine::new(event_tx.clone(), config.backend_kind(), config.bit_perfect, config.fade_in())?;
 
     // App state
-    let mut app = App::new(music_dir.clone());
+    let mut app = App::new(music_dir.clone(), config.clone());
     app.set_audio_engine(audio_engine);
     app.set_event_tx(event_tx.clone());
+    app.history = app::history::load();
+    app.loudness = library::loudness::load();
+    if hotkey_devices == Some(0) {
+        app.notify("Global hotkeys: no readable media-key device (join the input group)");
+    }
+    if config.pause_on_suspend && suspend_listener.is_none() {
+        app.notify("Pause on suspend: gdbus not found");
+    }
+    if let Some((port, Err(e))) = remote {
+        app.notify(format!("Remote control on port {} not started: {}", port, e));
+    }
 
     // Scan library in background
     let scan_dir = music_dir.clone();
     let scan_handle = std::thread::spawn(move || {
-        library::Library::scan(&scan_dir)
+        library::Library::scan(&scan_dir, &config)
     });
 
     // UI
     let mut ui = ui::Ui::new(music_dir.clone(), picker);
+    if std::env::args().skip(1).any(|arg| arg == "--frame-time") {
+        ui.frame_time = Some(Duration::ZERO);
+    }
 
     // Initial render
     terminal.draw(|frame| {
@@ -96,7 +140,14 @@ fn run_app(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> Result<()>
     // Wait for library scan to complete (non-blocking check in event loop)
     let mut scan_done = false;
     let mut scan_join = Some(scan_handle);
-    let mut _watcher: Option<notify::RecommendedWatcher> = None;
+    let mut _watcher: Option<std::sync::Arc<std::sync::Mutex<notify::RecommendedWatcher>>> = None;
+
+    // Only redraw when something visible changed (or once per second for the clock)
+    let mut dirty = false;
+    let mut last_clock_sec = 0u64;
+    let mut last_save = std::time::Instant::now();
+    let mut now_playing_shown: Option<now_playing::Key> = None;
+    let mut media_shown: Option<now_playing::Key> = None;
 
     loop {
         // Check if library scan is done
@@ -106,10 +157,12 @@ fn run_app(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> Result<()>
                     if let Some(handle) = scan_join.take() {
                         match handle.join() {
                             Ok(lib) => {
-                                app.library = lib;
+                                app.library = std::sync::Arc::new(lib);
                                 // Load all tracks into queue by default
                                 let all_indices: Vec<usize> = (0..app.library.tracks.len()).collect();
                                 app.handle_action(app::AppAction::AddToQueue(all_indices));
+                                // Not something the user did
+                                app.status_message = None;
                                 ui.refresh_dir_browser(&app);
 
                                 // Restore persisted state
@@ -121,30 +174,55 @@ fn run_app(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> Result<()>
                                     ui.pane_widths = saved.pane_widths;
                                     ui.info_view = InfoView::from_label(&saved.info_view);
                                     ui.right_split = saved.right_split.clamp(10, 90);
-                                    // Restore playlists (path → index remapping)
+                                    ui.layout_preset = LayoutPreset::from_label(&saved.layout);
+                                    ui.search_history = saved.search_history.clone();
+                                    ui.search_order = SearchOrder::from_label(&saved.search_order);
+                                    app.resume_positions = saved.resume_positions.clone();
+                                    app.sessions = saved.sessions.iter().map(|s| app::state::Session {
+                                        name: s.name.clone(),
+                                        tracks: s.tracks.clone(),
+                                        current_index: s.current_index,
+                                        position_secs: s.position_secs,
+                                        shuffle: s.shuffle,
+                                        repeat: RepeatMode::from_label(&s.repeat),
+                                    }).collect();
+                                    app.active_session = saved.active_session.clone();
+                                    ui.lyrics_pane.variants = saved.lyrics_variants.iter()
+                                        .map(|(p, v)| (p.clone(), lyrics::Variant::from_label(v)))
+                                        .collect();
+                                    if !ui.layout_preset.shows(app.focus) {
+                                        app.focus = FocusedPane::Playlist;
+                                    }
+                                    // Restore playlists, keeping entries for files that are currently missing
                                     let mut playlists = Vec::new();
                                     for sp in &saved.playlists {
-                                        let tracks: Vec<usize> = sp.tracks.iter()
-                                            .filter_map(|p| app.library.path_to_index(p))
-                                            .collect();
                                         playlists.push(app::state::Playlist {
                                             name: sp.name.clone(),
-                                            tracks,
+                                            tracks: sp.tracks.clone(),
+                                            allow_duplicates: sp.allow_duplicates,
+                                            generated: sp.generated,
                                         });
                                     }
                                     if playlists.is_empty() {
                                         playlists.push(app::state::Playlist::new("Bookmarks"));
                                     }
                                     app.playlists = playlists;
+                                    app.playlists_rev += 1;
                                 }
 
                                 scan_done = true;
                                 app.initial_scan_complete = true;
-                                _watcher = library::watcher::spawn_watcher(&music_dir, event_tx.clone());
+                                dirty = true;
+                                _watcher = library::watcher::spawn_watcher(
+                                    &music_dir,
+                                    event_tx.clone(),
+                                    app.config.clone(),
+                                );
                             }
                             Err(_) => {
                                 scan_done = true;
                                 app.initial_scan_complete = true;
+                                dirty = true;
                             }
                         }
                     }
@@ -152,148 +230,402 @@ fn run_app(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> Result<()>
             }
         }
 
+        // Wait for the next event, then drain everything already queued so a
+        // burst (fast mouse movement, wheel scrolling) costs a single render
+        let first = match event_rx.recv_timeout(Duration::from_millis(50)) {
+            Ok(event) => Some(event),
+            Err(crossbeam_channel::RecvTimeoutError::Timeout) => {
+                // Keep the splash animation smooth between ticks
+                if ui.show_splash {
+                    dirty = true;
+                }
+                None
+            }
+            Err(crossbeam_channel::RecvTimeoutError::Disconnected) => {
+                break;
+            }
+        };
+        let batch: Vec<Event> = first
+            .into_iter()
+            .chain(event_rx.try_iter().take(event::MAX_BATCH))
+            .collect();
+
         // Process events
-        match event_rx.recv_timeout(Duration::from_millis(50)) {
-            Ok(event) => {
-                let actions = match event {
-                    Event::Key(key) => {
-                        // On key press during splash: jump to fade-out phase
-                        if ui.show_splash {
-                            if let Some(start) = ui.splash_start {
-                                let elapsed = start.elapsed().as_secs_f32();
-                                if elapsed < 1.5 {
-                                    // Jump timeline to start of fade-out (1.5s mark)
-                                    ui.splash_start = Some(
-                                        std::time::Instant::now() - Duration::from_millis(1500)
-                                    );
-                                }
+        for event in event::coalesce(batch) {
+            let actions = match event {
+                Event::Key(key) => {
+                    dirty = true;
+                    ui.last_input = std::time::Instant::now();
+                    if ui.screensaver {
+                        // Wakes the screen without acting on the key; images drawn
+                        // with a terminal protocol need a full redraw to go away
+                        ui.screensaver = false;
+                        terminal.clear()?;
+                        vec![]
+                    } else if ui.show_splash {
+                    // On key press during splash: jump to fade-out phase
+                        if let Some(start) = ui.splash_start {
+                            let elapsed = start.elapsed().as_secs_f32();
+                            if elapsed < 1.5 {
+                                // Jump timeline to start of fade-out (1.5s mark)
+                                ui.splash_start = Some(
+                                    std::time::Instant::now() - Duration::from_millis(1500)
+                                );
                             }
-                            vec![]
-                        } else {
-                        // Handle queue selection directly for playlist focus
-                        // Skip when any modal is open
-                        if app.focus == FocusedPane::Playlist
-                            && !app.search_mode
-                            && !ui.show_search_modal
-                            && !ui.show_help_modal
-                            && !ui.show_playlist_modal
-                            && !ui.resize_mode
-                            && !ui.chord_pending
-                        {
-                            handler::update_queue_selection(&mut app, key);
-                        }
-                        handler::handle_key_event(key, &app, &mut ui)
                         }
+                        vec![]
+                    } else {
+                    // Handle queue selection directly for playlist focus
+                    // Skip when any modal is open
+                    if app.focus == FocusedPane::Playlist
+                        && !app.search_mode
+                        && !ui.show_search_modal
+                        && !ui.show_help_modal
+                        && ui.stats_modal.is_none()
+                        && ui.tag_modal.is_none()
+                        && ui.session_modal.is_none()
+                        && ui.export_modal.is_none()
+                        && !ui.show_playlist_modal
+                        && !ui.resize_mode
+                        && !ui.chord_pending
+                    {
+                        handler::update_queue_selection(&mut app, &ui.queue_pane.list, key);
                     }
-                    Event::Mouse(mouse) => {
-                        let size = terminal.size()?;
-                        let area = ratatui::layout::Rect::new(0, 0, size.width, size.height);
-                        handler::handle_mouse_event(mouse, &app, &mut ui, area)
+                    handler::handle_key_event(key, &app, &mut ui)
                     }
-                    Event::Resize(_, _) => {
-                        vec![] // Will re-render on next loop
+                }
+                Event::Mouse(_) if ui.screensaver => {
+                    dirty = true;
+                    ui.last_input = std::time::Instant::now();
+                    ui.screensaver = false;
+                    terminal.clear()?;
+                    vec![]
+                }
+                Event::Mouse(mouse) => {
+                    dirty = true;
+                    ui.last_input = std::time::Instant::now();
+                    let size = terminal.size()?;
+                    let area = ratatui::layout::Rect::new(0, 0, size.width, size.height);
+                    handler::handle_mouse_event(mouse, &app, &mut ui, area)
+                }
+                Event::Paste(text) => {
+                    dirty = true;
+                    ui.last_input = std::time::Instant::now();
+                    if ui.screensaver {
+                        // Like a key: wakes the screen and is dropped
+                        ui.screensaver = false;
+                        terminal.clear()?;
+                    } else {
+                        handler::handle_paste(&text, &app, &mut ui);
                     }
-                    Event::Tick => {
-                        // Auto-dismiss splash after full timeline (2s)
-                        if ui.show_splash {
-                            if let Some(start) = ui.splash_start {
-                                if start.elapsed().as_secs_f32() >= 2.0 {
-                                    ui.show_splash = false;
-                                    ui.splash_start = None;
-                                }
+                    vec![]
+                }
+                Event::Resize(_, _) => {
+                    dirty = true;
+                    vec![] // Will re-render on next loop
+                }
+                Event::Tick => {
+                    if let Some(controls) = media_controls.as_mut() {
+                        controls.poll();
+                    }
+                    if app.expire_status_message() {
+                        dirty = true;
+                    }
+                    if app.check_night_schedule() {
+                        dirty = true;
+                    }
+                    // Auto-dismiss splash after full timeline (2s)
+                    if ui.show_splash {
+                        if let Some(start) = ui.splash_start {
+                            if start.elapsed().as_secs_f32() >= 2.0 {
+                                ui.show_splash = false;
+                                ui.splash_start = None;
+                                // Drop a cover drawn by the art splash
+                                terminal.clear()?;
                             }
                         }
-                        // Refresh hover + focus from stored mouse position
-                        let size = terminal.size()?;
-                        let area = ratatui::layout::Rect::new(0, 0, size.width, size.height);
-                        handler::refresh_hover(&app, &mut ui, area)
                     }
-                    Event::LibraryReady(new_lib) => {
-                        app.replace_library(new_lib);
-                        ui.refresh_dir_browser(&app);
-                        ui.clamp_selections(&app);
-                        vec![]
+                    // Clock view ticks once per second; pending album art needs a
+                    // redraw to show up once the background load finishes
+                    let now_sec = std::time::SystemTime::now()
+                        .duration_since(std::time::UNIX_EPOCH)
+                        .map(|d| d.as_secs())
+                        .unwrap_or(0);
+                    if now_sec != last_clock_sec {
+                        // The screensaver moves every minute; clear the cover from its old spot
+                        if ui.screensaver && now_sec / 60 != last_clock_sec / 60 {
+                            terminal.clear()?;
+                        }
+                        last_clock_sec = now_sec;
+                        if ui.info_view == InfoView::Clock || ui.screensaver {
+                            dirty = true;
+                        }
                     }
-                    Event::Audio(audio_event) => {
-                        match audio_event {
-                            AudioEvent::PositionUpdate {
-                                position_secs,
-                                duration_secs,
-                            } => vec![app::AppAction::UpdatePosition {
-                                position_secs,
-                                duration_secs,
-                            }],
-                            AudioEvent::TrackFinished => vec![app::AppAction::TrackFinished],
-                            AudioEvent::TrackError(_) => {
-                                // Skip to next track on decode error
-                                vec![app::AppAction::NextTrack]
-                            }
-                            AudioEvent::Playing => {
-                                app.playback.state = app::state::PlayState::Playing;
-                                vec![]
-                            }
-                            AudioEvent::Paused => {
-                                app.playback.state = app::state::PlayState::Paused;
-                                vec![]
-                            }
-                            AudioEvent::Stopped => {
-                                app.playback.state = app::state::PlayState::Stopped;
+                    if ui.show_splash
+                        || (ui.info_view == InfoView::AlbumArt && ui.album_art_cache.is_loading())
+                        || (ui.screensaver && ui.backdrop_art.is_loading())
+                    {
+                        dirty = true;
+                    }
+                    let idle_for = Duration::from_secs_f64(app.config.screensaver_minutes.max(0.0) * 60.0);
+                    if !ui.screensaver
+                        && !ui.show_splash
+                        && !idle_for.is_zero()
+                        && ui.last_input.elapsed() >= idle_for
+                    {
+                        ui.screensaver = true;
+                        terminal.clear()?;
+                        dirty = true;
+                    }
+                    // Refresh hover + focus from stored mouse position
+                    let size = terminal.size()?;
+                    let area = ratatui::layout::Rect::new(0, 0, size.width, size.height);
+                    let actions = handler::refresh_hover(&app, &mut ui, area);
+                    if !actions.is_empty() {
+                        dirty = true;
+                    }
+                    actions
+                }
+                Event::TagsWritten { written, failed } => {
+                    dirty = true;
+                    match failed.first() {
+                        None => app.notify(format!("Tagged {} files", written)),
+                        Some((path, err)) => {
+                            let name = path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+                            app.notify(format!("Tagged {} files, {} failed ({}: {})", written, failed.len(), name, err));
+                        }
+                    }
+                    // Pick up the new tags (the watcher would too, after its debounce)
+                    vec![app::AppAction::LibrarySync]
+                }
+                Event::FilesMoved { moved, failed } => {
+                    dirty = true;
+                    app.remap_paths(&moved);
+                    match failed.first() {
+                        None => app.notify(format!("Moved {} files", moved.len())),
+                        Some((path, err)) => {
+                            let name = path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+                            app.notify(format!("Moved {} files, {} failed ({}: {})", moved.len(), failed.len(), name, err));
+                        }
+                    }
+                    vec![app::AppAction::LibrarySync]
+                }
+                Event::MediaKey(key) => {
+                    dirty = true;
+                    let playing = app.playback.state == PlayState::Playing;
+                    match key {
+                        MediaKey::PlayPause => vec![app::AppAction::PauseResume],
+                        MediaKey::Play if !playing => vec![app::AppAction::PauseResume],
+                        MediaKey::Pause if playing => vec![app::AppAction::PauseResume],
+                        MediaKey::Play | MediaKey::Pause => vec![],
+                        MediaKey::Next => vec![app::AppAction::NextTrack],
+                        MediaKey::Prev => vec![app::AppAction::PrevTrack],
+                    }
+                }
+                Event::Remote(request) => {
+                    // Run right away so the reply shows the command's effect
+                    dirty |= event::remote::answer(request, &mut app);
+                    vec![]
+                }
+                Event::Suspend => {
+                    dirty = true;
+                    // Paused here rather than queued, so sleep can be let through right after
+                    if app.playback.state == PlayState::Playing {
+                        app.notify("Paused for suspend");
+                        app.handle_action(app::AppAction::PauseResume);
+                    }
+                    if let Some(ref listener) = suspend_listener {
+                        listener.release();
+                    }
+                    vec![]
+                }
+                Event::LoudnessMeasured { path, lufs } => {
+                    dirty = true;
+                    app.loudness_measured(path, lufs);
+                    vec![]
+                }
+                Event::ChaptersRead { path, chapters } => {
+                    dirty = true;
+                    app.chapters_read(path, chapters);
+                    vec![]
+                }
+                Event::LoudnessFinished => {
+                    dirty = true;
+                    app.loudness_finished();
+                    vec![]
+                }
+                Event::SearchResults { generation, results } => {
+                    dirty |= ui.apply_search_results(generation, results, &app.library);
+                    vec![]
+                }
+                Event::LibraryReady(new_lib) => {
+                    dirty = true;
+                    let old_lib = std::sync::Arc::clone(&app.library);
+                    app.replace_library(*new_lib);
+                    ui.restore_selections(&old_lib, &app);
+                    // Re-run an open search so result indices match the new library
+                    if ui.show_search_modal {
+                        ui.search_input_changed();
+                    }
+                    vec![]
+                }
+                Event::Audio(audio_event) => {
+                    // Position updates arrive every 250 ms; only whole-second changes are visible
+                    dirty |= match &audio_event {
+                        AudioEvent::PositionUpdate { position_secs, .. } => {
+                            position_secs.floor() != app.playback.position_secs.floor()
+                        }
+                        _ => true,
+                    };
+                    match audio_event {
+                        AudioEvent::PositionUpdate {
+                            position_secs,
+                            duration_secs,
+                        } => vec![app::AppAction::UpdatePosition {
+                            position_secs,
+                            duration_secs,
+                        }],
+                        AudioEvent::OutputFormat { stream, sample_rate, exact } => {
+                            app.playback.output_format = Some((sample_rate, exact));
+                            app.playback.stream_format = Some(stream);
+                            vec![]
+                        }
+                        AudioEvent::TrackFinished => vec![app::AppAction::TrackFinished],
+                        AudioEvent::TrackError(_) => {
+                            // Skip to next track on decode error
+                            vec![app::AppAction::NextTrack]
+                        }
+                        AudioEvent::DeviceLost(err) => {
+                            app.notify(format!("Audio output lost ({}), reconnecting\u{2026}", err));
+                            // Paused before it reconnects, possibly to the laptop speakers
+                            if app.config.pause_on_disconnect && app.playback.state == PlayState::Playing {
+                                vec![app::AppAction::PauseResume]
+                            } else {
                                 vec![]
                             }
                         }
+                        AudioEvent::PreviewFinished => {
+                            app.preview = None;
+                            vec![]
+                        }
+                        AudioEvent::DeviceRestored => {
+                            app.notify("Audio output restored");
+                            vec![]
+                        }
+                        AudioEvent::BackendFallback(message) => {
+                            app.notify(format!("Audio backend {}", message));
+                            vec![]
+                        }
+                        AudioEvent::NoOutput(err) => {
+                            app.notify(format!("No audio output: {}", err));
+                            vec![]
+                        }
+                        AudioEvent::Playing => {
+                            app.playback.state = app::state::PlayState::Playing;
+                            vec![]
+                        }
+                        AudioEvent::Paused => {
+                            app.playback.state = app::state::PlayState::Paused;
+                            vec![]
+                        }
+                        AudioEvent::Stopped => {
+                            app.playback.state = app::state::PlayState::Stopped;
+                            vec![]
+                        }
                     }
-                };
-
-                for action in actions {
-                    app.handle_action(action);
                 }
+            };
+
+            for action in actions {
+                app.handle_action(action);
+            }
+
+            if app.should_quit {
+                break;
+            }
+        }
 
-                if app.should_quit {
-                    break;
+        if app.should_quit {
+            break;
+        }
+
+        // Debounced search: runs once typing pauses
+        if let Some((query, generation)) = ui.take_due_search() {
+            app.handle_action(app::AppAction::Search { query, generation });
+        }
+
+        // Preview mode: audition the track under the cursor once the selection settles
+        if app.preview_mode {
+            let due = ui.take_due_preview(handler::preview_target(&app, &ui));
+            // Already previewing it (with `P`): previewing again would stop it
+            if let Some(path) = due.filter(|p| app.preview.as_ref() != Some(p)) {
+                app.handle_action(app::AppAction::Preview(path));
+                dirty = true;
+            }
+        }
+
+        // Now-playing files follow track and play/pause changes
+        if app.config.now_playing_file {
+            let key = now_playing::key(&app);
+            if now_playing_shown.as_ref() != Some(&key) {
+                if let Err(e) = now_playing::write(&app) {
+                    app.notify(format!("Couldn't write now-playing file: {}", e));
+                    dirty = true;
                 }
+                now_playing_shown = Some(key);
             }
-            Err(crossbeam_channel::RecvTimeoutError::Timeout) => {
-                // Just re-render
+        }
+
+        // The OS media controls follow the same changes
+        if let Some(controls) = media_controls.as_mut() {
+            let key = now_playing::key(&app);
+            if media_shown.as_ref() != Some(&key) {
+                controls.update(&app);
+                media_shown = Some(key);
             }
-            Err(crossbeam_channel::RecvTimeoutError::Disconnected) => {
-                break;
+        }
+
+        // Autosave shortly after playlist/volume changes, and periodically for the rest.
+        // Not before the first scan: saved playlists haven't been restored yet.
+        if app.initial_scan_complete
+            && ((app.state_changed && last_save.elapsed() >= AUTOSAVE_DEBOUNCE)
+                || last_save.elapsed() >= AUTOSAVE_INTERVAL)
+        {
+            if let Err(e) = persist::save(&saved_state(&app, &ui)) {
+                app.notify(format!("Couldn't save state: {}", e));
+                dirty = true;
             }
+            app.state_changed = false;
+            last_save = std::time::Instant::now();
         }
 
         // Render
-        terminal.draw(|frame| {
-            ui.render(frame, &app);
-        })?;
+        if dirty {
+            let started = std::time::Instant::now();
+            terminal.draw(|frame| {
+                ui.render(frame, &app);
+            })?;
+            if ui.frame_time.is_some() {
+                ui.frame_time = Some(started.elapsed());
+            }
+            dirty = false;
+        }
     }
 
     // Save state on exit
-    let saved_playlists: Vec<persist::SavedPlaylist> = app.playlists.iter().map(|pl| {
-        persist::SavedPlaylist {
-            name: pl.name.clone(),
-            tracks: pl.tracks.iter()
-                .filter_map(|&idx| app.library.tracks.get(idx).map(|t| t.path.clone()))
-                .collect(),
-        }
-    }).collect();
-
-    let saved = persist::SavedState {
-        volume: app.playback.volume,
-        shuffle: app.playback.shuffle,
-        repeat: app.playback.repeat.as_str().to_string(),
-        pane_widths: ui.pane_widths,
-        playlists: saved_playlists,
-        info_view: ui.info_view.as_str().to_string(),
-        right_split: ui.right_split,
-    };
-
+    let saved = saved_state(&app, &ui);
     if let Err(e) = persist::save(&saved) {
         eprintln!("Warning: failed to save state: {}", e);
     }
+    if app.config.now_playing_file {
+        let _ = now_playing::clear();
+    }
 
     // Suppress rodio's "Dropping OutputStream" message:
     // 1. Redirect stderr to /dev/null
     // 2. Explicitly drop app (triggers AudioEngine → player thread shutdown)
     // 3. Brief sleep so the player thread can exit and drop OutputStream silently
+    #[cfg(unix)]
     unsafe {
         let devnull = libc::open(c"/dev/null".as_ptr(), libc::O_WRONLY);
         if devnull >= 0 {
@@ -307,6 +639,74 @@ fn run_app(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> Result<()>
     Ok(())
 }
 
+/// Snapshot of everything persisted between runs
+fn saved_state(app: &App, ui: &ui::Ui) -> persist::SavedState {
+    let saved_playlists: Vec<persist::SavedPlaylist> = app.playlists.iter().map(|pl| {
+        persist::SavedPlaylist {
+            name: pl.name.clone(),
+            tracks: pl.tracks.clone(),
+            allow_duplicates: pl.allow_duplicates,
+            generated: pl.generated,
+        }
+    }).collect();
+
+    persist::SavedState {
+        volume: app.playback.volume,
+        shuffle: app.playback.shuffle,
+        repeat: app.playback.repeat.as_str().to_string(),
+        pane_widths: ui.pane_widths,
+        playlists: saved_playlists,
+        info_view: ui.info_view.as_str().to_string(),
+        right_split: ui.right_split,
+        layout: ui.layout_preset.as_str().to_string(),
+        search_history: ui.search_history.clone(),
+        search_order: ui.search_order.as_str().to_string(),
+        lyrics_variants: ui.lyrics_pane.variants.iter()
+            .map(|(p, v)| (p.clone(), v.as_str().to_string()))
+            .collect(),
+        resume_positions: app.resume_positions.clone(),
+        sessions: app.sessions.iter().map(|s| persist::SavedSession {
+            name: s.name.clone(),
+            tracks: s.tracks.clone(),
+            current_index: s.current_index,
+            position_secs: s.position_secs,
+            shuffle: s.shuffle,
+            repeat: s.repeat.as_str().to_string(),
+        }).collect(),
+        active_session: app.active_session.clone(),
+    }
+}
+
+/// `ommp export <library|history> [FILE]`: scan or read the data and write it out
+/// without starting the player
+fn export_command(args: &[String]) -> Result<()> {
+    let usage = "usage: ommp export <library|history> [FILE]  (CSV for .csv, JSON otherwise)";
+    let read_only = args.iter().any(|a| a == "--read-only");
+    let args: Vec<&String> = args.iter().filter(|a| !a.starts_with("--")).collect();
+    let dataset = args
+        .first()
+        .and_then(|name| app::export::Dataset::from_name(name))
+        .ok_or_else(|| anyhow::anyhow!(usage))?;
+    if dataset == app::export::Dataset::Queue {
+        anyhow::bail!("the queue only exists while ommp runs; export it from the app with Ctrl+E, x");
+    }
+    let path = app::export::expand_home(args.get(1).map_or(dataset.default_path(), |p| p.to_string()).as_str());
+    let config = Config::load();
+    let music_dir = dirs_music_path();
+    if (read_only || config.read_only) && app::export::is_inside(&path, &music_dir) {
+        anyhow::bail!("{}", app::READ_ONLY_MESSAGE);
+    }
+    let library = if dataset == app::export::Dataset::Library {
+        library::Library::scan(&music_dir, &config)
+    } else {
+        library::Library::new()
+    };
+    let history = app::history::load();
+    let rows = app::export::write(dataset, &library, &[], &history, &path)?;
+    println!("Wrote {} {} rows to {}", rows, dataset.name(), path.display());
+    Ok(())
+}
+
 fn dirs_music_path() -> PathBuf {
     if let Some(home) = std::env::var_os("HOME") {
         let music = PathBuf::from(home).join("Music");
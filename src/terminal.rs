@@ -0,0 +1,132 @@
+use std::backtrace::Backtrace;
+use std::io::{self, Write};
+use std::panic::PanicHookInfo;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crossterm::{
+    event::{DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture},
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
+
+/// Puts the terminal into TUI mode and restores it when dropped, so every exit
+/// path (errors included) leaves a usable shell behind
+pub struct TerminalGuard;
+
+impl TerminalGuard {
+    pub fn enter() -> io::Result<Self> {
+        enable_raw_mode()?;
+        let mut stdout = io::stdout();
+        execute!(stdout, EnterAlternateScreen, EnableMouseCapture, EnableBracketedPaste)?;
+        // Explicitly enable mouse motion tracking (SGR any-event mode)
+        // Some terminals need this even after EnableMouseCapture
+        stdout.write_all(b"\x1b[?1003h")?;
+        stdout.flush()?;
+        Ok(Self)
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        restore();
+    }
+}
+
+/// Leave raw mode, mouse tracking, bracketed paste and the alternate screen. Safe to call twice.
+pub fn restore() {
+    let _ = disable_raw_mode();
+    let mut stdout = io::stdout();
+    let _ = execute!(
+        stdout,
+        crossterm::style::Print("\x1b[?1003l"),
+        LeaveAlternateScreen,
+        DisableMouseCapture,
+        DisableBracketedPaste,
+        crossterm::cursor::Show
+    );
+}
+
+/// Put `text` on the system clipboard with OSC 52, which works over SSH and in
+/// most terminals (some, like tmux, need it enabled)
+pub fn copy_to_clipboard(text: &str) -> io::Result<()> {
+    let mut stdout = io::stdout();
+    write!(stdout, "\x1b]52;c;{}\x07", base64(text.as_bytes()))?;
+    stdout.flush()
+}
+
+fn base64(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let b = [chunk[0], *chunk.get(1).unwrap_or(&0), *chunk.get(2).unwrap_or(&0)];
+        let n = (b[0] as u32) << 16 | (b[1] as u32) << 8 | b[2] as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(n >> (18 - 6 * i) & 63) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+/// On a panic in the UI thread: restore the terminal first, then print the panic
+/// readably and save a crash report with a backtrace. Panics on worker threads
+/// (decoder probes are caught and expected) leave the TUI alone.
+pub fn install_panic_hook() {
+    std::panic::set_hook(Box::new(|info| {
+        if std::thread::current().name() != Some("main") {
+            return;
+        }
+        restore();
+
+        let report = crash_report(info);
+        eprintln!("\nommp crashed: {}", panic_message(info));
+        if let Some(location) = info.location() {
+            eprintln!("  at {}:{}", location.file(), location.line());
+        }
+        match write_crash_report(&report) {
+            Ok(path) => eprintln!("Crash report written to {}", path.display()),
+            Err(e) => eprintln!("Couldn't write a crash report: {}", e),
+        }
+    }));
+}
+
+fn panic_message(info: &PanicHookInfo) -> String {
+    if let Some(s) = info.payload().downcast_ref::<&str>() {
+        s.to_string()
+    } else if let Some(s) = info.payload().downcast_ref::<String>() {
+        s.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
+
+fn crash_report(info: &PanicHookInfo) -> String {
+    let location = info
+        .location()
+        .map(|l| format!("{}:{}:{}", l.file(), l.line(), l.column()))
+        .unwrap_or_else(|| "unknown".to_string());
+    format!(
+        "ommp {} crash report\n\npanic: {}\nlocation: {}\n\nbacktrace:\n{}\n",
+        env!("CARGO_PKG_VERSION"),
+        panic_message(info),
+        location,
+        Backtrace::force_capture()
+    )
+}
+
+fn write_crash_report(report: &str) -> io::Result<PathBuf> {
+    let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
+    let dir = PathBuf::from(home).join(".config/ommp");
+    std::fs::create_dir_all(&dir)?;
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    let path = dir.join(format!("crash-{}.log", secs));
+    std::fs::write(&path, report)?;
+    Ok(path)
+}
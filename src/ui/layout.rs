@@ -1,5 +1,7 @@
 use ratatui::layout::{Constraint, Direction, Layout, Rect};
 
+use crate::app::state::LayoutPreset;
+
 #[derive(Debug, Clone)]
 pub struct LayoutAreas {
     pub status_bar: Rect,
@@ -12,7 +14,8 @@ pub struct LayoutAreas {
 }
 
 impl LayoutAreas {
-    pub fn compute(area: Rect, pane_widths: [u16; 3], right_split: u16) -> Self {
+    /// Hidden columns get an empty area at the edge of their neighbour
+    pub fn compute(area: Rect, pane_widths: [u16; 3], right_split: u16, preset: LayoutPreset) -> Self {
         let vertical = Layout::default()
             .direction(Direction::Vertical)
             .constraints([
@@ -28,13 +31,15 @@ impl LayoutAreas {
         let dashboard = vertical[2];
         let progress_bar = vertical[3];
 
+        // Hidden columns' shares go to the remaining ones, in proportion
+        let shown = [preset.shows_browser(), true, preset.shows_right()];
+        let widths: Vec<u32> = (0..3)
+            .map(|i| if shown[i] { pane_widths[i] as u32 } else { 0 })
+            .collect();
+        let total: u32 = widths.iter().sum::<u32>().max(1);
         let columns = Layout::default()
             .direction(Direction::Horizontal)
-            .constraints([
-                Constraint::Percentage(pane_widths[0]),
-                Constraint::Percentage(pane_widths[1]),
-                Constraint::Percentage(pane_widths[2]),
-            ])
+            .constraints(widths.iter().map(|&w| Constraint::Ratio(w, total)))
             .split(dashboard);
 
         let right_col = Layout::default()
@@ -0,0 +1,126 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::Span;
+use ratatui::widgets::Paragraph;
+use ratatui::Frame;
+
+use crate::library::collate;
+use crate::ui::text_input::{Edit, TextInput};
+
+/// Type-to-narrow filter for a library list (Artists, Albums, Genre). `/` opens a
+/// prompt on the pane's last row and the list shrinks as you type; `Enter` keeps the
+/// filter and goes back to j / k, `Esc` clears it. Matching ignores case and accents.
+///
+/// Panes keep `selected` as an index into the full list; the filter only decides
+/// which of those rows are shown.
+#[derive(Debug, Default)]
+pub struct ListFilter {
+    input: TextInput,
+    editing: bool,
+}
+
+impl ListFilter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_editing(&self) -> bool {
+        self.editing
+    }
+
+    pub fn is_active(&self) -> bool {
+        !self.input.is_empty()
+    }
+
+    pub fn text(&self) -> &str {
+        self.input.as_str()
+    }
+
+    pub fn clear(&mut self) {
+        self.input.clear();
+        self.editing = false;
+    }
+
+    /// Keys the filter takes before the global bindings: everything while typing,
+    /// `/` to start, and `Esc` to drop an applied filter
+    pub fn captures_key(&self, key: &KeyEvent) -> bool {
+        self.editing
+            || key.code == KeyCode::Char('/')
+            || (self.is_active() && key.code == KeyCode::Esc)
+    }
+
+    /// Handle a captured key; true when the filter text changed
+    pub fn handle_key(&mut self, key: &KeyEvent) -> bool {
+        if !self.editing {
+            match key.code {
+                KeyCode::Char('/') => self.editing = true,
+                KeyCode::Esc => {
+                    self.clear();
+                    return true;
+                }
+                _ => {}
+            }
+            return false;
+        }
+        match key.code {
+            KeyCode::Esc => {
+                self.clear();
+                true
+            }
+            KeyCode::Enter => {
+                self.editing = false;
+                false
+            }
+            _ => self.input.handle_key(key) == Edit::Changed,
+        }
+    }
+
+    /// Pasted text goes into the filter being typed
+    pub fn paste(&mut self, text: &str) {
+        if self.editing {
+            self.input.insert_str(text);
+        }
+    }
+
+    /// Indices of the rows whose label matches, all of them when no filter is set
+    pub fn matching<'a>(&self, labels: impl Iterator<Item = &'a str>) -> Vec<usize> {
+        if !self.is_active() {
+            return labels.enumerate().map(|(i, _)| i).collect();
+        }
+        let needle = collate::sort_key(self.input.as_str(), &[]);
+        labels
+            .enumerate()
+            .filter(|(_, label)| collate::sort_key(label, &[]).contains(&needle))
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Split the prompt row off the bottom of `area` while typing
+    pub fn split(&self, area: Rect) -> (Rect, Option<Rect>) {
+        if !self.editing || area.height < 2 {
+            return (area, None);
+        }
+        let [list, prompt] = Layout::vertical([Constraint::Min(1), Constraint::Length(1)]).areas(area);
+        (list, Some(prompt))
+    }
+
+    pub fn render_prompt(&self, frame: &mut Frame, area: Rect) {
+        let [slash, input_area] = Layout::horizontal([Constraint::Length(1), Constraint::Min(1)]).areas(area);
+        frame.render_widget(
+            Paragraph::new(Span::styled("/", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD))),
+            slash,
+        );
+        self.input.render(frame, input_area, Style::default().fg(Color::White));
+    }
+}
+
+/// Move `selected` (a full-list index) `delta` rows through the shown ones
+pub fn step(visible: &[usize], selected: usize, delta: isize) -> usize {
+    let Some(&last) = visible.last() else { return selected };
+    match visible.iter().position(|&i| i == selected) {
+        Some(pos) => visible[pos.saturating_add_signed(delta).min(visible.len() - 1)],
+        // Selection filtered out: start from the nearest shown row
+        None => visible.iter().copied().find(|&i| i > selected).unwrap_or(last),
+    }
+}
@@ -1,17 +1,29 @@
 pub mod layout;
+pub mod list_filter;
 pub mod pane;
+pub mod scrollable_list;
 pub mod panes;
+pub mod text_input;
 pub mod theme;
 pub mod widgets;
 
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
 use ratatui::Frame;
 use ratatui::style::{Color, Style};
 use ratatui::widgets::{Block, Borders};
-use widgets::{about_modal, help_modal, playlist_modal, search_modal};
+use widgets::{
+    about_modal, export_modal, help_modal, mode_overlay, playlist_modal, screensaver, search_modal, session_modal,
+    stats_modal, tag_modal,
+};
 use widgets::playlist_modal::PlaylistModalMode;
 
+use crate::app::stats::{Period, Stats};
 use crate::app::App;
-use crate::app::state::{FocusedPane, InfoView, Tab};
+use crate::library::Library;
+use crate::app::state::{FocusedPane, InfoView, LayoutPreset, SearchOrder, Tab};
+use crate::library::collate;
 use layout::LayoutAreas;
 use pane::Pane;
 use panes::albums_pane::AlbumsPane;
@@ -23,6 +35,7 @@ use panes::library_pane::LibraryPane;
 use panes::lyrics_pane::LyricsPane;
 use panes::playlists_pane::PlaylistsPane;
 use panes::queue_pane::QueuePane;
+use text_input::TextInput;
 use theme::Theme;
 use widgets::info_pane;
 use widgets::progress_bar;
@@ -53,6 +66,11 @@ pub struct Ui {
     pub dragging_border: Option<u8>,
     /// Right column split: info pane height percentage (top), lyrics gets the rest
     pub right_split: u16,
+    /// Which dashboard columns are shown (`L` cycles)
+    pub layout_preset: LayoutPreset,
+    /// How long the previous frame took to draw, shown top right; None unless
+    /// started with `--frame-time`
+    pub frame_time: Option<Duration>,
     /// Ctrl+E pressed, waiting for next key
     pub chord_pending: bool,
     /// Help modal visible
@@ -60,9 +78,13 @@ pub struct Ui {
     /// Search modal visible
     pub show_search_modal: bool,
     /// Search modal input text
-    pub search_modal_input: String,
-    /// Search modal filtered results (track indices)
+    pub search_modal_input: TextInput,
+    /// Search modal filtered results (track indices), in `search_order`
     pub search_modal_results: Vec<usize>,
+    /// The same results in relevance order, as the search returned them
+    search_modal_ranked: Vec<usize>,
+    /// How search results are sorted / grouped (Ctrl+O, persisted)
+    pub search_order: SearchOrder,
     /// Search modal selected result index
     pub search_modal_selected: usize,
     /// Search modal scroll offset
@@ -73,6 +95,16 @@ pub struct Ui {
     pub search_modal_result_area: ratatui::layout::Rect,
     /// Search modal hovered row index
     pub search_modal_hover_row: Option<usize>,
+    /// Time of the last search input edit not yet sent to the search worker
+    pub search_modal_edited: Option<Instant>,
+    /// Bumped per search request; stale worker results are dropped
+    pub search_generation: u64,
+    /// Recent search queries, newest last (persisted)
+    pub search_history: Vec<String>,
+    /// Entry of `search_history` shown in the input while cycling with Up/Down
+    pub search_history_pos: Option<usize>,
+    /// Tab completion of an `artist:` / `album:` / `genre:` filter: field, names, shown name
+    pub search_completion: Option<(String, Vec<String>, usize)>,
     /// Playlist modal visible ("b" key)
     pub show_playlist_modal: bool,
     /// Playlist modal selected index
@@ -80,9 +112,25 @@ pub struct Ui {
     /// Playlist modal mode (List / Create / Rename)
     pub playlist_modal_mode: PlaylistModalMode,
     /// Playlist modal text input (for create/rename)
-    pub playlist_modal_input: String,
+    pub playlist_modal_input: TextInput,
+    /// Name and tracks the playlist modal adds instead of the playing track ("B" on an album, artist or folder)
+    pub playlist_modal_target: Option<(String, Vec<PathBuf>)>,
+    /// Track the playlist modal toggles in playlists ("b" on a selected or playing track)
+    pub playlist_modal_track: Option<PathBuf>,
     /// About modal visible
     pub show_about_modal: bool,
+    /// Stats modal ("Ctrl+E, t"): the selected period and its report; None when closed
+    pub stats_modal: Option<(Period, Stats)>,
+    /// Batch tag editor ("T" on a selection, Ctrl+T in search)
+    pub tag_modal: Option<tag_modal::TagModal>,
+    /// Named sessions list (Ctrl+E, w)
+    pub session_modal: Option<session_modal::SessionModal>,
+    /// Export prompt (Ctrl+E, x)
+    pub export_modal: Option<export_modal::ExportModal>,
+    /// Preview mode: the track last under the cursor, and since when (None once
+    /// it has been previewed)
+    pub preview_selected: Option<PathBuf>,
+    preview_since: Option<Instant>,
     /// Splash screen visible at startup
     pub show_splash: bool,
     /// Splash screen start time
@@ -91,8 +139,24 @@ pub struct Ui {
     pub info_view: InfoView,
     /// Album art pixel cache
     pub album_art_cache: info_pane::AlbumArtCache,
+    /// Full-screen covers for the splash and screensaver, kept apart so the info
+    /// pane's cache isn't rescaled every time one shows
+    pub backdrop_art: info_pane::AlbumArtCache,
+    /// Idle screensaver showing; the next key or mouse event only dismisses it
+    pub screensaver: bool,
+    /// Last key or mouse event, for `screensaver_minutes`
+    pub last_input: Instant,
 }
 
+/// Idle time after the last keystroke before the search runs
+const SEARCH_DEBOUNCE: Duration = Duration::from_millis(100);
+/// How long a track stays selected before preview mode plays it, so scrolling
+/// past tracks doesn't start each one
+const PREVIEW_SETTLE: Duration = Duration::from_millis(400);
+
+/// Recent searches kept for Up/Down recall
+const SEARCH_HISTORY_LEN: usize = 50;
+
 impl Ui {
     pub fn new(music_dir: std::path::PathBuf, picker: ratatui_image::picker::Picker) -> Self {
         Self {
@@ -113,25 +177,45 @@ impl Ui {
             resize_mode: false,
             dragging_border: None,
             right_split: 50,
+            layout_preset: LayoutPreset::All,
+            frame_time: None,
             chord_pending: false,
             show_help_modal: false,
             show_search_modal: false,
-            search_modal_input: String::new(),
+            search_modal_input: TextInput::new(),
             search_modal_results: Vec::new(),
+            search_modal_ranked: Vec::new(),
+            search_order: SearchOrder::Relevance,
             search_modal_selected: 0,
             search_modal_scroll: 0,
             search_modal_result_height: 10,
             search_modal_result_area: ratatui::layout::Rect::default(),
             search_modal_hover_row: None,
+            search_modal_edited: None,
+            search_generation: 0,
+            search_history: Vec::new(),
+            search_history_pos: None,
+            search_completion: None,
             show_playlist_modal: false,
             playlist_modal_selected: 0,
             playlist_modal_mode: PlaylistModalMode::List,
-            playlist_modal_input: String::new(),
+            playlist_modal_target: None,
+            playlist_modal_track: None,
+            playlist_modal_input: TextInput::new(),
             show_about_modal: false,
+            stats_modal: None,
+            tag_modal: None,
+            session_modal: None,
+            export_modal: None,
+            preview_selected: None,
+            preview_since: None,
             show_splash: true,
             splash_start: Some(std::time::Instant::now()),
             info_view: InfoView::Clock,
             album_art_cache: info_pane::AlbumArtCache::new(picker),
+            backdrop_art: info_pane::AlbumArtCache::new(picker),
+            screensaver: false,
+            last_input: Instant::now(),
         }
     }
 
@@ -148,11 +232,19 @@ impl Ui {
             } else {
                 (1.0 - (elapsed - 1.5) / 0.5).max(0.0)
             };
-            about_modal::render_splash_screen(frame, frame.area(), &self.theme, opacity);
+            if app.config.art_splash && app.current_track().is_some() {
+                screensaver::render_art_splash(frame, frame.area(), app, &mut self.backdrop_art);
+            } else {
+                about_modal::render_splash_screen(frame, frame.area(), &self.theme, opacity);
+            }
+            return;
+        }
+        if self.screensaver {
+            screensaver::render_screensaver(frame, frame.area(), app, &self.theme, &mut self.backdrop_art);
             return;
         }
 
-        let areas = LayoutAreas::compute(frame.area(), self.pane_widths, self.right_split);
+        let areas = LayoutAreas::compute(frame.area(), self.pane_widths, self.right_split, self.layout_preset);
 
         // Status bar
         status_bar::render_status_bar(frame, areas.status_bar, app, &self.theme, self.resize_mode);
@@ -162,26 +254,30 @@ impl Ui {
 
         // Left pane (varies by tab)
         let lib_focused = app.focus == FocusedPane::Library;
-        match app.tab {
-            Tab::Queue => self.library_pane.render(frame, areas.library, lib_focused, app, &self.theme),
-            Tab::Directories => self.dir_browser_pane.render(frame, areas.library, lib_focused, app, &self.theme),
-            Tab::Artists => self.artists_pane.render(frame, areas.library, lib_focused, app, &self.theme),
-            Tab::Albums => self.albums_pane.render(frame, areas.library, lib_focused, app, &self.theme),
-            Tab::Genre => self.genre_pane.render(frame, areas.library, lib_focused, app, &self.theme),
-            Tab::Format => self.format_pane.render(frame, areas.library, lib_focused, app, &self.theme),
-            Tab::Playlists => self.playlists_pane.render(frame, areas.library, lib_focused, app, &self.theme),
+        if self.layout_preset.shows_browser() {
+            match app.tab {
+                Tab::Queue => self.library_pane.render(frame, areas.library, lib_focused, app, &self.theme),
+                Tab::Directories => self.dir_browser_pane.render(frame, areas.library, lib_focused, app, &self.theme),
+                Tab::Artists => self.artists_pane.render(frame, areas.library, lib_focused, app, &self.theme),
+                Tab::Albums => self.albums_pane.render(frame, areas.library, lib_focused, app, &self.theme),
+                Tab::Genre => self.genre_pane.render(frame, areas.library, lib_focused, app, &self.theme),
+                Tab::Format => self.format_pane.render(frame, areas.library, lib_focused, app, &self.theme),
+                Tab::Playlists => self.playlists_pane.render(frame, areas.library, lib_focused, app, &self.theme),
+            }
         }
 
         // Center pane (Queue)
         let playlist_focused = app.focus == FocusedPane::Playlist;
         self.queue_pane.render(frame, areas.playlist, playlist_focused, app, &self.theme);
 
-        // Right pane top (Info)
-        info_pane::render_info_pane(frame, areas.info_pane, app, &self.theme, self.info_view, &mut self.album_art_cache);
+        if self.layout_preset.shows_right() {
+            // Right pane top (Info)
+            info_pane::render_info_pane(frame, areas.info_pane, app, &self.theme, self.info_view, &mut self.album_art_cache);
 
-        // Right pane bottom (Lyrics)
-        let lyrics_focused = app.focus == FocusedPane::Lyrics;
-        self.lyrics_pane.render(frame, areas.lyrics, lyrics_focused, app, &self.theme);
+            // Right pane bottom (Lyrics)
+            let lyrics_focused = app.focus == FocusedPane::Lyrics;
+            self.lyrics_pane.render(frame, areas.lyrics, lyrics_focused, app, &self.theme);
+        }
 
         // Progress bar
         progress_bar::render_progress_bar(frame, areas.progress_bar, app, &self.theme);
@@ -199,6 +295,18 @@ impl Ui {
             frame.render_widget(overlay, focused_area);
         }
 
+        if let Some(took) = self.frame_time {
+            let text = format!(" {:.1} ms ", took.as_secs_f64() * 1000.0);
+            let area = frame.area();
+            let width = (text.len() as u16).min(area.width);
+            let corner = ratatui::layout::Rect { x: area.right() - width, width, height: area.height.min(1), ..area };
+            frame.render_widget(ratatui::widgets::Paragraph::new(text).style(Style::default().fg(Color::Yellow)), corner);
+        }
+
+        if let Some((text, _)) = &app.mode_overlay {
+            mode_overlay::render_mode_overlay(frame, frame.area(), text, &self.theme);
+        }
+
         // Modal overlays (rendered last, on top of everything)
         if self.show_search_modal {
             let (rh, ra) = search_modal::render_search_modal(
@@ -209,6 +317,8 @@ impl Ui {
                 self.search_modal_selected,
                 self.search_modal_scroll,
                 self.search_modal_hover_row,
+                self.search_completion.as_ref(),
+                self.search_order,
                 app,
                 &self.theme,
             );
@@ -224,6 +334,22 @@ impl Ui {
             about_modal::render_about_modal(frame, frame.area(), &self.theme);
         }
 
+        if let Some((period, stats)) = &self.stats_modal {
+            stats_modal::render_stats_modal(frame, frame.area(), *period, stats, &self.theme);
+        }
+
+        if let Some(modal) = &self.tag_modal {
+            tag_modal::render_tag_modal(frame, frame.area(), modal, &app.music_dir, &self.theme);
+        }
+
+        if let Some(modal) = &self.session_modal {
+            session_modal::render_session_modal(frame, frame.area(), modal, app, &self.theme);
+        }
+
+        if let Some(modal) = &self.export_modal {
+            export_modal::render_export_modal(frame, frame.area(), modal, &self.theme);
+        }
+
         if self.show_playlist_modal {
             playlist_modal::render_playlist_modal(
                 frame,
@@ -231,74 +357,208 @@ impl Ui {
                 self.playlist_modal_selected,
                 &self.playlist_modal_mode,
                 &self.playlist_modal_input,
+                self.playlist_modal_target.as_ref(),
+                self.playlist_modal_track.as_deref(),
                 app,
                 &self.theme,
             );
         }
     }
 
-    pub fn refresh_dir_browser(&mut self, app: &App) {
-        self.dir_browser_pane.refresh(app);
+    /// Search input changed: restart the debounce timer, or clear results right away for empty input
+    pub fn search_input_changed(&mut self) {
+        if self.search_modal_input.is_empty() {
+            self.cancel_pending_search();
+            self.search_modal_results.clear();
+            self.search_modal_ranked.clear();
+            self.search_modal_selected = 0;
+            self.search_modal_scroll = 0;
+        } else {
+            self.search_modal_edited = Some(Instant::now());
+        }
     }
 
-    pub fn clamp_selections(&mut self, app: &App) {
-        let artists_len = app.library.get_artists().len();
-        if artists_len == 0 {
-            self.artists_pane.selected = 0;
-            self.artists_pane.scroll_offset = 0;
-        } else {
-            self.artists_pane.selected = self.artists_pane.selected.min(artists_len - 1);
-            self.artists_pane.scroll_offset = self.artists_pane.scroll_offset.min(artists_len - 1);
+    /// Close the search modal, remembering the query in the search history
+    pub fn close_search_modal(&mut self) {
+        let query = self.search_modal_input.as_str().trim().to_string();
+        if !query.is_empty() {
+            self.search_history.retain(|q| *q != query);
+            self.search_history.push(query);
+            let excess = self.search_history.len().saturating_sub(SEARCH_HISTORY_LEN);
+            self.search_history.drain(..excess);
         }
+        self.show_search_modal = false;
+        self.search_history_pos = None;
+        self.search_completion = None;
+        self.cancel_pending_search();
+        self.search_modal_input.clear();
+        self.search_modal_results.clear();
+        self.search_modal_ranked.clear();
+        self.search_modal_selected = 0;
+        self.search_modal_scroll = 0;
+    }
 
-        let albums_len = app.library.get_albums().len();
-        if albums_len == 0 {
-            self.albums_pane.selected = 0;
-            self.albums_pane.scroll_offset = 0;
-        } else {
-            self.albums_pane.selected = self.albums_pane.selected.min(albums_len - 1);
-            self.albums_pane.scroll_offset = self.albums_pane.scroll_offset.min(albums_len - 1);
+    /// Step through the search history (older with `back`) into the input
+    pub fn recall_search(&mut self, back: bool) {
+        let len = self.search_history.len();
+        let pos = match (self.search_history_pos, back) {
+            (None, true) if len > 0 => Some(len - 1),
+            (Some(p), true) => Some(p.saturating_sub(1)),
+            (Some(p), false) if p + 1 < len => Some(p + 1),
+            // Past the newest entry: back to an empty input
+            _ => None,
+        };
+        self.search_history_pos = pos;
+        self.search_completion = None;
+        self.search_modal_input.set(pos.map(|p| self.search_history[p].clone()).unwrap_or_default());
+        self.search_input_changed();
+    }
+
+    /// Complete the `field:` filter being typed with library names, or step to the
+    /// next / previous name while completing. False if the input isn't a filter.
+    pub fn complete_search(&mut self, library: &Library, forward: bool) -> bool {
+        if self.search_completion.is_none() {
+            let Some((field, value)) = self.search_modal_input.as_str().split_once(':') else { return false };
+            let field = field.trim().to_lowercase();
+            let value = value.trim_start();
+            // A closed quote means the name is done and the rest narrows it down
+            let typed = match value.strip_prefix('"') {
+                Some(v) if v.contains('"') => return false,
+                Some(v) => v,
+                None => value,
+            };
+            let names = library.field_completions(&field, typed.trim());
+            if names.is_empty() {
+                return false;
+            }
+            let start = if forward { 0 } else { names.len() - 1 };
+            self.search_completion = Some((field, names, start));
+        } else if let Some((_, names, pos)) = self.search_completion.as_mut() {
+            *pos = if forward { (*pos + 1) % names.len() } else { (*pos + names.len() - 1) % names.len() };
         }
+        if let Some((field, names, pos)) = &self.search_completion {
+            self.search_modal_input.set(format!("{}:\"{}\" ", field, names[*pos]));
+            self.search_history_pos = None;
+            self.search_input_changed();
+        }
+        true
+    }
 
-        let genres_len = app.library.get_genres().len();
-        if genres_len == 0 {
-            self.genre_pane.selected = 0;
-            self.genre_pane.scroll_offset = 0;
-        } else {
-            self.genre_pane.selected = self.genre_pane.selected.min(genres_len - 1);
-            self.genre_pane.scroll_offset = self.genre_pane.scroll_offset.min(genres_len - 1);
+    /// Drop the pending debounce and ignore any search still running
+    pub fn cancel_pending_search(&mut self) {
+        self.search_modal_edited = None;
+        self.search_generation += 1;
+    }
+
+    /// Once input has been idle for the debounce period, return the query to run
+    /// and its generation
+    pub fn take_due_search(&mut self) -> Option<(String, u64)> {
+        let edited = self.search_modal_edited?;
+        if edited.elapsed() < SEARCH_DEBOUNCE {
+            return None;
         }
+        self.search_modal_edited = None;
+        self.search_generation += 1;
+        Some((self.search_modal_input.as_str().to_string(), self.search_generation))
+    }
 
-        let formats_len = app.library.get_formats().len();
-        if formats_len == 0 {
-            self.format_pane.selected = 0;
-            self.format_pane.scroll_offset = 0;
-        } else {
-            self.format_pane.selected = self.format_pane.selected.min(formats_len - 1);
-            self.format_pane.scroll_offset = self.format_pane.scroll_offset.min(formats_len - 1);
+    /// With preview mode on: `target` (the track under the cursor) once it has
+    /// stayed selected for the settle delay. Each selection previews once.
+    pub fn take_due_preview(&mut self, target: Option<PathBuf>) -> Option<PathBuf> {
+        if target != self.preview_selected {
+            self.preview_selected = target;
+            self.preview_since = Some(Instant::now());
+            return None;
         }
+        if self.preview_since?.elapsed() < PREVIEW_SETTLE {
+            return None;
+        }
+        self.preview_since = None;
+        self.preview_selected.clone()
+    }
 
-        let playlists_len = app.playlists.len();
-        if playlists_len == 0 {
-            self.playlists_pane.selected = 0;
-            self.playlists_pane.scroll_offset = 0;
-        } else {
-            self.playlists_pane.selected = self.playlists_pane.selected.min(playlists_len - 1);
-            self.playlists_pane.scroll_offset = self.playlists_pane.scroll_offset.min(playlists_len - 1);
+    /// Apply worker results if they belong to the latest search
+    pub fn apply_search_results(&mut self, generation: u64, results: Vec<usize>, library: &Library) -> bool {
+        if !self.show_search_modal || generation != self.search_generation {
+            return false;
         }
+        self.search_modal_ranked = results;
+        self.order_search_results(library);
+        true
+    }
 
-        // Reset library/dir browser to top since track indices changed
-        self.library_pane.selected = 0;
-        self.library_pane.scroll_offset = 0;
-        self.dir_browser_pane.selected = 0;
-        self.dir_browser_pane.scroll_offset = 0;
+    /// Switch to the next result order, re-sorting the current results
+    pub fn cycle_search_order(&mut self, library: &Library) {
+        self.search_order = self.search_order.next();
+        self.order_search_results(library);
+    }
+
+    fn order_search_results(&mut self, library: &Library) {
+        let mut results = self.search_modal_ranked.clone();
+        let key = |s: &str| collate::sort_key(s, &library.sort_articles);
+        let tracks = &library.tracks;
+        // Stable sorts, so ties keep relevance order
+        match self.search_order {
+            SearchOrder::Relevance => {}
+            SearchOrder::Artist => results.sort_by_cached_key(|&i| {
+                let t = &tracks[i];
+                (key(t.display_artist()), key(t.display_album()), t.disc_track())
+            }),
+            SearchOrder::Album => results.sort_by_cached_key(|&i| {
+                let t = &tracks[i];
+                (key(t.display_album()), key(t.album_owner()), t.disc_track())
+            }),
+            SearchOrder::Title => results.sort_by_cached_key(|&i| key(&tracks[i].title)),
+            SearchOrder::Duration => results.sort_by_key(|&i| tracks[i].duration),
+            // Untagged tracks last
+            SearchOrder::Bpm => results.sort_by(|&a, &b| match (tracks[a].bpm, tracks[b].bpm) {
+                (Some(x), Some(y)) => x.total_cmp(&y),
+                (x, y) => x.is_none().cmp(&y.is_none()),
+            }),
+        }
+        self.search_modal_results = results;
+        self.search_modal_selected = 0;
+        self.search_modal_scroll = 0;
+        self.search_modal_hover_row = None;
+    }
+
+    pub fn refresh_dir_browser(&mut self, app: &App) {
+        self.dir_browser_pane.refresh(app);
+    }
+
+    /// Carry pane selections over a library sync: each pane keeps its selected item
+    /// (at the same screen row) if it still exists, otherwise the position is clamped
+    pub fn restore_selections(&mut self, old: &Library, app: &App) {
+        let lib = &app.library;
+        reselect(&mut self.artists_pane.list.selected, &mut self.artists_pane.list.scroll_offset, old.get_artists(), lib.get_artists());
+        reselect(&mut self.albums_pane.list.selected, &mut self.albums_pane.list.scroll_offset, old.get_albums(), lib.get_albums());
+        reselect(&mut self.genre_pane.list.selected, &mut self.genre_pane.list.scroll_offset, old.get_genres(), lib.get_genres());
+        reselect(&mut self.format_pane.list.selected, &mut self.format_pane.list.scroll_offset, old.get_formats(), lib.get_formats());
+        self.playlists_pane.restore_after_sync(app);
+        self.library_pane.restore_after_sync(old, app);
+        self.dir_browser_pane.restore_after_sync(old, app);
 
         // Clamp queue pane scroll
         let queue_len = app.queue.tracks.len();
         if queue_len == 0 {
-            self.queue_pane.scroll_offset = 0;
+            self.queue_pane.list.scroll_offset = 0;
         } else {
-            self.queue_pane.scroll_offset = self.queue_pane.scroll_offset.min(queue_len - 1);
+            self.queue_pane.list.scroll_offset = self.queue_pane.list.scroll_offset.min(queue_len - 1);
+        }
+    }
+}
+
+/// Move `selected` to where `old[selected]` ended up in `new`, keeping its row on screen
+pub(crate) fn reselect<T: PartialEq>(selected: &mut usize, scroll_offset: &mut usize, old: &[T], new: &[T]) {
+    let row = selected.saturating_sub(*scroll_offset);
+    match old.get(*selected).and_then(|item| new.iter().position(|n| n == item)) {
+        Some(pos) => {
+            *selected = pos;
+            *scroll_offset = pos.saturating_sub(row);
+        }
+        None => {
+            *selected = (*selected).min(new.len().saturating_sub(1));
+            *scroll_offset = (*scroll_offset).min(*selected);
         }
     }
 }
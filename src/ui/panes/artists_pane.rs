@@ -1,36 +1,48 @@
 use crossterm::event::{KeyCode, KeyEvent, MouseButton, MouseEvent, MouseEventKind};
 use ratatui::layout::Rect;
-use ratatui::style::{Color, Modifier, Style};
+use ratatui::style::{Modifier, Style};
 use ratatui::text::{Line, Span};
-use ratatui::widgets::{Block, Borders, List, ListItem, Scrollbar, ScrollbarOrientation, ScrollbarState};
+use ratatui::widgets::{Block, Borders, List, ListItem};
 use ratatui::Frame;
 
+use crate::app::state::Tab;
 use crate::app::{App, AppAction};
+use crate::ui::list_filter::{self, ListFilter};
 use crate::ui::pane::Pane;
+use crate::ui::scrollable_list::{ScrollableList, HOVER_BG};
 use crate::ui::theme::Theme;
-
-const HOVER_BG: Color = Color::Indexed(238);
+use crate::ui::widgets::breadcrumb::{back_hint, breadcrumb};
 
 pub struct ArtistsPane {
-    pub selected: usize,
-    pub scroll_offset: usize,
-    pub hover_row: Option<usize>,
+    pub list: ScrollableList,
+    pub filter: ListFilter,
 }
 
 impl ArtistsPane {
     pub fn new() -> Self {
         Self {
-            selected: 0,
-            scroll_offset: 0,
-            hover_row: None,
+            list: ScrollableList::new(),
+            filter: ListFilter::new(),
         }
     }
+
+    /// Indices of the artists the filter leaves shown
+    fn visible(&self, app: &App) -> Vec<usize> {
+        self.filter.matching(app.library.get_artists().iter().map(String::as_str))
+    }
+
+    /// Selected artist and their tracks, for adding to a playlist
+    pub fn selection_tracks(&self, app: &App) -> Option<(String, Vec<usize>)> {
+        let artist = app.library.get_artists().get(self.list.selected)?;
+        Some((artist.clone(), app.library.get_tracks_by_artist(artist)))
+    }
 }
 
 impl Pane for ArtistsPane {
     fn render(&mut self, frame: &mut Frame, area: Rect, focused: bool, app: &App, theme: &Theme) {
         let artists = app.library.get_artists();
-        let count = artists.len();
+        let visible = self.visible(app);
+        let count = visible.len();
         let border_color = if focused {
             theme.border_focused
         } else {
@@ -46,119 +58,110 @@ impl Pane for ArtistsPane {
                 theme.fg
             }));
 
-        let inner = block.inner(area);
-        let inner_height = inner.height as usize;
+        let block = if self.filter.is_active() {
+            let crumbs = [Tab::Artists.title().to_string(), format!("/{}", self.filter.text())];
+            let block = block.title(breadcrumb(&crumbs, area.width, theme));
+            if self.filter.is_editing() {
+                block
+            } else {
+                block.title_bottom(back_hint("Esc: clear filter", theme))
+            }
+        } else {
+            block
+        };
+
+        let (list_area, prompt_area) = self.filter.split(block.inner(area));
+        let inner_height = list_area.height as usize;
 
         // Auto-scroll
         if count > 0 {
-            if self.selected < self.scroll_offset {
-                self.scroll_offset = self.selected;
-            }
-            if inner_height > 0 && self.selected >= self.scroll_offset + inner_height {
-                self.scroll_offset = self.selected - inner_height + 1;
-            }
+            self.list.selected = list_filter::step(&visible, self.list.selected, 0);
+            let pos = visible.iter().position(|&i| i == self.list.selected).unwrap_or(0);
+            self.list.follow(pos, inner_height);
         }
 
-        let has_scrollbar = count > inner_height;
         let highlight = Style::default()
             .bg(theme.highlight_bg)
             .fg(theme.highlight_fg)
             .add_modifier(Modifier::BOLD);
 
-        let items: Vec<ListItem> = artists
+        let playing = app.current_track();
+        let items: Vec<ListItem> = visible
             .iter()
             .enumerate()
-            .skip(self.scroll_offset)
+            .skip(self.list.scroll_offset)
             .take(inner_height)
-            .map(|(i, artist)| {
-                let is_selected = i == self.selected;
-                let is_hovered = self.hover_row == Some(i);
+            .map(|(row, &i)| {
+                let artist = &artists[i];
+                let is_selected = i == self.list.selected;
+                let is_hovered = self.list.is_hovered(row);
+                let is_playing = playing.is_some_and(|t| app.library.is_by_artist(t, artist));
+                let base = if is_playing { theme.current_track_style } else { Style::default().fg(theme.fg) };
                 let style = if is_selected && focused {
                     highlight
                 } else if is_hovered {
-                    Style::default().fg(theme.fg).bg(HOVER_BG)
+                    base.bg(HOVER_BG)
                 } else {
-                    Style::default().fg(theme.fg)
+                    base
                 };
-                ListItem::new(Line::from(Span::styled(format!("  {}", artist), style)))
+                let prefix = if is_playing { "\u{F04B} " } else { "  " }; // nf-fa-play
+                ListItem::new(Line::from(Span::styled(format!("{}{}", prefix, artist), style)))
             })
             .collect();
 
         let list = List::new(items).block(block);
         frame.render_widget(list, area);
-
-        if has_scrollbar {
-            let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight)
-                .begin_symbol(None)
-                .end_symbol(None);
-            let mut scrollbar_state = ScrollbarState::new(count)
-                .position(self.scroll_offset);
-            frame.render_stateful_widget(
-                scrollbar,
-                area.inner(ratatui::layout::Margin { vertical: 1, horizontal: 0 }),
-                &mut scrollbar_state,
-            );
+        if let Some(prompt) = prompt_area {
+            self.filter.render_prompt(frame, prompt);
         }
+
+        self.list.render_scrollbar(frame, area, count, inner_height);
     }
 
     fn handle_key(&mut self, key: KeyEvent, app: &App) -> Option<AppAction> {
+        // Up / Down still move through the list while the filter is typed
+        if self.filter.captures_key(&key) && !(self.filter.is_editing() && matches!(key.code, KeyCode::Up | KeyCode::Down)) {
+            if self.filter.handle_key(&key) {
+                self.list.selected = list_filter::step(&self.visible(app), self.list.selected, 0);
+                self.list.scroll_offset = 0;
+            }
+            return None;
+        }
         let artists = app.library.get_artists();
-        let count = artists.len();
-        if count == 0 {
+        let visible = self.visible(app);
+        if visible.is_empty() {
+            return None;
+        }
+
+        if let Some(delta) = self.list.nav_delta(key.code) {
+            self.list.selected = list_filter::step(&visible, self.list.selected, delta);
             return None;
         }
 
         match key.code {
-            KeyCode::Char('j') | KeyCode::Down => {
-                if self.selected < count - 1 {
-                    self.selected += 1;
-                }
-                None
-            }
-            KeyCode::Char('k') | KeyCode::Up => {
-                if self.selected > 0 {
-                    self.selected -= 1;
-                }
-                None
-            }
             KeyCode::Enter => {
-                if self.selected < count {
-                    let tracks = app.library.get_tracks_by_artist(&artists[self.selected]);
+                if let Some(artist) = artists.get(self.list.selected) {
+                    let tracks = app.library.get_tracks_by_artist(artist);
                     if !tracks.is_empty() {
                         return Some(AppAction::AddToQueue(tracks));
                     }
                 }
                 None
             }
-            KeyCode::Home | KeyCode::Char('g') => {
-                self.selected = 0;
-                self.scroll_offset = 0;
-                None
-            }
-            KeyCode::End | KeyCode::Char('G') => {
-                self.selected = count.saturating_sub(1);
-                None
-            }
             _ => None,
         }
     }
 
     fn handle_mouse(&mut self, event: MouseEvent, area: Rect, app: &App) -> Option<AppAction> {
         let block = Block::default().borders(Borders::ALL);
-        let inner = block.inner(area);
-        let count = app.library.get_artists().len();
+        // The filter prompt row isn't part of the list
+        let (list_area, _) = self.filter.split(block.inner(area));
+        let visible = self.visible(app);
 
         match event.kind {
             MouseEventKind::Down(MouseButton::Left) => {
-                if event.column >= inner.x
-                    && event.column < inner.x + inner.width
-                    && event.row >= inner.y
-                    && event.row < inner.y + inner.height
-                {
-                    let clicked = self.scroll_offset + (event.row - inner.y) as usize;
-                    if clicked < count {
-                        self.selected = clicked;
-                    }
+                if let Some(row) = self.list.row_at(list_area, event.column, event.row, visible.len()) {
+                    self.list.selected = visible[row];
                 }
                 None
             }
@@ -169,17 +172,12 @@ impl Pane for ArtistsPane {
     }
 
     fn handle_scroll(&mut self, up: bool, app: &App) -> Option<AppAction> {
-        let count = app.library.get_artists().len();
-        if count == 0 {
+        let visible = self.visible(app);
+        if visible.is_empty() {
             return None;
         }
-        if up {
-            self.scroll_offset = self.scroll_offset.saturating_sub(3);
-            self.selected = self.selected.saturating_sub(3);
-        } else {
-            self.scroll_offset = (self.scroll_offset + 3).min(count.saturating_sub(1));
-            self.selected = (self.selected + 3).min(count.saturating_sub(1));
-        }
+        let delta = self.list.wheel(up, visible.len());
+        self.list.selected = list_filter::step(&visible, self.list.selected, delta);
         None
     }
 }
@@ -2,22 +2,24 @@ use crossterm::event::{KeyCode, KeyEvent, MouseButton, MouseEvent, MouseEventKin
 use ratatui::layout::Rect;
 use ratatui::style::{Color, Modifier, Style};
 use ratatui::text::{Line, Span};
-use ratatui::widgets::{Block, Borders, List, ListItem, Scrollbar, ScrollbarOrientation, ScrollbarState};
+use ratatui::widgets::{Block, Borders, List, ListItem};
 use ratatui::Frame;
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 
 use crate::app::{App, AppAction};
+use crate::library::Library;
 use crate::ui::pane::Pane;
+use crate::ui::scrollable_list::{ScrollableList, HOVER_BG};
 use crate::ui::theme::Theme;
-
-const HOVER_BG: Color = Color::Indexed(238);
+use crate::ui::widgets::breadcrumb::{back_hint, breadcrumb};
 
 pub struct DirBrowserPane {
     pub current_dir: PathBuf,
     pub entries: Vec<DirEntry>,
-    pub selected: usize,
-    pub scroll_offset: usize,
-    pub hover_row: Option<usize>,
+    pub list: ScrollableList,
+    /// (selected, scroll_offset) last used in each visited directory
+    positions: HashMap<PathBuf, (usize, usize)>,
 }
 
 #[derive(Debug, Clone)]
@@ -32,10 +34,101 @@ impl DirBrowserPane {
         Self {
             current_dir: music_dir,
             entries: Vec::new(),
-            selected: 0,
-            scroll_offset: 0,
-            hover_row: None,
+            list: ScrollableList::new(),
+            positions: HashMap::new(),
+        }
+    }
+
+    /// Enter `dir`, remembering where we were in the current one and
+    /// returning to where we left off in `dir`
+    fn change_dir(&mut self, dir: PathBuf, app: &App) {
+        let from = std::mem::replace(&mut self.current_dir, dir);
+        self.positions.insert(from.clone(), (self.list.selected, self.list.scroll_offset));
+        self.refresh(app);
+        (self.list.selected, self.list.scroll_offset) = match self.positions.get(&self.current_dir) {
+            Some(&pos) => pos,
+            // Going up into a directory we never browsed: select the one we came from
+            None => {
+                let child = from.strip_prefix(&self.current_dir).ok()
+                    .and_then(|rest| rest.components().next())
+                    .map(|c| c.as_os_str().to_string_lossy().into_owned());
+                let sel = self.entries.iter()
+                    .position(|e| matches!(e, DirEntry::Directory(n) if Some(n) == child.as_ref()))
+                    .unwrap_or(0);
+                (sel, 0)
+            }
+        };
+        self.clamp();
+    }
+
+    /// Library index of the selected file, if a track (not a folder) is selected
+    pub fn selected_track(&self) -> Option<usize> {
+        match self.entries.get(self.list.selected)? {
+            DirEntry::Track(idx) => Some(*idx),
+            _ => None,
+        }
+    }
+
+    /// Open the folder containing `path` and select the file in it
+    pub fn reveal(&mut self, path: &Path, app: &App) {
+        let Some(dir) = path.parent() else { return };
+        if dir != self.current_dir {
+            self.change_dir(dir.to_path_buf(), app);
+        }
+        if let Some(pos) = self.entries.iter().position(|e| {
+            matches!(e, DirEntry::Track(idx) if app.library.tracks.get(*idx).is_some_and(|t| t.path == path))
+        }) {
+            self.list.selected = pos;
+        }
+    }
+
+    /// The current directory and the tracks directly in it, for batch tag edits
+    pub fn selection_tracks(&self) -> Option<(String, Vec<usize>)> {
+        let name = self.current_dir.file_name()?.to_string_lossy().into_owned();
+        let tracks = self.entries.iter()
+            .filter_map(|e| match e {
+                DirEntry::Track(idx) => Some(*idx),
+                _ => None,
+            })
+            .collect();
+        Some((name, tracks))
+    }
+
+    fn clamp(&mut self) {
+        let last = self.entries.len().saturating_sub(1);
+        self.list.selected = self.list.selected.min(last);
+        self.list.scroll_offset = self.list.scroll_offset.min(self.list.selected);
+    }
+
+    /// After a library sync: stay in the current directory (or its nearest surviving
+    /// ancestor) and keep the selected entry if it still exists
+    pub fn restore_after_sync(&mut self, old: &Library, app: &App) {
+        let selected = self.entries.get(self.list.selected).cloned();
+        let row = self.list.selected.saturating_sub(self.list.scroll_offset);
+
+        while self.current_dir != app.music_dir && !app.library.has_directory(&self.current_dir) {
+            match self.current_dir.parent() {
+                Some(parent) if parent.starts_with(&app.music_dir) => self.current_dir = parent.to_path_buf(),
+                _ => self.current_dir = app.music_dir.clone(),
+            }
+        }
+        self.refresh(app);
+
+        let found = selected.and_then(|sel| {
+            self.entries.iter().position(|e| match (&sel, e) {
+                (DirEntry::ParentDir, DirEntry::ParentDir) => true,
+                (DirEntry::Directory(a), DirEntry::Directory(b)) => a == b,
+                (DirEntry::Track(a), DirEntry::Track(b)) => {
+                    old.tracks.get(*a).map(|t| &t.path) == app.library.tracks.get(*b).map(|t| &t.path)
+                }
+                _ => false,
+            })
+        });
+        if let Some(pos) = found {
+            self.list.selected = pos;
+            self.list.scroll_offset = pos.saturating_sub(row);
         }
+        self.clamp();
     }
 
     pub fn refresh(&mut self, app: &App) {
@@ -64,7 +157,7 @@ impl Pane for DirBrowserPane {
             theme.border_unfocused
         };
 
-        let block = Block::default()
+        let mut block = Block::default()
             .borders(Borders::ALL)
             .border_style(Style::default().fg(border_color))
             .title_style(Style::default().fg(if focused {
@@ -72,35 +165,41 @@ impl Pane for DirBrowserPane {
             } else {
                 theme.fg
             }));
+        // Inside a subfolder: the path from the music folder down
+        if let Ok(rel) = self.current_dir.strip_prefix(&app.music_dir) {
+            if rel.components().next().is_some() {
+                let crumbs: Vec<String> = std::iter::once("Directories".to_string())
+                    .chain(rel.components().map(|c| c.as_os_str().to_string_lossy().into_owned()))
+                    .collect();
+                block = block
+                    .title(breadcrumb(&crumbs, area.width, theme))
+                    .title_bottom(back_hint("Backspace: up", theme));
+            }
+        }
 
         let inner = block.inner(area);
         let inner_height = inner.height as usize;
 
         // Auto-scroll
         if count > 0 {
-            if self.selected < self.scroll_offset {
-                self.scroll_offset = self.selected;
-            }
-            if inner_height > 0 && self.selected >= self.scroll_offset + inner_height {
-                self.scroll_offset = self.selected - inner_height + 1;
-            }
+            self.list.follow(self.list.selected, inner_height);
         }
 
-        let has_scrollbar = count > inner_height;
         let highlight = Style::default()
             .bg(theme.highlight_bg)
             .fg(theme.highlight_fg)
             .add_modifier(Modifier::BOLD);
 
+        let playing = app.current_track().map(|t| t.path.as_path());
         let items: Vec<ListItem> = self
             .entries
             .iter()
             .enumerate()
-            .skip(self.scroll_offset)
+            .skip(self.list.scroll_offset)
             .take(inner_height)
             .map(|(i, entry)| {
-                let is_selected = i == self.selected;
-                let is_hovered = self.hover_row == Some(i);
+                let is_selected = i == self.list.selected;
+                let is_hovered = self.list.is_hovered(i);
 
                 match entry {
                     DirEntry::ParentDir => {
@@ -118,31 +217,45 @@ impl Pane for DirBrowserPane {
                         }
                     }
                     DirEntry::Directory(name) => {
+                        let is_playing = playing.is_some_and(|p| p.starts_with(self.current_dir.join(name)));
+                        let icon = if is_playing { "  \u{F04B} " } else { "  \u{F07B} " }; // nf-fa-play / nf-fa-folder
                         if is_selected && focused {
                             ListItem::new(Line::from(vec![
-                                Span::styled("  \u{F07B} ", highlight),
+                                Span::styled(icon, highlight),
                                 Span::styled(format!("{}/", name), highlight),
                             ]))
                         } else {
                             let bg = if is_hovered { HOVER_BG } else { Color::Reset };
+                            let (icon_style, name_style) = if is_playing {
+                                (theme.current_track_style, theme.current_track_style)
+                            } else {
+                                (Style::default().fg(Color::Green), Style::default().fg(theme.fg))
+                            };
                             ListItem::new(Line::from(vec![
-                                Span::styled("  \u{F07B} ", Style::default().fg(Color::Green).bg(bg)),
-                                Span::styled(format!("{}/", name), Style::default().fg(theme.fg).bg(bg)),
+                                Span::styled(icon, icon_style.bg(bg)),
+                                Span::styled(format!("{}/", name), name_style.bg(bg)),
                             ]))
                         }
                     }
                     DirEntry::Track(idx) => {
                         let t = &app.library.tracks[*idx];
+                        let is_playing = playing == Some(t.path.as_path());
+                        let icon = if is_playing { "  \u{F04B} " } else { "  \u{F001} " }; // nf-fa-play / nf-fa-music
                         if is_selected && focused {
                             ListItem::new(Line::from(vec![
-                                Span::styled("  \u{F001} ", highlight),
+                                Span::styled(icon, highlight),
                                 Span::styled(&t.title, highlight),
                             ]))
                         } else {
                             let bg = if is_hovered { HOVER_BG } else { Color::Reset };
+                            let (icon_style, name_style) = if is_playing {
+                                (theme.current_track_style, theme.current_track_style)
+                            } else {
+                                (Style::default().fg(Color::Cyan), Style::default().fg(theme.fg))
+                            };
                             ListItem::new(Line::from(vec![
-                                Span::styled("  \u{F001} ", Style::default().fg(Color::Cyan).bg(bg)),
-                                Span::styled(&t.title, Style::default().fg(theme.fg).bg(bg)),
+                                Span::styled(icon, icon_style.bg(bg)),
+                                Span::styled(&t.title, name_style.bg(bg)),
                             ]))
                         }
                     }
@@ -153,18 +266,7 @@ impl Pane for DirBrowserPane {
         let list = List::new(items).block(block);
         frame.render_widget(list, area);
 
-        if has_scrollbar {
-            let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight)
-                .begin_symbol(None)
-                .end_symbol(None);
-            let mut scrollbar_state = ScrollbarState::new(count)
-                .position(self.scroll_offset);
-            frame.render_stateful_widget(
-                scrollbar,
-                area.inner(ratatui::layout::Margin { vertical: 1, horizontal: 0 }),
-                &mut scrollbar_state,
-            );
-        }
+        self.list.render_scrollbar(frame, area, count, inner_height);
     }
 
     fn handle_key(&mut self, key: KeyEvent, app: &App) -> Option<AppAction> {
@@ -173,34 +275,21 @@ impl Pane for DirBrowserPane {
             return None;
         }
 
+        if self.list.navigate(key.code, count) {
+            return None;
+        }
+
         match key.code {
-            KeyCode::Char('j') | KeyCode::Down => {
-                if self.selected < count - 1 {
-                    self.selected += 1;
-                }
-                None
-            }
-            KeyCode::Char('k') | KeyCode::Up => {
-                if self.selected > 0 {
-                    self.selected -= 1;
-                }
-                None
-            }
             KeyCode::Enter => {
-                match &self.entries[self.selected] {
+                match &self.entries[self.list.selected] {
                     DirEntry::ParentDir => {
                         if let Some(parent) = self.current_dir.parent() {
-                            self.current_dir = parent.to_path_buf();
-                            self.selected = 0;
-                            self.scroll_offset = 0;
-                            self.refresh(app);
+                            self.change_dir(parent.to_path_buf(), app);
                         }
                     }
                     DirEntry::Directory(name) => {
-                        self.current_dir = self.current_dir.join(name);
-                        self.selected = 0;
-                        self.scroll_offset = 0;
-                        self.refresh(app);
+                        let dir = self.current_dir.join(name);
+                        self.change_dir(dir, app);
                     }
                     DirEntry::Track(idx) => {
                         return Some(AppAction::AddToQueue(vec![*idx]));
@@ -210,22 +299,10 @@ impl Pane for DirBrowserPane {
             }
             KeyCode::Backspace => {
                 if let Some(parent) = self.current_dir.parent() {
-                    self.current_dir = parent.to_path_buf();
-                    self.selected = 0;
-                    self.scroll_offset = 0;
-                    self.refresh(app);
+                    self.change_dir(parent.to_path_buf(), app);
                 }
                 None
             }
-            KeyCode::Home | KeyCode::Char('g') => {
-                self.selected = 0;
-                self.scroll_offset = 0;
-                None
-            }
-            KeyCode::End | KeyCode::Char('G') => {
-                self.selected = count.saturating_sub(1);
-                None
-            }
             _ => None,
         }
     }
@@ -237,15 +314,8 @@ impl Pane for DirBrowserPane {
 
         match event.kind {
             MouseEventKind::Down(MouseButton::Left) => {
-                if event.column >= inner.x
-                    && event.column < inner.x + inner.width
-                    && event.row >= inner.y
-                    && event.row < inner.y + inner.height
-                {
-                    let clicked = self.scroll_offset + (event.row - inner.y) as usize;
-                    if clicked < count {
-                        self.selected = clicked;
-                    }
+                if let Some(clicked) = self.list.row_at(inner, event.column, event.row, count) {
+                    self.list.selected = clicked;
                 }
                 None
             }
@@ -260,13 +330,8 @@ impl Pane for DirBrowserPane {
         if count == 0 {
             return None;
         }
-        if up {
-            self.scroll_offset = self.scroll_offset.saturating_sub(3);
-            self.selected = self.selected.saturating_sub(3);
-        } else {
-            self.scroll_offset = (self.scroll_offset + 3).min(count.saturating_sub(1));
-            self.selected = (self.selected + 3).min(count.saturating_sub(1));
-        }
+        let delta = self.list.wheel(up, count);
+        self.list.move_by(delta, count);
         None
     }
 }
@@ -2,27 +2,22 @@ use crossterm::event::{KeyCode, KeyEvent, MouseButton, MouseEvent, MouseEventKin
 use ratatui::layout::Rect;
 use ratatui::style::{Color, Modifier, Style};
 use ratatui::text::{Line, Span};
-use ratatui::widgets::{Block, Borders, List, ListItem, Scrollbar, ScrollbarOrientation, ScrollbarState};
+use ratatui::widgets::{Block, Borders, List, ListItem};
 use ratatui::Frame;
 
 use crate::app::{App, AppAction};
 use crate::ui::pane::Pane;
+use crate::ui::scrollable_list::{ScrollableList, HOVER_BG};
 use crate::ui::theme::Theme;
 
-const HOVER_BG: Color = Color::Indexed(238);
-
 pub struct FormatPane {
-    pub selected: usize,
-    pub scroll_offset: usize,
-    pub hover_row: Option<usize>,
+    pub list: ScrollableList,
 }
 
 impl FormatPane {
     pub fn new() -> Self {
         Self {
-            selected: 0,
-            scroll_offset: 0,
-            hover_row: None,
+            list: ScrollableList::new(),
         }
     }
 }
@@ -51,15 +46,9 @@ impl Pane for FormatPane {
 
         // Auto-scroll
         if count > 0 {
-            if self.selected < self.scroll_offset {
-                self.scroll_offset = self.selected;
-            }
-            if inner_height > 0 && self.selected >= self.scroll_offset + inner_height {
-                self.scroll_offset = self.selected - inner_height + 1;
-            }
+            self.list.follow(self.list.selected, inner_height);
         }
 
-        let has_scrollbar = count > inner_height;
         let highlight = Style::default()
             .bg(theme.highlight_bg)
             .fg(theme.highlight_fg)
@@ -68,11 +57,11 @@ impl Pane for FormatPane {
         let items: Vec<ListItem> = formats
             .iter()
             .enumerate()
-            .skip(self.scroll_offset)
+            .skip(self.list.scroll_offset)
             .take(inner_height)
             .map(|(i, fmt)| {
-                let is_selected = i == self.selected;
-                let is_hovered = self.hover_row == Some(i);
+                let is_selected = i == self.list.selected;
+                let is_hovered = self.list.is_hovered(i);
                 let track_count = app.library.get_tracks_by_format(fmt).len();
                 let style = if is_selected && focused {
                     highlight
@@ -98,18 +87,7 @@ impl Pane for FormatPane {
         let list = List::new(items).block(block);
         frame.render_widget(list, area);
 
-        if has_scrollbar {
-            let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight)
-                .begin_symbol(None)
-                .end_symbol(None);
-            let mut scrollbar_state = ScrollbarState::new(count)
-                .position(self.scroll_offset);
-            frame.render_stateful_widget(
-                scrollbar,
-                area.inner(ratatui::layout::Margin { vertical: 1, horizontal: 0 }),
-                &mut scrollbar_state,
-            );
-        }
+        self.list.render_scrollbar(frame, area, count, inner_height);
     }
 
     fn handle_key(&mut self, key: KeyEvent, app: &App) -> Option<AppAction> {
@@ -119,37 +97,20 @@ impl Pane for FormatPane {
             return None;
         }
 
+        if self.list.navigate(key.code, count) {
+            return None;
+        }
+
         match key.code {
-            KeyCode::Char('j') | KeyCode::Down => {
-                if self.selected < count - 1 {
-                    self.selected += 1;
-                }
-                None
-            }
-            KeyCode::Char('k') | KeyCode::Up => {
-                if self.selected > 0 {
-                    self.selected -= 1;
-                }
-                None
-            }
             KeyCode::Enter => {
-                if self.selected < count {
-                    let tracks = app.library.get_tracks_by_format(&formats[self.selected]);
+                if self.list.selected < count {
+                    let tracks = app.library.get_tracks_by_format(&formats[self.list.selected]);
                     if !tracks.is_empty() {
                         return Some(AppAction::AddToQueue(tracks));
                     }
                 }
                 None
             }
-            KeyCode::Home | KeyCode::Char('g') => {
-                self.selected = 0;
-                self.scroll_offset = 0;
-                None
-            }
-            KeyCode::End | KeyCode::Char('G') => {
-                self.selected = count.saturating_sub(1);
-                None
-            }
             _ => None,
         }
     }
@@ -161,15 +122,8 @@ impl Pane for FormatPane {
 
         match event.kind {
             MouseEventKind::Down(MouseButton::Left) => {
-                if event.column >= inner.x
-                    && event.column < inner.x + inner.width
-                    && event.row >= inner.y
-                    && event.row < inner.y + inner.height
-                {
-                    let clicked = self.scroll_offset + (event.row - inner.y) as usize;
-                    if clicked < count {
-                        self.selected = clicked;
-                    }
+                if let Some(clicked) = self.list.row_at(inner, event.column, event.row, count) {
+                    self.list.selected = clicked;
                 }
                 None
             }
@@ -184,13 +138,8 @@ impl Pane for FormatPane {
         if count == 0 {
             return None;
         }
-        if up {
-            self.scroll_offset = self.scroll_offset.saturating_sub(3);
-            self.selected = self.selected.saturating_sub(3);
-        } else {
-            self.scroll_offset = (self.scroll_offset + 3).min(count.saturating_sub(1));
-            self.selected = (self.selected + 3).min(count.saturating_sub(1));
-        }
+        let delta = self.list.wheel(up, count);
+        self.list.move_by(delta, count);
         None
     }
 }
@@ -2,11 +2,17 @@ use crossterm::event::{KeyCode, KeyEvent, MouseButton, MouseEvent, MouseEventKin
 use ratatui::layout::Rect;
 use ratatui::style::{Color, Modifier, Style};
 use ratatui::text::{Line, Span};
-use ratatui::widgets::{Block, Borders, List, ListItem, Scrollbar, ScrollbarOrientation, ScrollbarState};
+use ratatui::widgets::{Block, Borders, List, ListItem};
 use ratatui::Frame;
+use std::collections::HashSet;
+use std::path::PathBuf;
 
 use crate::app::{App, AppAction};
+use crate::app::state::Playlist;
+use crate::library::cleanup::Issue;
+use crate::library::{Album, Folder, Library};
 use crate::ui::pane::Pane;
+use crate::ui::scrollable_list::{ScrollableList, HOVER_BG};
 use crate::ui::theme::Theme;
 
 /// An entry in the flattened library list
@@ -15,82 +21,168 @@ enum LibraryEntry {
     SectionHeader(String),
     Separator,
     AllTracks(usize),
-    PlaylistEntry { idx: usize, name: String, count: usize },
-    FavoriteDir(String),
-    Album { name: String, artist: String },
+    PlaylistEntry { idx: usize, name: String, count_label: String },
+    /// Index into `Library::get_folders`
+    Folder(usize),
+    /// Index into `Library::get_albums`
+    Album(usize),
+    /// Library track index, listed under its expanded album
+    AlbumTrack(usize),
+    /// Tracks with a metadata problem, for tagging cleanup
+    Issue(Issue),
 }
 
-const HOVER_BG: Color = Color::Indexed(238);
-
 /// Library browser for the Queue tab.
-/// Shows 4 sections: Playlist, Directories, Albums.
+/// Shows 4 sections: Playlist, Cleanup (only while some metadata is missing),
+/// Directories, Albums.
 pub struct LibraryPane {
-    pub selected: usize,
-    pub scroll_offset: usize,
-    pub hover_row: Option<usize>,
+    pub list: ScrollableList,
+    /// Folders in the Directories section whose subfolders are shown
+    expanded: HashSet<PathBuf>,
+    /// Albums whose tracks are listed below them
+    open_albums: HashSet<Album>,
 }
 
 impl LibraryPane {
     pub fn new() -> Self {
         Self {
-            selected: 0,
-            scroll_offset: 0,
-            hover_row: None,
+            list: ScrollableList::new(),
+            expanded: HashSet::new(),
+            open_albums: HashSet::new(),
+        }
+    }
+
+    /// Selected folder, album or cleanup view and its tracks, for adding to a
+    /// playlist or editing tags
+    pub fn selection_tracks(&self, app: &App) -> Option<(String, Vec<usize>)> {
+        let entries = self.build_entries(&app.library, &app.playlists);
+        match entries.get(self.list.selected)? {
+            LibraryEntry::Folder(folder_idx) => {
+                let folder = &app.library.get_folders()[*folder_idx];
+                Some((folder.label.clone(), app.library.get_tracks_under(&folder.path)))
+            }
+            LibraryEntry::Album(album_idx) => {
+                let album = &app.library.get_albums()[*album_idx];
+                Some((album.title(), app.library.get_tracks_by_album(album)))
+            }
+            LibraryEntry::AlbumTrack(i) => Some((app.library.tracks.get(*i)?.title.clone(), vec![*i])),
+            LibraryEntry::Issue(issue) => Some((issue.label().to_string(), app.library.tracks_with(*issue).to_vec())),
+            _ => None,
+        }
+    }
+
+    /// Library index of the selected track row under an expanded album
+    pub fn selected_track(&self, app: &App) -> Option<usize> {
+        match self.build_entries(&app.library, &app.playlists).get(self.list.selected)? {
+            LibraryEntry::AlbumTrack(i) => Some(*i),
+            _ => None,
         }
     }
 
-    fn build_entries(app: &App) -> Vec<LibraryEntry> {
+    fn build_entries(&self, library: &Library, playlists: &[Playlist]) -> Vec<LibraryEntry> {
         let mut entries = Vec::new();
 
         // --- Playlist ---
         entries.push(LibraryEntry::SectionHeader("\u{F054} Playlist".into()));
-        entries.push(LibraryEntry::AllTracks(app.library.tracks.len()));
-        for (idx, pl) in app.playlists.iter().enumerate() {
+        entries.push(LibraryEntry::AllTracks(library.tracks.len()));
+        for (idx, pl) in playlists.iter().enumerate() {
             entries.push(LibraryEntry::PlaylistEntry {
                 idx,
                 name: pl.name.clone(),
-                count: pl.tracks.len(),
+                count_label: pl.count_label(library),
             });
         }
 
         entries.push(LibraryEntry::Separator);
 
-        // --- Directories ---
-        let mut dirs = std::collections::BTreeSet::new();
-        for t in &app.library.tracks {
-            if let Some(parent) = t.path.parent() {
-                if let Some(name) = parent.file_name().and_then(|n| n.to_str()) {
-                    dirs.insert(name.to_string());
-                }
-            }
+        // --- Cleanup ---
+        let issues: Vec<Issue> = Issue::ALL.into_iter()
+            .filter(|&issue| !library.tracks_with(issue).is_empty())
+            .collect();
+        if !issues.is_empty() {
+            entries.push(LibraryEntry::SectionHeader("\u{F054} Cleanup".into()));
+            entries.extend(issues.into_iter().map(LibraryEntry::Issue));
+            entries.push(LibraryEntry::Separator);
         }
+
+        // --- Directories ---
+        let folders = library.get_folders();
         entries.push(LibraryEntry::SectionHeader(format!(
             "\u{F054} Directories ({})",
-            dirs.len()
+            folders.iter().filter(|f| f.depth == 0).count()
         )));
-        for d in &dirs {
-            entries.push(LibraryEntry::FavoriteDir(d.clone()));
+        // Subfolders only show below expanded parents
+        let mut hidden_below: Option<usize> = None;
+        for (i, folder) in folders.iter().enumerate() {
+            if hidden_below.is_some_and(|d| folder.depth > d) {
+                continue;
+            }
+            hidden_below = (folder.has_children && !self.expanded.contains(&folder.path)).then_some(folder.depth);
+            entries.push(LibraryEntry::Folder(i));
         }
 
         entries.push(LibraryEntry::Separator);
 
         // --- Albums ---
-        let albums = app.library.get_albums();
+        let albums = library.get_albums();
         entries.push(LibraryEntry::SectionHeader(format!(
             "\u{F054} Albums ({})",
             albums.len()
         )));
-        for (album, artist) in albums {
-            entries.push(LibraryEntry::Album { name: album, artist });
+        for (i, album) in albums.iter().enumerate() {
+            entries.push(LibraryEntry::Album(i));
+            if self.open_albums.contains(album) {
+                entries.extend(library.get_tracks_by_album(album).into_iter().map(LibraryEntry::AlbumTrack));
+            }
         }
 
         entries
     }
+
+    fn toggle_folder(&mut self, folder: &Folder) {
+        if folder.has_children && !self.expanded.remove(&folder.path) {
+            self.expanded.insert(folder.path.clone());
+        }
+    }
+
+    fn toggle_album(&mut self, album: &Album) {
+        if !self.open_albums.remove(album) {
+            self.open_albums.insert(album.clone());
+        }
+    }
+
+    /// Identity of an entry that survives a rescan (indices into the cached lists don't)
+    fn entry_key(entry: &LibraryEntry, library: &Library) -> String {
+        match entry {
+            LibraryEntry::SectionHeader(s) => format!("header:{}", s.split(" (").next().unwrap_or(s)),
+            LibraryEntry::Separator => "separator".to_string(),
+            LibraryEntry::AllTracks(_) => "all".to_string(),
+            LibraryEntry::PlaylistEntry { idx, .. } => format!("playlist:{}", idx),
+            LibraryEntry::Folder(i) => format!("dir:{}", library.get_folders()[*i].path.display()),
+            LibraryEntry::Album(i) => {
+                let album = &library.get_albums()[*i];
+                format!("album:{}\0{}\0{}", album.name, album.artist, album.edition.as_deref().unwrap_or_default())
+            }
+            LibraryEntry::AlbumTrack(i) => format!("track:{}", library.tracks[*i].path.display()),
+            LibraryEntry::Issue(issue) => format!("issue:{:?}", issue),
+        }
+    }
+
+    /// Keep the selected entry after a library sync if it still exists
+    pub fn restore_after_sync(&mut self, old: &Library, app: &App) {
+        let old_keys: Vec<String> = self.build_entries(old, &app.playlists).iter()
+            .map(|e| Self::entry_key(e, old))
+            .collect();
+        let new_keys: Vec<String> = self.build_entries(&app.library, &app.playlists).iter()
+            .map(|e| Self::entry_key(e, &app.library))
+            .collect();
+        crate::ui::reselect(&mut self.list.selected, &mut self.list.scroll_offset, &old_keys, &new_keys);
+    }
 }
 
 impl Pane for LibraryPane {
     fn render(&mut self, frame: &mut Frame, area: Rect, focused: bool, app: &App, theme: &Theme) {
-        let entries = Self::build_entries(app);
+        let entries = self.build_entries(&app.library, &app.playlists);
         let count = entries.len();
         let border_color = if focused {
             theme.border_focused
@@ -112,24 +204,18 @@ impl Pane for LibraryPane {
 
         // Auto-scroll
         if count > 0 {
-            if self.selected < self.scroll_offset {
-                self.scroll_offset = self.selected;
-            }
-            if inner_height > 0 && self.selected >= self.scroll_offset + inner_height {
-                self.scroll_offset = self.selected - inner_height + 1;
-            }
+            self.list.follow(self.list.selected, inner_height);
         }
 
-        let has_scrollbar = count > inner_height;
 
         let items: Vec<ListItem> = entries
             .iter()
             .enumerate()
-            .skip(self.scroll_offset)
+            .skip(self.list.scroll_offset)
             .take(inner_height)
             .map(|(i, entry)| {
-                let is_selected = i == self.selected;
-                let is_hovered = self.hover_row == Some(i);
+                let is_selected = i == self.list.selected;
+                let is_hovered = self.list.is_hovered(i);
                 let highlight = Style::default()
                     .bg(theme.highlight_bg)
                     .fg(theme.highlight_fg)
@@ -156,11 +242,24 @@ impl Pane for LibraryPane {
                         ListItem::new(Line::from(""))
                     }
                     LibraryEntry::AllTracks(track_count) => {
+                        // Duplicates skipped by the scanner (symlinks / hard links)
+                        let stats = &app.library.scan_stats;
+                        let skipped = stats.duplicates + stats.loops;
+                        let mut skipped_text = if skipped > 0 {
+                            format!(" \u{00B7} {} skipped", skipped)
+                        } else {
+                            String::new()
+                        };
+                        // Directories/files that failed or timed out (network shares)
+                        if !stats.unreachable.is_empty() {
+                            skipped_text.push_str(&format!(" \u{00B7} {} unreachable", stats.unreachable.len()));
+                        }
                         if is_selected && focused {
                             ListItem::new(Line::from(vec![
                                 Span::styled("  \u{F001} ", highlight),
                                 Span::styled("All Tracks", highlight),
                                 Span::styled(format!(" ({})", track_count), highlight),
+                                Span::styled(skipped_text, highlight),
                             ]))
                         } else {
                             ListItem::new(Line::from(vec![
@@ -170,56 +269,120 @@ impl Pane for LibraryPane {
                                     format!(" ({})", track_count),
                                     Style::default().fg(Color::DarkGray).bg(hover_bg),
                                 ),
+                                Span::styled(
+                                    skipped_text,
+                                    Style::default().fg(Color::Rgb(255, 200, 80)).bg(hover_bg),
+                                ),
                             ]))
                         }
                     }
-                    LibraryEntry::PlaylistEntry { name, count, .. } => {
+                    LibraryEntry::PlaylistEntry { name, count_label, .. } => {
                         let icon = "\u{F005} "; // ★
                         if is_selected && focused {
                             ListItem::new(Line::from(vec![
                                 Span::styled(format!("  {}", icon), highlight),
                                 Span::styled(name.as_str(), highlight),
-                                Span::styled(format!(" ({})", count), highlight),
+                                Span::styled(count_label.as_str(), highlight),
                             ]))
                         } else {
                             ListItem::new(Line::from(vec![
                                 Span::styled(format!("  {}", icon), Style::default().fg(Color::Yellow).bg(hover_bg)),
                                 Span::styled(name.as_str(), Style::default().fg(theme.fg).bg(hover_bg)),
                                 Span::styled(
-                                    format!(" ({})", count),
+                                    count_label.as_str(),
                                     Style::default().fg(Color::DarkGray).bg(hover_bg),
                                 ),
                             ]))
                         }
                     }
-                    LibraryEntry::FavoriteDir(name) => {
+                    LibraryEntry::Folder(folder_idx) => {
+                        let folder = &app.library.get_folders()[*folder_idx];
+                        let expanded = self.expanded.contains(&folder.path);
+                        let arrow = match (folder.has_children, expanded) {
+                            (false, _) => "  ",
+                            (true, false) => "\u{F0DA} ", // nf-fa-caret_right
+                            (true, true) => "\u{F0D7} ",  // nf-fa-caret_down
+                        };
+                        let icon = if expanded { "\u{F07C} " } else { "\u{F07B} " }; // nf-fa-folder_open / folder
+                        let indent = "  ".repeat(folder.depth + 1);
                         if is_selected && focused {
                             ListItem::new(Line::from(vec![
-                                Span::styled("  \u{F07B} ", highlight),
-                                Span::styled(format!("{}/", name), highlight),
+                                Span::styled(format!("{}{}{}", indent, arrow, icon), highlight),
+                                Span::styled(format!("{}/", folder.label), highlight),
+                                Span::styled(format!(" ({})", folder.track_count), highlight),
                             ]))
                         } else {
                             ListItem::new(Line::from(vec![
-                                Span::styled("  \u{F07B} ", Style::default().fg(Color::Green).bg(hover_bg)),
-                                Span::styled(format!("{}/", name), Style::default().fg(theme.fg).bg(hover_bg)),
+                                Span::styled(indent, Style::default().bg(hover_bg)),
+                                Span::styled(arrow, Style::default().fg(Color::DarkGray).bg(hover_bg)),
+                                Span::styled(icon, Style::default().fg(Color::Green).bg(hover_bg)),
+                                Span::styled(format!("{}/", folder.label), Style::default().fg(theme.fg).bg(hover_bg)),
+                                Span::styled(
+                                    format!(" ({})", folder.track_count),
+                                    Style::default().fg(Color::DarkGray).bg(hover_bg),
+                                ),
                             ]))
                         }
                     }
-                    LibraryEntry::Album { name, artist } => {
-                        let album_display = if name.is_empty() {
-                            "Unknown Album"
+                    LibraryEntry::AlbumTrack(track_idx) => {
+                        let track = &app.library.tracks[*track_idx];
+                        let number = format!("      {:>4}  ", track.format_number());
+                        // Compilations name each track's own artist
+                        let artist = if track.compilation {
+                            format!("  {}", track.display_artist())
                         } else {
-                            name.as_str()
+                            String::new()
                         };
-                        let artist_display = if artist.is_empty() {
-                            ""
+                        let duration = format!("  {}", track.format_duration());
+                        if is_selected && focused {
+                            ListItem::new(Line::from(vec![
+                                Span::styled(number, highlight),
+                                Span::styled(track.title.as_str(), highlight),
+                                Span::styled(artist, highlight),
+                                Span::styled(duration, highlight),
+                            ]))
+                        } else {
+                            ListItem::new(Line::from(vec![
+                                Span::styled(number, Style::default().fg(Color::DarkGray).bg(hover_bg)),
+                                Span::styled(track.title.as_str(), Style::default().fg(theme.fg).bg(hover_bg)),
+                                Span::styled(artist, Style::default().fg(Color::Gray).bg(hover_bg)),
+                                Span::styled(duration, Style::default().fg(Color::DarkGray).bg(hover_bg)),
+                            ]))
+                        }
+                    }
+                    LibraryEntry::Issue(issue) => {
+                        let count = format!(" ({})", app.library.tracks_with(*issue).len());
+                        if is_selected && focused {
+                            ListItem::new(Line::from(vec![
+                                Span::styled("  \u{F071} ", highlight),
+                                Span::styled(issue.label(), highlight),
+                                Span::styled(count, highlight),
+                            ]))
+                        } else {
+                            ListItem::new(Line::from(vec![
+                                Span::styled("  \u{F071} ", Style::default().fg(Color::Rgb(255, 200, 80)).bg(hover_bg)),
+                                Span::styled(issue.label(), Style::default().fg(theme.fg).bg(hover_bg)),
+                                Span::styled(count, Style::default().fg(Color::DarkGray).bg(hover_bg)),
+                            ]))
+                        }
+                    }
+                    LibraryEntry::Album(album_idx) => {
+                        let album = &app.library.get_albums()[*album_idx];
+                        let album_display = if album.name.is_empty() {
+                            "Unknown Album".to_string()
+                        } else {
+                            album.title()
+                        };
+                        let artist_display = album.artist.as_str();
+                        let arrow = if self.open_albums.contains(album) {
+                            "\u{F0D7} " // nf-fa-caret_down
                         } else {
-                            artist.as_str()
+                            "\u{F0DA} " // nf-fa-caret_right
                         };
 
                         if is_selected && focused {
                             let mut spans = vec![
-                                Span::styled("  \u{F0025} ", highlight),
+                                Span::styled(format!("  {}\u{F0025} ", arrow), highlight),
                                 Span::styled(album_display, highlight),
                             ];
                             if !artist_display.is_empty() {
@@ -231,8 +394,10 @@ impl Pane for LibraryPane {
                             ListItem::new(Line::from(spans))
                         } else {
                             let mut spans = vec![
+                                Span::styled("  ", Style::default().bg(hover_bg)),
+                                Span::styled(arrow, Style::default().fg(Color::DarkGray).bg(hover_bg)),
                                 Span::styled(
-                                    "  \u{F0025} ",
+                                    "\u{F0025} ",
                                     Style::default().fg(Color::Magenta).bg(hover_bg),
                                 ),
                                 Span::styled(album_display, Style::default().fg(theme.fg).bg(hover_bg)),
@@ -253,63 +418,36 @@ impl Pane for LibraryPane {
         let list = List::new(items).block(block);
         frame.render_widget(list, area);
 
-        // Scrollbar
-        if has_scrollbar {
-            let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight)
-                .begin_symbol(None)
-                .end_symbol(None);
-            let mut scrollbar_state = ScrollbarState::new(count)
-                .position(self.scroll_offset);
-            frame.render_stateful_widget(
-                scrollbar,
-                area.inner(ratatui::layout::Margin { vertical: 1, horizontal: 0 }),
-                &mut scrollbar_state,
-            );
-        }
+        self.list.render_scrollbar(frame, area, count, inner_height);
     }
 
     fn handle_key(&mut self, key: KeyEvent, app: &App) -> Option<AppAction> {
-        let entries = Self::build_entries(app);
+        let entries = self.build_entries(&app.library, &app.playlists);
         let count = entries.len();
         if count == 0 {
             return None;
         }
 
-        match key.code {
-            KeyCode::Char('j') | KeyCode::Down => {
-                if self.selected < count - 1 {
-                    self.selected += 1;
-                    // Skip separators when navigating
-                    if matches!(entries.get(self.selected), Some(LibraryEntry::Separator))
-                        && self.selected < count - 1
-                    {
-                        self.selected += 1;
-                    }
-                }
-                None
-            }
-            KeyCode::Char('k') | KeyCode::Up => {
-                if self.selected > 0 {
-                    self.selected -= 1;
-                    // Skip separators when navigating
-                    if matches!(entries.get(self.selected), Some(LibraryEntry::Separator))
-                        && self.selected > 0
-                    {
-                        self.selected -= 1;
-                    }
-                }
-                None
+        if let Some(delta) = self.list.nav_delta(key.code) {
+            self.list.move_by(delta, count);
+            // Skip separators when navigating
+            if matches!(entries.get(self.list.selected), Some(LibraryEntry::Separator)) {
+                self.list.move_by(delta.signum(), count);
             }
+            return None;
+        }
+
+        match key.code {
             KeyCode::Enter => {
-                if self.selected >= count {
+                if self.list.selected >= count {
                     return None;
                 }
-                match &entries[self.selected] {
+                match &entries[self.list.selected] {
                     LibraryEntry::SectionHeader(_) | LibraryEntry::Separator => None,
                     LibraryEntry::PlaylistEntry { idx, .. } => {
                         if let Some(pl) = app.playlists.get(*idx) {
                             if !pl.tracks.is_empty() {
-                                return Some(AppAction::AddToQueue(pl.tracks.clone()));
+                                return Some(AppAction::QueuePlaylist(*idx));
                             }
                         }
                         None
@@ -322,44 +460,54 @@ impl Pane for LibraryPane {
                             None
                         }
                     }
-                    LibraryEntry::FavoriteDir(dir_name) => {
-                        let indices: Vec<usize> = app
-                            .library
-                            .tracks
-                            .iter()
-                            .enumerate()
-                            .filter(|(_, t)| {
-                                t.path
-                                    .parent()
-                                    .and_then(|p| p.file_name())
-                                    .and_then(|n| n.to_str())
-                                    == Some(dir_name.as_str())
-                            })
-                            .map(|(i, _)| i)
-                            .collect();
+                    LibraryEntry::Folder(folder_idx) => {
+                        // The whole subtree, nested folders included
+                        let folder = &app.library.get_folders()[*folder_idx];
+                        let indices = app.library.get_tracks_under(&folder.path);
                         if !indices.is_empty() {
                             Some(AppAction::AddToQueue(indices))
                         } else {
                             None
                         }
                     }
-                    LibraryEntry::Album { name, .. } => {
-                        let tracks = app.library.get_tracks_by_album(name);
+                    LibraryEntry::Album(album_idx) => {
+                        let album = &app.library.get_albums()[*album_idx];
+                        let tracks = app.library.get_tracks_by_album(album);
                         if !tracks.is_empty() {
                             Some(AppAction::AddToQueue(tracks))
                         } else {
                             None
                         }
                     }
+                    LibraryEntry::AlbumTrack(track_idx) => Some(AppAction::AddToQueue(vec![*track_idx])),
+                    LibraryEntry::Issue(issue) => {
+                        let tracks = app.library.tracks_with(*issue);
+                        (!tracks.is_empty()).then(|| AppAction::AddToQueue(tracks.to_vec()))
+                    }
                 }
             }
-            KeyCode::Home | KeyCode::Char('g') => {
-                self.selected = 0;
-                self.scroll_offset = 0;
-                None
-            }
-            KeyCode::End | KeyCode::Char('G') => {
-                self.selected = count.saturating_sub(1);
+            KeyCode::Char('o') => {
+                match entries.get(self.list.selected) {
+                    Some(LibraryEntry::Folder(folder_idx)) => {
+                        self.toggle_folder(&app.library.get_folders()[*folder_idx]);
+                    }
+                    Some(LibraryEntry::Album(album_idx)) => {
+                        self.toggle_album(&app.library.get_albums()[*album_idx]);
+                    }
+                    // Fold the album the track is in, leaving its row selected
+                    Some(LibraryEntry::AlbumTrack(_)) => {
+                        let header = entries[..self.list.selected].iter().enumerate().rev()
+                            .find_map(|(i, e)| match e {
+                                LibraryEntry::Album(album_idx) => Some((i, *album_idx)),
+                                _ => None,
+                            });
+                        if let Some((row, album_idx)) = header {
+                            self.toggle_album(&app.library.get_albums()[album_idx]);
+                            self.list.selected = row;
+                        }
+                    }
+                    _ => {}
+                }
                 None
             }
             _ => None,
@@ -369,18 +517,26 @@ impl Pane for LibraryPane {
     fn handle_mouse(&mut self, event: MouseEvent, area: Rect, app: &App) -> Option<AppAction> {
         let block = Block::default().borders(Borders::ALL);
         let inner = block.inner(area);
-        let count = Self::build_entries(app).len();
+        let entries = self.build_entries(&app.library, &app.playlists);
+        let count = entries.len();
 
         match event.kind {
             MouseEventKind::Down(MouseButton::Left) => {
-                if event.column >= inner.x
-                    && event.column < inner.x + inner.width
-                    && event.row >= inner.y
-                    && event.row < inner.y + inner.height
-                {
-                    let clicked = self.scroll_offset + (event.row - inner.y) as usize;
-                    if clicked < count {
-                        self.selected = clicked;
+                if let Some(clicked) = self.list.row_at(inner, event.column, event.row, count) {
+                    self.list.selected = clicked;
+                    // Clicking a folder's or album's arrow expands/collapses it
+                    match &entries[clicked] {
+                        LibraryEntry::Folder(folder_idx) => {
+                            let folder = &app.library.get_folders()[*folder_idx];
+                            let arrow_x = inner.x + 2 * (folder.depth as u16 + 1);
+                            if (arrow_x..arrow_x + 2).contains(&event.column) {
+                                self.toggle_folder(folder);
+                            }
+                        }
+                        LibraryEntry::Album(album_idx) if (inner.x + 2..inner.x + 4).contains(&event.column) => {
+                            self.toggle_album(&app.library.get_albums()[*album_idx]);
+                        }
+                        _ => {}
                     }
                 }
                 None
@@ -392,17 +548,12 @@ impl Pane for LibraryPane {
     }
 
     fn handle_scroll(&mut self, up: bool, app: &App) -> Option<AppAction> {
-        let count = Self::build_entries(app).len();
+        let count = self.build_entries(&app.library, &app.playlists).len();
         if count == 0 {
             return None;
         }
-        if up {
-            self.scroll_offset = self.scroll_offset.saturating_sub(3);
-            self.selected = self.selected.saturating_sub(3);
-        } else {
-            self.scroll_offset = (self.scroll_offset + 3).min(count.saturating_sub(1));
-            self.selected = (self.selected + 3).min(count.saturating_sub(1));
-        }
+        let delta = self.list.wheel(up, count);
+        self.list.move_by(delta, count);
         None
     }
 }
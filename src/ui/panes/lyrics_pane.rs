@@ -1,44 +1,323 @@
 use crossterm::event::{KeyCode, KeyEvent, MouseEvent, MouseEventKind};
-use ratatui::layout::Rect;
-use ratatui::style::Style;
-use ratatui::widgets::{Block, Borders};
+use ratatui::layout::{Alignment, Constraint, Direction, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Paragraph, Wrap};
 use ratatui::Frame;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use unicode_width::UnicodeWidthStr;
 
 use crate::app::{App, AppAction};
+use crate::library::lyrics::{self, LyricLine, Variant};
 use crate::ui::pane::Pane;
+use crate::ui::text_input::TextInput;
 use crate::ui::theme::Theme;
 use crate::ui::widgets::info_pane;
 
+const MATCH_BG: Color = Color::Rgb(90, 80, 20);
+const CURRENT_MATCH_BG: Color = Color::Rgb(255, 200, 80);
+
 pub struct LyricsPane {
     pub scroll_offset: u16,
+    /// Track the lyrics were loaded for, and its lines (None: no lyrics, show track info)
+    loaded: Option<(PathBuf, Option<Vec<LyricLine>>)>,
+    /// The loaded lines as displayed: the chosen variant, blank lines compressed
+    shown: Option<Vec<LyricLine>>,
+    /// Per-track choice of original / romanized / translated lines (persisted)
+    pub variants: HashMap<PathBuf, Variant>,
+    /// Search being typed after `/`
+    search_input: Option<TextInput>,
+    /// Last confirmed search (lowercase) and the lines containing it
+    query: String,
+    matches: Vec<usize>,
+    current_match: usize,
+    /// Text width at the last render, to turn line numbers into wrapped rows
+    width: u16,
 }
 
 impl LyricsPane {
     pub fn new() -> Self {
-        Self { scroll_offset: 0 }
+        Self {
+            scroll_offset: 0,
+            loaded: None,
+            shown: None,
+            variants: HashMap::new(),
+            search_input: None,
+            query: String::new(),
+            matches: Vec::new(),
+            current_match: 0,
+            width: 0,
+        }
+    }
+
+    /// Keys the pane takes before the global bindings: everything while a search
+    /// is typed, and n / N / Esc while one is active
+    pub fn captures_key(&self, key: &KeyEvent) -> bool {
+        self.search_input.is_some()
+            || (self.lines().is_some() && key.code == KeyCode::Char('/'))
+            || (!self.query.is_empty() && matches!(key.code, KeyCode::Char('n' | 'N') | KeyCode::Esc))
+    }
+
+    /// Pasted text goes into the search being typed
+    pub fn paste(&mut self, text: &str) {
+        if let Some(input) = self.search_input.as_mut() {
+            input.insert_str(text);
+        }
+    }
+
+    fn lines(&self) -> Option<&[LyricLine]> {
+        self.shown.as_deref()
+    }
+
+    fn variant(&self) -> Variant {
+        self.loaded.as_ref()
+            .and_then(|(p, _)| self.variants.get(p).copied())
+            .unwrap_or(Variant::All)
+    }
+
+    /// Rebuild the displayed lines from the loaded ones
+    fn refresh_shown(&mut self, compress_blank: bool) {
+        let variant = self.variant();
+        self.shown = self.loaded.as_ref()
+            .and_then(|(_, lines)| lines.as_deref())
+            .map(|lines| {
+                let mut shown = lyrics::select(lines, variant);
+                if compress_blank {
+                    lyrics::compress_blank(&mut shown);
+                }
+                shown
+            });
+    }
+
+    /// Step to the track's next lyrics variant and remember it
+    fn cycle_variant(&mut self, app: &App) {
+        let Some((path, Some(lines))) = &self.loaded else { return };
+        let available = lyrics::variants(lines);
+        if available.len() < 2 {
+            return;
+        }
+        let pos = available.iter().position(|&v| v == self.variant()).unwrap_or(0);
+        let next = available[(pos + 1) % available.len()];
+        if next == Variant::All {
+            self.variants.remove(path);
+        } else {
+            self.variants.insert(path.clone(), next);
+        }
+        self.refresh_shown(app.config.lyrics_compress_blank);
+        if !self.query.is_empty() {
+            self.run_search(self.query.clone());
+        }
+    }
+
+    /// Drop the loaded lyrics so they're read from disk again on the next frame
+    pub fn forget(&mut self) {
+        self.loaded = None;
+        self.shown = None;
+    }
+
+    /// Load lyrics when the playing track changed; a new track starts at the top
+    fn sync(&mut self, app: &App) {
+        let path = app.current_track().map(|t| t.path.clone());
+        if self.loaded.as_ref().map(|(p, _)| p) == path.as_ref() {
+            return;
+        }
+        self.loaded = app.current_track().map(|t| (t.path.clone(), lyrics::load(t)));
+        self.refresh_shown(app.config.lyrics_compress_blank);
+        self.scroll_offset = 0;
+        self.search_input = None;
+        self.query.clear();
+        self.matches.clear();
+        self.current_match = 0;
+    }
+
+    fn run_search(&mut self, query: String) {
+        self.query = query.to_lowercase();
+        self.matches = match self.lines() {
+            Some(lines) if !self.query.is_empty() => lines.iter()
+                .enumerate()
+                .filter(|(_, l)| !find_matches(&l.text, &self.query).is_empty())
+                .map(|(i, _)| i)
+                .collect(),
+            _ => Vec::new(),
+        };
+        // Start from the first match below the current view
+        let top = self.scroll_offset;
+        self.current_match = self.matches.iter()
+            .position(|&i| self.row_of(i) >= top)
+            .unwrap_or(0);
+        self.show_current_match();
+    }
+
+    /// Wrapped row the line starts on
+    fn row_of(&self, line: usize) -> u16 {
+        let width = self.width.max(1) as usize;
+        self.lines()
+            .map(|lines| {
+                lines.iter()
+                    .take(line)
+                    .map(|l| l.text.width().div_ceil(width).max(1))
+                    .sum::<usize>()
+            })
+            .unwrap_or(0) as u16
+    }
+
+    fn show_current_match(&mut self) {
+        if let Some(&line) = self.matches.get(self.current_match) {
+            // A couple of lines of context above
+            self.scroll_offset = self.row_of(line).saturating_sub(2);
+        }
+    }
+
+    fn render_lyrics(&self, frame: &mut Frame, area: Rect, lines: &[LyricLine], centered: bool, theme: &Theme) {
+        let current_line = self.matches.get(self.current_match).copied();
+        let text: Vec<Line> = lines.iter()
+            .enumerate()
+            .map(|(i, l)| {
+                let normal = Style::default().fg(theme.fg);
+                let ranges = if self.query.is_empty() { Vec::new() } else { find_matches(&l.text, &self.query) };
+                if ranges.is_empty() {
+                    return Line::from(Span::styled(l.text.as_str(), normal));
+                }
+                let hit = if current_line == Some(i) {
+                    Style::default().fg(Color::Black).bg(CURRENT_MATCH_BG).add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default().fg(Color::White).bg(MATCH_BG)
+                };
+                let mut spans = Vec::new();
+                let mut pos = 0;
+                for (start, end) in ranges {
+                    spans.push(Span::styled(&l.text[pos..start], normal));
+                    spans.push(Span::styled(&l.text[start..end], hit));
+                    pos = end;
+                }
+                spans.push(Span::styled(&l.text[pos..], normal));
+                Line::from(spans)
+            })
+            .collect();
+        let alignment = if centered { Alignment::Center } else { Alignment::Left };
+        let para = Paragraph::new(text)
+            .alignment(alignment)
+            .wrap(Wrap { trim: false })
+            .scroll((self.scroll_offset, 0));
+        frame.render_widget(para, area);
     }
 }
 
 impl Pane for LyricsPane {
     fn render(&mut self, frame: &mut Frame, area: Rect, focused: bool, app: &App, theme: &Theme) {
+        self.sync(app);
         let border_color = if focused {
             theme.border_focused
         } else {
             theme.border_unfocused
         };
 
-        let block = Block::default()
+        let mut block = Block::default()
             .borders(Borders::ALL)
             .border_style(Style::default().fg(border_color));
+        if self.lines().is_some() {
+            block = match self.variant() {
+                Variant::All => block.title(" Lyrics "),
+                v => block.title(format!(" Lyrics \u{00b7} {} ", v.as_str())),
+            };
+            if !self.query.is_empty() {
+                let count = if self.matches.is_empty() {
+                    "no matches".to_string()
+                } else {
+                    format!("{}/{}", self.current_match + 1, self.matches.len())
+                };
+                block = block.title(
+                    Line::from(Span::styled(
+                        format!(" /{} {} ", self.query, count),
+                        Style::default().fg(CURRENT_MATCH_BG),
+                    ))
+                    .right_aligned(),
+                );
+            }
+        }
 
         let inner = block.inner(area);
         frame.render_widget(block, area);
 
-        info_pane::render_track_info(frame, inner, app, theme);
+        if self.lines().is_none() {
+            info_pane::render_track_info(frame, inner, app, theme, self.scroll_offset);
+            return;
+        }
+
+        // Search prompt on the last row while typing
+        let (text_area, prompt_area) = match &self.search_input {
+            Some(_) if inner.height > 1 => {
+                let rows = Layout::default()
+                    .direction(Direction::Vertical)
+                    .constraints([Constraint::Min(1), Constraint::Length(1)])
+                    .split(inner);
+                (rows[0], Some(rows[1]))
+            }
+            _ => (inner, None),
+        };
+        // Side padding, as long as some text still fits
+        let pad = app.config.lyrics_padding.min(text_area.width.saturating_sub(10) / 2);
+        let text_area = Rect {
+            x: text_area.x + pad,
+            width: text_area.width - 2 * pad,
+            ..text_area
+        };
+        self.width = text_area.width;
+        let centered = app.config.lyrics_align.eq_ignore_ascii_case("center");
+        if let Some(lines) = self.lines() {
+            self.render_lyrics(frame, text_area, lines, centered, theme);
+        }
+
+        if let (Some(prompt), Some(input)) = (prompt_area, &self.search_input) {
+            let [slash, input_area] = Layout::horizontal([Constraint::Length(1), Constraint::Min(1)]).areas(prompt);
+            frame.render_widget(
+                Paragraph::new(Span::styled("/", Style::default().fg(CURRENT_MATCH_BG).add_modifier(Modifier::BOLD))),
+                slash,
+            );
+            input.render(frame, input_area, Style::default().fg(Color::White));
+        }
     }
 
-    fn handle_key(&mut self, key: KeyEvent, _app: &App) -> Option<AppAction> {
+    fn handle_key(&mut self, key: KeyEvent, app: &App) -> Option<AppAction> {
+        self.sync(app);
+        if let Some(input) = self.search_input.as_mut() {
+            match key.code {
+                KeyCode::Esc => self.search_input = None,
+                KeyCode::Enter => {
+                    let query = self.search_input.take().unwrap_or_default();
+                    self.run_search(query.as_str().to_string());
+                }
+                _ => {
+                    input.handle_key(&key);
+                }
+            }
+            return None;
+        }
         match key.code {
+            KeyCode::Char('/') if self.lines().is_some() => {
+                self.search_input = Some(TextInput::new());
+                None
+            }
+            KeyCode::Char('v') => {
+                self.cycle_variant(app);
+                None
+            }
+            KeyCode::Char('n') if !self.matches.is_empty() => {
+                self.current_match = (self.current_match + 1) % self.matches.len();
+                self.show_current_match();
+                None
+            }
+            KeyCode::Char('N') if !self.matches.is_empty() => {
+                self.current_match = (self.current_match + self.matches.len() - 1) % self.matches.len();
+                self.show_current_match();
+                None
+            }
+            KeyCode::Esc => {
+                self.query.clear();
+                self.matches.clear();
+                None
+            }
             KeyCode::Char('j') | KeyCode::Down => {
                 self.scroll_offset = self.scroll_offset.saturating_add(1);
                 None
@@ -78,3 +357,26 @@ impl Pane for LyricsPane {
         None
     }
 }
+
+/// Byte ranges of case-insensitive occurrences of `needle` (lowercase) in `hay`
+fn find_matches(hay: &str, needle: &str) -> Vec<(usize, usize)> {
+    let needle: Vec<char> = needle.chars().collect();
+    if needle.is_empty() {
+        return Vec::new();
+    }
+    let chars: Vec<(usize, char)> = hay.char_indices()
+        .map(|(i, c)| (i, c.to_lowercase().next().unwrap_or(c)))
+        .collect();
+    let mut ranges = Vec::new();
+    let mut i = 0;
+    while i + needle.len() <= chars.len() {
+        if chars[i..i + needle.len()].iter().map(|&(_, c)| c).eq(needle.iter().copied()) {
+            let end = chars.get(i + needle.len()).map_or(hay.len(), |&(b, _)| b);
+            ranges.push((chars[i].0, end));
+            i += needle.len();
+        } else {
+            i += 1;
+        }
+    }
+    ranges
+}
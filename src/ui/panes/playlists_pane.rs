@@ -2,41 +2,280 @@ use crossterm::event::{KeyCode, KeyEvent, MouseButton, MouseEvent, MouseEventKin
 use ratatui::layout::Rect;
 use ratatui::style::{Color, Modifier, Style};
 use ratatui::text::{Line, Span};
-use ratatui::widgets::{Block, Borders, List, ListItem, Scrollbar, ScrollbarOrientation, ScrollbarState};
+use ratatui::widgets::{Block, Borders, List, ListItem};
 use ratatui::Frame;
+use std::collections::HashSet;
+use std::path::PathBuf;
 
 use crate::app::{App, AppAction};
 use crate::ui::pane::Pane;
+use crate::ui::scrollable_list::{ScrollableList, HOVER_BG};
 use crate::ui::theme::Theme;
+use crate::ui::widgets::breadcrumb::breadcrumb;
+const ALBUM_COLOR: Color = Color::Rgb(255, 220, 100);
 
-const HOVER_BG: Color = Color::Indexed(238);
+/// Playlist whose tracks are shown, and where the playlist list was left
+struct OpenPlaylist {
+    idx: usize,
+    saved: ScrollableList,
+}
+
+/// A row of the contents view: an album header over a run of consecutive entries
+/// from the same album, or one entry (its position in the playlist)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Row {
+    Album { start: usize, len: usize },
+    Track(usize),
+}
 
 pub struct PlaylistsPane {
-    pub selected: usize,
-    pub scroll_offset: usize,
-    pub hover_row: Option<usize>,
+    pub list: ScrollableList,
+    /// Contents view (Enter on a playlist); `list` then indexes its rows
+    open: Option<OpenPlaylist>,
+    /// Albums folded to their header in the contents view, by `album_key`
+    collapsed: HashSet<String>,
+    /// Entry position to select once a J / K move has been applied, and whether
+    /// it was a whole album (its header is selected then)
+    follow: Option<(usize, bool)>,
 }
 
 impl PlaylistsPane {
     pub fn new() -> Self {
         Self {
-            selected: 0,
-            scroll_offset: 0,
-            hover_row: None,
+            list: ScrollableList::new(),
+            open: None,
+            collapsed: HashSet::new(),
+            follow: None,
+        }
+    }
+
+    /// Rows in the current mode: one per playlist, or the open playlist's headers and tracks
+    fn row_count(&self, app: &App) -> usize {
+        match &self.open {
+            Some(open) => self.rows(app, open.idx).len(),
+            None => app.playlists.len(),
+        }
+    }
+
+    fn rows(&self, app: &App, idx: usize) -> Vec<Row> {
+        let Some(pl) = app.playlists.get(idx) else { return Vec::new() };
+        let keys: Vec<String> = pl.tracks.iter().map(|p| album_key(app, p)).collect();
+        let mut rows = Vec::new();
+        let mut start = 0;
+        while start < keys.len() {
+            let len = keys[start..].iter().take_while(|k| **k == keys[start]).count();
+            rows.push(Row::Album { start, len });
+            if !self.collapsed.contains(&keys[start]) {
+                rows.extend((start..start + len).map(Row::Track));
+            }
+            start += len;
+        }
+        rows
+    }
+
+    fn open_playlist(&mut self, idx: usize) {
+        let saved = std::mem::take(&mut self.list);
+        self.open = Some(OpenPlaylist { idx, saved });
+    }
+
+    fn close_playlist(&mut self) {
+        if let Some(open) = self.open.take() {
+            self.list = open.saved;
+            self.list.hover_row = None;
+        }
+    }
+
+    /// Clamp positions after the library or playlists changed under the pane
+    pub fn restore_after_sync(&mut self, app: &App) {
+        if self.open.as_ref().is_some_and(|o| o.idx >= app.playlists.len()) {
+            self.close_playlist();
+        }
+        let last = self.row_count(app).saturating_sub(1);
+        self.list.selected = self.list.selected.min(last);
+        self.list.scroll_offset = self.list.scroll_offset.min(last);
+    }
+
+    /// Tracks `a` inserts into the queue: the selected playlist, or in the contents
+    /// view the selected entry or album
+    pub fn selection_paths(&self, app: &App) -> Vec<PathBuf> {
+        let Some(open) = &self.open else {
+            return app.playlists.get(self.list.selected).map(|pl| pl.tracks.clone()).unwrap_or_default();
+        };
+        let Some(pl) = app.playlists.get(open.idx) else { return Vec::new() };
+        match self.rows(app, open.idx).get(self.list.selected) {
+            Some(&Row::Album { start, len }) => pl.tracks[start..start + len].to_vec(),
+            Some(&Row::Track(pos)) => vec![pl.tracks[pos].clone()],
+            None => Vec::new(),
+        }
+    }
+
+    fn render_contents(&self, app: &App, idx: usize, rows: &[Row], focused: bool, height: usize, theme: &Theme) -> Vec<ListItem<'static>> {
+        let Some(pl) = app.playlists.get(idx) else { return Vec::new() };
+        let highlight = Style::default()
+            .bg(theme.highlight_bg)
+            .fg(theme.highlight_fg)
+            .add_modifier(Modifier::BOLD);
+        rows.iter()
+            .enumerate()
+            .skip(self.list.scroll_offset)
+            .take(height)
+            .map(|(i, row)| {
+                let bg = if self.list.is_hovered(i) { HOVER_BG } else { Color::Reset };
+                let selected = i == self.list.selected && focused;
+                let pick = |style: Style| if selected { highlight } else { style.bg(bg) };
+                match *row {
+                    Row::Album { start, len } => {
+                        let track = app.library.track_by_path(&pl.tracks[start]);
+                        let label = match track {
+                            Some(t) => format!("{} \u{2014} {}", t.display_album(), t.album_owner()),
+                            None => "Unavailable".to_string(),
+                        };
+                        let folded = self.collapsed.contains(&album_key(app, &pl.tracks[start]));
+                        let arrow = if folded { "\u{25B8}" } else { "\u{25BE}" };
+                        ListItem::new(Line::from(vec![
+                            Span::styled(
+                                format!("  {} {}", arrow, label),
+                                pick(Style::default().fg(ALBUM_COLOR).add_modifier(Modifier::BOLD)),
+                            ),
+                            Span::styled(format!("  ({})", len), pick(Style::default().fg(Color::DarkGray))),
+                        ]))
+                    }
+                    Row::Track(pos) => {
+                        let path = &pl.tracks[pos];
+                        let (title, artist, style) = match app.library.track_by_path(path) {
+                            Some(t) => (t.title.clone(), t.artist.clone(), Style::default().fg(theme.fg)),
+                            None => (
+                                path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default(),
+                                String::new(),
+                                Style::default().fg(Color::DarkGray).add_modifier(Modifier::CROSSED_OUT),
+                            ),
+                        };
+                        ListItem::new(Line::from(vec![
+                            Span::styled(format!("    {:>3} {}", pos + 1, title), pick(style)),
+                            Span::styled(format!("  {}", artist), pick(Style::default().fg(Color::DarkGray))),
+                        ]))
+                    }
+                }
+            })
+            .collect()
+    }
+
+    fn handle_contents_key(&mut self, key: KeyEvent, app: &App, idx: usize) -> Option<AppAction> {
+        let rows = self.rows(app, idx);
+        let row = rows.get(self.list.selected).copied();
+        match key.code {
+            KeyCode::Esc | KeyCode::Backspace => {
+                self.close_playlist();
+                None
+            }
+            KeyCode::Enter => match row? {
+                Row::Album { start, .. } => {
+                    self.toggle_album(app, idx, start);
+                    None
+                }
+                Row::Track(pos) => Some(AppAction::PlayPlaylistFrom { playlist_idx: idx, pos }),
+            },
+            KeyCode::Char('o') => {
+                let start = match row? {
+                    Row::Album { start, .. } => start,
+                    // Fold the album the track is in, leaving its header selected
+                    Row::Track(_) => {
+                        let (header, start) = rows[..self.list.selected].iter().enumerate().rev()
+                            .find_map(|(i, r)| match *r {
+                                Row::Album { start, .. } => Some((i, start)),
+                                Row::Track(_) => None,
+                            })?;
+                        self.list.selected = header;
+                        start
+                    }
+                };
+                self.toggle_album(app, idx, start);
+                None
+            }
+            KeyCode::Char('d') | KeyCode::Delete => {
+                let positions = match row? {
+                    Row::Album { start, len } => (start..start + len).collect(),
+                    Row::Track(pos) => vec![pos],
+                };
+                Some(AppAction::RemovePlaylistEntries { playlist_idx: idx, positions })
+            }
+            KeyCode::Char('J') | KeyCode::Char('K') => {
+                let down = key.code == KeyCode::Char('J');
+                let len = app.playlists.get(idx)?.tracks.len();
+                let (start, block, to) = match row? {
+                    Row::Track(pos) if down && pos + 1 < len => (pos, 1, pos + 1),
+                    Row::Track(pos) if !down && pos > 0 => (pos, 1, pos - 1),
+                    // A whole album jumps over the neighbouring one
+                    Row::Album { start, len: n } => {
+                        let neighbour = rows.iter().filter_map(|r| match *r {
+                            Row::Album { start: s, len: l } => Some((s, l)),
+                            Row::Track(_) => None,
+                        });
+                        let (_, m) = if down {
+                            neighbour.clone().find(|&(s, _)| s == start + n)?
+                        } else {
+                            neighbour.clone().find(|&(s, l)| s + l == start)?
+                        };
+                        (start, n, if down { start + m } else { start - m })
+                    }
+                    Row::Track(_) => return None,
+                };
+                self.follow = Some((to, matches!(row, Some(Row::Album { .. }))));
+                Some(AppAction::MovePlaylistEntries { playlist_idx: idx, start, len: block, to })
+            }
+            code => {
+                self.list.navigate(code, rows.len());
+                None
+            }
+        }
+    }
+
+    fn toggle_album(&mut self, app: &App, idx: usize, start: usize) {
+        let Some(path) = app.playlists.get(idx).and_then(|pl| pl.tracks.get(start)) else { return };
+        let key = album_key(app, path);
+        if !self.collapsed.remove(&key) {
+            self.collapsed.insert(key);
+        }
+    }
+
+    /// Keep the selection on the moved entry, or the header of a moved album
+    fn follow_move(&mut self, app: &App) {
+        let (Some((to, moved_album)), Some(idx)) = (self.follow.take(), self.open.as_ref().map(|o| o.idx)) else {
+            return;
+        };
+        let rows = self.rows(app, idx);
+        let target = rows.iter().position(|r| match *r {
+            Row::Album { start, .. } => moved_album && start == to,
+            Row::Track(pos) => !moved_album && pos == to,
+        });
+        if let Some(row) = target {
+            self.list.selected = row;
         }
     }
 }
 
+/// Entries of the same album group together; files missing from the library share one group
+fn album_key(app: &App, path: &std::path::Path) -> String {
+    match app.library.track_by_path(path) {
+        Some(t) => format!("{}\u{0}{}", t.display_album(), t.album_owner()),
+        None => String::new(),
+    }
+}
+
 impl Pane for PlaylistsPane {
     fn render(&mut self, frame: &mut Frame, area: Rect, focused: bool, app: &App, theme: &Theme) {
-        let count = app.playlists.len();
+        if self.open.as_ref().is_some_and(|o| o.idx >= app.playlists.len()) {
+            self.close_playlist();
+        }
+        self.follow_move(app);
+        let count = self.row_count(app);
         let border_color = if focused {
             theme.border_focused
         } else {
             theme.border_unfocused
         };
 
-        let block = Block::default()
+        let mut block = Block::default()
             .borders(Borders::ALL)
             .border_style(Style::default().fg(border_color))
             .title_style(Style::default().fg(if focused {
@@ -44,117 +283,97 @@ impl Pane for PlaylistsPane {
             } else {
                 theme.fg
             }));
+        if let Some(pl) = self.open.as_ref().and_then(|o| app.playlists.get(o.idx)) {
+            block = block
+                .title(breadcrumb(&["Playlists".to_string(), pl.name.clone()], area.width, theme))
+                .title_bottom(Line::from(Span::styled(
+                    " Enter: play/fold  d: remove  J/K: move  o: fold  Esc: back ",
+                    Style::default().fg(Color::DarkGray),
+                )));
+        }
 
         let inner = block.inner(area);
         let inner_height = inner.height as usize;
 
         // Auto-scroll
         if count > 0 {
-            if self.selected >= count {
-                self.selected = count.saturating_sub(1);
-            }
-            if self.selected < self.scroll_offset {
-                self.scroll_offset = self.selected;
-            }
-            if inner_height > 0 && self.selected >= self.scroll_offset + inner_height {
-                self.scroll_offset = self.selected - inner_height + 1;
+            if self.list.selected >= count {
+                self.list.selected = count.saturating_sub(1);
             }
+            self.list.follow(self.list.selected, inner_height);
         }
 
-        let has_scrollbar = count > inner_height;
         let highlight = Style::default()
             .bg(theme.highlight_bg)
             .fg(theme.highlight_fg)
             .add_modifier(Modifier::BOLD);
 
-        let items: Vec<ListItem> = app
-            .playlists
-            .iter()
-            .enumerate()
-            .skip(self.scroll_offset)
-            .take(inner_height)
-            .map(|(i, pl)| {
-                let is_selected = i == self.selected;
-                let is_hovered = self.hover_row == Some(i);
-
-                if is_selected && focused {
-                    ListItem::new(Line::from(vec![
-                        Span::styled(format!("  \u{F005} {}", pl.name), highlight),
-                        Span::styled(format!(" ({})", pl.tracks.len()), highlight),
-                    ]))
-                } else {
-                    let bg = if is_hovered { HOVER_BG } else { Color::Reset };
-                    ListItem::new(Line::from(vec![
-                        Span::styled(
-                            "  \u{F005} ",
-                            Style::default().fg(Color::Yellow).bg(bg),
-                        ),
-                        Span::styled(
-                            pl.name.as_str(),
-                            Style::default().fg(theme.fg).bg(bg),
-                        ),
-                        Span::styled(
-                            format!(" ({})", pl.tracks.len()),
-                            Style::default().fg(Color::DarkGray).bg(bg),
-                        ),
-                    ]))
-                }
-            })
-            .collect();
+        let items: Vec<ListItem> = if let Some(idx) = self.open.as_ref().map(|o| o.idx) {
+            let rows = self.rows(app, idx);
+            self.render_contents(app, idx, &rows, focused, inner_height, theme)
+        } else {
+            app
+                .playlists
+                .iter()
+                .enumerate()
+                .skip(self.list.scroll_offset)
+                .take(inner_height)
+                .map(|(i, pl)| {
+                    let is_selected = i == self.list.selected;
+                    let is_hovered = self.list.is_hovered(i);
+
+                    if is_selected && focused {
+                        ListItem::new(Line::from(vec![
+                            Span::styled(format!("  \u{F005} {}", pl.name), highlight),
+                            Span::styled(pl.count_label(&app.library), highlight),
+                        ]))
+                    } else {
+                        let bg = if is_hovered { HOVER_BG } else { Color::Reset };
+                        ListItem::new(Line::from(vec![
+                            Span::styled(
+                                "  \u{F005} ",
+                                Style::default().fg(Color::Yellow).bg(bg),
+                            ),
+                            Span::styled(
+                                pl.name.as_str(),
+                                Style::default().fg(theme.fg).bg(bg),
+                            ),
+                            Span::styled(
+                                pl.count_label(&app.library),
+                                Style::default().fg(Color::DarkGray).bg(bg),
+                            ),
+                        ]))
+                    }
+                })
+                .collect()
+        };
 
         let list = List::new(items).block(block);
         frame.render_widget(list, area);
 
-        if has_scrollbar {
-            let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight)
-                .begin_symbol(None)
-                .end_symbol(None);
-            let mut scrollbar_state = ScrollbarState::new(count)
-                .position(self.scroll_offset);
-            frame.render_stateful_widget(
-                scrollbar,
-                area.inner(ratatui::layout::Margin { vertical: 1, horizontal: 0 }),
-                &mut scrollbar_state,
-            );
-        }
+        self.list.render_scrollbar(frame, area, count, inner_height);
     }
 
     fn handle_key(&mut self, key: KeyEvent, app: &App) -> Option<AppAction> {
+        if let Some(idx) = self.open.as_ref().map(|o| o.idx) {
+            return self.handle_contents_key(key, app, idx);
+        }
         let count = app.playlists.len();
         if count == 0 {
             return None;
         }
 
+        if self.list.navigate(key.code, count) {
+            return None;
+        }
+
         match key.code {
-            KeyCode::Char('j') | KeyCode::Down => {
-                if self.selected < count - 1 {
-                    self.selected += 1;
-                }
-                None
-            }
-            KeyCode::Char('k') | KeyCode::Up => {
-                if self.selected > 0 {
-                    self.selected -= 1;
-                }
-                None
-            }
             KeyCode::Enter => {
-                if let Some(pl) = app.playlists.get(self.selected) {
-                    if !pl.tracks.is_empty() {
-                        return Some(AppAction::AddToQueue(pl.tracks.clone()));
-                    }
+                if self.list.selected < count {
+                    self.open_playlist(self.list.selected);
                 }
                 None
             }
-            KeyCode::Home | KeyCode::Char('g') => {
-                self.selected = 0;
-                self.scroll_offset = 0;
-                None
-            }
-            KeyCode::End | KeyCode::Char('G') => {
-                self.selected = count.saturating_sub(1);
-                None
-            }
             _ => None,
         }
     }
@@ -162,34 +381,22 @@ impl Pane for PlaylistsPane {
     fn handle_mouse(&mut self, event: MouseEvent, area: Rect, app: &App) -> Option<AppAction> {
         let block = Block::default().borders(Borders::ALL);
         let inner = block.inner(area);
-        let count = app.playlists.len();
+        let count = self.row_count(app);
 
         match event.kind {
             MouseEventKind::Down(MouseButton::Left) => {
-                if event.column >= inner.x
-                    && event.column < inner.x + inner.width
-                    && event.row >= inner.y
-                    && event.row < inner.y + inner.height
-                {
-                    let clicked = self.scroll_offset + (event.row - inner.y) as usize;
-                    if clicked < count {
-                        self.selected = clicked;
-                    }
+                if let Some(clicked) = self.list.row_at(inner, event.column, event.row, count) {
+                    self.list.selected = clicked;
                 }
                 None
             }
-            MouseEventKind::ScrollDown => {
+            MouseEventKind::ScrollDown | MouseEventKind::ScrollUp => {
                 if count > 0 {
-                    self.scroll_offset = (self.scroll_offset + 3).min(count.saturating_sub(1));
-                    self.selected = (self.selected + 3).min(count.saturating_sub(1));
+                    let delta = self.list.wheel(event.kind == MouseEventKind::ScrollUp, count);
+                    self.list.move_by(delta, count);
                 }
                 None
             }
-            MouseEventKind::ScrollUp => {
-                self.scroll_offset = self.scroll_offset.saturating_sub(3);
-                self.selected = self.selected.saturating_sub(3);
-                None
-            }
             _ => None,
         }
     }
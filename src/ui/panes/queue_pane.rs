@@ -2,26 +2,133 @@ use crossterm::event::{KeyCode, KeyEvent, MouseButton, MouseEvent, MouseEventKin
 use ratatui::layout::Rect;
 use ratatui::style::{Color, Modifier, Style};
 use ratatui::text::{Line, Span};
-use ratatui::widgets::{Block, Borders, List, ListItem, Scrollbar, ScrollbarOrientation, ScrollbarState};
+use ratatui::widgets::{Block, Borders, List, ListItem};
 use ratatui::Frame;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Weak};
+use std::time::SystemTime;
 use unicode_width::UnicodeWidthStr;
 
+use crate::app::state::{FocusedPane, PlayState, RepeatMode};
 use crate::app::{App, AppAction};
+use crate::library::Library;
+use crate::localtime;
 use crate::ui::pane::Pane;
+use crate::ui::scrollable_list::{ScrollableList, HOVER_BG};
 use crate::ui::theme::Theme;
 
-const HOVER_BG: Color = Color::Indexed(238); // very dark gray
-
 pub struct QueuePane {
-    pub scroll_offset: usize,
-    pub hover_row: Option<usize>,
+    /// Scroll position and hover row; the cursor itself is `app.queue.selected_index`
+    pub list: ScrollableList,
+    /// Every path in some playlist (the star after the title), as of `starred_rev`
+    starred: HashSet<PathBuf>,
+    starred_rev: Option<u64>,
+    /// Playlist header totals, keyed by playlist, `App::playlists_rev` and library
+    header: Option<(usize, u64, Weak<Library>, String)>,
+    /// Column text of the rows last drawn, from `list.scroll_offset` down. With a whole
+    /// library queued only these rows are ever formatted, and only when the entry under
+    /// them, the scroll position, the widths or the library change.
+    rows: Vec<RowText>,
+    rows_key: (usize, usize, usize),
+    rows_library: Weak<Library>,
 }
 
 impl QueuePane {
     pub fn new() -> Self {
         Self {
-            scroll_offset: 0,
-            hover_row: None,
+            list: ScrollableList::new(),
+            starred: HashSet::new(),
+            starred_rev: None,
+            header: None,
+            rows: Vec::new(),
+            rows_key: (0, 0, 0),
+            rows_library: Weak::new(),
+        }
+    }
+
+    /// Rebuild the playlist-membership set after playlists change
+    fn refresh_starred(&mut self, app: &App) {
+        if self.starred_rev == Some(app.playlists_rev) {
+            return;
+        }
+        self.starred = app.playlists.iter().flat_map(|pl| pl.tracks.iter().cloned()).collect();
+        self.starred_rev = Some(app.playlists_rev);
+        self.rows.clear();
+    }
+
+    fn header_label(&mut self, app: &App) -> Option<String> {
+        if let Some(seed) = app.radio.as_ref().and_then(|p| app.library.track_by_path(p)) {
+            // nf-md-radio
+            return Some(format!(" \u{F0439} Radio \u{00b7} {} ", seed.title));
+        }
+        let idx = app.queue.playlist?;
+        let pl = app.playlists.get(idx)?;
+        let fresh = |(i, rev, lib, _): &(usize, u64, Weak<Library>, String)| {
+            *i == idx && *rev == app.playlists_rev && lib.as_ptr() == Arc::as_ptr(&app.library)
+        };
+        if !self.header.as_ref().is_some_and(fresh) {
+            let label = format!(" \u{F005} {}{} ", pl.name, pl.count_label(&app.library));
+            self.header = Some((idx, app.playlists_rev, Arc::downgrade(&app.library), label));
+        }
+        self.header.as_ref().map(|(.., label)| label.clone())
+    }
+}
+
+/// Formatted columns of one queue row; styles are applied per frame
+struct RowText {
+    path: PathBuf,
+    /// None when the file isn't in the library; `title` then holds the whole row
+    ext: Option<String>,
+    title: String,
+    artist: String,
+    dur: String,
+    num: String,
+}
+
+struct Columns {
+    title: usize,
+    artist: usize,
+    ext: usize,
+    dur: usize,
+    num: usize,
+    row: usize,
+}
+
+impl RowText {
+    fn new(path: &Path, app: &App, starred: &HashSet<PathBuf>, cols: &Columns) -> Self {
+        let Some(track) = app.library.track_by_path(path) else {
+            let name = path.file_name().map(|n| n.to_string_lossy()).unwrap_or_default();
+            return Self {
+                path: path.to_path_buf(),
+                ext: None,
+                title: fit_to_width(&format!("  {} (unavailable)", name), cols.row),
+                artist: String::new(),
+                dur: String::new(),
+                num: String::new(),
+            };
+        };
+        let ext = track
+            .path
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or("?")
+            .to_uppercase();
+        // Star integrated into title text so it stays next to the title
+        let title_text = if starred.contains(path) {
+            format!("{} \u{F005}", track.title) // "Title nf-fa-star"
+        } else {
+            track.title.clone()
+        };
+        Self {
+            path: path.to_path_buf(),
+            // Right-align ext to ext_col_width
+            ext: Some(format!("{:>width$}", ext, width = cols.ext)),
+            title: fit_to_width(&title_text, cols.title),
+            artist: fit_to_width(track.display_artist(), cols.artist),
+            // Right-align dur to dur_col_width
+            dur: format!("{:>width$}", track.format_duration(), width = cols.dur),
+            num: fit_to_width(&format!("{:>width$}", track.format_number(), width = cols.num), cols.num),
         }
     }
 }
@@ -38,6 +145,45 @@ fn format_color(ext: &str) -> Color {
     }
 }
 
+/// Wall-clock start time ("21:47") of the `len` queue entries from `from` on; None for
+/// entries that won't play next in order (the current track and those before it,
+/// unavailable files). Empty while stopped, shuffling or repeating tracks one by one.
+fn start_times(app: &App, from: usize, len: usize) -> Vec<Option<String>> {
+    let Some(current) = app.queue.current_index else { return Vec::new() };
+    if app.playback.state == PlayState::Stopped || app.playback.shuffle
+        || matches!(app.playback.repeat, RepeatMode::One | RepeatMode::Count)
+    {
+        return Vec::new();
+    }
+    let now = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs_f64();
+    // Start of the entry after the current one, then add up everything before `from`
+    let mut start = now + (app.playback.duration_secs - app.playback.position_secs).max(0.0);
+    let duration = |i: usize| app.library.track_by_path(&app.queue.tracks[i]).map(|t| t.duration.as_secs_f64());
+    for i in current + 1..from.min(app.queue.tracks.len()) {
+        start += duration(i).unwrap_or(0.0);
+    }
+    (from..(from + len).min(app.queue.tracks.len()))
+        .map(|i| {
+            if i <= current {
+                return None;
+            }
+            let secs = duration(i)?;
+            let label = clock_label(start as i64);
+            start += secs;
+            Some(label)
+        })
+        .collect()
+}
+
+/// Local "HH:MM" of a Unix time
+fn clock_label(ts: i64) -> String {
+    let (h, m, _) = localtime::local_hms(ts);
+    format!("{:02}:{:02}", h, m)
+}
+
 /// Truncate a string to fit within `max_width` columns, adding "…" if needed.
 /// Pads with spaces to exactly fill `max_width`.
 fn fit_to_width(s: &str, max_width: usize) -> String {
@@ -81,6 +227,11 @@ impl Pane for QueuePane {
             } else {
                 theme.fg
             }));
+        // Header with the playlist's totals while the queue holds its contents
+        let block = match self.header_label(app) {
+            Some(label) => block.title(label),
+            None => block,
+        };
 
         let inner = block.inner(area);
         let inner_height = inner.height as usize;
@@ -88,45 +239,89 @@ impl Pane for QueuePane {
 
         // Auto-scroll to keep selected_index visible
         if count > 0 {
-            if app.queue.selected_index < self.scroll_offset {
-                self.scroll_offset = app.queue.selected_index;
-            }
-            if inner_height > 0 && app.queue.selected_index >= self.scroll_offset + inner_height {
-                self.scroll_offset = app.queue.selected_index - inner_height + 1;
-            }
+            self.list.follow(app.queue.selected_index, inner_height);
         }
 
-        // Column layout: prefix(2) + title(55%) + artist(45%) + ext(4) + gap(1) + dur(5) + trail(1)
+        // Column layout: prefix(2) + [num + gap(1)] + title(55%) + artist(45%) + ext(4) + gap(1)
+        //   + [start(5) + gap(1)] + dur(5) + trail(1)
         let ext_col_width = 4;
         let dur_col_width = 5;
         let prefix_width = 2;
-        let fixed_width = prefix_width + 1 + ext_col_width + 1 + dur_col_width + 1;
+        let show_numbers = app.config.queue_track_numbers;
+        // As wide as the widest visible number ("03", "2-11"), so nothing is clipped
+        let num_col_width = if show_numbers {
+            app.queue.tracks.iter()
+                .skip(self.list.scroll_offset)
+                .take(inner_height)
+                .filter_map(|p| app.library.track_by_path(p))
+                .map(|t| t.format_number().len())
+                .max()
+                .unwrap_or(0)
+                .max(2)
+        } else {
+            0
+        };
+        let num_total = if show_numbers { num_col_width + 1 } else { 0 };
+        let show_starts = app.config.queue_start_times;
+        let start_total = if show_starts { 6 } else { 0 };
+        let fixed_width = prefix_width + num_total + 1 + ext_col_width + 1 + start_total + dur_col_width + 1;
         let flex_total = inner_width.saturating_sub(fixed_width);
         let title_max = (flex_total * 55 / 100).max(4);
         let artist_max = flex_total.saturating_sub(title_max).max(4);
-        let has_scrollbar = count > inner_height;
+        let cols = Columns {
+            title: title_max,
+            artist: artist_max,
+            ext: ext_col_width,
+            dur: dur_col_width,
+            num: num_col_width,
+            row: inner_width,
+        };
+
+        self.refresh_starred(app);
+        let key = (self.list.scroll_offset, inner_width, num_col_width);
+        if self.rows_key != key || self.rows_library.as_ptr() != Arc::as_ptr(&app.library) {
+            self.rows.clear();
+            self.rows_key = key;
+            self.rows_library = Arc::downgrade(&app.library);
+        }
+        self.rows.truncate(inner_height);
+        for (r, path) in app.queue.tracks.iter().skip(self.list.scroll_offset).take(inner_height).enumerate() {
+            if self.rows.get(r).is_some_and(|row| row.path == *path) {
+                continue;
+            }
+            let text = RowText::new(path, app, &self.starred, &cols);
+            match self.rows.get_mut(r) {
+                Some(row) => *row = text,
+                None => self.rows.push(text),
+            }
+        }
+
+        let starts = if show_starts { start_times(app, self.list.scroll_offset, inner_height) } else { Vec::new() };
 
-        let items: Vec<ListItem> = app
-            .queue
-            .tracks
+        let items: Vec<ListItem> = self
+            .rows
             .iter()
-            .enumerate()
-            .skip(self.scroll_offset)
-            .take(inner_height)
-            .map(|(i, &track_idx)| {
-                let track = &app.library.tracks[track_idx];
+            .zip(self.list.scroll_offset..count)
+            .map(|(row, i)| {
+                let path = &row.path;
+                let Some(ext_padded) = &row.ext else {
+                    // Not in the library (e.g. unmounted drive): grayed out, skipped during playback
+                    let style = if i == app.queue.selected_index && focused {
+                        Style::default().bg(theme.highlight_bg).fg(Color::DarkGray)
+                    } else if self.list.is_hovered(i) {
+                        Style::default().fg(Color::DarkGray).bg(HOVER_BG)
+                    } else {
+                        Style::default().fg(Color::DarkGray)
+                    };
+                    return ListItem::new(Line::from(Span::styled(
+                        row.title.clone(),
+                        style.add_modifier(Modifier::CROSSED_OUT),
+                    )));
+                };
+                let ext = ext_padded.trim_start();
                 let is_current = app.queue.current_index == Some(i);
                 let is_selected = i == app.queue.selected_index;
 
-                let artist = track.display_artist();
-                let ext = track
-                    .path
-                    .extension()
-                    .and_then(|e| e.to_str())
-                    .unwrap_or("?")
-                    .to_uppercase();
-                let dur = track.format_duration();
-
                 // Base styles
                 let sel_style = Style::default()
                     .bg(theme.highlight_bg)
@@ -136,7 +331,7 @@ impl Pane for QueuePane {
                 let normal_style = Style::default().fg(theme.fg);
                 let dim_style = Style::default().fg(Color::Gray);
 
-                let is_hovered = self.hover_row == Some(i);
+                let is_hovered = self.list.is_hovered(i);
 
                 let (title_style, artist_style, ext_style, dur_style, prefix_style) =
                     if is_selected && focused {
@@ -146,7 +341,7 @@ impl Pane for QueuePane {
                         (
                             cur_style.bg(bg),
                             dim_style.bg(bg),
-                            Style::default().fg(format_color(&ext)).add_modifier(Modifier::BOLD).bg(bg),
+                            Style::default().fg(format_color(ext)).add_modifier(Modifier::BOLD).bg(bg),
                             Style::default().fg(Color::DarkGray).bg(bg),
                             cur_style.bg(bg),
                         )
@@ -154,7 +349,7 @@ impl Pane for QueuePane {
                         (
                             normal_style.bg(HOVER_BG),
                             dim_style.bg(HOVER_BG),
-                            Style::default().fg(format_color(&ext)).bg(HOVER_BG),
+                            Style::default().fg(format_color(ext)).bg(HOVER_BG),
                             Style::default().fg(Color::DarkGray).bg(HOVER_BG),
                             normal_style.bg(HOVER_BG),
                         )
@@ -162,28 +357,24 @@ impl Pane for QueuePane {
                         (
                             normal_style,
                             dim_style,
-                            Style::default().fg(format_color(&ext)),
+                            Style::default().fg(format_color(ext)),
                             Style::default().fg(Color::DarkGray),
                             normal_style,
                         )
                     };
 
-                let in_playlist = app.playlists.iter().any(|pl| pl.tracks.contains(&track_idx));
-                let prefix = if is_current { "\u{F04B} " } else { "  " }; // nf-fa-play
-
-                // Star integrated into title text so it stays next to the title
-                let title_text = if in_playlist {
-                    format!("{} \u{F005}", track.title) // "Title nf-fa-star"
+                // nf-fa-play, or nf-fa-bookmark on a long track that resumes mid-way
+                let resumable = app.resume_positions.contains_key(path)
+                    && app.library.track_by_path(path).is_some_and(|t| app.is_resumable(t.duration.as_secs_f64()));
+                let (prefix, prefix_style) = if is_current {
+                    ("\u{F04B} ", prefix_style)
+                } else if resumable && !(is_selected && focused) {
+                    ("\u{F02E} ", prefix_style.fg(Color::Cyan))
+                } else if resumable {
+                    ("\u{F02E} ", prefix_style)
                 } else {
-                    track.title.clone()
+                    ("  ", prefix_style)
                 };
-                let title_fitted = fit_to_width(&title_text, title_max);
-                let artist_fitted = fit_to_width(artist, artist_max);
-
-                // Right-align ext to ext_col_width
-                let ext_padded = format!("{:>width$}", ext, width = ext_col_width);
-                // Right-align dur to dur_col_width
-                let dur_padded = format!("{:>width$}", dur, width = dur_col_width);
 
                 // Row background for gap spans (keeps selection/hover highlight continuous)
                 let row_bg = if is_selected && focused {
@@ -194,35 +385,41 @@ impl Pane for QueuePane {
                     Style::default()
                 };
 
-                ListItem::new(Line::from(vec![
-                    Span::styled(prefix, prefix_style),
-                    Span::styled(title_fitted, title_style),
-                    Span::styled(artist_fitted, artist_style),
+                let mut spans = vec![Span::styled(prefix, prefix_style)];
+                if show_numbers {
+                    spans.push(Span::styled(row.num.clone(), dur_style));
+                    spans.push(Span::styled(" ", row_bg));
+                }
+                spans.extend([
+                    Span::styled(row.title.clone(), title_style),
+                    Span::styled(row.artist.clone(), artist_style),
                     Span::styled(" ", row_bg),
-                    Span::styled(ext_padded, ext_style),
+                    Span::styled(ext_padded.clone(), ext_style),
                     Span::styled(" ", row_bg),
-                    Span::styled(dur_padded, dur_style),
+                ]);
+                if show_starts {
+                    let start = starts.get(i - self.list.scroll_offset).cloned().flatten().unwrap_or_default();
+                    spans.push(Span::styled(format!("{:>5}", start), dur_style));
+                    spans.push(Span::styled(" ", row_bg));
+                }
+                spans.extend([
+                    Span::styled(row.dur.clone(), dur_style),
                     Span::styled(" ", row_bg),
-                ]))
+                ]);
+                // Insertion point for 'a' in the library: underline the row new tracks go after
+                if is_selected && app.focus == FocusedPane::Library {
+                    return ListItem::new(Line::from(spans).patch_style(
+                        Style::default().add_modifier(Modifier::UNDERLINED).underline_color(Color::Yellow),
+                    ));
+                }
+                ListItem::new(Line::from(spans))
             })
             .collect();
 
         let list = List::new(items).block(block);
         frame.render_widget(list, area);
 
-        // Scrollbar (rendered over the right border)
-        if has_scrollbar {
-            let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight)
-                .begin_symbol(None)
-                .end_symbol(None);
-            let mut scrollbar_state = ScrollbarState::new(count)
-                .position(self.scroll_offset);
-            frame.render_stateful_widget(
-                scrollbar,
-                area.inner(ratatui::layout::Margin { vertical: 1, horizontal: 0 }),
-                &mut scrollbar_state,
-            );
-        }
+        self.list.render_scrollbar(frame, area, count, inner_height);
     }
 
     fn handle_key(&mut self, key: KeyEvent, app: &App) -> Option<AppAction> {
@@ -232,8 +429,6 @@ impl Pane for QueuePane {
         }
 
         match key.code {
-            KeyCode::Char('j') | KeyCode::Down => None,
-            KeyCode::Char('k') | KeyCode::Up => None,
             KeyCode::Enter => {
                 if count > 0 {
                     Some(AppAction::PlayQueueIndex(app.queue.selected_index))
@@ -249,29 +444,15 @@ impl Pane for QueuePane {
                 }
             }
             KeyCode::Char('c') => Some(AppAction::ClearQueue),
+            KeyCode::Char('u') => Some(AppAction::DedupeQueue),
             _ => None,
         }
     }
 
-    fn handle_mouse(&mut self, event: MouseEvent, area: Rect, app: &App) -> Option<AppAction> {
-        let block = Block::default().borders(Borders::ALL);
-        let inner = block.inner(area);
-        let count = app.queue.tracks.len();
-
+    fn handle_mouse(&mut self, event: MouseEvent, _area: Rect, app: &App) -> Option<AppAction> {
         match event.kind {
-            MouseEventKind::Down(MouseButton::Left) => {
-                if event.column >= inner.x
-                    && event.column < inner.x + inner.width
-                    && event.row >= inner.y
-                    && event.row < inner.y + inner.height
-                {
-                    let clicked = self.scroll_offset + (event.row - inner.y) as usize;
-                    if clicked < count {
-                        // Selection handled by handler.rs
-                    }
-                }
-                None
-            }
+            // Selection is handled by handler.rs
+            MouseEventKind::Down(MouseButton::Left) => None,
             MouseEventKind::ScrollDown => self.handle_scroll(false, app),
             MouseEventKind::ScrollUp => self.handle_scroll(true, app),
             _ => None,
@@ -283,14 +464,8 @@ impl Pane for QueuePane {
         if count == 0 {
             return None;
         }
-        if up {
-            self.scroll_offset = self.scroll_offset.saturating_sub(3);
-            let new_sel = app.queue.selected_index.saturating_sub(3);
-            Some(AppAction::SetQueueSelection(new_sel))
-        } else {
-            self.scroll_offset = (self.scroll_offset + 3).min(count.saturating_sub(1));
-            let new_sel = (app.queue.selected_index + 3).min(count.saturating_sub(1));
-            Some(AppAction::SetQueueSelection(new_sel))
-        }
+        let delta = self.list.wheel(up, count);
+        let new_sel = app.queue.selected_index.saturating_add_signed(delta).min(count - 1);
+        Some(AppAction::SetQueueSelection(new_sel))
     }
 }
@@ -0,0 +1,109 @@
+use crossterm::event::KeyCode;
+use ratatui::layout::{Margin, Rect};
+use ratatui::style::Color;
+use ratatui::widgets::{Scrollbar, ScrollbarOrientation, ScrollbarState};
+use ratatui::Frame;
+
+/// Background of the row under the mouse
+pub const HOVER_BG: Color = Color::Indexed(238);
+/// Rows moved per mouse wheel notch
+const WHEEL_ROWS: usize = 3;
+
+/// Selection, scroll position and hover row of a pane's list, with the scrolling
+/// rules every pane shares: the selection stays on screen, the wheel moves the view
+/// and the selection together, and a scrollbar shows once the rows overflow. The
+/// same keys move through every list: `j` / `k`, `PageUp` / `PageDown` by a
+/// screenful, `g` / `G` (`Home` / `End`) to either end.
+///
+/// `selected` is whatever index the pane keys rows by (filtered panes keep a
+/// full-list index); `scroll_offset` and `hover_row` count shown rows.
+#[derive(Debug, Default, Clone)]
+pub struct ScrollableList {
+    pub selected: usize,
+    pub scroll_offset: usize,
+    pub hover_row: Option<usize>,
+    /// Rows that fit on screen at the last render, for paging
+    height: usize,
+}
+
+impl ScrollableList {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Scroll just enough that shown row `pos` fits in `height` rows
+    pub fn follow(&mut self, pos: usize, height: usize) {
+        self.height = height;
+        if pos < self.scroll_offset {
+            self.scroll_offset = pos;
+        }
+        if height > 0 && pos >= self.scroll_offset + height {
+            self.scroll_offset = pos - height + 1;
+        }
+    }
+
+    /// Rows a navigation key moves the selection by, `None` for other keys. The
+    /// ends are `isize::MIN` / `MAX`, so clamp with a saturating add.
+    pub fn nav_delta(&self, code: KeyCode) -> Option<isize> {
+        let page = self.height.saturating_sub(1).max(1) as isize;
+        match code {
+            KeyCode::Char('j') | KeyCode::Down => Some(1),
+            KeyCode::Char('k') | KeyCode::Up => Some(-1),
+            KeyCode::PageDown => Some(page),
+            KeyCode::PageUp => Some(-page),
+            KeyCode::Char('g') | KeyCode::Home => Some(isize::MIN),
+            KeyCode::Char('G') | KeyCode::End => Some(isize::MAX),
+            _ => None,
+        }
+    }
+
+    /// Move the selection of a list of `count` rows by a navigation key; false when
+    /// `code` isn't one
+    pub fn navigate(&mut self, code: KeyCode, count: usize) -> bool {
+        let Some(delta) = self.nav_delta(code) else { return false };
+        self.move_by(delta, count);
+        true
+    }
+
+    /// Move the selection `delta` rows, staying within `count` rows
+    pub fn move_by(&mut self, delta: isize, count: usize) {
+        self.selected = self.selected.saturating_add_signed(delta).min(count.saturating_sub(1));
+    }
+
+    pub fn is_hovered(&self, row: usize) -> bool {
+        self.hover_row == Some(row)
+    }
+
+    /// Shown row under the screen cell `(x, y)` of the list area `inner`, if one of
+    /// `count` rows is there
+    pub fn row_at(&self, inner: Rect, x: u16, y: u16, count: usize) -> Option<usize> {
+        let inside = x >= inner.x && x < inner.x + inner.width && y >= inner.y && y < inner.y + inner.height;
+        let row = self.scroll_offset + (y.checked_sub(inner.y)?) as usize;
+        (inside && row < count).then_some(row)
+    }
+
+    /// Scroll the view a wheel notch through `count` rows; returns how many rows the
+    /// selection should move with it
+    pub fn wheel(&mut self, up: bool, count: usize) -> isize {
+        if up {
+            self.scroll_offset = self.scroll_offset.saturating_sub(WHEEL_ROWS);
+            -(WHEEL_ROWS as isize)
+        } else {
+            self.scroll_offset = (self.scroll_offset + WHEEL_ROWS).min(count.saturating_sub(1));
+            WHEEL_ROWS as isize
+        }
+    }
+
+    /// Vertical scrollbar along the right border of a bordered pane, when `count`
+    /// rows don't fit in `height`
+    pub fn render_scrollbar(&self, frame: &mut Frame, area: Rect, count: usize, height: usize) {
+        if count <= height {
+            return;
+        }
+        let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight)
+            .begin_symbol(None)
+            .end_symbol(None);
+        let mut state = ScrollbarState::new(count).position(self.scroll_offset);
+        frame.render_stateful_widget(scrollbar, area.inner(Margin { vertical: 1, horizontal: 0 }), &mut state);
+    }
+}
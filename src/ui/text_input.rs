@@ -0,0 +1,154 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::layout::{Position, Rect};
+use ratatui::style::Style;
+use ratatui::widgets::Paragraph;
+use ratatui::Frame;
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+/// What a key did to a `TextInput`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Edit {
+    /// Not an editing key; the caller handles it
+    Unhandled,
+    /// Only the cursor moved
+    Moved,
+    /// The text changed
+    Changed,
+}
+
+/// One-line text input with a cursor and readline-style editing: Left/Right,
+/// Home/End, Backspace/Delete, Ctrl+W (delete word) and Ctrl+U (delete to start).
+/// The cursor moves by grapheme cluster, so a CJK character or an accented letter
+/// typed as base + combining mark is one step.
+#[derive(Debug, Clone, Default)]
+pub struct TextInput {
+    text: String,
+    /// Byte offset of the cursor, always on a grapheme boundary
+    cursor: usize,
+}
+
+impl TextInput {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.text.is_empty()
+    }
+
+    /// Replace the text, cursor at the end
+    pub fn set(&mut self, text: impl Into<String>) {
+        self.text = text.into();
+        self.cursor = self.text.len();
+    }
+
+    pub fn clear(&mut self) {
+        self.text.clear();
+        self.cursor = 0;
+    }
+
+    pub fn insert_str(&mut self, s: &str) {
+        self.text.insert_str(self.cursor, s);
+        self.cursor += s.len();
+    }
+
+    pub fn handle_key(&mut self, key: &KeyEvent) -> Edit {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        let before = self.text.len();
+        match key.code {
+            KeyCode::Char('w') if ctrl => {
+                let start = self.word_start();
+                self.text.drain(start..self.cursor);
+                self.cursor = start;
+            }
+            KeyCode::Char('u') if ctrl => {
+                self.text.drain(..self.cursor);
+                self.cursor = 0;
+            }
+            KeyCode::Char(c) if !ctrl && !key.modifiers.contains(KeyModifiers::ALT) => {
+                self.text.insert(self.cursor, c);
+                self.cursor += c.len_utf8();
+            }
+            KeyCode::Backspace => {
+                if let Some(prev) = self.prev_boundary() {
+                    self.text.drain(prev..self.cursor);
+                    self.cursor = prev;
+                }
+            }
+            KeyCode::Delete => {
+                if let Some(next) = self.next_boundary() {
+                    self.text.drain(self.cursor..next);
+                }
+            }
+            KeyCode::Left => {
+                self.cursor = self.prev_boundary().unwrap_or(self.cursor);
+                return Edit::Moved;
+            }
+            KeyCode::Right => {
+                self.cursor = self.next_boundary().unwrap_or(self.cursor);
+                return Edit::Moved;
+            }
+            KeyCode::Home => {
+                self.cursor = 0;
+                return Edit::Moved;
+            }
+            KeyCode::End => {
+                self.cursor = self.text.len();
+                return Edit::Moved;
+            }
+            _ => return Edit::Unhandled,
+        }
+        if self.text.len() == before { Edit::Moved } else { Edit::Changed }
+    }
+
+    fn prev_boundary(&self) -> Option<usize> {
+        self.text[..self.cursor].grapheme_indices(true).next_back().map(|(i, _)| i)
+    }
+
+    fn next_boundary(&self) -> Option<usize> {
+        self.text[self.cursor..].graphemes(true).next().map(|g| self.cursor + g.len())
+    }
+
+    /// Start of the word before the cursor, skipping spaces right before it
+    fn word_start(&self) -> usize {
+        let head = self.text[..self.cursor].trim_end();
+        head.char_indices()
+            .rev()
+            .find(|(_, c)| c.is_whitespace())
+            .map_or(0, |(i, c)| i + c.len_utf8())
+    }
+
+    /// Draw the text in `area`, scrolled so the cursor stays in view, and put the
+    /// terminal cursor there (IME candidate windows follow it)
+    pub fn render(&self, frame: &mut Frame, area: Rect, style: Style) {
+        if area.width == 0 || area.height == 0 {
+            return;
+        }
+        let width = area.width as usize;
+        let cursor_col = self.text[..self.cursor].width();
+        // Drop whole graphemes from the left until the cursor cell fits
+        let mut skipped = 0;
+        let mut start = 0;
+        for (i, g) in self.text.grapheme_indices(true) {
+            if cursor_col - skipped < width || i >= self.cursor {
+                break;
+            }
+            skipped += g.width();
+            start = i + g.len();
+        }
+        let mut shown = 0;
+        let end = self.text[start..].grapheme_indices(true)
+            .find(|(_, g)| {
+                shown += g.width();
+                shown > width
+            })
+            .map_or(self.text.len(), |(i, _)| start + i);
+        frame.render_widget(Paragraph::new(&self.text[start..end]).style(style), area);
+        frame.set_cursor_position(Position::new(area.x + (cursor_col - skipped) as u16, area.y));
+    }
+}
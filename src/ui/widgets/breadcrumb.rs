@@ -0,0 +1,48 @@
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use unicode_width::UnicodeWidthStr;
+
+use crate::ui::theme::Theme;
+
+const SEPARATOR: &str = " \u{25B8} ";
+const ELLIPSIS: &str = "\u{2026}";
+
+/// Pane title like " Directories ▸ Rock ▸ Radiohead " for a filtered or drilled-into
+/// view. When it's wider than the pane, the crumbs after the first are dropped from
+/// the left behind a "…" so the innermost level stays readable.
+pub fn breadcrumb(crumbs: &[String], width: u16, theme: &Theme) -> Line<'static> {
+    // Borders and the padding spaces
+    let avail = (width as usize).saturating_sub(4);
+    let line_width = |parts: &[&str]| {
+        parts.iter().map(|p| p.width()).sum::<usize>() + SEPARATOR.width() * parts.len().saturating_sub(1)
+    };
+    let mut parts: Vec<&str> = crumbs.iter().map(String::as_str).collect();
+    let mut elided = false;
+    while parts.len() > if elided { 3 } else { 2 } && line_width(&parts) > avail {
+        if elided {
+            parts.remove(2);
+        } else {
+            parts[1] = ELLIPSIS;
+            elided = true;
+        }
+    }
+
+    let mut spans = vec![Span::raw(" ")];
+    for (i, part) in parts.iter().enumerate() {
+        if i > 0 {
+            spans.push(Span::styled(SEPARATOR, theme.dim_style));
+        }
+        if i + 1 == parts.len() && i > 0 {
+            spans.push(Span::styled(part.to_string(), Style::default().add_modifier(Modifier::BOLD)));
+        } else {
+            spans.push(Span::raw(part.to_string()));
+        }
+    }
+    spans.push(Span::raw(" "));
+    Line::from(spans)
+}
+
+/// Dim bottom-right hint for backing out of the view, e.g. " Esc: clear filter "
+pub fn back_hint(text: &str, theme: &Theme) -> Line<'static> {
+    Line::from(Span::styled(format!(" {} ", text), theme.dim_style)).right_aligned()
+}
@@ -0,0 +1,90 @@
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Clear, Paragraph};
+use ratatui::Frame;
+
+use crate::app::export::Dataset;
+use crate::ui::text_input::TextInput;
+use crate::ui::theme::Theme;
+
+const ACCENT: Color = Color::Rgb(120, 220, 180);
+
+/// Export prompt (Ctrl+E, x): which data set, and the file to write
+pub struct ExportModal {
+    pub dataset: Dataset,
+    pub path: TextInput,
+}
+
+impl ExportModal {
+    pub fn new() -> Self {
+        let mut path = TextInput::new();
+        path.set(Dataset::Library.default_path());
+        Self { dataset: Dataset::Library, path }
+    }
+
+    /// Switch to the next data set, following along with the file name unless it was edited
+    pub fn cycle(&mut self) {
+        let next = self.dataset.next();
+        if self.path.as_str() == self.dataset.default_path() {
+            self.path.set(next.default_path());
+        }
+        self.dataset = next;
+    }
+}
+
+pub fn render_export_modal(frame: &mut Frame, area: Rect, modal: &ExportModal, theme: &Theme) {
+    let width = 60.min(area.width);
+    let height = 6.min(area.height);
+    let rect = Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    };
+
+    frame.render_widget(Clear, rect);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(ACCENT))
+        .title(" Export ")
+        .title_style(Style::default().fg(ACCENT).add_modifier(Modifier::BOLD));
+    let inner = block.inner(rect);
+    frame.render_widget(block, rect);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Length(1), Constraint::Min(1)])
+        .split(inner);
+    let dim = Style::default().fg(Color::DarkGray);
+
+    let mut spans = vec![Span::styled(" Data: ", Style::default().fg(ACCENT).add_modifier(Modifier::BOLD))];
+    for dataset in Dataset::ALL {
+        let style = if dataset == modal.dataset {
+            Style::default().bg(theme.highlight_bg).fg(theme.highlight_fg).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(theme.fg)
+        };
+        spans.push(Span::styled(format!(" {} ", dataset.name()), style));
+        spans.push(Span::raw(" "));
+    }
+    frame.render_widget(Paragraph::new(Line::from(spans)), chunks[0]);
+
+    let prompt = " File: ";
+    let [prompt_area, input_area] = Layout::horizontal([
+        Constraint::Length(prompt.len() as u16),
+        Constraint::Min(1),
+    ])
+    .areas(chunks[1]);
+    frame.render_widget(
+        Paragraph::new(Span::styled(prompt, Style::default().fg(ACCENT).add_modifier(Modifier::BOLD))),
+        prompt_area,
+    );
+    modal.path.render(frame, input_area, Style::default().fg(Color::White));
+
+    frame.render_widget(
+        Paragraph::new(Span::styled(" Tab: data set  Enter: export (.csv, else JSON)  Esc: cancel", dim)),
+        Rect { y: chunks[2].y + chunks[2].height.saturating_sub(1), height: 1, ..chunks[2] },
+    );
+}
@@ -8,26 +8,56 @@ use crate::ui::theme::Theme;
 
 const KEYBINDINGS: &[(&str, &str)] = &[
     ("Ctrl+E, s", "Search"),
+    ("Ctrl+E, S", "Repeat last search"),
     ("Ctrl+E, h", "Help (this modal)"),
     ("Ctrl+E, r", "Resize mode"),
     ("Ctrl+E, i", "About OMMP"),
     ("Ctrl+E, l", "Sync library"),
+    ("Ctrl+E, t", "Listening stats"),
+    ("Ctrl+E, n", "Analyze loudness (again to stop)"),
+    ("Ctrl+E, m", "Build mood playlists"),
+    ("Ctrl+E, N", "Night mode (compress loud peaks)"),
+    ("Ctrl+E, p", "Preview mode (preview on select)"),
+    ("Ctrl+E, w", "Sessions (saved queues)"),
+    ("Ctrl+E, x", "Export library / queue / history"),
+    ("Ctrl+E, c", "Clear album art / lyrics caches"),
     ("", ""),
     ("Space", "Play / Pause"),
+    ("S", "Stop"),
     ("n / N", "Next / Previous track"),
+    ("0", "Replay current track from the start"),
+    ("z", "Jump to a random queue entry"),
+    ("Ctrl+N / Ctrl+P", "Next / Previous album in queue"),
     ("+ / -", "Volume up / down"),
     ("\u{2192} / \u{2190}", "Seek forward / backward"),
+    ("Shift+\u{2192} / \u{2190}", "Long seek forward / backward"),
+    ("> / <", "Next / previous chapter (or jump)"),
     ("s", "Toggle shuffle"),
     ("r", "Cycle repeat mode"),
-    ("b", "Add to playlist"),
+    ("b", "Add selected track to playlist"),
+    ("B", "Add selected album / artist to playlist"),
+    ("R", "Start radio from selected track"),
+    ("P", "Preview selected track (10 s)"),
     ("", ""),
     ("1-7", "Switch tab"),
+    ("L", "Cycle layout preset"),
     ("Tab / Shift+Tab", "Cycle pane focus"),
     ("j / k", "Navigate list"),
+    ("PgUp / PgDn", "Move a screenful"),
     ("g / G", "Jump to first / last"),
     ("Enter", "Select / Activate"),
-    ("d", "Remove from queue"),
+    ("o", "Expand / collapse folder or album"),
+    ("/", "Filter Artists / Albums / Genre list"),
+    ("/ , n / N", "Search lyrics, next / previous match"),
+    ("v", "Lyrics: original / romanized / translated"),
+    ("a", "Insert after queue selection"),
+    ("T", "Batch edit tags / organize selection"),
+    ("y", "Copy file path"),
+    ("O", "Show file in Directories"),
+    ("d", "Remove from queue / playlist"),
+    ("J / K", "Move playlist entry down / up"),
     ("c", "Clear queue"),
+    ("u", "Remove duplicates from queue"),
     ("q", "Quit"),
 ];
 
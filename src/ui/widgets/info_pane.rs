@@ -1,5 +1,8 @@
-use std::path::{Path, PathBuf};
-use std::time::SystemTime;
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+
+use image::imageops::FilterType;
+use image::DynamicImage;
 
 use ratatui::layout::{Alignment, Rect};
 use ratatui::style::{Color, Modifier, Style};
@@ -13,100 +16,123 @@ use ratatui_image::StatefulImage;
 
 use crate::app::state::InfoView;
 use crate::app::App;
+use crate::library::cover::find_cover_image;
+use crate::localtime;
 use crate::ui::theme::Theme;
 
 // ── AlbumArtCache ────────────────────────────────────────────────────────
 
+/// Track directory + target size in cells
+type ArtKey = (PathBuf, (u16, u16));
+
+/// Scaled covers kept around so flipping between recent albums doesn't reload
+const MAX_CACHED_ART: usize = 8;
+
 pub struct AlbumArtCache {
-    track_dir: Option<PathBuf>,
     picker: Picker,
-    protocol: Option<StatefulProtocol>,
+    /// Scaled protocols for the current pane size, least recently shown first
+    /// (None = no cover found), with the decoded size of each in bytes
+    entries: Vec<(ArtKey, Option<StatefulProtocol>, usize)>,
+    /// Cover being decoded and downscaled on a background thread
+    pending: Option<(ArtKey, Receiver<Option<DynamicImage>>)>,
 }
 
 impl AlbumArtCache {
     pub fn new(picker: Picker) -> Self {
         Self {
-            track_dir: None,
             picker,
-            protocol: None,
+            entries: Vec::new(),
+            pending: None,
         }
     }
 
-    fn needs_reload(&self, dir: Option<&Path>) -> bool {
-        match (&self.track_dir, dir) {
-            (Some(a), Some(b)) => a != b,
-            (None, None) => false,
-            _ => true,
-        }
+    /// A cover is still being scaled in the background
+    pub fn is_loading(&self) -> bool {
+        self.pending.is_some()
     }
 
-    fn load(&mut self, dir: Option<&Path>) {
-        self.track_dir = dir.map(|d| d.to_path_buf());
-        self.protocol = None;
-
-        let dir = match dir {
-            Some(d) => d,
-            None => return,
-        };
-
-        let cover_path = match find_cover_image(dir) {
-            Some(p) => p,
-            None => return,
-        };
-
-        let img = match image::open(&cover_path) {
-            Ok(i) => i,
-            Err(_) => return,
-        };
+    /// Approximate memory held by the cached covers
+    pub fn bytes(&self) -> usize {
+        self.entries.iter().map(|(.., bytes)| bytes).sum()
+    }
 
-        // StatefulProtocol handles resizing automatically per-frame
-        self.protocol = Some(self.picker.new_resize_protocol(img));
+    /// Drop every cached cover; the shown one is reloaded on the next frame
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.pending = None;
     }
-}
 
-fn find_cover_image(dir: &Path) -> Option<PathBuf> {
-    let entries = std::fs::read_dir(dir).ok()?;
-    for entry in entries.flatten() {
-        let path = entry.path();
-        if !path.is_file() {
-            continue;
+    /// Return the cached protocol for `key`, starting a background load on a miss.
+    /// Outer None = still loading, inner None = directory has no cover.
+    fn get(&mut self, key: &ArtKey, budget: usize) -> Option<Option<&mut StatefulProtocol>> {
+        // Pane resized: every cached protocol was scaled for the old size
+        if self.entries.first().is_some_and(|((_, size), ..)| *size != key.1) {
+            self.entries.clear();
         }
-        // Check extension first (fast path)
-        if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
-            let l = ext.to_ascii_lowercase();
-            if l == "jpg" || l == "jpeg" || l == "png" {
-                return Some(path);
+
+        if let Some((pending_key, rx)) = &self.pending {
+            if pending_key == key {
+                match rx.try_recv() {
+                    Ok(img) => {
+                        let bytes = img.as_ref().map_or(0, |i| i.width() as usize * i.height() as usize * 4);
+                        let protocol = img.map(|i| self.picker.new_resize_protocol(i));
+                        self.insert(key.clone(), protocol, bytes, budget);
+                        self.pending = None;
+                    }
+                    Err(TryRecvError::Empty) => {}
+                    Err(TryRecvError::Disconnected) => {
+                        self.insert(key.clone(), None, 0, budget);
+                        self.pending = None;
+                    }
+                }
             }
         }
-        // No extension or unknown ext — check magic bytes
-        if is_image_by_magic(&path) {
-            return Some(path);
+
+        if let Some(i) = self.entries.iter().position(|(k, ..)| k == key) {
+            // Most recently shown goes last, so it's evicted last
+            let entry = self.entries.remove(i);
+            self.entries.push(entry);
+            let last = self.entries.len() - 1;
+            return Some(self.entries[last].1.as_mut());
+        }
+
+        if self.pending.as_ref().is_none_or(|(k, _)| k != key) {
+            self.spawn_load(key.clone());
         }
+        None
     }
-    None
-}
 
-/// Check file header bytes to detect JPEG/PNG regardless of extension.
-fn is_image_by_magic(path: &Path) -> bool {
-    use std::fs::File;
-    use std::io::Read;
-    let mut f = match File::open(path) {
-        Ok(f) => f,
-        Err(_) => return false,
-    };
-    let mut buf = [0u8; 8];
-    if f.read_exact(&mut buf).is_err() {
-        return false;
-    }
-    // JPEG: FF D8 FF
-    if buf[0] == 0xFF && buf[1] == 0xD8 && buf[2] == 0xFF {
-        return true;
+    /// Add a cover, evicting the least recently shown ones over the count or byte
+    /// budget (the new cover itself is always kept)
+    fn insert(&mut self, key: ArtKey, protocol: Option<StatefulProtocol>, bytes: usize, budget: usize) {
+        self.entries.push((key, protocol, bytes));
+        while self.entries.len() > 1 && (self.entries.len() > MAX_CACHED_ART || self.bytes() > budget) {
+            self.entries.remove(0);
+        }
     }
-    // PNG: 89 50 4E 47 0D 0A 1A 0A
-    if buf == [0x89, 0x50, 0x4E, 0x47, 0x0D, 0x0A, 0x1A, 0x0A] {
-        return true;
+
+    /// Decode and downscale the cover off the UI thread so huge images
+    /// never go through ratatui-image's per-frame resize at full size.
+    fn spawn_load(&mut self, key: ArtKey) {
+        let (tx, rx) = mpsc::channel();
+        let (dir, (cells_w, cells_h)) = key.clone();
+        let (fw, fh) = self.picker.font_size();
+        let max_w = cells_w as u32 * fw.max(1) as u32;
+        let max_h = cells_h as u32 * fh.max(1) as u32;
+        std::thread::spawn(move || {
+            let img = find_cover_image(&dir)
+                .and_then(|path| image::open(path).ok())
+                .map(|img| {
+                    if img.width() > max_w || img.height() > max_h {
+                        img.resize(max_w, max_h, FilterType::Triangle)
+                    } else {
+                        img
+                    }
+                });
+            let _ = tx.send(img);
+        });
+        self.pending = Some((key, rx));
     }
-    false
 }
 
 // ── Public render function ───────────────────────────────────────────────
@@ -160,24 +186,12 @@ const DIGIT_PATTERNS: [&[&str]; 11] = [
     &["    ", " ██ ", "    ", " ██ ", "    "],
 ];
 
-fn render_clock(frame: &mut Frame, area: Rect, _theme: &Theme) {
+pub fn render_clock(frame: &mut Frame, area: Rect, _theme: &Theme) {
     if area.width < 4 || area.height < 5 {
         return;
     }
 
-    let now = SystemTime::now()
-        .duration_since(SystemTime::UNIX_EPOCH)
-        .unwrap_or_default()
-        .as_secs();
-
-    let (hours, minutes, _) = {
-        let total_secs = now as i64;
-        let mut tm = unsafe { std::mem::zeroed::<libc::tm>() };
-        unsafe {
-            libc::localtime_r(&total_secs as *const i64, &mut tm);
-        }
-        (tm.tm_hour as u32, tm.tm_min as u32, tm.tm_sec as u32)
-    };
+    let (hours, minutes, _) = localtime::local_hms(localtime::now());
 
     // Build digit indices: HH:MM
     let digits = [
@@ -240,69 +254,73 @@ fn render_clock(frame: &mut Frame, area: Rect, _theme: &Theme) {
 
 // ── Album Art View ───────────────────────────────────────────────────────
 
-fn render_album_art(frame: &mut Frame, area: Rect, app: &App, cache: &mut AlbumArtCache) {
+pub fn render_album_art(frame: &mut Frame, area: Rect, app: &App, cache: &mut AlbumArtCache) {
     if area.width == 0 || area.height == 0 {
         return;
     }
 
-    let track_dir = app.current_track().and_then(|t| t.path.parent().map(|p| p.to_path_buf()));
+    let track_dir = match app.current_track().and_then(|t| t.path.parent()) {
+        Some(d) => d.to_path_buf(),
+        None => return render_no_art(frame, area),
+    };
 
-    if cache.needs_reload(track_dir.as_deref()) {
-        cache.load(track_dir.as_deref());
-    }
+    // Center the image area: most album art is square, so
+    // compute a centered sub-rect matching the aspect ratio.
+    let font = cache.picker.font_size();
+    // Terminal cell aspect ratio: font_w / font_h
+    // For a square image, we need w_cells * font_w == h_cells * font_h
+    let (fw, fh) = (font.0.max(1) as u32, font.1.max(1) as u32);
+    // Desired square in pixels: min(area.width * fw, area.height * fh)
+    let px_w = area.width as u32 * fw;
+    let px_h = area.height as u32 * fh;
+    let side = px_w.min(px_h);
+    let fit_w = ((side / fw) as u16).min(area.width);
+    let fit_h = ((side / fh) as u16).min(area.height);
+    let x_off = (area.width.saturating_sub(fit_w)) / 2;
+    let y_off = (area.height.saturating_sub(fit_h)) / 2;
+    let centered = Rect {
+        x: area.x + x_off,
+        y: area.y + y_off,
+        width: fit_w,
+        height: fit_h,
+    };
 
-    match cache.protocol {
-        Some(ref mut protocol) => {
-            // Center the image area: most album art is square, so
-            // compute a centered sub-rect matching the aspect ratio.
-            let font = cache.picker.font_size();
-            // Terminal cell aspect ratio: font_w / font_h
-            // For a square image, we need w_cells * font_w == h_cells * font_h
-            let (fw, fh) = (font.0.max(1) as u32, font.1.max(1) as u32);
-            // Desired square in pixels: min(area.width * fw, area.height * fh)
-            let px_w = area.width as u32 * fw;
-            let px_h = area.height as u32 * fh;
-            let side = px_w.min(px_h);
-            let fit_w = (side / fw) as u16;
-            let fit_h = (side / fh) as u16;
-            let x_off = (area.width.saturating_sub(fit_w)) / 2;
-            let y_off = (area.height.saturating_sub(fit_h)) / 2;
-            let centered = Rect {
-                x: area.x + x_off,
-                y: area.y + y_off,
-                width: fit_w.min(area.width),
-                height: fit_h.min(area.height),
-            };
+    let budget = (app.config.art_cache_mb * 1024.0 * 1024.0) as usize;
+    match cache.get(&(track_dir, (fit_w, fit_h)), budget) {
+        Some(Some(protocol)) => {
             let widget = StatefulImage::default();
             frame.render_stateful_widget(widget, centered, protocol);
         }
-        None => {
-            // No album art placeholder
-            let placeholder = vec![
-                Line::from(""),
-                Line::from(Span::styled(
-                    "\u{266A}",
-                    Style::default().fg(Color::DarkGray).add_modifier(Modifier::BOLD),
-                )),
-                Line::from(""),
-                Line::from(Span::styled("No Album Art", Style::default().fg(Color::DarkGray))),
-            ];
-            let para = Paragraph::new(placeholder).alignment(Alignment::Center);
-            let v_offset = if area.height > 4 { (area.height - 4) / 2 } else { 0 };
-            let centered = Rect {
-                x: area.x,
-                y: area.y + v_offset,
-                width: area.width,
-                height: area.height.saturating_sub(v_offset),
-            };
-            frame.render_widget(para, centered);
-        }
+        Some(None) => render_no_art(frame, area),
+        // Still scaling in the background; leave the area blank until it arrives
+        None => {}
     }
 }
 
+fn render_no_art(frame: &mut Frame, area: Rect) {
+    let placeholder = vec![
+        Line::from(""),
+        Line::from(Span::styled(
+            "\u{266A}",
+            Style::default().fg(Color::DarkGray).add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+        Line::from(Span::styled("No Album Art", Style::default().fg(Color::DarkGray))),
+    ];
+    let para = Paragraph::new(placeholder).alignment(Alignment::Center);
+    let v_offset = if area.height > 4 { (area.height - 4) / 2 } else { 0 };
+    let centered = Rect {
+        x: area.x,
+        y: area.y + v_offset,
+        width: area.width,
+        height: area.height.saturating_sub(v_offset),
+    };
+    frame.render_widget(para, centered);
+}
+
 // ── Track Info View ──────────────────────────────────────────────────────
 
-pub fn render_track_info(frame: &mut Frame, area: Rect, app: &App, theme: &Theme) {
+pub fn render_track_info(frame: &mut Frame, area: Rect, app: &App, theme: &Theme, scroll: u16) {
     let track = match app.current_track() {
         Some(t) => t,
         None => {
@@ -326,10 +344,11 @@ pub fn render_track_info(frame: &mut Frame, area: Rect, app: &App, theme: &Theme
         .map(|b| format!("{} kbps", b))
         .unwrap_or_else(|| "N/A".to_string());
 
-    let track_num_str = track
-        .track_number
-        .map(|n| n.to_string())
-        .unwrap_or_else(|| "N/A".to_string());
+    let track_num_str = match (track.track_number, track.disc_number) {
+        (Some(n), Some(d)) => format!("{} (disc {})", n, d),
+        (Some(n), None) => n.to_string(),
+        _ => "N/A".to_string(),
+    };
 
     let duration_str = track.format_duration();
 
@@ -346,7 +365,13 @@ pub fn render_track_info(frame: &mut Frame, area: Rect, app: &App, theme: &Theme
             Color::Rgb(200, 130, 255),
             Style::default().fg(Color::Rgb(220, 170, 255))),
         ("Album Artist",
-            if track.album_artist.is_empty() { "N/A".to_string() } else { track.album_artist.clone() },
+            if track.compilation {
+                format!("{} (compilation)", track.album_owner())
+            } else if track.album_artist.is_empty() {
+                "N/A".to_string()
+            } else {
+                track.album_artist.clone()
+            },
             Color::Rgb(200, 130, 255),
             Style::default().fg(theme.fg)),
         ("Genre",
@@ -359,15 +384,21 @@ pub fn render_track_info(frame: &mut Frame, area: Rect, app: &App, theme: &Theme
         ("Duration", duration_str,
             Color::Rgb(120, 220, 180),
             Style::default().fg(theme.fg)),
+        ("BPM", track.bpm.map(|b| format!("{}", b)).unwrap_or_else(|| "N/A".to_string()),
+            Color::Rgb(120, 220, 180),
+            Style::default().fg(theme.fg)),
         ("Bitrate", bitrate_str,
             Color::Rgb(255, 220, 100),
             Style::default().fg(theme.fg)),
         ("Format", format_ext,
             Color::Rgb(255, 220, 100),
             Style::default().fg(theme.fg)),
+        ("Quality", track.format_quality(),
+            Color::Rgb(255, 220, 100),
+            Style::default().fg(theme.fg)),
     ];
 
-    let lines: Vec<Line> = fields
+    let mut lines: Vec<Line> = fields
         .iter()
         .map(|(label, value, label_color, val_style)| {
             Line::from(vec![
@@ -377,6 +408,39 @@ pub fn render_track_info(frame: &mut Frame, area: Rect, app: &App, theme: &Theme
         })
         .collect();
 
-    let para = Paragraph::new(lines).wrap(Wrap { trim: false });
+    // Audiobook chapters with the current one marked (< / > to move between them)
+    if !app.playback.chapters.is_empty() {
+        let current = app.playback.current_chapter();
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            format!("{:>13}", "Chapters"),
+            Style::default().fg(Color::Rgb(120, 220, 180)),
+        )));
+        for (i, chapter) in app.playback.chapters.iter().enumerate() {
+            let (marker, style) = if current == Some(i) {
+                ("\u{F04B}", theme.current_track_style) // nf-fa-play
+            } else {
+                (" ", Style::default().fg(theme.fg))
+            };
+            lines.push(Line::from(vec![
+                Span::styled(format!("{:>9} ", format_chapter_time(chapter.start)), Style::default().fg(Color::DarkGray)),
+                Span::styled(format!("{} ", marker), style),
+                Span::styled(chapter.title.as_str(), style),
+            ]));
+        }
+    }
+
+    let para = Paragraph::new(lines).wrap(Wrap { trim: false }).scroll((scroll, 0));
     frame.render_widget(para, area);
 }
+
+/// 3725 → "1:02:05", 125 → "2:05"
+fn format_chapter_time(secs: f64) -> String {
+    let total = secs as u64;
+    let (h, m, s) = (total / 3600, total / 60 % 60, total % 60);
+    if h > 0 {
+        format!("{}:{:02}:{:02}", h, m, s)
+    } else {
+        format!("{}:{:02}", m, s)
+    }
+}
@@ -5,4 +5,11 @@ pub mod help_modal;
 pub mod search_modal;
 pub mod playlist_modal;
 pub mod about_modal;
+pub mod stats_modal;
+pub mod tag_modal;
+pub mod session_modal;
+pub mod export_modal;
+pub mod mode_overlay;
+pub mod breadcrumb;
 pub mod info_pane;
+pub mod screensaver;
@@ -0,0 +1,32 @@
+use ratatui::layout::{Alignment, Rect};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Clear, Paragraph};
+use ratatui::Frame;
+use unicode_width::UnicodeWidthStr;
+
+use crate::ui::theme::Theme;
+
+/// Brief centered box after a shuffle / repeat change, e.g. "Repeat: One"
+pub fn render_mode_overlay(frame: &mut Frame, area: Rect, text: &str, theme: &Theme) {
+    let width = (text.width() as u16 + 6).min(area.width);
+    let height = 3.min(area.height);
+    let rect = Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    };
+
+    frame.render_widget(Clear, rect);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.border_focused));
+    let para = Paragraph::new(Line::from(Span::styled(
+        text,
+        Style::default().fg(theme.fg).add_modifier(Modifier::BOLD),
+    )))
+    .alignment(Alignment::Center)
+    .block(block);
+    frame.render_widget(para, rect);
+}
@@ -3,8 +3,10 @@ use ratatui::style::{Color, Modifier, Style};
 use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, Borders, Clear, List, ListItem, Paragraph};
 use ratatui::Frame;
+use std::path::{Path, PathBuf};
 
 use crate::app::App;
+use crate::ui::text_input::TextInput;
 use crate::ui::theme::Theme;
 
 #[derive(Debug, Clone, PartialEq)]
@@ -14,12 +16,15 @@ pub enum PlaylistModalMode {
     Rename,
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn render_playlist_modal(
     frame: &mut Frame,
     area: Rect,
     selected: usize,
     mode: &PlaylistModalMode,
-    input: &str,
+    input: &TextInput,
+    target: Option<&(String, Vec<PathBuf>)>,
+    track: Option<&Path>,
     app: &App,
     theme: &Theme,
 ) {
@@ -27,10 +32,14 @@ pub fn render_playlist_modal(
 
     frame.render_widget(Clear, modal);
 
-    let title = match mode {
-        PlaylistModalMode::List => " Playlist ",
-        PlaylistModalMode::Create => " New Playlist ",
-        PlaylistModalMode::Rename => " Rename Playlist ",
+    let title = match (mode, target) {
+        (PlaylistModalMode::List, Some((name, paths))) => format!(" Add \"{}\" ({}) to Playlist ", name, paths.len()),
+        (PlaylistModalMode::List, None) => match track.and_then(|p| app.library.track_by_path(p)) {
+            Some(t) => format!(" Playlist \u{2014} {} ", t.title),
+            None => " Playlist ".to_string(),
+        },
+        (PlaylistModalMode::Create, _) => " New Playlist ".to_string(),
+        (PlaylistModalMode::Rename, _) => " Rename Playlist ".to_string(),
     };
 
     let block = Block::default()
@@ -54,15 +63,17 @@ pub fn render_playlist_modal(
                 .split(inner);
 
             let prompt = if *mode == PlaylistModalMode::Create { "Name:" } else { "New name:" };
-            let input_line = Line::from(vec![
-                Span::styled(
-                    format!(" {} ", prompt),
-                    Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
-                ),
-                Span::styled(input, Style::default().fg(Color::White)),
-                Span::styled("_", Style::default().fg(Color::Yellow).add_modifier(Modifier::SLOW_BLINK)),
-            ]);
-            frame.render_widget(Paragraph::new(input_line), chunks[0]);
+            let prompt = format!(" {} ", prompt);
+            let [prompt_area, input_area] = Layout::horizontal([
+                Constraint::Length(prompt.len() as u16),
+                Constraint::Min(1),
+            ])
+            .areas(chunks[0]);
+            frame.render_widget(
+                Paragraph::new(Span::styled(prompt, Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD))),
+                prompt_area,
+            );
+            input.render(frame, input_area, Style::default().fg(Color::White));
 
             let hint = Line::from(Span::styled(
                 " Enter: confirm  Esc: cancel",
@@ -92,17 +103,16 @@ pub fn render_playlist_modal(
                 return;
             }
 
-            let current_track_idx = app.queue.current_index
-                .and_then(|qi| app.queue.tracks.get(qi).copied());
-
             let items: Vec<ListItem> = app
                 .playlists
                 .iter()
                 .enumerate()
                 .map(|(i, pl)| {
                     let is_selected = i == selected;
-                    let already_in = current_track_idx
-                        .is_some_and(|ti| pl.tracks.contains(&ti));
+                    let already_in = match target {
+                        Some((_, paths)) => paths.iter().all(|p| pl.tracks.contains(p)),
+                        None => track.is_some_and(|p| pl.tracks.iter().any(|t| t == p)),
+                    };
 
                     let check = if already_in { "\u{F00C} " } else { "  " };
                     let icon = "\u{F005} ";
@@ -130,17 +140,25 @@ pub fn render_playlist_modal(
                         Style::default().fg(Color::DarkGray)
                     };
 
-                    ListItem::new(Line::from(vec![
+                    let mut spans = vec![
                         Span::styled(check, check_style),
                         Span::styled(icon, if is_selected { style } else { Style::default().fg(Color::Yellow) }),
                         Span::styled(&pl.name, style),
-                        Span::styled(format!(" ({})", pl.tracks.len()), count_style),
-                    ]))
+                        Span::styled(pl.count_label(&app.library), count_style),
+                    ];
+                    if pl.allow_duplicates {
+                        spans.push(Span::styled(" \u{F0C5}", count_style)); // nf-fa-copy
+                    }
+                    ListItem::new(Line::from(spans))
                 })
                 .collect();
 
             let hint = Line::from(Span::styled(
-                " Enter: toggle  a: add  d: delete  r: rename  Esc: close ",
+                if target.is_some() {
+                    " Enter: add all  u: duplicates  a: new  d: delete  r: rename  Esc: close "
+                } else {
+                    " Enter: toggle  x: remove  u: duplicates  a: add  d: delete  r: rename  Esc: close "
+                },
                 Style::default().fg(Color::DarkGray),
             ));
 
@@ -13,19 +13,10 @@ pub fn render_progress_bar(frame: &mut Frame, area: Rect, app: &App, theme: &The
         .borders(Borders::ALL)
         .border_style(Style::default().fg(theme.border_unfocused));
 
-    let inner = block.inner(area);
     frame.render_widget(block, area);
+    let cols = columns(area);
 
-    let cols = Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints([
-            Constraint::Length(3),  // Play icon
-            Constraint::Min(10),   // Gauge bar
-            Constraint::Length(13), // Time display
-        ])
-        .split(inner);
-
-    // Play icon
+    // Prev / play-pause / next, each clickable
     let icon = match app.playback.state {
         PlayState::Playing => "\u{F04B}",  // nf-fa-play
         PlayState::Paused => "\u{F04C}",   // nf-fa-pause
@@ -36,10 +27,11 @@ pub fn render_progress_bar(frame: &mut Frame, area: Rect, app: &App, theme: &The
         PlayState::Paused => Color::Rgb(255, 200, 80),
         PlayState::Stopped => Color::Rgb(255, 100, 100),
     };
-    let icon_widget = Paragraph::new(Line::from(Span::styled(
-        format!(" {}", icon),
-        Style::default().fg(icon_color),
-    )));
+    let icon_widget = Paragraph::new(Line::from(vec![
+        Span::styled(" \u{F048} ", Style::default().fg(Color::Gray)),  // nf-fa-step_backward
+        Span::styled(format!("{} ", icon), Style::default().fg(icon_color)),
+        Span::styled("\u{F051}", Style::default().fg(Color::Gray)),  // nf-fa-step_forward
+    ]));
     frame.render_widget(icon_widget, cols[0]);
 
     // Gauge
@@ -49,9 +41,19 @@ pub fn render_progress_bar(frame: &mut Frame, area: Rect, app: &App, theme: &The
         0.0
     };
 
+    // Audiobooks: current chapter name over the bar
+    let label = match app.playback.current_chapter() {
+        Some(i) => format!(
+            "{} ({}/{})",
+            app.playback.chapters[i].title,
+            i + 1,
+            app.playback.chapters.len()
+        ),
+        None => String::new(),
+    };
     let gauge = Gauge::default()
         .ratio(ratio)
-        .label("")
+        .label(Span::styled(label, Style::default().fg(Color::White)))
         .gauge_style(Style::default().fg(theme.progress_filled).bg(theme.progress_empty));
     frame.render_widget(gauge, cols[1]);
 
@@ -65,21 +67,40 @@ pub fn render_progress_bar(frame: &mut Frame, area: Rect, app: &App, theme: &The
     frame.render_widget(time_widget, cols[2]);
 }
 
-/// Returns the gauge area for mouse click seeking
-pub fn progress_gauge_area(area: Rect) -> Rect {
-    let block = Block::default().borders(Borders::ALL);
-    let inner = block.inner(area);
-
-    let cols = Layout::default()
+/// Transport controls, gauge bar and time display
+fn columns(area: Rect) -> std::rc::Rc<[Rect]> {
+    let inner = Block::default().borders(Borders::ALL).inner(area);
+    Layout::default()
         .direction(Direction::Horizontal)
         .constraints([
-            Constraint::Length(3),
-            Constraint::Min(10),
-            Constraint::Length(13),
+            Constraint::Length(7),  // Prev / play / next
+            Constraint::Min(10),   // Gauge bar
+            Constraint::Length(13), // Time display
         ])
-        .split(inner);
+        .split(inner)
+}
+
+/// Returns the gauge area for mouse click seeking
+pub fn progress_gauge_area(area: Rect) -> Rect {
+    columns(area)[1]
+}
+
+/// Which transport control column `x` is on: " ⏮ ▶ ⏭"
+pub fn transport_hit_test(area: Rect, x: u16) -> Option<TransportHit> {
+    let controls = columns(area)[0];
+    match x.checked_sub(controls.x)? {
+        0..=2 => Some(TransportHit::Prev),
+        3..=4 => Some(TransportHit::PlayPause),
+        5..=6 => Some(TransportHit::Next),
+        _ => None,
+    }
+}
 
-    cols[1]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransportHit {
+    Prev,
+    PlayPause,
+    Next,
 }
 
 fn format_time(secs: f64) -> String {
@@ -0,0 +1,89 @@
+use ratatui::layout::{Alignment, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Paragraph};
+use ratatui::Frame;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::app::state::PlayState;
+use crate::app::App;
+use crate::ui::theme::Theme;
+use crate::ui::widgets::info_pane::{self, AlbumArtCache};
+
+const BG: Color = Color::Rgb(10, 5, 30);
+/// Rows under the art: gap, 5-row clock, gap, title, details
+const INFO_ROWS: u16 = 9;
+/// Art smaller than this many rows isn't worth showing
+const MIN_ART_ROWS: u16 = 6;
+
+/// Startup splash for a resumed session (`art_splash`): the playing track's cover
+/// with what's about to pick up again under it
+pub fn render_art_splash(frame: &mut Frame, area: Rect, app: &App, cache: &mut AlbumArtCache) {
+    frame.render_widget(Block::default().style(Style::default().bg(BG)), area);
+    let art = Rect { height: area.height.saturating_sub(3), ..area };
+    info_pane::render_album_art(frame, art, app, cache);
+
+    let Some(track) = app.current_track() else { return };
+    let caption = Line::from(vec![
+        Span::styled("Resuming ", Style::default().fg(Color::DarkGray)),
+        Span::styled(track.title.as_str(), Style::default().fg(Color::White).add_modifier(Modifier::BOLD)),
+        Span::styled(format!(" \u{00b7} {}", track.display_artist()), Style::default().fg(Color::Gray)),
+    ]);
+    let caption_area = Rect { y: art.y + art.height + 1, height: 1, ..area };
+    frame.render_widget(Paragraph::new(caption).alignment(Alignment::Center), caption_area);
+}
+
+/// Full-screen idle view (`screensaver_minutes`): the playing track's cover, a large
+/// clock and what's playing. Everything sits in a box smaller than the screen that
+/// moves every minute, so nothing stays lit in one place on an OLED panel.
+pub fn render_screensaver(frame: &mut Frame, area: Rect, app: &App, theme: &Theme, cache: &mut AlbumArtCache) {
+    frame.render_widget(Block::default().style(Style::default().bg(BG)), area);
+    let track = app.current_track();
+
+    let width = (area.width * 2 / 3).max(24).min(area.width);
+    let art_rows = (area.height * 3 / 4).saturating_sub(INFO_ROWS);
+    let art_rows = if track.is_some() && art_rows >= MIN_ART_ROWS { art_rows } else { 0 };
+    let height = (art_rows + INFO_ROWS).min(area.height);
+
+    let minute = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs() / 60);
+    let x = area.x + (minute * 7 % (area.width - width + 1) as u64) as u16;
+    let y = area.y + (minute * 3 % (area.height - height + 1) as u64) as u16;
+    let content = Rect { x, y, width, height };
+
+    if art_rows > 0 {
+        info_pane::render_album_art(frame, Rect { height: art_rows, ..content }, app, cache);
+    }
+    let clock = Rect { y: content.y + art_rows + 1, height: 5, ..content };
+    info_pane::render_clock(frame, clock, theme);
+
+    let Some(track) = track else { return };
+    let state = match app.playback.state {
+        PlayState::Playing => "\u{25B6}",
+        PlayState::Paused => "\u{23F8}",
+        PlayState::Stopped => "\u{25A0}",
+    };
+    let lines = vec![
+        Line::from(Span::styled(
+            track.title.as_str(),
+            Style::default().fg(Color::White).add_modifier(Modifier::BOLD),
+        )),
+        Line::from(Span::styled(
+            format!(
+                "{} \u{00b7} {}   {} {} / {}",
+                track.display_artist(),
+                track.display_album(),
+                state,
+                format_time(app.playback.position_secs),
+                format_time(app.playback.duration_secs),
+            ),
+            Style::default().fg(Color::Gray),
+        )),
+    ];
+    let info = Rect { y: clock.y + clock.height + 1, height: 2, ..content };
+    frame.render_widget(Paragraph::new(lines).alignment(Alignment::Center), info.intersection(area));
+}
+
+fn format_time(secs: f64) -> String {
+    let total = secs as u64;
+    format!("{}:{:02}", total / 60, total % 60)
+}
@@ -4,7 +4,9 @@ use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, Borders, Clear, List, ListItem, Paragraph, Scrollbar, ScrollbarOrientation, ScrollbarState};
 use ratatui::Frame;
 
+use crate::app::state::SearchOrder;
 use crate::app::App;
+use crate::ui::text_input::TextInput;
 use crate::ui::theme::Theme;
 
 const HOVER_BG: Color = Color::Indexed(238);
@@ -13,11 +15,13 @@ const HOVER_BG: Color = Color::Indexed(238);
 pub fn render_search_modal(
     frame: &mut Frame,
     area: Rect,
-    input: &str,
+    input: &TextInput,
     results: &[usize],
     selected: usize,
     scroll: usize,
     hover_row: Option<usize>,
+    completion: Option<&(String, Vec<String>, usize)>,
+    order: SearchOrder,
     app: &App,
     theme: &Theme,
 ) -> (usize, Rect) {
@@ -28,8 +32,23 @@ pub fn render_search_modal(
     let block = Block::default()
         .borders(Borders::ALL)
         .border_style(Style::default().fg(Color::Cyan))
-        .title(" Search ")
-        .title_style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD));
+        .title(match order {
+            SearchOrder::Relevance => " Search ".to_string(),
+            o => format!(" Search \u{00b7} by {} ", o.as_str().to_lowercase()),
+        })
+        .title_style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD))
+        .title_bottom(Line::from(Span::styled(
+            match completion {
+                Some((field, names, pos)) => format!(
+                    " Tab / Shift+Tab: next / previous {} ({}/{}) ",
+                    field,
+                    pos + 1,
+                    names.len()
+                ),
+                None => " Enter: play  ^A: append  ^N: play next  ^B: playlist  ^G: album  ^D: folder  ^T: tags  ^O: order ".to_string(),
+            },
+            Style::default().fg(Color::DarkGray),
+        )));
 
     let inner = block.inner(modal);
     frame.render_widget(block, modal);
@@ -45,12 +64,12 @@ pub fn render_search_modal(
         .split(inner);
 
     // Input line with cursor
-    let input_line = Line::from(vec![
-        Span::styled(" > ", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
-        Span::styled(input, Style::default().fg(Color::White)),
-        Span::styled("_", Style::default().fg(Color::Cyan).add_modifier(Modifier::SLOW_BLINK)),
-    ]);
-    frame.render_widget(Paragraph::new(input_line), chunks[0]);
+    let [prompt_area, input_area] = Layout::horizontal([Constraint::Length(3), Constraint::Min(1)]).areas(chunks[0]);
+    frame.render_widget(
+        Paragraph::new(Span::styled(" > ", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD))),
+        prompt_area,
+    );
+    input.render(frame, input_area, Style::default().fg(Color::White));
 
     // Separator
     let sep = "─".repeat(chunks[1].width as usize);
@@ -65,7 +84,7 @@ pub fn render_search_modal(
 
     if results.is_empty() {
         let msg = if input.is_empty() {
-            "Type to search..."
+            "Type to search... (\u{2191} for recent searches)"
         } else {
             "No results found"
         };
@@ -87,9 +106,24 @@ pub fn render_search_modal(
                 let is_selected = i == selected;
                 let is_hovered = hover_row == Some(i);
 
-                let artist = track.display_artist();
-                let title_w = (result_width * 55 / 100).max(4);
-                let artist_w = result_width.saturating_sub(title_w + 3); // 3 = prefix + gap
+                // Grouped orders name the artist / album on the first row of each group
+                // (and at the top of the view) instead of every row
+                let group = |idx: usize| {
+                    let t = &app.library.tracks[idx];
+                    match order {
+                        SearchOrder::Album => t.display_album(),
+                        _ => t.display_artist(),
+                    }
+                };
+                let group_start = order.groups()
+                    && (i == scroll || i == 0 || group(results[i - 1]) != group(track_idx));
+                let artist = if order.groups() && !group_start { "" } else { group(track_idx) };
+                // Sorted by tempo: the BPM on the right
+                let bpm = (order == SearchOrder::Bpm)
+                    .then(|| track.bpm.map(|b| format!("{:>4.0} ", b)).unwrap_or_else(|| "   - ".to_string()));
+                let bpm_w = bpm.as_ref().map_or(0, |b| b.len());
+                let title_w = (result_width.saturating_sub(bpm_w) * 55 / 100).max(4);
+                let artist_w = result_width.saturating_sub(title_w + 3 + bpm_w); // 3 = prefix + gap
 
                 let title_fitted = fit_to_width(&track.title, title_w);
                 let artist_fitted = fit_to_width(artist, artist_w);
@@ -111,14 +145,23 @@ pub fn render_search_modal(
                         Style::default().fg(Color::Gray),
                     )
                 };
+                let artist_style = if group_start && !is_selected {
+                    artist_style.fg(Color::Cyan).add_modifier(Modifier::BOLD)
+                } else {
+                    artist_style
+                };
 
                 let prefix = if is_selected { " > " } else { "   " };
 
-                ListItem::new(Line::from(vec![
+                let mut spans = vec![
                     Span::styled(prefix, style),
                     Span::styled(title_fitted, style),
                     Span::styled(artist_fitted, artist_style),
-                ]))
+                ];
+                if let Some(bpm) = bpm {
+                    spans.push(Span::styled(bpm, artist_style));
+                }
+                ListItem::new(Line::from(spans))
             })
             .collect();
 
@@ -0,0 +1,133 @@
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Clear, List, ListItem, Paragraph};
+use ratatui::Frame;
+
+use crate::app::App;
+use crate::ui::text_input::TextInput;
+use crate::ui::theme::Theme;
+
+const ACCENT: Color = Color::Rgb(120, 220, 180);
+
+/// Named sessions list (Ctrl+E, w): restore, save the current queue, delete
+pub struct SessionModal {
+    pub selected: usize,
+    /// Name being typed for "save as"
+    pub naming: Option<TextInput>,
+}
+
+impl SessionModal {
+    pub fn new(app: &App) -> Self {
+        // Start on the session that's loaded
+        let selected = app
+            .active_session
+            .as_ref()
+            .and_then(|name| app.sessions.iter().position(|s| &s.name == name))
+            .unwrap_or(0);
+        Self { selected, naming: None }
+    }
+}
+
+pub fn render_session_modal(frame: &mut Frame, area: Rect, modal: &SessionModal, app: &App, theme: &Theme) {
+    let rect = centered_rect(50, 45, area);
+
+    frame.render_widget(Clear, rect);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(ACCENT))
+        .title(if modal.naming.is_some() { " Save Session As " } else { " Sessions " })
+        .title_style(Style::default().fg(ACCENT).add_modifier(Modifier::BOLD));
+
+    let inner = block.inner(rect);
+    frame.render_widget(block, rect);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(1), Constraint::Length(1)])
+        .split(inner);
+    let dim = Style::default().fg(Color::DarkGray);
+
+    if let Some(input) = &modal.naming {
+        let prompt = " Name: ";
+        let [prompt_area, input_area] = Layout::horizontal([
+            Constraint::Length(prompt.len() as u16),
+            Constraint::Min(1),
+        ])
+        .areas(Rect { height: 1, ..chunks[0] });
+        frame.render_widget(
+            Paragraph::new(Span::styled(prompt, Style::default().fg(ACCENT).add_modifier(Modifier::BOLD))),
+            prompt_area,
+        );
+        input.render(frame, input_area, Style::default().fg(Color::White));
+        frame.render_widget(
+            Paragraph::new(Span::styled(" Enter: save (replaces a session of the same name)  Esc: cancel", dim)),
+            chunks[1],
+        );
+        return;
+    }
+
+    if app.sessions.is_empty() {
+        frame.render_widget(
+            Paragraph::new(Span::styled("  No saved sessions. Press 'a' to save the current queue.", dim)),
+            chunks[0],
+        );
+    } else {
+        let items: Vec<ListItem> = app
+            .sessions
+            .iter()
+            .enumerate()
+            .map(|(i, session)| {
+                let is_selected = i == modal.selected;
+                let is_active = app.active_session.as_ref() == Some(&session.name);
+                let style = if is_selected {
+                    Style::default().bg(theme.highlight_bg).fg(theme.highlight_fg).add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default().fg(theme.fg)
+                };
+                let detail_style = if is_selected { style } else { dim };
+                let marker = if is_active { "\u{F04B} " } else { "  " };
+                let playing = session
+                    .current_index
+                    .and_then(|ci| session.tracks.get(ci))
+                    .and_then(|p| app.library.track_by_path(p))
+                    .map(|t| format!(" \u{00b7} {}", t.title))
+                    .unwrap_or_default();
+                ListItem::new(Line::from(vec![
+                    Span::styled(marker, if is_selected { style } else { theme.current_track_style }),
+                    Span::styled(session.name.clone(), style),
+                    Span::styled(format!(" ({} tracks{})", session.tracks.len(), playing), detail_style),
+                ]))
+            })
+            .collect();
+        frame.render_widget(List::new(items), chunks[0]);
+    }
+    frame.render_widget(
+        Paragraph::new(Span::styled(
+            " Enter: restore  a: save as  s: save over  d: delete  Esc: close ",
+            dim,
+        )),
+        chunks[1],
+    );
+}
+
+fn centered_rect(percent_x: u16, percent_y: u16, area: Rect) -> Rect {
+    let vertical = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Percentage((100 - percent_y) / 2),
+            Constraint::Percentage(percent_y),
+            Constraint::Percentage((100 - percent_y) / 2),
+        ])
+        .split(area);
+
+    Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage((100 - percent_x) / 2),
+            Constraint::Percentage(percent_x),
+            Constraint::Percentage((100 - percent_x) / 2),
+        ])
+        .split(vertical[1])[1]
+}
@@ -0,0 +1,147 @@
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Clear, Paragraph};
+use ratatui::Frame;
+use unicode_width::UnicodeWidthStr;
+
+use crate::app::stats::{format_listening, Entry, Period, Stats};
+use crate::ui::theme::Theme;
+
+const ACCENT: Color = Color::Rgb(100, 220, 255);
+const BAR: Color = Color::Rgb(120, 255, 180);
+const BAR_WIDTH: usize = 24;
+
+pub fn render_stats_modal(frame: &mut Frame, area: Rect, period: Period, stats: &Stats, theme: &Theme) {
+    let modal = centered_rect(80, 80, area);
+
+    frame.render_widget(Clear, modal);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(ACCENT))
+        .title(format!(" Listening Stats \u{2014} {} ", period.label()))
+        .title_style(Style::default().fg(ACCENT).add_modifier(Modifier::BOLD))
+        .title_bottom(Line::from(Span::styled(
+            " h/l: period  e: export JSON  v: export CSV  Esc: close ",
+            Style::default().fg(Color::DarkGray),
+        )));
+
+    let inner = block.inner(modal);
+    frame.render_widget(block, modal);
+
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(2), Constraint::Min(1)])
+        .split(inner);
+
+    let summary = Line::from(vec![
+        Span::styled(format!(" {} ", format_listening(stats.secs)), Style::default().fg(ACCENT).add_modifier(Modifier::BOLD)),
+        Span::styled("listened  ", Style::default().fg(Color::Gray)),
+        Span::styled(format!("{} ", stats.plays), Style::default().fg(ACCENT).add_modifier(Modifier::BOLD)),
+        Span::styled("plays  ", Style::default().fg(Color::Gray)),
+        Span::styled(format!("{} ", stats.distinct_tracks), Style::default().fg(ACCENT).add_modifier(Modifier::BOLD)),
+        Span::styled("tracks", Style::default().fg(Color::Gray)),
+    ]);
+    frame.render_widget(Paragraph::new(summary), rows[0]);
+
+    if stats.plays == 0 {
+        frame.render_widget(
+            Paragraph::new(Line::from(Span::styled(
+                "  Nothing played in this period yet.",
+                Style::default().fg(Color::DarkGray),
+            ))),
+            rows[1],
+        );
+        return;
+    }
+
+    let cols = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(45), Constraint::Percentage(55)])
+        .split(rows[1]);
+
+    // Left: listening time per day/week/month/year, then formats
+    let max = stats.timeline.iter().map(|e| e.secs).fold(0.0, f64::max);
+    let mut left = vec![section("Listening time")];
+    for e in &stats.timeline {
+        let filled = if max > 0.0 { (e.secs / max * BAR_WIDTH as f64).round() as usize } else { 0 };
+        left.push(Line::from(vec![
+            Span::styled(format!("  {:<9}", e.name), Style::default().fg(theme.fg)),
+            Span::styled("\u{2588}".repeat(filled), Style::default().fg(BAR)),
+            Span::styled("\u{2591}".repeat(BAR_WIDTH - filled), Style::default().fg(Color::Indexed(238))),
+            Span::styled(format!(" {}", format_listening(e.secs)), Style::default().fg(Color::DarkGray)),
+        ]));
+    }
+    left.push(Line::from(""));
+    left.push(section("Formats"));
+    for e in &stats.formats {
+        let pct = e.plays as f64 * 100.0 / stats.plays as f64;
+        left.push(Line::from(vec![
+            Span::styled(format!("  {:<6}", e.name), Style::default().fg(Color::Rgb(255, 220, 100))),
+            Span::styled(format!("{:>5.1}%  ", pct), Style::default().fg(theme.fg)),
+            Span::styled(format!("{} plays", e.plays), Style::default().fg(Color::DarkGray)),
+        ]));
+    }
+    frame.render_widget(Paragraph::new(left), cols[0]);
+
+    // Right: top artists, albums and tracks
+    let width = cols[1].width as usize;
+    let mut right = Vec::new();
+    for (title, entries) in [
+        ("Top artists", &stats.top_artists),
+        ("Top albums", &stats.top_albums),
+        ("Top tracks", &stats.top_tracks),
+    ] {
+        right.push(section(title));
+        right.extend(entries.iter().take(5).enumerate().map(|(i, e)| ranked_line(i, e, width, theme)));
+        right.push(Line::from(""));
+    }
+    frame.render_widget(Paragraph::new(right), cols[1]);
+}
+
+fn section(title: &str) -> Line<'static> {
+    Line::from(Span::styled(
+        format!(" {}", title),
+        Style::default().fg(Color::Rgb(200, 170, 255)).add_modifier(Modifier::BOLD),
+    ))
+}
+
+/// "  1. Name ........ 12 plays"
+fn ranked_line(i: usize, e: &Entry, width: usize, theme: &Theme) -> Line<'static> {
+    let count = format!(" {} plays", e.plays);
+    let avail = width.saturating_sub(6 + count.len());
+    let mut name = String::new();
+    for ch in e.name.chars() {
+        if name.width() + ch.to_string().width() > avail {
+            name.push('\u{2026}');
+            break;
+        }
+        name.push(ch);
+    }
+    Line::from(vec![
+        Span::styled(format!("  {:>2}. ", i + 1), Style::default().fg(Color::DarkGray)),
+        Span::styled(name, Style::default().fg(theme.fg)),
+        Span::styled(count, Style::default().fg(Color::DarkGray)),
+    ])
+}
+
+fn centered_rect(percent_x: u16, percent_y: u16, area: Rect) -> Rect {
+    let vertical = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Percentage((100 - percent_y) / 2),
+            Constraint::Percentage(percent_y),
+            Constraint::Percentage((100 - percent_y) / 2),
+        ])
+        .split(area);
+
+    Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage((100 - percent_x) / 2),
+            Constraint::Percentage(percent_x),
+            Constraint::Percentage((100 - percent_x) / 2),
+        ])
+        .split(vertical[1])[1]
+}
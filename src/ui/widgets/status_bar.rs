@@ -3,9 +3,11 @@ use ratatui::style::{Color, Modifier, Style};
 use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, Borders, Paragraph};
 use ratatui::Frame;
+use unicode_width::UnicodeWidthStr;
 
 use crate::app::App;
 use crate::app::state::{PlayState, SyncState};
+use crate::library::track::format_stream;
 use crate::ui::theme::Theme;
 
 pub fn render_status_bar(frame: &mut Frame, area: Rect, app: &App, theme: &Theme, resize_mode: bool) {
@@ -21,23 +23,38 @@ pub fn render_status_bar(frame: &mut Frame, area: Rect, app: &App, theme: &Theme
             .border_style(Style::default().fg(Color::Rgb(255, 200, 80)))
             .title(" [SYNCING] ")
             .title_style(Style::default().fg(Color::Rgb(255, 200, 80)).add_modifier(Modifier::BOLD))
+    } else if let Some(job) = &app.loudness_job {
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Rgb(255, 200, 80)))
+            .title(format!(" [LOUDNESS {}/{}] ", job.done, job.total))
+            .title_style(Style::default().fg(Color::Rgb(255, 200, 80)).add_modifier(Modifier::BOLD))
+    } else if app.library.scan_stats.root_offline && app.initial_scan_complete {
+        // Music directory unreadable at the last scan (unmounted drive, share down)
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Rgb(255, 100, 100)))
+            .title(" [OFFLINE] ")
+            .title_style(Style::default().fg(Color::Rgb(255, 100, 100)).add_modifier(Modifier::BOLD))
     } else {
         Block::default()
             .borders(Borders::ALL)
             .border_style(Style::default().fg(theme.border_unfocused))
     };
+    // Action feedback (e.g. "Already in Bookmarks") on the right of the top border
+    let block = match &app.status_message {
+        Some((msg, _)) => block.title(
+            Line::from(Span::styled(
+                format!(" {} ", msg),
+                Style::default().fg(Color::Rgb(100, 220, 255)).add_modifier(Modifier::BOLD),
+            ))
+            .right_aligned(),
+        ),
+        None => block,
+    };
 
-    let inner = block.inner(area);
     frame.render_widget(block, area);
-
-    let cols = Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints([
-            Constraint::Percentage(25),
-            Constraint::Percentage(50),
-            Constraint::Percentage(25),
-        ])
-        .split(inner);
+    let cols = columns(area);
 
     // Left: Play state + time
     let state_icon = match app.playback.state {
@@ -61,7 +78,18 @@ pub fn render_status_bar(frame: &mut Frame, area: Rect, app: &App, theme: &Theme
         PlayState::Stopped => Color::Rgb(255, 100, 100),  // soft red
     };
 
-    let left_line1 = Line::from(vec![
+    // Codec/stream format: decoded values from the player, tag properties until they arrive
+    let quality = app.current_track().map(|t| {
+        let ext = t.path.extension()
+            .map(|e| e.to_string_lossy().to_uppercase())
+            .unwrap_or_default();
+        match app.playback.stream_format {
+            Some(f) => format_stream(&ext, Some(f.sample_rate), f.bits.or(t.bit_depth), Some(f.channels)),
+            None => t.format_quality(),
+        }
+    });
+
+    let mut left_line1_spans = vec![
         Span::styled(
             format!(" {} {}", state_icon, match app.playback.state {
                 PlayState::Playing => "Playing",
@@ -70,14 +98,36 @@ pub fn render_status_bar(frame: &mut Frame, area: Rect, app: &App, theme: &Theme
             }),
             Style::default().fg(state_color).add_modifier(Modifier::BOLD),
         ),
-    ]);
+    ];
+    if let Some(q) = quality.filter(|q| !q.is_empty()) {
+        left_line1_spans.push(Span::styled(format!("  {}", q), Style::default().fg(Color::Rgb(255, 220, 100))));
+    }
+    let left_line1 = Line::from(left_line1_spans);
 
-    let left_line2 = Line::from(vec![
+    let mut left_line2_spans = vec![
         Span::styled(
             format!(" {}/{}{}", pos, dur, bitrate),
             Style::default().fg(Color::Gray),
         ),
-    ]);
+    ];
+    // Active device rate; green when bit-perfect (native rate, no resampling; the
+    // player skips all processing in that mode)
+    if let Some((rate, exact)) = app.playback.output_format {
+        let rate_style = if exact && app.config.bit_perfect {
+            Style::default().fg(Color::Rgb(80, 255, 120))
+        } else {
+            Style::default().fg(Color::DarkGray)
+        };
+        left_line2_spans.push(Span::styled(format!(" {}", format_rate(rate)), rate_style));
+    }
+    // Queue position, e.g. "track 37/214" (the queue row played, also under shuffle)
+    if let Some(qi) = app.queue.current_index.filter(|&qi| qi < app.queue.tracks.len()) {
+        left_line2_spans.push(Span::styled(
+            format!("  track {}/{}", qi + 1, app.queue.tracks.len()),
+            Style::default().fg(Color::Rgb(100, 220, 255)),
+        ));
+    }
+    let left_line2 = Line::from(left_line2_spans);
 
     let left = Paragraph::new(vec![left_line1, left_line2]);
     frame.render_widget(left, cols[0]);
@@ -117,7 +167,9 @@ pub fn render_status_bar(frame: &mut Frame, area: Rect, app: &App, theme: &Theme
     let repeat_style = match app.playback.repeat {
         crate::app::state::RepeatMode::Off => Style::default().fg(Color::DarkGray),
         crate::app::state::RepeatMode::All => Style::default().fg(Color::Rgb(120, 255, 180)).add_modifier(Modifier::BOLD),
-        crate::app::state::RepeatMode::One => Style::default().fg(Color::Rgb(255, 220, 100)).add_modifier(Modifier::BOLD),
+        crate::app::state::RepeatMode::One | crate::app::state::RepeatMode::Count => {
+            Style::default().fg(Color::Rgb(255, 220, 100)).add_modifier(Modifier::BOLD)
+        }
     };
 
     // Volume staircase with gradient: green → yellow → orange → red
@@ -133,7 +185,16 @@ pub fn render_status_bar(frame: &mut Frame, area: Rect, app: &App, theme: &Theme
         Color::Rgb(255, 70, 70),   // red
     ];
     let filled = (vol_pct as u16 * 8 / 100).min(8) as usize;
-    let mut vol_spans = Vec::with_capacity(10);
+    let mut vol_spans = Vec::with_capacity(12);
+    if app.preview_mode || app.preview.is_some() {
+        // nf-fa-headphones, lit while a preview plays
+        let color = if app.preview.is_some() { Color::Rgb(120, 220, 180) } else { Color::DarkGray };
+        vol_spans.push(Span::styled("\u{F025} ", Style::default().fg(color)));
+    }
+    if app.playback.night_mode {
+        // nf-md-weather_night
+        vol_spans.push(Span::styled("\u{F0594} ", Style::default().fg(Color::Rgb(200, 170, 255))));
+    }
     for (i, &ch) in STEPS.iter().enumerate() {
         let style = if i < filled {
             Style::default().fg(vol_colors[i]).add_modifier(Modifier::BOLD)
@@ -148,8 +209,8 @@ pub fn render_status_bar(frame: &mut Frame, area: Rect, app: &App, theme: &Theme
 
     let is_bookmarked = app.queue.current_index
         .and_then(|qi| app.queue.tracks.get(qi))
-        .is_some_and(|&ti| {
-            app.playlists.iter().any(|pl| pl.tracks.contains(&ti))
+        .is_some_and(|p| {
+            app.playlists.iter().any(|pl| pl.tracks.contains(p))
         });
     let bookmark_style = if is_bookmarked {
         Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
@@ -160,13 +221,76 @@ pub fn render_status_bar(frame: &mut Frame, area: Rect, app: &App, theme: &Theme
     let right_line2 = Line::from(vec![
         Span::styled("\u{F005} ", bookmark_style),  // nf-fa-star
         Span::styled("\u{F074} ", shuffle_style),   // nf-fa-random
-        Span::styled(format!("{} ", app.playback.repeat.symbol()), repeat_style),
+        Span::styled(format!("{} ", repeat_label(app)), repeat_style),
     ]).alignment(Alignment::Right);
 
     let right = Paragraph::new(vec![right_line1, right_line2]);
     frame.render_widget(right, cols[2]);
 }
 
+/// Repeat icon, with the plays still to come in repeat-count mode ("×2")
+fn repeat_label(app: &App) -> String {
+    let symbol = app.playback.repeat.symbol();
+    if app.playback.repeat == crate::app::state::RepeatMode::Count {
+        format!("{}\u{00d7}{}", symbol, app.playback.repeats_left)
+    } else {
+        symbol.to_string()
+    }
+}
+
+/// Play state, track info and volume/mode columns
+fn columns(area: Rect) -> std::rc::Rc<[Rect]> {
+    let inner = Block::default().borders(Borders::ALL).inner(area);
+    Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage(25),
+            Constraint::Percentage(50),
+            Constraint::Percentage(25),
+        ])
+        .split(inner)
+}
+
+/// Clickable parts of the status bar
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatusHit {
+    PlayPause,
+    Bookmark,
+    Shuffle,
+    Repeat,
+}
+
+/// What's under (x, y): the play state on the left, or the star / shuffle / repeat
+/// icons, which sit right-aligned on the second line of the right column
+pub fn status_hit_test(area: Rect, app: &App, x: u16, y: u16) -> Option<StatusHit> {
+    let cols = columns(area);
+    let (left, right) = (cols[0], cols[2]);
+    if y == left.y && x >= left.x && x < left.x + left.width {
+        return Some(StatusHit::PlayPause);
+    }
+    if y != right.y + 1 {
+        return None;
+    }
+    let repeat_width = repeat_label(app).width() as u16 + 1;
+    let end = right.x + right.width;
+    let start = end.checked_sub(4 + repeat_width)?;
+    match x.checked_sub(start)? {
+        0..=1 => Some(StatusHit::Bookmark),
+        2..=3 => Some(StatusHit::Shuffle),
+        n if n < 4 + repeat_width => Some(StatusHit::Repeat),
+        _ => None,
+    }
+}
+
+/// 44100 → "44.1kHz", 96000 → "96kHz"
+fn format_rate(hz: u32) -> String {
+    if hz.is_multiple_of(1000) {
+        format!("{}kHz", hz / 1000)
+    } else {
+        format!("{:.1}kHz", hz as f64 / 1000.0)
+    }
+}
+
 fn format_time(secs: f64) -> String {
     let total = secs as u64;
     let m = total / 60;
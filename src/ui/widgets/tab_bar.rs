@@ -1,12 +1,92 @@
-use ratatui::layout::{Alignment, Rect};
+use ratatui::layout::Rect;
 use ratatui::style::{Color, Modifier, Style};
 use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, Borders, Paragraph};
 use ratatui::Frame;
+use unicode_width::UnicodeWidthStr;
 
 use crate::app::state::Tab;
 use crate::ui::theme::Theme;
 
+const DIVIDER: &str = " \u{2502} ";
+const MORE_LEFT: &str = "\u{2039} ";
+const MORE_RIGHT: &str = " \u{203A}";
+
+/// Each tab has its own accent color
+const TAB_COLORS: [Color; 7] = [
+    Color::Rgb(100, 220, 255), // Queue - sky blue
+    Color::Rgb(120, 255, 180), // Directories - mint green
+    Color::Rgb(255, 180, 100), // Artists - orange
+    Color::Rgb(200, 130, 255), // Albums - purple
+    Color::Rgb(255, 120, 150), // Genre - pink
+    Color::Rgb(150, 200, 120), // Format - olive
+    Color::Rgb(255, 220, 100), // Playlists - gold
+];
+
+/// Tabs that fit on screen and where they go
+struct TabLayout {
+    /// (tab index, x offset from the inner area, label)
+    tabs: Vec<(usize, u16, String)>,
+    /// Tabs hidden to the left / right when scrolled
+    more_left: bool,
+    more_right: bool,
+    /// Centering offset of the whole line
+    pad: u16,
+}
+
+/// Full titles if they fit, then "1 Que"-style abbreviations, then a window of
+/// abbreviated tabs scrolled to keep the current one visible
+fn layout(width: u16, current: Tab) -> TabLayout {
+    let width = width as usize;
+    let full: Vec<String> = Tab::ALL.iter().map(|t| t.title().to_string()).collect();
+    let short: Vec<String> = Tab::ALL.iter()
+        .enumerate()
+        .map(|(i, t)| format!("{} {}", i + 1, t.title().chars().take(3).collect::<String>()))
+        .collect();
+    let line_width = |labels: &[String]| -> usize {
+        labels.iter().map(|l| l.width()).sum::<usize>() + DIVIDER.width() * labels.len().saturating_sub(1)
+    };
+
+    let labels = if line_width(&full) <= width { full } else { short };
+    let (mut first, mut last) = (0, labels.len() - 1);
+    let cur = current.index();
+    // Drop tabs from whichever end is farther from the current tab until the rest fit
+    loop {
+        let markers = if first > 0 { MORE_LEFT.width() } else { 0 }
+            + if last < labels.len() - 1 { MORE_RIGHT.width() } else { 0 };
+        if first == last || line_width(&labels[first..=last]) + markers <= width {
+            break;
+        }
+        if cur - first > last - cur {
+            first += 1;
+        } else {
+            last -= 1;
+        }
+    }
+
+    let more_left = first > 0;
+    let more_right = last < labels.len() - 1;
+    let total = line_width(&labels[first..=last])
+        + if more_left { MORE_LEFT.width() } else { 0 }
+        + if more_right { MORE_RIGHT.width() } else { 0 };
+    let mut x = if more_left { MORE_LEFT.width() } else { 0 };
+    let mut tabs = Vec::new();
+    for (i, label) in labels.into_iter().enumerate().take(last + 1).skip(first) {
+        if i > first {
+            x += DIVIDER.width();
+        }
+        let w = label.width();
+        tabs.push((i, x as u16, label));
+        x += w;
+    }
+    TabLayout {
+        tabs,
+        more_left,
+        more_right,
+        pad: (width.saturating_sub(total) / 2) as u16,
+    }
+}
+
 pub fn render_tab_bar(
     frame: &mut Frame,
     area: Rect,
@@ -21,22 +101,16 @@ pub fn render_tab_bar(
     let inner = block.inner(area);
     frame.render_widget(block, area);
 
-    // Each tab has its own accent color
-    let tab_colors: [Color; 6] = [
-        Color::Rgb(100, 220, 255), // Queue - sky blue
-        Color::Rgb(120, 255, 180), // Directories - mint green
-        Color::Rgb(255, 180, 100), // Artists - orange
-        Color::Rgb(200, 130, 255), // Albums - purple
-        Color::Rgb(255, 120, 150), // Genre - pink
-        Color::Rgb(255, 220, 100), // Playlists - gold
-    ];
-
-    let mut spans = Vec::new();
-    for (i, tab) in Tab::ALL.iter().enumerate() {
-        if i > 0 {
-            spans.push(Span::styled(" \u{2502} ", theme.dim_style));
+    let layout = layout(inner.width, current);
+    let mut spans = vec![Span::raw(" ".repeat(layout.pad as usize))];
+    if layout.more_left {
+        spans.push(Span::styled(MORE_LEFT, theme.dim_style));
+    }
+    for (n, (i, _, label)) in layout.tabs.into_iter().enumerate() {
+        if n > 0 {
+            spans.push(Span::styled(DIVIDER, theme.dim_style));
         }
-        let color = tab_colors[i % tab_colors.len()];
+        let color = TAB_COLORS[i % TAB_COLORS.len()];
         let style = if i == current.index() {
             Style::default().fg(color).add_modifier(Modifier::BOLD)
         } else if hovered == Some(i) {
@@ -44,16 +118,17 @@ pub fn render_tab_bar(
         } else {
             theme.tab_inactive
         };
-        spans.push(Span::styled(tab.title(), style));
+        spans.push(Span::styled(label, style));
+    }
+    if layout.more_right {
+        spans.push(Span::styled(MORE_RIGHT, theme.dim_style));
     }
 
-    let line = Line::from(spans);
-    let paragraph = Paragraph::new(line).alignment(Alignment::Center);
-    frame.render_widget(paragraph, inner);
+    frame.render_widget(Paragraph::new(Line::from(spans)), inner);
 }
 
 /// Returns which tab index was clicked given mouse x position
-pub fn tab_hit_test(area: Rect, mouse_x: u16) -> Option<usize> {
+pub fn tab_hit_test(area: Rect, current: Tab, mouse_x: u16) -> Option<usize> {
     let block = Block::default().borders(Borders::ALL);
     let inner = block.inner(area);
 
@@ -61,39 +136,9 @@ pub fn tab_hit_test(area: Rect, mouse_x: u16) -> Option<usize> {
         return None;
     }
 
-    // Calculate total content width to find center offset
-    let divider_len = 3; // " │ "
-    let mut total_width: usize = 0;
-    for (i, tab) in Tab::ALL.iter().enumerate() {
-        if i > 0 {
-            total_width += divider_len;
-        }
-        total_width += tab.title().len();
-    }
-
-    let inner_w = inner.width as usize;
-    let pad_left = if inner_w > total_width {
-        (inner_w - total_width) / 2
-    } else {
-        0
-    };
-
-    let rel_x = (mouse_x - inner.x) as usize;
-    if rel_x < pad_left {
-        return None;
-    }
-
-    let content_x = rel_x - pad_left;
-    let mut pos = 0;
-    for (i, tab) in Tab::ALL.iter().enumerate() {
-        if i > 0 {
-            pos += divider_len;
-        }
-        let title_len = tab.title().len();
-        if content_x < pos + title_len {
-            return Some(i);
-        }
-        pos += title_len;
-    }
-    None
+    let layout = layout(inner.width, current);
+    let rel_x = (mouse_x - inner.x).checked_sub(layout.pad)?;
+    layout.tabs.iter()
+        .find(|(_, x, label)| rel_x >= *x && rel_x < x + label.width() as u16)
+        .map(|(i, _, _)| *i)
 }
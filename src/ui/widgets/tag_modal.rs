@@ -0,0 +1,199 @@
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Clear, Paragraph};
+use ratatui::Frame;
+use unicode_width::UnicodeWidthStr;
+
+use crate::library::organize::Move;
+use crate::library::tag_edit::{Change, TagField};
+use std::path::Path;
+use crate::ui::text_input::TextInput;
+use crate::ui::theme::Theme;
+
+const ACCENT: Color = Color::Rgb(255, 180, 100);
+
+/// Batch tag modal steps: pick a field, type the value, review the diff
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TagStep {
+    Choose,
+    Input(TagField),
+    Preview,
+    /// Dry run of moving the files into Artist/Album/NN - Title.ext
+    Organize,
+}
+
+/// Batch tag edit over a selection ("T" on an album/artist/folder, Ctrl+T in search)
+pub struct TagModal {
+    /// What the tracks came from, e.g. "OK Computer"
+    pub name: String,
+    /// Library indices, in the order track numbers are assigned
+    pub tracks: Vec<usize>,
+    pub step: TagStep,
+    pub input: TextInput,
+    /// Pending changes shown in the preview
+    pub changes: Vec<Change>,
+    /// Pending moves shown in the organize dry run
+    pub moves: Vec<Move>,
+    pub scroll: usize,
+}
+
+impl TagModal {
+    pub fn new(name: String, tracks: Vec<usize>) -> Self {
+        Self {
+            name,
+            tracks,
+            step: TagStep::Choose,
+            input: TextInput::new(),
+            changes: Vec::new(),
+            moves: Vec::new(),
+            scroll: 0,
+        }
+    }
+}
+
+pub fn render_tag_modal(frame: &mut Frame, area: Rect, modal: &TagModal, root: &Path, theme: &Theme) {
+    let rect = centered_rect(60, 60, area);
+
+    frame.render_widget(Clear, rect);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(ACCENT))
+        .title(format!(" Edit Tags \u{2014} {} ({} tracks) ", modal.name, modal.tracks.len()))
+        .title_style(Style::default().fg(ACCENT).add_modifier(Modifier::BOLD));
+
+    let inner = block.inner(rect);
+    frame.render_widget(block, rect);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(1), Constraint::Length(1)])
+        .split(inner);
+
+    let key_style = Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD);
+    let dim = Style::default().fg(Color::DarkGray);
+
+    let (lines, hint): (Vec<Line>, &str) = match modal.step {
+        TagStep::Choose => (
+            vec![
+                Line::from(""),
+                Line::from(vec![Span::styled("  a  ", key_style), Span::styled("Set album artist", Style::default().fg(theme.fg))]),
+                Line::from(vec![Span::styled("  g  ", key_style), Span::styled("Set genre", Style::default().fg(theme.fg))]),
+                Line::from(vec![
+                    Span::styled("  n  ", key_style),
+                    Span::styled(format!("Renumber tracks 1\u{2013}{} in list order", modal.tracks.len()), Style::default().fg(theme.fg)),
+                ]),
+                Line::from(vec![
+                    Span::styled("  t  ", key_style),
+                    Span::styled("Fill missing titles from file names", Style::default().fg(theme.fg)),
+                ]),
+                Line::from(vec![
+                    Span::styled("  o  ", key_style),
+                    Span::styled("Organize files into Artist/Album/NN - Title", Style::default().fg(theme.fg)),
+                ]),
+            ],
+            " a/g/n/t/o: choose  Esc: close ",
+        ),
+        TagStep::Input(field) => (
+            vec![
+                Line::from(""),
+                Line::from(Span::styled(format!(" {}: ", field.label()), key_style)),
+                Line::from(Span::styled("   Leave empty to clear the tag", dim)),
+            ],
+            " Enter: preview  Esc: back ",
+        ),
+        TagStep::Preview => {
+            let mut lines = vec![Line::from(Span::styled(
+                format!(
+                    " {} of {} files change ({} already set)",
+                    modal.changes.len(),
+                    modal.tracks.len(),
+                    modal.tracks.len() - modal.changes.len()
+                ),
+                Style::default().fg(theme.fg).add_modifier(Modifier::BOLD),
+            ))];
+            let visible = (chunks[0].height as usize).saturating_sub(1);
+            for change in modal.changes.iter().skip(modal.scroll).take(visible) {
+                let name = change.path.file_name().map(|n| n.to_string_lossy()).unwrap_or_default();
+                let old = if change.old.is_empty() { "(none)" } else { change.old.as_str() };
+                let new = if change.new.is_empty() { "(none)" } else { change.new.as_str() };
+                lines.push(Line::from(vec![
+                    Span::styled(format!("  {}  ", name), Style::default().fg(theme.fg)),
+                    Span::styled(old.to_string(), Style::default().fg(Color::Rgb(255, 100, 100)).add_modifier(Modifier::CROSSED_OUT)),
+                    Span::styled(" \u{2192} ", dim),
+                    Span::styled(new.to_string(), Style::default().fg(Color::Rgb(80, 255, 120))),
+                ]));
+            }
+            let hint = if modal.changes.is_empty() {
+                " Nothing to change  Esc: back "
+            } else {
+                " Enter: write tags  j/k: scroll  Esc: back "
+            };
+            (lines, hint)
+        }
+        TagStep::Organize => {
+            let mut lines = vec![Line::from(Span::styled(
+                format!(
+                    " {} of {} files move ({} already in place or blocked)",
+                    modal.moves.len(),
+                    modal.tracks.len(),
+                    modal.tracks.len() - modal.moves.len()
+                ),
+                Style::default().fg(theme.fg).add_modifier(Modifier::BOLD),
+            ))];
+            let rel = |p: &Path| p.strip_prefix(root).unwrap_or(p).display().to_string();
+            let visible = (chunks[0].height as usize).saturating_sub(1) / 2;
+            for m in modal.moves.iter().skip(modal.scroll).take(visible) {
+                lines.push(Line::from(Span::styled(
+                    format!("  {}", rel(&m.from)),
+                    Style::default().fg(Color::Rgb(255, 100, 100)),
+                )));
+                lines.push(Line::from(vec![
+                    Span::styled("  \u{2192} ", dim),
+                    Span::styled(rel(&m.to), Style::default().fg(Color::Rgb(80, 255, 120))),
+                ]));
+            }
+            let hint = if modal.moves.is_empty() {
+                " Nothing to move  Esc: back "
+            } else {
+                " Enter: move files  j/k: scroll  Esc: back "
+            };
+            (lines, hint)
+        }
+    };
+
+    frame.render_widget(Paragraph::new(lines), chunks[0]);
+    // The value being typed goes after its label on the second row
+    if let TagStep::Input(field) = modal.step {
+        let label = format!(" {}: ", field.label()).width() as u16;
+        let row = Rect {
+            x: chunks[0].x + label,
+            y: chunks[0].y + 1,
+            width: chunks[0].width.saturating_sub(label),
+            height: chunks[0].height.saturating_sub(1).min(1),
+        };
+        modal.input.render(frame, row, Style::default().fg(Color::White));
+    }
+    frame.render_widget(Paragraph::new(Line::from(Span::styled(hint, dim))), chunks[1]);
+}
+
+fn centered_rect(percent_x: u16, percent_y: u16, area: Rect) -> Rect {
+    let vertical = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Percentage((100 - percent_y) / 2),
+            Constraint::Percentage(percent_y),
+            Constraint::Percentage((100 - percent_y) / 2),
+        ])
+        .split(area);
+
+    Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage((100 - percent_x) / 2),
+            Constraint::Percentage(percent_x),
+            Constraint::Percentage((100 - percent_x) / 2),
+        ])
+        .split(vertical[1])[1]
+}